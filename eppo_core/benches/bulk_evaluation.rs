@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use eppo_core::{
+    eval::{get_all_assignments, get_assignment, EvaluationOptions},
+    ufc::UniversalFlagConfig,
+    Attributes, Configuration, SdkMetadata,
+};
+
+const FLAG_KEYS: [&str; 5] = [
+    "new-user-onboarding",
+    "kill-switch",
+    "numeric_flag",
+    "integer-flag",
+    "json-config-flag",
+];
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let flags = UniversalFlagConfig::from_json(
+        SdkMetadata {
+            name: "test",
+            version: "0.1.0",
+        },
+        std::fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap(),
+    )
+    .unwrap();
+    let configuration = Configuration::from_server_response(flags, None);
+    let now = Utc::now();
+    let subject_key = "subject1".into();
+    let attributes: Arc<Attributes> = Arc::new(
+        [
+            ("country".into(), "US".into()),
+            ("email".into(), "subject1@example.com".into()),
+            ("age".into(), 30.0.into()),
+        ]
+        .into(),
+    );
+
+    let mut group = c.benchmark_group("bulk-evaluation");
+    // Throughput is reported per evaluated flag.
+    group.throughput(Throughput::Elements(FLAG_KEYS.len() as u64));
+    group.bench_function("get_assignment", |b| {
+        b.iter(|| {
+            for flag_key in FLAG_KEYS {
+                let _ = black_box(get_assignment(
+                    black_box(Some(&configuration)),
+                    black_box(flag_key),
+                    black_box(&subject_key),
+                    black_box(&attributes),
+                    black_box(None),
+                    black_box(now),
+                    black_box(&EvaluationOptions::default()),
+                ));
+            }
+        })
+    });
+    group.bench_function("get_all_assignments", |b| {
+        b.iter(|| {
+            get_all_assignments(
+                black_box(Some(&configuration)),
+                black_box(&subject_key),
+                black_box(&attributes),
+                black_box(Some(&FLAG_KEYS)),
+                black_box(now),
+                black_box(&EvaluationOptions::default()),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
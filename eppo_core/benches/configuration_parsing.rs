@@ -0,0 +1,127 @@
+use std::{
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use eppo_core::{
+    configuration_fetcher::FetchResult,
+    configuration_source::ConfigurationSource,
+    configuration_store::ConfigurationStore,
+    poller_thread::{PollerThread, PollerThreadConfig},
+    ufc::UniversalFlagConfig,
+    Configuration, Result, SdkMetadata,
+};
+
+const SDK_METADATA: SdkMetadata = SdkMetadata {
+    name: "test",
+    version: "0.1.0",
+};
+
+/// Simulate a large configuration by duplicating flags from `flags-v1.json` under new keys.
+fn large_configuration(copies: usize) -> Vec<u8> {
+    let mut json: serde_json::Value =
+        serde_json::from_slice(&std::fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap())
+            .unwrap();
+    let flags = json["flags"].as_object().unwrap().clone();
+    let all_flags = json["flags"].as_object_mut().unwrap();
+    for i in 0..copies {
+        for (key, flag) in &flags {
+            let key = format!("{key}-{i}");
+            let mut flag = flag.clone();
+            flag["key"] = key.clone().into();
+            all_flags.insert(key, flag);
+        }
+    }
+    serde_json::to_vec(&json).unwrap()
+}
+
+/// A source that parses the same configuration on every fetch, either inline or on the blocking
+/// thread pool.
+struct ParsingSource {
+    json: Vec<u8>,
+    background: bool,
+    /// Notified when a fetch starts.
+    started: mpsc::Sender<()>,
+}
+
+impl ConfigurationSource for ParsingSource {
+    fn sdk_metadata(&self) -> SdkMetadata {
+        SDK_METADATA
+    }
+
+    async fn fetch(&mut self) -> Result<FetchResult> {
+        let _ = self.started.send(());
+        let flags = if self.background {
+            UniversalFlagConfig::from_json_async(SDK_METADATA, self.json.clone()).await?
+        } else {
+            UniversalFlagConfig::from_json(SDK_METADATA, self.json.clone())?
+        };
+        Ok(FetchResult::Modified(Box::new(
+            Configuration::from_server_response(flags, None),
+        )))
+    }
+}
+
+/// Measure how long `shutdown()` takes when it's called while the poller is parsing
+/// configuration.
+///
+/// Parsing on the blocking pool is not cancelled by shutdown, so `settle` is the time to wait
+/// after each iteration (outside of measurement) for it to finish. Otherwise, it would compete
+/// for CPU with the following iterations.
+fn shutdown_during_parse(json: &[u8], background: bool, settle: Duration, iters: u64) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let (started, fetch_started) = mpsc::channel();
+        let source = ParsingSource {
+            json: json.to_vec(),
+            background,
+            started,
+        };
+        let poller = PollerThread::start_with_config(
+            source,
+            Arc::new(ConfigurationStore::new()),
+            PollerThreadConfig::new(),
+        )
+        .unwrap();
+        fetch_started.recv().unwrap();
+
+        let start = Instant::now();
+        poller.shutdown().unwrap();
+        total += start.elapsed();
+
+        std::thread::sleep(settle);
+    }
+    total
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let json = large_configuration(50);
+    let parse_time = {
+        let start = Instant::now();
+        UniversalFlagConfig::from_json(SDK_METADATA, json.clone()).unwrap();
+        start.elapsed()
+    };
+
+    let mut group = c.benchmark_group("configuration-parsing");
+    group.bench_function("from_json", |b| {
+        b.iter(|| UniversalFlagConfig::from_json(SDK_METADATA, black_box(json.clone())))
+    });
+    group.finish();
+
+    // With inline parsing, stop has to wait for the parse to complete. With parsing on the
+    // blocking pool, the poller stops right away.
+    let mut group = c.benchmark_group("poller-shutdown-during-parse");
+    group.sample_size(20);
+    group.bench_function("inline", |b| {
+        b.iter_custom(|iters| shutdown_during_parse(&json, false, Duration::ZERO, iters))
+    });
+    group.bench_function("spawn_blocking", |b| {
+        b.iter_custom(|iters| shutdown_during_parse(&json, true, parse_time * 2, iters))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
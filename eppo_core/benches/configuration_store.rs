@@ -0,0 +1,105 @@
+use std::{
+    sync::{Arc, Barrier, RwLock},
+    time::{Duration, Instant},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use eppo_core::{
+    configuration_store::ConfigurationStore, ufc::UniversalFlagConfig, Configuration, SdkMetadata,
+};
+
+const READERS: usize = 16;
+
+/// The store implementation before reads became lock-free, kept as a baseline.
+#[derive(Default)]
+struct RwLockStore {
+    configuration: RwLock<Option<Arc<Configuration>>>,
+}
+
+impl RwLockStore {
+    fn get_configuration(&self) -> Option<Arc<Configuration>> {
+        self.configuration.read().unwrap().clone()
+    }
+
+    fn set_configuration(&self, configuration: Arc<Configuration>) {
+        *self.configuration.write().unwrap() = Some(configuration);
+    }
+}
+
+fn configuration() -> Arc<Configuration> {
+    let flags = UniversalFlagConfig::from_json(
+        SdkMetadata {
+            name: "test",
+            version: "0.1.0",
+        },
+        br#"{
+          "createdAt": "2024-07-18T00:00:00Z",
+          "format": "SERVER",
+          "environment": {"name": "test"},
+          "flags": {}
+        }"#
+        .to_vec(),
+    )
+    .unwrap();
+    Arc::new(Configuration::from_server_response(flags, None))
+}
+
+/// Run `iters` reads on each of `READERS` threads concurrently and return the wall time.
+fn concurrent_reads<S: Send + Sync + 'static>(
+    store: &Arc<S>,
+    read: fn(&S) -> Option<Arc<Configuration>>,
+    iters: u64,
+) -> Duration {
+    let barrier = Arc::new(Barrier::new(READERS + 1));
+    let readers: Vec<_> = (0..READERS)
+        .map(|_| {
+            let store = store.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..iters {
+                    black_box(read(&store));
+                }
+            })
+        })
+        .collect();
+
+    barrier.wait();
+    let start = Instant::now();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    start.elapsed()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let store = Arc::new(ConfigurationStore::new());
+    store.set_configuration(configuration());
+    let rwlock_store = Arc::new(RwLockStore::default());
+    rwlock_store.set_configuration(configuration());
+
+    let mut group = c.benchmark_group("configuration-store");
+    // Throughput is reported per read on a single thread.
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("get_configuration/single-thread", |b| {
+        b.iter(|| store.get_configuration())
+    });
+    group.bench_function("get_configuration/rwlock-single-thread", |b| {
+        b.iter(|| rwlock_store.get_configuration())
+    });
+    group.bench_function("get_configuration/16-threads", |b| {
+        b.iter_custom(|iters| {
+            concurrent_reads(&store, ConfigurationStore::get_configuration, iters)
+        })
+    });
+    group.bench_function("get_configuration/rwlock-16-threads", |b| {
+        b.iter_custom(|iters| {
+            concurrent_reads(&rwlock_store, RwLockStore::get_configuration, iters)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -5,7 +5,7 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughpu
 
 use eppo_core::ufc::UniversalFlagConfig;
 use eppo_core::{
-    eval::{get_assignment, get_assignment_details},
+    eval::{get_assignment, get_assignment_details, EvaluationOptions},
     Configuration, SdkMetadata,
 };
 
@@ -34,6 +34,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -46,6 +47,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -65,6 +67,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -77,6 +80,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -96,6 +100,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -108,6 +113,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -127,6 +133,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -139,6 +146,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -158,6 +166,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
@@ -170,6 +179,7 @@ fn criterion_benchmark(c: &mut Criterion) {
                     black_box(&attributes),
                     black_box(None),
                     black_box(now),
+                    black_box(&EvaluationOptions::default()),
                 )
             })
         });
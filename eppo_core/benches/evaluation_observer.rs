@@ -0,0 +1,93 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use eppo_core::{
+    configuration_store::ConfigurationStore,
+    eval::{eval_details::FlagEvaluationCode, EvaluationObserver, Evaluator, EvaluatorConfig},
+    timestamp::SystemClock,
+    ufc::UniversalFlagConfig,
+    Configuration, SdkMetadata,
+};
+
+struct NoopObserver;
+
+impl EvaluationObserver for NoopObserver {
+    fn on_evaluation(&self, flag_key: &str, duration: Duration, outcome: FlagEvaluationCode) {
+        black_box((flag_key, duration, outcome));
+    }
+}
+
+fn evaluator(observer: Option<Arc<dyn EvaluationObserver>>) -> Evaluator {
+    let sdk_metadata = SdkMetadata {
+        name: "test",
+        version: "0.1.0",
+    };
+    let flags = UniversalFlagConfig::from_json(
+        sdk_metadata,
+        std::fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap(),
+    )
+    .unwrap();
+    let configuration_store = Arc::new(ConfigurationStore::new());
+    configuration_store
+        .set_configuration(Arc::new(Configuration::from_server_response(flags, None)));
+    Evaluator::new(EvaluatorConfig {
+        configuration_store,
+        sdk_metadata,
+        clock: Arc::new(SystemClock),
+        observer,
+        sharder: None,
+        default_attributes: Arc::default(),
+        coerce_numeric_types: false,
+        bandit_evaluation_options: Default::default(),
+    })
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let without_observer = evaluator(None);
+    let with_observer = evaluator(Some(Arc::new(NoopObserver)));
+    let attributes = Arc::new([("country".into(), "US".into())].into());
+    let subject_key = "subject1".into();
+
+    let mut group = c.benchmark_group("evaluation-observer");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("get_assignment", |b| {
+        b.iter(|| {
+            without_observer.get_assignment(
+                black_box("new-user-onboarding"),
+                black_box(&subject_key),
+                black_box(&attributes),
+                black_box(None),
+            )
+        })
+    });
+    group.bench_function("get_assignment_with_observer", |b| {
+        b.iter(|| {
+            with_observer.get_assignment(
+                black_box("new-user-onboarding"),
+                black_box(&subject_key),
+                black_box(&attributes),
+                black_box(None),
+            )
+        })
+    });
+    group.bench_function("get_bandit_action", |b| {
+        b.iter(|| {
+            without_observer.get_bandit_action(
+                black_box("new-user-onboarding"),
+                black_box(&subject_key),
+                black_box(&Default::default()),
+                black_box(&HashMap::new()),
+                None,
+                black_box(&"default".into()),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().noise_threshold(0.02);
+    targets = criterion_benchmark);
+criterion_main!(benches);
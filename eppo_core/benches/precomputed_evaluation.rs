@@ -0,0 +1,69 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::Utc;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use eppo_core::{
+    eval::{get_precomputed_configuration, PrecomputeOptions},
+    ufc::UniversalFlagConfig,
+    Configuration, ContextAttributes, SdkMetadata, Str,
+};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let flags = UniversalFlagConfig::from_json(
+        SdkMetadata {
+            name: "test",
+            version: "0.1.0",
+        },
+        std::fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap(),
+    )
+    .unwrap();
+    let configuration = Configuration::from_server_response(flags, None);
+    let now = Utc::now();
+    let subject_key = "subject1".into();
+    let attributes = Arc::new(
+        [("country", "US"), ("email", "subject1@example.com")]
+            .into_iter()
+            .collect::<ContextAttributes>(),
+    );
+    let flag_actions = HashMap::new();
+    let flag_filter: HashSet<Str> = ["new-user-onboarding".into(), "kill-switch".into()]
+        .into_iter()
+        .collect();
+
+    let mut group = c.benchmark_group("precomputed-evaluation");
+    group.bench_function("all-flags", |b| {
+        b.iter(|| {
+            get_precomputed_configuration(
+                black_box(Some(&configuration)),
+                black_box(&subject_key),
+                black_box(&attributes),
+                black_box(&flag_actions),
+                black_box(now),
+                black_box(&PrecomputeOptions::default()),
+            )
+        })
+    });
+    group.bench_function("filtered-flags", |b| {
+        b.iter(|| {
+            get_precomputed_configuration(
+                black_box(Some(&configuration)),
+                black_box(&subject_key),
+                black_box(&attributes),
+                black_box(&flag_actions),
+                black_box(now),
+                black_box(&PrecomputeOptions {
+                    flag_filter: Some(&flag_filter),
+                    ..Default::default()
+                }),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,122 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::Utc;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use regex::Regex;
+
+use eppo_core::{
+    eval::{get_assignment, EvaluationOptions},
+    ufc::UniversalFlagConfig,
+    Configuration, SdkMetadata,
+};
+
+/// Targeting rule conditions of the benchmarked flag, as `(attribute, pattern)` pairs.
+const CONDITIONS: [(&str, &str); 5] = [
+    ("email", r"@example\.com$"),
+    ("country", "^(US|CA|MX)$"),
+    ("device", "(?i)iphone|ipad"),
+    ("app_version", r"^2\.\d+\.\d+$"),
+    ("plan", "^(pro|enterprise)-.*"),
+];
+
+fn flags_json() -> Vec<u8> {
+    let conditions = CONDITIONS
+        .iter()
+        .map(|(attribute, pattern)| {
+            serde_json::json!({"attribute": attribute, "operator": "MATCHES", "value": pattern})
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_vec(&serde_json::json!({
+        "createdAt": "2024-07-18T00:00:00Z",
+        "format": "SERVER",
+        "environment": {"name": "test"},
+        "flags": {
+            "regex-flag": {
+                "key": "regex-flag",
+                "enabled": true,
+                "variationType": "BOOLEAN",
+                "totalShards": 10000,
+                "variations": {
+                    "on": {"key": "on", "value": true},
+                    "off": {"key": "off", "value": false}
+                },
+                "allocations": [
+                    {
+                        "key": "targeted",
+                        "rules": [{"conditions": conditions}],
+                        "splits": [{"variationKey": "on", "shards": []}],
+                        "doLog": false
+                    },
+                    {
+                        "key": "default",
+                        "splits": [{"variationKey": "off", "shards": []}],
+                        "doLog": false
+                    }
+                ]
+            }
+        }
+    }))
+    .unwrap()
+}
+
+/// How regex conditions were evaluated before patterns were compiled with configuration, kept as
+/// a baseline.
+fn matches_uncompiled(attributes: &HashMap<&str, &str>) -> bool {
+    CONDITIONS.iter().all(|(attribute, pattern)| {
+        let regex = Regex::new(pattern).unwrap();
+        attributes
+            .get(attribute)
+            .is_some_and(|value| regex.is_match(value))
+    })
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let flags = UniversalFlagConfig::from_json(
+        SdkMetadata {
+            name: "test",
+            version: "0.1.0",
+        },
+        flags_json(),
+    )
+    .unwrap();
+    let configuration = Configuration::from_server_response(flags, None);
+    let now = Utc::now();
+
+    let values = [
+        ("email", "user@example.com"),
+        ("country", "US"),
+        ("device", "iPhone"),
+        ("app_version", "2.3.1"),
+        ("plan", "pro-annual"),
+    ];
+    let attributes = Arc::new(
+        values
+            .iter()
+            .map(|(key, value)| ((*key).into(), (*value).into()))
+            .collect(),
+    );
+    let raw_attributes = HashMap::from(values);
+
+    let mut group = c.benchmark_group("regex-conditions");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("get_assignment", |b| {
+        b.iter(|| {
+            get_assignment(
+                black_box(Some(&configuration)),
+                black_box("regex-flag"),
+                black_box(&"subject1".into()),
+                black_box(&attributes),
+                black_box(None),
+                black_box(now),
+                black_box(&EvaluationOptions::default()),
+            )
+        })
+    });
+    group.bench_function("match-uncompiled", |b| {
+        b.iter(|| matches_uncompiled(black_box(&raw_attributes)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use eppo_core::sharder::{Md5Sharder, PreSaltedSharder, Sharder, Xxh3Sharder};
+
+const TOTAL_SHARDS: u32 = 10_000;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // Split shard check: `{salt}-{subject_key}`.
+    let split_input = ["8a6f2e1bd0c9", "-", "user-1234567890"];
+    // Bandit action shuffle: `{flag_key}-{subject_key}-{action_key}`.
+    let action_input = [
+        "banner-bandit-flag",
+        "-",
+        "user-1234567890",
+        "-",
+        "nike-air-max",
+    ];
+    let pre_salted = PreSaltedSharder::new(&[split_input[0], split_input[1]], TOTAL_SHARDS);
+
+    let mut group = c.benchmark_group("sharder");
+    group.bench_function("split/md5", |b| {
+        b.iter(|| Md5Sharder.shard(black_box(&split_input), TOTAL_SHARDS))
+    });
+    group.bench_function("split/md5-pre-salted", |b| {
+        b.iter(|| pre_salted.shard(black_box(&split_input[2..])))
+    });
+    group.bench_function("split/xxh3", |b| {
+        b.iter(|| Xxh3Sharder.shard(black_box(&split_input), TOTAL_SHARDS))
+    });
+    group.bench_function("bandit-action/md5", |b| {
+        b.iter(|| Md5Sharder.shard(black_box(&action_input), TOTAL_SHARDS))
+    });
+    group.bench_function("bandit-action/xxh3", |b| {
+        b.iter(|| Xxh3Sharder.shard(black_box(&action_input), TOTAL_SHARDS))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
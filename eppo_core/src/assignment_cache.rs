@@ -0,0 +1,258 @@
+//! Caches used to deduplicate assignment and bandit events.
+//!
+//! When the same subject is evaluated many times, it would normally produce the same event over
+//! and over again. An [`AssignmentCache`] remembers what was logged for each subject/flag pair, so
+//! that SDKs can skip logging events that don't carry any new information.
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    events::{AssignmentEvent, BanditEvent},
+    Str,
+};
+
+/// Cache key. Identifies a subject/flag pair.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AssignmentCacheKey {
+    pub subject_key: Str,
+    pub flag_key: Str,
+}
+
+/// Cache value. Identifies what was served to the subject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssignmentCacheValue {
+    Assignment {
+        allocation_key: Str,
+        variation_key: Str,
+    },
+    Bandit {
+        action_key: Str,
+        model_version: Str,
+    },
+}
+
+impl AssignmentCacheKey {
+    /// Split assignment event into cache key and value.
+    pub fn from_assignment_event(
+        event: &AssignmentEvent,
+    ) -> (AssignmentCacheKey, AssignmentCacheValue) {
+        (
+            AssignmentCacheKey {
+                subject_key: event.subject.clone(),
+                flag_key: event.base.feature_flag.clone(),
+            },
+            AssignmentCacheValue::Assignment {
+                allocation_key: event.base.allocation.clone(),
+                variation_key: event.base.variation.clone(),
+            },
+        )
+    }
+
+    /// Split bandit event into cache key and value.
+    pub fn from_bandit_event(event: &BanditEvent) -> (AssignmentCacheKey, AssignmentCacheValue) {
+        (
+            AssignmentCacheKey {
+                subject_key: event.subject.clone(),
+                flag_key: event.flag_key.clone(),
+            },
+            AssignmentCacheValue::Bandit {
+                action_key: event.action.clone(),
+                model_version: event.model_version.clone(),
+            },
+        )
+    }
+}
+
+/// A cache of logged events.
+///
+/// Implementations must be thread-safe.
+pub trait AssignmentCache {
+    /// Record that `value` was logged for `key`.
+    ///
+    /// Returns `true` if the event should be logged (the key was not in cache or it was holding a
+    /// different value). Returns `false` if the same value has already been logged.
+    fn insert(&self, key: AssignmentCacheKey, value: AssignmentCacheValue) -> bool;
+}
+
+/// An in-memory [`AssignmentCache`] that holds up to `capacity` entries and evicts least recently
+/// used entries.
+pub struct LruAssignmentCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Default)]
+struct LruState {
+    /// Monotonically increasing counter used to track recency.
+    tick: u64,
+    entries: HashMap<AssignmentCacheKey, (AssignmentCacheValue, u64)>,
+    /// Maps tick of last use to entry key. The first entry is the least recently used one.
+    recency: BTreeMap<u64, AssignmentCacheKey>,
+}
+
+impl LruAssignmentCache {
+    /// Default capacity of the cache.
+    pub const DEFAULT_CAPACITY: usize = 50_000;
+
+    /// Create a new cache holding up to `capacity` entries.
+    pub fn new(capacity: usize) -> LruAssignmentCache {
+        LruAssignmentCache {
+            capacity: capacity.max(1),
+            state: Mutex::new(LruState::default()),
+        }
+    }
+}
+
+impl Default for LruAssignmentCache {
+    fn default() -> LruAssignmentCache {
+        LruAssignmentCache::new(LruAssignmentCache::DEFAULT_CAPACITY)
+    }
+}
+
+impl AssignmentCache for LruAssignmentCache {
+    fn insert(&self, key: AssignmentCacheKey, value: AssignmentCacheValue) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let state = &mut *state;
+
+        state.tick += 1;
+        let tick = state.tick;
+
+        if let Some((old_value, old_tick)) = state.entries.get_mut(&key) {
+            state.recency.remove(old_tick);
+            state.recency.insert(tick, key);
+            *old_tick = tick;
+            if *old_value == value {
+                return false;
+            }
+            *old_value = value;
+            return true;
+        }
+
+        if state.entries.len() >= self.capacity {
+            if let Some((_, evicted)) = state.recency.pop_first() {
+                state.entries.remove(&evicted);
+            }
+        }
+
+        state.recency.insert(tick, key.clone());
+        state.entries.insert(key, (value, tick));
+        true
+    }
+}
+
+/// An in-memory [`AssignmentCache`] that forgets entries after `ttl` has passed since they were
+/// logged.
+pub struct TtlAssignmentCache {
+    ttl: Duration,
+    state: Mutex<TtlState>,
+}
+
+struct TtlState {
+    entries: HashMap<AssignmentCacheKey, (AssignmentCacheValue, Instant)>,
+    last_purge: Instant,
+}
+
+impl TtlAssignmentCache {
+    /// Create a new cache that expires entries after `ttl`.
+    pub fn new(ttl: Duration) -> TtlAssignmentCache {
+        TtlAssignmentCache {
+            ttl,
+            state: Mutex::new(TtlState {
+                entries: HashMap::new(),
+                last_purge: Instant::now(),
+            }),
+        }
+    }
+}
+
+impl AssignmentCache for TtlAssignmentCache {
+    fn insert(&self, key: AssignmentCacheKey, value: AssignmentCacheValue) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        let now = Instant::now();
+
+        // Purge expired entries once per ttl, so the cache doesn't grow indefinitely.
+        if now.duration_since(state.last_purge) >= self.ttl {
+            let ttl = self.ttl;
+            state
+                .entries
+                .retain(|_, (_, inserted_at)| now.duration_since(*inserted_at) < ttl);
+            state.last_purge = now;
+        }
+
+        match state.entries.get(&key) {
+            Some((old_value, inserted_at))
+                if *old_value == value && now.duration_since(*inserted_at) < self.ttl =>
+            {
+                false
+            }
+            _ => {
+                state.entries.insert(key, (value, now));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        AssignmentCache, AssignmentCacheKey, AssignmentCacheValue, LruAssignmentCache,
+        TtlAssignmentCache,
+    };
+
+    fn key(subject: &str) -> AssignmentCacheKey {
+        AssignmentCacheKey {
+            subject_key: subject.into(),
+            flag_key: "flag".into(),
+        }
+    }
+
+    fn value(variation: &str) -> AssignmentCacheValue {
+        AssignmentCacheValue::Assignment {
+            allocation_key: "allocation".into(),
+            variation_key: variation.into(),
+        }
+    }
+
+    #[test]
+    fn lru_dedupes_same_value() {
+        let cache = LruAssignmentCache::new(10);
+
+        assert!(cache.insert(key("alice"), value("a")));
+        assert!(!cache.insert(key("alice"), value("a")));
+        assert!(cache.insert(key("alice"), value("b")));
+        assert!(cache.insert(key("alice"), value("a")));
+        assert!(cache.insert(key("bob"), value("a")));
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let cache = LruAssignmentCache::new(2);
+
+        assert!(cache.insert(key("alice"), value("a")));
+        assert!(cache.insert(key("bob"), value("a")));
+        // Touch alice, so bob is the least recently used.
+        assert!(!cache.insert(key("alice"), value("a")));
+        assert!(cache.insert(key("charlie"), value("a")));
+
+        assert!(!cache.insert(key("alice"), value("a")));
+        assert!(cache.insert(key("bob"), value("a")));
+    }
+
+    #[test]
+    fn ttl_expires_entries() {
+        let cache = TtlAssignmentCache::new(Duration::from_millis(50));
+
+        assert!(cache.insert(key("alice"), value("a")));
+        assert!(!cache.insert(key("alice"), value("a")));
+
+        std::thread::sleep(Duration::from_millis(60));
+
+        assert!(cache.insert(key("alice"), value("a")));
+    }
+}
@@ -7,7 +7,7 @@ use crate::Str;
 
 mod context_attributes;
 
-pub use context_attributes::ContextAttributes;
+pub use context_attributes::{AttributeKind, ContextAttributes};
 
 /// Type alias for a HashMap representing key-value pairs of attributes.
 ///
@@ -30,10 +30,18 @@ pub type Attributes = HashMap<Str, AttributeValue>;
 /// categorical). Storing kind is helpful to make `Attributes` ↔ `ContextAttributes` conversion
 /// isomorphic.
 ///
+/// Multi-valued attributes (e.g., a list of entitlements) are represented as
+/// [arrays](AttributeValue::array). Arrays only match `ONE_OF` and `NOT_ONE_OF` conditions and
+/// are ignored by bandits.
+///
+/// Non-finite numbers (NaN and infinities) cannot be compared meaningfully and cannot be
+/// represented in JSON, so they are converted to null (with a warning).
+///
 /// Note that attribute kind is stripped during serialization, so Attribute → JSON → Attribute
 /// conversion is lossy.
 #[derive(Debug, Clone, PartialEq, PartialOrd, derive_more::From, Serialize, Deserialize)]
-#[from(NumericAttribute, CategoricalAttribute, f64, bool, Str, String, &str, Arc<str>, Arc<String>, Cow<'_, str>)]
+#[from(bool, Str, String, &str, Arc<str>, Arc<String>, Cow<'_, str>)]
+#[serde(from = "AttributeValueImpl")]
 pub struct AttributeValue(AttributeValueImpl);
 #[derive(Debug, Clone, PartialEq, PartialOrd, derive_more::From, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -43,20 +51,77 @@ enum AttributeValueImpl {
     #[from(CategoricalAttribute, Str, bool, String, &str, Arc<str>, Arc<String>, Cow<'_, str>)]
     Categorical(CategoricalAttribute),
     #[from(ignore)]
+    Array(Vec<AttributeValue>),
+    #[from(ignore)]
     Null,
 }
 
+impl From<AttributeValueImpl> for AttributeValue {
+    /// Wrap the value, converting non-finite numbers to null.
+    fn from(value: AttributeValueImpl) -> AttributeValue {
+        let number = match &value {
+            AttributeValueImpl::Numeric(NumericAttribute(v))
+            | AttributeValueImpl::Categorical(CategoricalAttribute(
+                CategoricalAttributeImpl::Number(v),
+            )) => Some(*v),
+            _ => None,
+        };
+        match number {
+            Some(v) if !v.is_finite() => {
+                log::warn!(target: "eppo", "non-finite attribute value {v} is treated as null");
+                AttributeValue::null()
+            }
+            _ => AttributeValue(value),
+        }
+    }
+}
+
+impl From<f64> for AttributeValue {
+    #[inline]
+    fn from(value: f64) -> AttributeValue {
+        AttributeValue::numeric(value)
+    }
+}
+
+impl From<NumericAttribute> for AttributeValue {
+    #[inline]
+    fn from(value: NumericAttribute) -> AttributeValue {
+        AttributeValue::numeric(value)
+    }
+}
+
+impl From<CategoricalAttribute> for AttributeValue {
+    #[inline]
+    fn from(value: CategoricalAttribute) -> AttributeValue {
+        AttributeValue::categorical(value)
+    }
+}
+
 impl AttributeValue {
-    /// Create a numeric attribute.
+    /// Create a numeric attribute. Non-finite numbers are converted to null.
     #[inline]
     pub fn numeric(value: impl Into<NumericAttribute>) -> AttributeValue {
-        AttributeValue(AttributeValueImpl::Numeric(value.into()))
+        AttributeValueImpl::Numeric(value.into()).into()
     }
 
-    /// Create a categorical attribute.
+    /// Create a categorical attribute. Non-finite numbers are converted to null.
     #[inline]
     pub fn categorical(value: impl Into<CategoricalAttribute>) -> AttributeValue {
-        AttributeValue(AttributeValueImpl::Categorical(value.into()))
+        AttributeValueImpl::Categorical(value.into()).into()
+    }
+
+    /// Create a multi-valued attribute. Elements are expected to be strings, numbers, or
+    /// booleans. Other elements never match any condition.
+    ///
+    /// # Examples
+    /// ```
+    /// # use eppo_core::AttributeValue;
+    /// let entitlements = AttributeValue::array(["pro", "beta"]);
+    /// ```
+    pub fn array<T: Into<AttributeValue>>(values: impl IntoIterator<Item = T>) -> AttributeValue {
+        AttributeValue(AttributeValueImpl::Array(
+            values.into_iter().map(Into::into).collect(),
+        ))
     }
 
     #[inline]
@@ -83,7 +148,7 @@ impl AttributeValue {
     /// Try coercing attribute to a string.
     ///
     /// String attributes are returned as is. Number and boolean attributes are converted to string.
-    pub(crate) fn coerce_to_string(&self) -> Option<Cow<str>> {
+    pub(crate) fn coerce_to_string(&self) -> Option<Cow<'_, str>> {
         match self.as_attribute_value()? {
             AttributeValueRef::String(s) => Some(Cow::Borrowed(s)),
             AttributeValueRef::Number(v) => Some(Cow::Owned(v.to_string())),
@@ -91,6 +156,14 @@ impl AttributeValue {
         }
     }
 
+    /// Returns elements of an array attribute.
+    pub(crate) fn as_array(&self) -> Option<&[AttributeValue]> {
+        match self {
+            AttributeValue(AttributeValueImpl::Array(values)) => Some(values),
+            _ => None,
+        }
+    }
+
     pub(crate) fn as_str(&self) -> Option<&Str> {
         match self {
             AttributeValue(AttributeValueImpl::Categorical(CategoricalAttribute(
@@ -110,17 +183,21 @@ impl AttributeValue {
 /// Not all numbers in programming are numeric attributes. If a number is used to represent an
 /// enumeration or on/off values, it is a [categorical attribute](CategoricalAttribute).
 #[derive(
-    Debug,
-    Clone,
-    PartialEq,
-    PartialOrd,
-    derive_more::From,
-    derive_more::Into,
-    Serialize,
-    Deserialize,
+    Debug, Clone, PartialEq, PartialOrd, derive_more::From, derive_more::Into, Deserialize,
 )]
 pub struct NumericAttribute(f64);
 
+impl Serialize for NumericAttribute {
+    /// Non-finite numbers are serialized as null, so events always contain valid JSON.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_finite() {
+            serializer.serialize_f64(self.0)
+        } else {
+            serializer.serialize_none()
+        }
+    }
+}
+
 impl NumericAttribute {
     pub(crate) fn to_f64(&self) -> f64 {
         self.0
@@ -138,13 +215,27 @@ enum CategoricalAttributeImpl {
     #[from(forward)]
     String(Str),
     #[from]
+    #[serde(serialize_with = "serialize_finite")]
     Number(f64),
     #[from]
     Boolean(bool),
 }
 
+/// Serialize non-finite numbers as null, so events always contain valid JSON.
+fn serialize_finite<S: serde::Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    NumericAttribute(*value).serialize(serializer)
+}
+
 impl CategoricalAttribute {
-    pub(crate) fn to_str(&self) -> Cow<str> {
+    /// Returns the number if the attribute holds one.
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            CategoricalAttribute(CategoricalAttributeImpl::Number(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_str(&self) -> Cow<'_, str> {
         match self {
             CategoricalAttribute(CategoricalAttributeImpl::String(s)) => Cow::Borrowed(s),
             CategoricalAttribute(CategoricalAttributeImpl::Number(v)) => Cow::Owned(v.to_string()),
@@ -177,7 +268,7 @@ impl<'a> From<&'a AttributeValue> for Option<AttributeValueRef<'a>> {
             AttributeValue(AttributeValueImpl::Categorical(categorical)) => {
                 Some(AttributeValueRef::from(categorical))
             }
-            AttributeValue(AttributeValueImpl::Null) => None,
+            AttributeValue(AttributeValueImpl::Array(_) | AttributeValueImpl::Null) => None,
         }
     }
 }
@@ -219,6 +310,9 @@ mod pyo3_impl {
                 AttributeValue(AttributeValueImpl::Categorical(categorical)) => {
                     categorical.to_object(py)
                 }
+                AttributeValue(AttributeValueImpl::Array(values)) => {
+                    PyList::new_bound(py, values.iter().map(|it| it.to_object(py))).into()
+                }
                 AttributeValue(AttributeValueImpl::Null) => py.None(),
             }
         }
@@ -260,6 +354,9 @@ mod pyo3_impl {
             if value.is_none() {
                 return Ok(AttributeValue::null());
             }
+            if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+                return Ok(AttributeValue(AttributeValueImpl::Array(value.extract()?)));
+            }
             Err(PyTypeError::new_err(
                 "invalid type for subject attribute value",
             ))
@@ -297,7 +394,7 @@ mod pyo3_impl {
 
 #[cfg(feature = "magnus")]
 mod magnus_impl {
-    use magnus::{value::ReprValue, RString, Ruby, TryConvert};
+    use magnus::{value::ReprValue, RArray, RString, Ruby, TryConvert};
 
     use crate::{AttributeValue, CategoricalAttribute, NumericAttribute};
 
@@ -305,10 +402,12 @@ mod magnus_impl {
 
     impl TryConvert for AttributeValue {
         fn try_convert(val: magnus::Value) -> Result<Self, magnus::Error> {
-            (NumericAttribute::try_convert(val).map(|it| Self(AttributeValueImpl::Numeric(it))))
+            if let Some(array) = RArray::from_value(val) {
+                return Ok(Self(AttributeValueImpl::Array(array.to_vec()?)));
+            }
+            (NumericAttribute::try_convert(val).map(AttributeValue::numeric))
                 .or_else(|_| {
-                    CategoricalAttribute::try_convert(val)
-                        .map(|it| Self(AttributeValueImpl::Categorical(it)))
+                    CategoricalAttribute::try_convert(val).map(AttributeValue::categorical)
                 })
                 .or_else(|_|
                 // Return null attribute as a fallback
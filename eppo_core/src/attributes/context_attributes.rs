@@ -1,4 +1,7 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -29,6 +32,22 @@ pub struct ContextAttributes {
     pub categorical: Arc<HashMap<Str, CategoricalAttribute>>,
 }
 
+/// Kind of a number attribute when converting [`Attributes`] to [`ContextAttributes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeKind {
+    /// The number is a quantity and is scored with a numeric coefficient.
+    Numeric,
+    /// The number is an identifier (e.g., a zip code) and is scored by its string form with a
+    /// categorical coefficient.
+    Categorical,
+}
+
+/// Split attributes by their kind.
+///
+/// Numbers that were created as numeric (e.g., with [`AttributeValue::numeric()`] or from `f64`)
+/// become numeric attributes; strings, booleans, and numbers created with
+/// [`AttributeValue::categorical()`] become categorical attributes. Use
+/// [`ContextAttributes::from_attributes_with()`] to override the kind of number attributes.
 impl From<Attributes> for ContextAttributes {
     fn from(value: Attributes) -> Self {
         ContextAttributes::from_iter(value)
@@ -41,18 +60,109 @@ where
     V: Into<AttributeValue>,
 {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let (categorical, numeric) = iter.into_iter().fold(
+        ContextAttributes::split(iter, |_, _, kind| kind)
+    }
+}
+
+impl ContextAttributes {
+    /// Split attributes by their kind, calling `kind` to decide the kind of every attribute
+    /// holding a number. Strings and booleans are always categorical.
+    ///
+    /// Bandit models score categorical attributes by their string form, so a number converted to
+    /// a categorical attribute matches categorical coefficients for its string representation
+    /// (e.g., `94107.0` matches `"94107"`).
+    ///
+    /// # Examples
+    /// ```
+    /// # use eppo_core::{AttributeKind, Attributes, ContextAttributes};
+    /// let attributes = [
+    ///     ("age".into(), 30.0.into()),
+    ///     ("zip_code".into(), 94107.0.into()),
+    /// ].into_iter().collect::<Attributes>();
+    ///
+    /// let context = ContextAttributes::from_attributes_with(attributes, |key, _| {
+    ///     if key == "zip_code" {
+    ///         AttributeKind::Categorical
+    ///     } else {
+    ///         AttributeKind::Numeric
+    ///     }
+    /// });
+    /// assert!(context.numeric.contains_key("age"));
+    /// assert!(context.categorical.contains_key("zip_code"));
+    /// ```
+    pub fn from_attributes_with<K, V>(
+        attributes: impl IntoIterator<Item = (K, V)>,
+        mut kind: impl FnMut(&str, f64) -> AttributeKind,
+    ) -> ContextAttributes
+    where
+        K: Into<Str>,
+        V: Into<AttributeValue>,
+    {
+        ContextAttributes::split(attributes, |key, value, _| kind(key, value))
+    }
+
+    /// Split attributes by their kind. Same as [`ContextAttributes::from_attributes_with()`] but
+    /// number attributes with keys in `categorical` are converted to categorical attributes and
+    /// other numbers keep their kind.
+    pub fn from_attributes_with_categorical<K, V>(
+        attributes: impl IntoIterator<Item = (K, V)>,
+        categorical: &HashSet<Str>,
+    ) -> ContextAttributes
+    where
+        K: Into<Str>,
+        V: Into<AttributeValue>,
+    {
+        ContextAttributes::split(attributes, |key, _, kind| {
+            if categorical.contains(key) {
+                AttributeKind::Categorical
+            } else {
+                kind
+            }
+        })
+    }
+
+    /// `kind` is called with key, value, and the kind stored in the value of every number
+    /// attribute.
+    fn split<K, V>(
+        attributes: impl IntoIterator<Item = (K, V)>,
+        mut kind: impl FnMut(&str, f64, AttributeKind) -> AttributeKind,
+    ) -> ContextAttributes
+    where
+        K: Into<Str>,
+        V: Into<AttributeValue>,
+    {
+        let (categorical, numeric) = attributes.into_iter().fold(
             (HashMap::new(), HashMap::new()),
             |(mut categorical, mut numeric), (key, value)| {
-                match value.into() {
-                    AttributeValue(AttributeValueImpl::Categorical(value)) => {
-                        categorical.insert(key.into(), value);
-                    }
+                let key = key.into();
+                let (number, stored_kind) = match value.into() {
                     AttributeValue(AttributeValueImpl::Numeric(value)) => {
-                        numeric.insert(key.into(), value);
+                        (value.to_f64(), AttributeKind::Numeric)
+                    }
+                    AttributeValue(AttributeValueImpl::Categorical(value)) => {
+                        match value.as_f64() {
+                            Some(number) => (number, AttributeKind::Categorical),
+                            None => {
+                                categorical.insert(key, value);
+                                return (categorical, numeric);
+                            }
+                        }
                     }
                     AttributeValue(AttributeValueImpl::Null) => {
                         // Nulls are missing values and are ignored.
+                        return (categorical, numeric);
+                    }
+                    AttributeValue(AttributeValueImpl::Array(_)) => {
+                        // Bandit models have no coefficients for multi-valued attributes.
+                        return (categorical, numeric);
+                    }
+                };
+                match kind(&key, number, stored_kind) {
+                    AttributeKind::Numeric => {
+                        numeric.insert(key, NumericAttribute::from(number));
+                    }
+                    AttributeKind::Categorical => {
+                        categorical.insert(key, CategoricalAttribute::from(number));
                     }
                 }
                 (categorical, numeric)
@@ -63,9 +173,7 @@ where
             categorical: Arc::new(categorical),
         }
     }
-}
 
-impl ContextAttributes {
     /// Convert contextual attributes to generic `Attributes`.
     pub fn to_generic_attributes(&self) -> Attributes {
         let mut result = HashMap::with_capacity(self.numeric.len() + self.categorical.capacity());
@@ -81,7 +189,10 @@ impl ContextAttributes {
 
 #[cfg(feature = "pyo3")]
 mod pyo3_impl {
-    use std::{collections::HashMap, sync::Arc};
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    };
 
     use pyo3::prelude::*;
 
@@ -113,17 +224,28 @@ mod pyo3_impl {
         }
 
         /// Create an ContextAttributes instance from a dictionary of attributes.
-
+        ///
         /// Args:
         ///     attributes (Dict[str, Union[float, int, bool, str]]): A dictionary where keys are attribute names
         ///         and values are attribute values which can be of type float, int, bool, or str.
-
+        ///     categorical_attributes (Optional[Set[str]]): Keys of number attributes that should be
+        ///         treated as categorical (e.g., zip codes). Other numbers are numeric.
+        ///
         /// Returns:
         ///     ContextAttributes: An instance of the ContextAttributes class
         ///         with numeric and categorical attributes separated.
         #[staticmethod]
-        fn from_dict(attributes: Attributes) -> ContextAttributes {
-            attributes.into()
+        #[pyo3(signature = (attributes, categorical_attributes=None))]
+        fn from_dict(
+            attributes: Attributes,
+            categorical_attributes: Option<HashSet<Str>>,
+        ) -> ContextAttributes {
+            match categorical_attributes {
+                Some(categorical) => {
+                    ContextAttributes::from_attributes_with_categorical(attributes, &categorical)
+                }
+                None => attributes.into(),
+            }
         }
 
         /// Note that this copies internal attributes, so changes to returned value won't have
@@ -141,3 +263,72 @@ mod pyo3_impl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{AttributeValue, Attributes, Str};
+
+    use super::{AttributeKind, ContextAttributes};
+
+    fn attributes() -> Attributes {
+        [
+            ("age".into(), 30.0.into()),
+            ("zip_code".into(), 94107.0.into()),
+            ("plan_tier".into(), AttributeValue::categorical(2.0)),
+            ("country".into(), "US".into()),
+            ("is_premium".into(), true.into()),
+            ("referrer".into(), AttributeValue::null()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn keys<V>(map: &std::collections::HashMap<Str, V>) -> Vec<&str> {
+        let mut keys = map.keys().map(|key| key.as_str()).collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn default_mapping_keeps_stored_kind() {
+        let context = ContextAttributes::from(attributes());
+
+        assert_eq!(keys(&context.numeric), ["age", "zip_code"]);
+        assert_eq!(
+            keys(&context.categorical),
+            ["country", "is_premium", "plan_tier"]
+        );
+    }
+
+    #[test]
+    fn overridden_numeric_attribute_is_categorical() {
+        let categorical = HashSet::from([Str::from("zip_code")]);
+
+        let context =
+            ContextAttributes::from_attributes_with_categorical(attributes(), &categorical);
+
+        assert_eq!(keys(&context.numeric), ["age"]);
+        assert_eq!(
+            keys(&context.categorical),
+            ["country", "is_premium", "plan_tier", "zip_code"]
+        );
+        assert_eq!(context.categorical["zip_code"].to_str(), "94107");
+    }
+
+    #[test]
+    fn kind_callback_decides_all_numbers() {
+        let context = ContextAttributes::from_attributes_with(attributes(), |key, _| {
+            if key == "age" {
+                AttributeKind::Categorical
+            } else {
+                AttributeKind::Numeric
+            }
+        });
+
+        assert_eq!(keys(&context.numeric), ["plan_tier", "zip_code"]);
+        assert_eq!(keys(&context.categorical), ["age", "country", "is_premium"]);
+        assert_eq!(context.categorical["age"].to_str(), "30");
+    }
+}
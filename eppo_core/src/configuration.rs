@@ -1,13 +1,86 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 
 use crate::{
     bandits::{BanditConfiguration, BanditResponse},
-    ufc::UniversalFlagConfig,
-    Str,
+    configuration_persistence::PersistedConfiguration,
+    error::EvaluationFailure,
+    memory_usage::MemoryUsage,
+    ufc::{ParseDiagnostics, UniversalFlagConfig},
+    EvaluationError, Result, SdkMetadata, Str,
 };
 
+/// Timestamps and environment of a [`Configuration`]. Useful for monitoring configuration
+/// staleness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurationTimestamps {
+    /// Timestamp when configuration was fetched by the SDK.
+    pub fetched_at: DateTime<Utc>,
+    /// Timestamp when flags configuration was published (created) on the server.
+    pub published_at: DateTime<Utc>,
+    /// Name of the environment this configuration belongs to.
+    pub environment: Str,
+}
+
+/// Flags that differ between two configurations. See [`Configuration::diff()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigurationDiff {
+    /// Flags present only in the new configuration, sorted by key.
+    pub added: Vec<Str>,
+    /// Flags present only in the old configuration, sorted by key.
+    pub removed: Vec<Str>,
+    /// Flags present in both configurations whose configuration (e.g., allocations or
+    /// variations) differs, sorted by key.
+    pub changed: Vec<Str>,
+}
+
+impl ConfigurationDiff {
+    /// Returns `true` if no flags have been added, removed, or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl std::fmt::Display for ConfigurationDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "added: {:?}, removed: {:?}, changed: {:?}",
+            self.added, self.removed, self.changed
+        )
+    }
+}
+
+/// Approximate memory occupied by a [`Configuration`]. See [`Configuration::memory_usage()`].
+///
+/// Estimates count string lengths and collection capacities but ignore allocator overhead, so
+/// they are only useful to track the order of magnitude and growth of memory usage (e.g., to size
+/// containers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigurationMemoryStats {
+    /// Compiled flags, in bytes.
+    pub flags_bytes: usize,
+    /// Bandit models, in bytes.
+    pub bandits_bytes: usize,
+    /// Original flags configuration JSON kept alongside compiled flags, in bytes.
+    pub raw_json_bytes: usize,
+    /// Number of flags, including disabled flags and flags that failed to parse.
+    pub flag_count: usize,
+    /// Number of bandit models.
+    pub bandit_count: usize,
+}
+
+impl ConfigurationMemoryStats {
+    /// Total memory occupied by configuration, in bytes.
+    pub fn total_bytes(&self) -> usize {
+        self.flags_bytes + self.bandits_bytes + self.raw_json_bytes
+    }
+}
+
 /// Remote configuration for the eppo client. It's a central piece that defines client behavior.
 #[derive(Debug)]
 pub struct Configuration {
@@ -15,8 +88,9 @@ pub struct Configuration {
     pub fetched_at: DateTime<Utc>,
     /// Flags configuration.
     pub flags: UniversalFlagConfig,
-    /// Bandits configuration.
-    pub bandits: Option<BanditResponse>,
+    /// Bandits configuration. Shared between configurations when bandit models are carried
+    /// forward (see [`Configuration::from_server_response_with_shared_bandits()`]).
+    pub bandits: Option<Arc<BanditResponse>>,
 }
 
 impl Configuration {
@@ -24,6 +98,16 @@ impl Configuration {
     pub fn from_server_response(
         config: UniversalFlagConfig,
         bandits: Option<BanditResponse>,
+    ) -> Configuration {
+        Configuration::from_server_response_with_shared_bandits(config, bandits.map(Arc::new))
+    }
+
+    /// Create a new configuration from flags response and bandit models that may be shared with
+    /// another configuration (e.g., bandit models fetched by an earlier poll that are carried
+    /// forward without re-fetching them).
+    pub fn from_server_response_with_shared_bandits(
+        config: UniversalFlagConfig,
+        bandits: Option<Arc<BanditResponse>>,
     ) -> Configuration {
         let now = Utc::now();
 
@@ -34,6 +118,50 @@ impl Configuration {
         }
     }
 
+    /// Serialize configuration, so it can be shared with other processes (e.g., via a custom
+    /// [`ConfigurationStoreBackend`](crate::configuration_store::ConfigurationStoreBackend)).
+    ///
+    /// Flags configuration is stored in its original (UFC JSON) form. Use
+    /// [`Configuration::from_bytes()`] to deserialize.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        PersistedConfiguration::new(self, None, None).to_bytes()
+    }
+
+    /// Deserialize configuration serialized with [`Configuration::to_bytes()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` cannot be parsed.
+    pub fn from_bytes(sdk_metadata: SdkMetadata, bytes: &[u8]) -> Result<Configuration> {
+        PersistedConfiguration::from_bytes(bytes)?.to_configuration(sdk_metadata)
+    }
+
+    /// Flags configuration in UFC JSON format, exactly as it was received from the server.
+    ///
+    /// The result can be loaded back with
+    /// [`UniversalFlagConfig::from_json()`](crate::ufc::UniversalFlagConfig::from_json), so this
+    /// is useful to snapshot configuration that the process was serving (e.g., for incident
+    /// forensics).
+    pub fn to_ufc_json(&self) -> &[u8] {
+        self.flags.to_json()
+    }
+
+    /// Bandit models in the same JSON format as the server response, or `None` if configuration
+    /// has no bandits.
+    ///
+    /// Unlike [`Configuration::to_ufc_json()`], bandit models are re-serialized, so the output is
+    /// not byte-for-byte identical to the server response. Object keys are sorted, so the same
+    /// models always produce the same output.
+    pub fn to_bandits_json(&self) -> Result<Option<Vec<u8>>> {
+        let Some(bandits) = &self.bandits else {
+            return Ok(None);
+        };
+        // `serde_json::Value` keeps object keys sorted, whereas `HashMap` iteration order is
+        // random.
+        let value = serde_json::to_value(bandits)?;
+        Ok(Some(serde_json::to_vec(&value)?))
+    }
+
     /// Return a bandit variant for the specified flag key and string flag variation.
     pub(crate) fn get_bandit_key<'a>(&'a self, flag_key: &str, variation: &str) -> Option<&'a Str> {
         self.flags
@@ -56,4 +184,422 @@ impl Configuration {
     pub fn flag_keys(&self) -> HashSet<Str> {
         self.flags.compiled.flags.keys().cloned().collect()
     }
+
+    /// Problems found while parsing flags configuration (e.g., flags that failed to parse).
+    pub fn parse_diagnostics(&self) -> &ParseDiagnostics {
+        self.flags.diagnostics()
+    }
+
+    /// Get a set of flags that are present in configuration but failed to parse (e.g., because
+    /// server uses a newer format that this SDK version does not understand).
+    pub fn unparsed_flag_keys(&self) -> HashSet<Str> {
+        self.flags
+            .compiled
+            .flags
+            .iter()
+            .filter(|(_, flag)| {
+                matches!(
+                    flag,
+                    Err(EvaluationFailure::Error(
+                        EvaluationError::UnexpectedConfigurationParseError
+                    ))
+                )
+            })
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Compare flags of this configuration with a newer `other` configuration.
+    ///
+    /// A flag is considered changed if its compiled configuration differs in a way that may affect
+    /// evaluation (e.g., allocations, served variations, or whether it's enabled). Bandit models are
+    /// not compared.
+    pub fn diff(&self, other: &Configuration) -> ConfigurationDiff {
+        let old = &self.flags.compiled.flags;
+        let new = &other.flags.compiled.flags;
+
+        let mut diff = ConfigurationDiff::default();
+        for (key, value) in new {
+            match old.get(key) {
+                None => diff.added.push(key.clone()),
+                Some(old_value) if old_value != value => diff.changed.push(key.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .keys()
+            .filter(|key| !new.contains_key(*key))
+            .cloned()
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Returns approximate memory occupied by this configuration.
+    ///
+    /// Estimate for flags is computed once when configuration is compiled. Estimate for bandit
+    /// models is computed on every call.
+    pub fn memory_usage(&self) -> ConfigurationMemoryStats {
+        ConfigurationMemoryStats {
+            flags_bytes: self.flags.compiled.flags_bytes,
+            bandits_bytes: self.bandits.as_ref().map_or(0, MemoryUsage::heap_bytes),
+            raw_json_bytes: self.flags.wire_json.capacity(),
+            flag_count: self.flags.compiled.flags.len(),
+            bandit_count: self.bandits.as_ref().map_or(0, |it| it.bandits.len()),
+        }
+    }
+
+    /// Get a set of all available bandits.
+    pub fn bandit_keys(&self) -> HashSet<Str> {
+        self.bandits
+            .iter()
+            .flat_map(|it| it.bandits.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Name of the environment this configuration belongs to.
+    pub fn environment_name(&self) -> &str {
+        &self.flags.compiled.environment.name
+    }
+
+    /// Get timestamps of the configuration: when it was fetched by the SDK and when it was
+    /// published on the server.
+    pub fn timestamps(&self) -> ConfigurationTimestamps {
+        ConfigurationTimestamps {
+            fetched_at: self.fetched_at,
+            published_at: self.flags.compiled.created_at,
+            environment: self.flags.compiled.environment.name.clone(),
+        }
+    }
+
+    /// Returns `true` if configuration was fetched more than `max_age` ago.
+    ///
+    /// Stale configuration is still valid for evaluation. This can be used to detect that the SDK
+    /// failed to refresh configuration (e.g., during a network outage).
+    pub fn is_stale(&self, max_age: std::time::Duration) -> bool {
+        self.is_stale_at(max_age, Utc::now())
+    }
+
+    /// Same as [`Configuration::is_stale()`] but checks the age at `now`.
+    pub fn is_stale_at(&self, max_age: std::time::Duration, now: DateTime<Utc>) -> bool {
+        is_stale(self.fetched_at, max_age, now)
+    }
+}
+
+/// Returns `true` if configuration fetched at `fetched_at` is older than `max_age` at `now`.
+pub(crate) fn is_stale(
+    fetched_at: DateTime<Utc>,
+    max_age: std::time::Duration,
+    now: DateTime<Utc>,
+) -> bool {
+    // Clock may go backwards, in which case configuration is not stale.
+    now.signed_duration_since(fetched_at)
+        .to_std()
+        .is_ok_and(|age| age > max_age)
+}
+
+#[cfg(test)]
+impl Configuration {
+    /// Create configuration without bandits from flags configuration `json` (e.g., an inline
+    /// literal or a fixture).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `json` fails to parse.
+    pub(crate) fn from_ufc_json_for_tests(json: impl Into<Vec<u8>>) -> Configuration {
+        Configuration::from_json_for_tests(json, None)
+    }
+
+    /// Create configuration from flags configuration `flags_json` and bandit models
+    /// `bandits_json`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either fails to parse.
+    pub(crate) fn from_json_for_tests(
+        flags_json: impl Into<Vec<u8>>,
+        bandits_json: Option<&[u8]>,
+    ) -> Configuration {
+        let flags =
+            UniversalFlagConfig::from_json(crate::test_server::SDK_METADATA, flags_json.into())
+                .unwrap();
+        let bandits = bandits_json.map(|json| serde_json::from_slice(json).unwrap());
+        Configuration::from_server_response(flags, bandits)
+    }
+}
+
+#[cfg(feature = "pyo3")]
+mod pyo3_impl {
+    use pyo3::prelude::*;
+
+    use crate::pyo3::TryToPyObject;
+
+    use super::ConfigurationMemoryStats;
+
+    impl TryToPyObject for ConfigurationMemoryStats {
+        fn try_to_pyobject(&self, py: Python) -> PyResult<PyObject> {
+            serde_pyobject::to_pyobject(py, self)
+                .map(|it| it.unbind())
+                .map_err(|err| err.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::{
+        bandits::BanditResponse,
+        test_server::{BANDITS_JSON, UFC_JSON},
+        Configuration, Str,
+    };
+
+    use super::ConfigurationDiff;
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_flags() {
+        let before = Configuration::from_ufc_json_for_tests(include_bytes!(
+            "../tests/fixtures/flags-diff-before.json"
+        ));
+        let after = Configuration::from_ufc_json_for_tests(include_bytes!(
+            "../tests/fixtures/flags-diff-after.json"
+        ));
+
+        assert_eq!(
+            before.diff(&after),
+            ConfigurationDiff {
+                added: vec!["new-checkout".into()],
+                removed: vec!["legacy-flag".into()],
+                changed: vec!["button-color".into()],
+            }
+        );
+        assert_eq!(
+            after.diff(&before),
+            ConfigurationDiff {
+                added: vec!["legacy-flag".into()],
+                removed: vec!["new-checkout".into()],
+                changed: vec!["button-color".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn exports_flags_as_received() {
+        let json = include_bytes!("../tests/fixtures/flags-diff-before.json");
+        let configuration = Configuration::from_ufc_json_for_tests(json);
+
+        assert_eq!(configuration.to_ufc_json(), json);
+        assert!(configuration.to_bandits_json().unwrap().is_none());
+    }
+
+    #[test]
+    fn exports_bandits_canonically() {
+        let bandits: BanditResponse = serde_json::from_str(BANDITS_JSON).unwrap();
+        let mut configuration = Configuration::from_ufc_json_for_tests(UFC_JSON.as_bytes());
+        configuration.bandits = Some(Arc::new(bandits));
+
+        let exported = configuration.to_bandits_json().unwrap().unwrap();
+        let reparsed: BanditResponse = serde_json::from_slice(&exported).unwrap();
+        configuration.bandits = Some(Arc::new(reparsed));
+
+        assert_eq!(configuration.to_bandits_json().unwrap().unwrap(), exported);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&exported).unwrap(),
+            serde_json::from_str::<serde_json::Value>(BANDITS_JSON).unwrap()
+        );
+    }
+
+    /// Flags configuration with `count` string flags.
+    fn generated_flags_json(count: usize) -> Vec<u8> {
+        let flags = (0..count)
+            .map(|i| {
+                (
+                    format!("flag-{i}"),
+                    serde_json::json!({
+                        "key": format!("flag-{i}"),
+                        "enabled": true,
+                        "variationType": "STRING",
+                        "totalShards": 10000,
+                        "variations": {
+                            "a": {"key": "a", "value": "variant-a"},
+                            "b": {"key": "b", "value": "variant-b"}
+                        },
+                        "allocations": [{
+                            "key": format!("allocation-{i}"),
+                            "rules": [{"conditions": [{
+                                "attribute": "country",
+                                "operator": "ONE_OF",
+                                "value": ["US", "Canada", "Mexico"]
+                            }]}],
+                            "splits": [
+                                {
+                                    "variationKey": "a",
+                                    "shards": [{"salt": format!("salt-{i}"), "ranges": [{"start": 0, "end": 5000}]}]
+                                },
+                                {
+                                    "variationKey": "b",
+                                    "shards": [{"salt": format!("salt-{i}"), "ranges": [{"start": 5000, "end": 10000}]}]
+                                }
+                            ],
+                            "doLog": true
+                        }]
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        serde_json::to_vec(&serde_json::json!({
+            "createdAt": "2024-07-18T00:00:00Z",
+            "format": "SERVER",
+            "environment": {"name": "test"},
+            "flags": flags
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn memory_usage_tracks_configuration_size() {
+        let small = Configuration::from_ufc_json_for_tests(generated_flags_json(10).as_slice())
+            .memory_usage();
+        let large = Configuration::from_ufc_json_for_tests(generated_flags_json(1000).as_slice())
+            .memory_usage();
+
+        assert_eq!(small.flag_count, 10);
+        assert_eq!(large.flag_count, 1000);
+        assert_eq!(small.raw_json_bytes, generated_flags_json(10).len());
+        assert_eq!(large.raw_json_bytes, generated_flags_json(1000).len());
+        // Each flag has an allocation, two splits, and a rule, so it takes at least a few hundred
+        // bytes.
+        assert!(small.flags_bytes > 10 * 300, "{small:?}");
+        // Memory usage grows linearly with the number of flags (up to hash map capacity).
+        assert!(
+            large.flags_bytes > 50 * small.flags_bytes,
+            "{small:?} {large:?}"
+        );
+        assert!(
+            large.flags_bytes < 200 * small.flags_bytes,
+            "{small:?} {large:?}"
+        );
+        assert_eq!(large.bandits_bytes, 0);
+        assert_eq!(large.bandit_count, 0);
+        assert_eq!(
+            large.total_bytes(),
+            large.flags_bytes + large.raw_json_bytes
+        );
+    }
+
+    #[test]
+    fn memory_usage_counts_bandits() {
+        let mut configuration = Configuration::from_ufc_json_for_tests(UFC_JSON.as_bytes());
+        let mut bandits: BanditResponse = serde_json::from_str(BANDITS_JSON).unwrap();
+        bandits.bandits.clear();
+        configuration.bandits = Some(Arc::new(bandits.clone()));
+        let empty = configuration.memory_usage();
+
+        bandits.bandits.insert(
+            "bandit".into(),
+            serde_json::from_value(serde_json::json!({
+                "banditKey": "bandit",
+                "modelName": "falcon",
+                "modelVersion": "v1",
+                "updatedAt": "2024-07-18T00:00:00Z",
+                "modelData": {
+                    "gamma": 1.0,
+                    "defaultActionScore": 0.0,
+                    "actionProbabilityFloor": 0.0,
+                    "coefficients": {
+                        "action": {
+                            "actionKey": "action",
+                            "intercept": 1.0,
+                            "subjectNumericCoefficients": [],
+                            "subjectCategoricalCoefficients": [],
+                            "actionNumericCoefficients": [
+                                {"attributeKey": "price", "coefficient": 1.0, "missingValueCoefficient": 0.0}
+                            ],
+                            "actionCategoricalCoefficients": []
+                        }
+                    }
+                }
+            }))
+            .unwrap(),
+        );
+        configuration.bandits = Some(Arc::new(bandits));
+        let with_bandit = configuration.memory_usage();
+
+        assert_eq!(empty.bandit_count, 0);
+        assert_eq!(with_bandit.bandit_count, 1);
+        assert!(with_bandit.bandits_bytes > empty.bandits_bytes);
+    }
+
+    #[test]
+    fn configuration_is_stale_after_max_age() {
+        let mut configuration = Configuration::from_ufc_json_for_tests(UFC_JSON.as_bytes());
+        let max_age = std::time::Duration::from_secs(60);
+        assert!(!configuration.is_stale(max_age));
+
+        configuration.fetched_at = Utc::now() - chrono::Duration::seconds(120);
+        assert!(configuration.is_stale(max_age));
+        assert!(!configuration.is_stale_at(max_age, configuration.fetched_at));
+
+        // Clock going backwards.
+        configuration.fetched_at = Utc::now() + chrono::Duration::seconds(120);
+        assert!(!configuration.is_stale(max_age));
+    }
+
+    #[test]
+    fn diff_of_same_flags_is_empty() {
+        let before = Configuration::from_ufc_json_for_tests(include_bytes!(
+            "../tests/fixtures/flags-diff-before.json"
+        ));
+        let again = Configuration::from_ufc_json_for_tests(include_bytes!(
+            "../tests/fixtures/flags-diff-before.json"
+        ));
+
+        assert!(before.diff(&again).is_empty());
+    }
+
+    #[test]
+    fn diff_compares_conditions() {
+        fn configuration(pattern: &str) -> Configuration {
+            Configuration::from_ufc_json_for_tests(format!(
+                r#"{{
+                  "createdAt": "2024-07-18T00:00:00Z",
+                  "environment": {{"name": "test"}},
+                  "flags": {{
+                    "regex-flag": {{
+                      "key": "regex-flag",
+                      "enabled": true,
+                      "variationType": "BOOLEAN",
+                      "variations": {{"on": {{"key": "on", "value": true}}}},
+                      "allocations": [{{
+                        "key": "allocation",
+                        "rules": [{{"conditions": [
+                          {{"attribute": "email", "operator": "MATCHES", "value": "{pattern}"}}
+                        ]}}],
+                        "splits": [{{"variationKey": "on", "shards": []}}],
+                        "doLog": true
+                      }}],
+                      "totalShards": 10000
+                    }}
+                  }}
+                }}"#
+            ))
+        }
+
+        assert!(configuration(".*@example.com")
+            .diff(&configuration(".*@example.com"))
+            .is_empty());
+        assert_eq!(
+            configuration(".*@example.com")
+                .diff(&configuration(".*@example.org"))
+                .changed,
+            vec![Str::from("regex-flag")]
+        );
+    }
 }
@@ -1,21 +1,178 @@
 //! An HTTP client that fetches configuration from the server.
-use reqwest::{StatusCode, Url};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use reqwest::{
+    header::{
+        HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+        LAST_MODIFIED, RETRY_AFTER,
+    },
+    StatusCode, Url,
+};
 
 use crate::{
-    bandits::BanditResponse, ufc::UniversalFlagConfig, Configuration, Error, Result, SdkMetadata,
+    bandits::BanditResponse,
+    configuration_persistence::{self, ConfigurationPersistence, PersistedConfiguration},
+    ufc::UniversalFlagConfig,
+    Configuration, Error, Result, SdkMetadata,
 };
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ConfigurationFetcherConfig {
     pub base_url: String,
+    /// Base URLs to try in order. If a server cannot be reached or responds with a 5xx error, the
+    /// fetcher fails over to the next one and keeps using the last working URL on subsequent
+    /// fetches.
+    ///
+    /// If empty, `base_url` is used as the only entry.
+    pub base_urls: Vec<String>,
     pub api_key: String,
     pub sdk_metadata: SdkMetadata,
+    /// Additional HTTP headers to send with every request (e.g., to pass through a gateway).
+    pub headers: Vec<(String, String)>,
+    /// Timeout for connecting to the server and for the whole request.
+    ///
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`].
+    pub request_timeout: Duration,
+    /// Fetch obfuscated (client) flags configuration instead of the server one.
+    ///
+    /// Obfuscated configuration hides flag keys, attributes, and values, so it is suitable for
+    /// distribution to untrusted environments.
+    pub obfuscated: bool,
+    /// Fetch bandit models even if flags configuration does not reference any bandits.
+    ///
+    /// By default, bandit models are only requested when some flag is associated with a bandit,
+    /// saving a round trip for accounts without bandits.
+    pub always_fetch_bandits: bool,
+    /// Additional query parameters to send with every request (e.g., to identify a wrapper SDK as
+    /// `wrapperName`/`wrapperVersion`).
+    ///
+    /// They are sent after the standard parameters (`apiKey`, `sdkName`, `sdkVersion`, and
+    /// `coreVersion`), which cannot be overridden: such parameters are dropped with a warning when
+    /// the fetcher is created (see [`validate_query_params()`]). `apiKey` is not sent with
+    /// [`AuthMode::BearerHeader`] and cannot be added back.
+    pub query_params: Vec<(String, String)>,
+    /// Maximum size (in bytes) of a single response body (after decompression). Larger responses
+    /// are aborted with [`Error::ResponseTooLarge`] without buffering them whole.
+    ///
+    /// Defaults to [`DEFAULT_MAX_RESPONSE_BYTES`].
+    pub max_response_bytes: usize,
+    /// How to send `api_key` to the server.
+    ///
+    /// Defaults to [`AuthMode::QueryParam`].
+    pub auth_mode: AuthMode,
+}
+
+/// How the fetcher authenticates with the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthMode {
+    /// Send API key as `apiKey` query parameter.
+    #[default]
+    QueryParam,
+    /// Send API key in `Authorization: Bearer <api_key>` header, so it does not appear in request
+    /// URLs (e.g., in proxy logs).
+    BearerHeader,
+}
+
+pub const DEFAULT_BASE_URL: &str = "https://fscdn.eppo.cloud/api";
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+
+const UFC_ENDPOINT: &str = "/flag-config/v1/config";
+const OBFUSCATED_UFC_ENDPOINT: &str = "/flag-config/v1/obfuscated-config";
+const BANDIT_ENDPOINT: &str = "/flag-config/v1/bandits";
+
+/// Query parameters set by the fetcher itself.
+const RESERVED_QUERY_PARAMS: [&str; 4] = ["apiKey", "sdkName", "sdkVersion", "coreVersion"];
+
+/// Check that `api_key` is not blank.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidConfiguration`] if API key is empty or consists of whitespace only.
+pub fn validate_api_key(api_key: &str) -> Result<()> {
+    if api_key.trim().is_empty() {
+        return Err(Error::InvalidConfiguration(
+            "api_key cannot be blank".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check that `base_url` can be used to build request URLs.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidConfiguration`] if base URL is not a valid URL.
+pub fn validate_base_url(base_url: &str) -> Result<()> {
+    Url::parse(&format!("{base_url}{UFC_ENDPOINT}"))
+        .map_err(|err| Error::InvalidConfiguration(format!("invalid base_url: {err}")))?;
+    Ok(())
+}
+
+/// Check that `query_params` don't override query parameters set by the SDK (`apiKey`, `sdkName`,
+/// `sdkVersion`, and `coreVersion`).
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidConfiguration`] if any of the parameters is reserved.
+pub fn validate_query_params(query_params: &[(String, String)]) -> Result<()> {
+    if let Some((name, _)) = query_params
+        .iter()
+        .find(|(name, _)| is_reserved_query_param(name))
+    {
+        return Err(Error::InvalidConfiguration(format!(
+            "query parameter {name:?} is set by the SDK and cannot be overridden"
+        )));
+    }
+    Ok(())
 }
 
-pub const DEFAULT_BASE_URL: &'static str = "https://fscdn.eppo.cloud/api";
+fn is_reserved_query_param(name: &str) -> bool {
+    RESERVED_QUERY_PARAMS.contains(&name)
+}
+
+/// Result of [`ConfigurationFetcher::fetch_configuration()`].
+#[derive(Debug)]
+pub enum FetchResult {
+    /// Server returned a new configuration.
+    Modified(Box<Configuration>),
+    /// Configuration has not changed since the last successful fetch, so there is nothing to
+    /// update.
+    NotModified,
+}
+
+/// Paths to configuration files on the local filesystem (e.g., for bootstrapping or air-gapped
+/// deployments).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurationFile {
+    /// Path to a UFC flags configuration JSON file (same format as the server response).
+    pub flags: PathBuf,
+    /// Path to an optional bandit models JSON file.
+    pub bandits: Option<PathBuf>,
+}
+
+impl ConfigurationFile {
+    /// Read and parse configuration files.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Io`] if either of files cannot be read.
+    /// - [`Error::ConfigurationParseError`] if either of files cannot be parsed.
+    pub fn read(&self, sdk_metadata: SdkMetadata) -> Result<Configuration> {
+        log::debug!(target: "eppo", "reading configuration from {}", self.flags.display());
+        let flags = UniversalFlagConfig::from_json(sdk_metadata, std::fs::read(&self.flags)?)?;
+        let bandits = self
+            .bandits
+            .as_ref()
+            .map(|path| -> Result<BanditResponse> {
+                Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+            })
+            .transpose()?;
 
-const UFC_ENDPOINT: &'static str = "/flag-config/v1/config";
-const BANDIT_ENDPOINT: &'static str = "/flag-config/v1/bandits";
+        Ok(Configuration::from_server_response(flags, bandits))
+    }
+}
 
 /// A client that fetches Eppo configuration from the server.
 pub struct ConfigurationFetcher {
@@ -25,104 +182,1111 @@ pub struct ConfigurationFetcher {
     /// If we receive a 401 Unauthorized error during a request, it means the API key is not
     /// valid. We cache this error so we don't issue additional requests to the server.
     unauthorized: bool,
+    /// Index (in `base_urls()`) of the last base URL that worked. It is tried first on the next
+    /// fetch.
+    base_url_index: usize,
+    /// Cache validators of the last successfully fetched flags configuration. They are sent back
+    /// to the server, so it can respond with 304 Not Modified if configuration hasn't changed.
+    ufc_validators: CacheValidators,
+    /// Size (in bytes) of response bodies received by the last successful fetch.
+    last_payload_bytes: usize,
+    /// Bandit models of the last successfully fetched configuration. They are carried forward to
+    /// new configurations while bandit models are not due for a refresh.
+    bandits: Option<Arc<BanditResponse>>,
+    /// Whether the next fetch should re-fetch bandit models. See
+    /// [`ConfigurationFetcher::set_refresh_bandits()`].
+    refresh_bandits: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+struct CacheValidators {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+}
+
+impl CacheValidators {
+    fn from_headers(headers: &HeaderMap) -> CacheValidators {
+        CacheValidators {
+            etag: headers.get(ETAG).cloned(),
+            last_modified: headers.get(LAST_MODIFIED).cloned(),
+        }
+    }
 }
 
 impl ConfigurationFetcher {
     pub fn new(config: ConfigurationFetcherConfig) -> ConfigurationFetcher {
-        let client = reqwest::Client::new();
+        let client = reqwest::Client::builder()
+            .connect_timeout(config.request_timeout)
+            .build()
+            // `reqwest::Client::new()` panics in the same situation.
+            .expect("should be able to build HTTP client");
+
+        ConfigurationFetcher::with_client(client, config)
+    }
+
+    /// Create a fetcher that sends requests using the provided `client`.
+    ///
+    /// This allows sharing an existing client (and its connection pool, TLS identity, proxies,
+    /// default headers, etc.) with the rest of the application.
+    ///
+    /// Everything the fetcher needs (query parameters, `config.headers`, and
+    /// `config.request_timeout`) is applied to each request, so it works the same as with a client
+    /// created by [`ConfigurationFetcher::new()`]. Connection timeout, however, can only be
+    /// configured on the client itself.
+    pub fn with_client(
+        client: reqwest::Client,
+        mut config: ConfigurationFetcherConfig,
+    ) -> ConfigurationFetcher {
+        config.query_params.retain(|(name, _)| {
+            let reserved = is_reserved_query_param(name);
+            if reserved {
+                log::warn!(target: "eppo", "ignoring query parameter {name:?} as it is set by the SDK");
+            }
+            !reserved
+        });
 
         ConfigurationFetcher {
             client,
             config,
             unauthorized: false,
+            base_url_index: 0,
+            ufc_validators: CacheValidators::default(),
+            last_payload_bytes: 0,
+            bandits: None,
+            refresh_bandits: true,
         }
     }
 
-    pub async fn fetch_configuration(&mut self) -> Result<Configuration> {
+    /// Set whether subsequent fetches should re-fetch bandit models.
+    ///
+    /// When `false`, bandit models from the last successful fetch are reused for new flags
+    /// configuration, saving a request for the (large) bandit models payload. Bandit models are
+    /// still fetched if there are no previous ones. Defaults to `true`.
+    pub fn set_refresh_bandits(&mut self, refresh: bool) {
+        self.refresh_bandits = refresh;
+    }
+
+    /// Fetch configuration from the server.
+    ///
+    /// Returns [`FetchResult::NotModified`] if the server reports that flags configuration hasn't
+    /// changed since the last successful fetch.
+    pub async fn fetch_configuration(&mut self) -> Result<FetchResult> {
         if self.unauthorized {
             return Err(Error::Unauthorized);
         }
 
-        let ufc = self.fetch_ufc_configuration().await?;
+        let base_urls = self.base_urls().to_vec();
+        let mut last_error = None;
+        // Start with the last working URL and wrap around.
+        let start = self.base_url_index;
+        for index in (0..base_urls.len()).map(|i| (start + i) % base_urls.len()) {
+            let base_url = &base_urls[index];
+            match self.fetch_configuration_from(base_url).await {
+                Err(err) if should_fail_over(&err) => {
+                    log::warn!(target: "eppo", "failed to fetch configuration from {base_url}: {err}");
+                    last_error = Some(err);
+                }
+                result => {
+                    self.base_url_index = index;
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.expect("base_urls should not be empty"))
+    }
+
+    /// Size (in bytes) of decompressed response bodies received by the last successful
+    /// [`ConfigurationFetcher::fetch_configuration()`] call. This is zero if configuration has not
+    /// been modified.
+    pub fn last_payload_bytes(&self) -> usize {
+        self.last_payload_bytes
+    }
+
+    /// Save `configuration` to `persistence` along with cache validators of the last fetch, so
+    /// [`ConfigurationFetcher::restore_persisted_configuration()`] can restore both after restart.
+    ///
+    /// Errors are logged and otherwise ignored as persistence is an optimization.
+    pub fn persist_configuration(
+        &self,
+        configuration: &Configuration,
+        persistence: &dyn ConfigurationPersistence,
+    ) {
+        let to_string = |value: &Option<HeaderValue>| {
+            value
+                .as_ref()
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        let persisted = PersistedConfiguration::new(
+            configuration,
+            to_string(&self.ufc_validators.etag),
+            to_string(&self.ufc_validators.last_modified),
+        );
+        configuration_persistence::save(persistence, &persisted);
+    }
+
+    /// Load configuration saved by [`ConfigurationFetcher::persist_configuration()`] and restore
+    /// cache validators, so the next fetch gets 304 Not Modified if configuration hasn't changed
+    /// since.
+    ///
+    /// Returns `None` if there's no saved configuration or it cannot be parsed (a warning is logged
+    /// in that case).
+    pub fn restore_persisted_configuration(
+        &mut self,
+        persistence: &dyn ConfigurationPersistence,
+    ) -> Option<Configuration> {
+        let (persisted, configuration) =
+            configuration_persistence::load(persistence, self.config.sdk_metadata)?;
+        let to_header = |value: Option<String>| value.and_then(|it| HeaderValue::try_from(it).ok());
+        self.ufc_validators = CacheValidators {
+            etag: to_header(persisted.etag),
+            last_modified: to_header(persisted.last_modified),
+        };
+        self.bandits = configuration.bandits.clone();
+        Some(configuration)
+    }
+
+    /// Metadata of the SDK the fetcher was created for.
+    pub fn sdk_metadata(&self) -> SdkMetadata {
+        self.config.sdk_metadata
+    }
+
+    /// Read configuration from local files instead of the server.
+    ///
+    /// See [`ConfigurationFile::read()`].
+    pub fn read_configuration_file(&self, file: &ConfigurationFile) -> Result<Configuration> {
+        file.read(self.config.sdk_metadata)
+    }
+
+    fn base_urls(&self) -> &[String] {
+        if self.config.base_urls.is_empty() {
+            std::slice::from_ref(&self.config.base_url)
+        } else {
+            &self.config.base_urls
+        }
+    }
+
+    async fn fetch_configuration_from(&mut self, base_url: &str) -> Result<FetchResult> {
+        let Some((ufc, validators)) = self.fetch_ufc_configuration(base_url).await? else {
+            self.last_payload_bytes = 0;
+            return Ok(FetchResult::NotModified);
+        };
+        let mut payload_bytes = ufc.to_json().len();
 
-        let bandits = if ufc.compiled.flag_to_bandit_associations.is_empty() {
+        let bandits = if ufc.compiled.flag_to_bandit_associations.is_empty()
+            && !self.config.always_fetch_bandits
+        {
             // We don't need bandits configuration if there are no bandits.
             None
+        } else if let Some(bandits) = self
+            .bandits
+            .as_ref()
+            .filter(|bandits| !self.refresh_bandits && bandits_match(&ufc, bandits))
+        {
+            log::debug!(target: "eppo", "reusing previously fetched UFC bandits configuration");
+            Some(bandits.clone())
         } else {
-            Some(self.fetch_bandits_configuration().await?)
+            let (bandits, bandits_bytes) = self.fetch_bandits_configuration(base_url).await?;
+            payload_bytes += bandits_bytes;
+            Some(Arc::new(bandits))
         };
 
-        Ok(Configuration::from_server_response(ufc, bandits))
+        // Only remember validators once the whole configuration is fetched, so that a failed
+        // bandits request is retried on the next poll instead of getting 304 for flags.
+        self.ufc_validators = validators;
+        self.last_payload_bytes = payload_bytes;
+        self.bandits = bandits.clone();
+
+        Ok(FetchResult::Modified(Box::new(
+            Configuration::from_server_response_with_shared_bandits(ufc, bandits),
+        )))
     }
 
-    async fn fetch_ufc_configuration(&mut self) -> Result<UniversalFlagConfig> {
-        let url = Url::parse_with_params(
-            &format!("{}{}", self.config.base_url, UFC_ENDPOINT),
-            &[
-                ("apiKey", &*self.config.api_key),
-                ("sdkName", self.config.sdk_metadata.name),
-                ("sdkVersion", self.config.sdk_metadata.version),
-                ("coreVersion", env!("CARGO_PKG_VERSION")),
-            ],
-        )
-        .map_err(|err| Error::InvalidBaseUrl(err))?;
+    /// Returns `None` if flags configuration has not been modified since the last fetch.
+    async fn fetch_ufc_configuration(
+        &mut self,
+        base_url: &str,
+    ) -> Result<Option<(UniversalFlagConfig, CacheValidators)>> {
+        let endpoint = if self.config.obfuscated {
+            OBFUSCATED_UFC_ENDPOINT
+        } else {
+            UFC_ENDPOINT
+        };
+        let url = Url::parse_with_params(&format!("{base_url}{endpoint}"), self.query_params())
+            .map_err(Error::InvalidBaseUrl)?;
+
+        let mut headers = self.headers()?;
+        if let Some(etag) = &self.ufc_validators.etag {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = &self.ufc_validators.last_modified {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
 
         log::debug!(target: "eppo", "fetching UFC flags configuration");
-        let response = self.client.get(url).send().await?;
+        let response = self.get(url, headers).send().await?;
 
-        let response = response.error_for_status().map_err(|err| {
-            if err.status() == Some(StatusCode::UNAUTHORIZED) {
-                    log::warn!(target: "eppo", "client is not authorized. Check your API key");
-                    self.unauthorized = true;
-                    return Error::Unauthorized;
-                } else {
-                    log::warn!(target: "eppo", "received non-200 response while fetching new configuration: {:?}", err);
-                    return Error::from(err);
+        if response.status() == StatusCode::NOT_MODIFIED {
+            log::debug!(target: "eppo", "UFC flags configuration has not been modified");
+            return Ok(None);
+        }
 
-            }
-        })?;
+        let response = self.error_for_status(response)?;
 
-        let configuration = UniversalFlagConfig::from_json(
+        let validators = CacheValidators::from_headers(response.headers());
+        // Parse off the async path, so the poller stays responsive to stop commands.
+        let configuration = UniversalFlagConfig::from_json_async(
             self.config.sdk_metadata,
-            response.bytes().await?.into(),
-        )?;
+            self.read_body(response).await?,
+        )
+        .await?;
 
         log::debug!(target: "eppo", "successfully fetched UFC flags configuration");
 
-        Ok(configuration)
+        Ok(Some((configuration, validators)))
     }
 
-    async fn fetch_bandits_configuration(&mut self) -> Result<BanditResponse> {
-        let url = Url::parse_with_params(
-            &format!("{}{}", self.config.base_url, BANDIT_ENDPOINT),
-            &[
-                ("apiKey", &*self.config.api_key),
-                ("sdkName", self.config.sdk_metadata.name),
-                ("sdkVersion", self.config.sdk_metadata.version),
-                ("coreVersion", env!("CARGO_PKG_VERSION")),
-            ],
-        )
-        .map_err(|err| Error::InvalidBaseUrl(err))?;
+    /// Returns bandits configuration and the size of response body.
+    async fn fetch_bandits_configuration(
+        &mut self,
+        base_url: &str,
+    ) -> Result<(BanditResponse, usize)> {
+        let url =
+            Url::parse_with_params(&format!("{base_url}{BANDIT_ENDPOINT}"), self.query_params())
+                .map_err(Error::InvalidBaseUrl)?;
 
         log::debug!(target: "eppo", "fetching UFC bandits configuration");
-        let response = self.client.get(url).send().await?;
+        let response = self.get(url, self.headers()?).send().await?;
 
-        let response = response.error_for_status().map_err(|err| {
+        let response = self.error_for_status(response)?;
+
+        let body = self.read_body(response).await?;
+        let configuration = serde_json::from_slice(&body)?;
+
+        log::debug!(target: "eppo", "successfully fetched UFC bandits configuration");
+
+        Ok((configuration, body.len()))
+    }
+
+    fn error_for_status(&mut self, response: reqwest::Response) -> Result<reqwest::Response> {
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = parse_retry_after(response.headers());
+            log::warn!(target: "eppo", "configuration requests are rate limited (retry after: {retry_after:?})");
+            return Err(Error::RateLimited { retry_after });
+        }
+
+        response.error_for_status().map_err(|err| {
             if err.status() == Some(StatusCode::UNAUTHORIZED) {
                     log::warn!(target: "eppo", "client is not authorized. Check your API key");
                     self.unauthorized = true;
-                    return Error::Unauthorized;
+                    Error::Unauthorized
                 } else {
                     log::warn!(target: "eppo", "received non-200 response while fetching new configuration: {:?}", err);
-                    return Error::from(err);
+                    Error::from(err)
 
             }
-        })?;
+        })
+    }
 
-        let configuration = response.json().await?;
+    /// Read response body, failing early if it exceeds `config.max_response_bytes`.
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>> {
+        let limit = self.config.max_response_bytes;
+        let too_large = |actual_hint| {
+            log::warn!(target: "eppo",
+                "configuration response exceeds the limit of {limit} bytes (content length: {actual_hint:?})");
+            Error::ResponseTooLarge { limit, actual_hint }
+        };
 
-        log::debug!(target: "eppo", "successfully fetched UFC bandits configuration");
+        // Content-Length is not available for chunked responses, so the limit is checked while
+        // reading as well.
+        let content_length = response.content_length();
+        if let Some(length) = content_length {
+            if length > limit as u64 {
+                return Err(too_large(Some(length)));
+            }
+        }
+
+        let mut body = Vec::with_capacity(content_length.unwrap_or(0) as usize);
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(too_large(None));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Build a request with per-request settings applied.
+    fn get(&self, url: Url, headers: HeaderMap) -> reqwest::RequestBuilder {
+        // Timed out requests return a network error, so the poller retries on the next interval.
+        self.client
+            .get(url)
+            .headers(headers)
+            .timeout(self.config.request_timeout)
+    }
+
+    /// Standard query parameters followed by `config.query_params`.
+    fn query_params(&self) -> impl Iterator<Item = (&str, &str)> {
+        let api_key = match self.config.auth_mode {
+            AuthMode::QueryParam => Some(("apiKey", &*self.config.api_key)),
+            AuthMode::BearerHeader => None,
+        };
+        let standard = [
+            ("sdkName", self.config.sdk_metadata.name),
+            ("sdkVersion", self.config.sdk_metadata.version),
+            ("coreVersion", env!("CARGO_PKG_VERSION")),
+        ];
+        // Reserved parameters are dropped when the fetcher is created.
+        let extra = self
+            .config
+            .query_params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()));
+        api_key.into_iter().chain(standard).chain(extra)
+    }
+
+    fn headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::with_capacity(self.config.headers.len());
+        for (name, value) in &self.config.headers {
+            let invalid_header = || Error::InvalidHeader(name.clone());
+            headers.append(
+                HeaderName::try_from(name).map_err(|_| invalid_header())?,
+                HeaderValue::try_from(value).map_err(|_| invalid_header())?,
+            );
+        }
+        if self.config.auth_mode == AuthMode::BearerHeader {
+            let mut value = HeaderValue::try_from(format!("Bearer {}", self.config.api_key))
+                .map_err(|_| Error::InvalidHeader(AUTHORIZATION.to_string()))?;
+            // Keep API key out of debug output.
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+}
+
+/// Returns `true` if the server could not be reached or failed to handle the request, so another
+/// base URL should be tried.
+///
+/// Client errors (e.g., unauthorized) are not retried as other servers would respond the same way.
+/// Whether `bandits` has every bandit referenced by `ufc`, at the model version `ufc` references
+/// (if known). Otherwise, bandit models have to be re-fetched even if a refresh is not due.
+fn bandits_match(ufc: &UniversalFlagConfig, bandits: &BanditResponse) -> bool {
+    let compiled = &ufc.compiled;
+    let has_all_bandits = compiled
+        .flag_to_bandit_associations
+        .values()
+        .flat_map(HashMap::values)
+        .all(|variation| bandits.bandits.contains_key(&variation.key));
+    let has_same_versions =
+        compiled
+            .bandit_model_versions
+            .iter()
+            .all(|(bandit_key, model_version)| {
+                bandits
+                    .bandits
+                    .get(bandit_key)
+                    .is_some_and(|bandit| &bandit.model_version == model_version)
+            });
+    has_all_bandits && has_same_versions
+}
+
+fn should_fail_over(err: &Error) -> bool {
+    match err {
+        Error::NetworkError {
+            status: Some(status),
+            ..
+        } => status.is_server_error(),
+        Error::NetworkError {
+            status: None,
+            source,
+            ..
+        } => source.is_connect() || source.is_timeout(),
+        _ => false,
+    }
+}
+
+/// Parse `Retry-After` header, which is either a number of seconds or an HTTP date.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means we can retry right away.
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use chrono::Utc;
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+
+    use super::{
+        parse_retry_after, validate_api_key, validate_base_url, validate_query_params, AuthMode,
+        ConfigurationFetcher, ConfigurationFetcherConfig, FetchResult, BANDIT_ENDPOINT,
+        DEFAULT_BASE_URL, UFC_ENDPOINT,
+    };
+    use crate::{
+        bandits::BanditResponse,
+        test_server::{TestResponse, TestServer, BANDITS_JSON, UFC_JSON, UFC_WITH_BANDITS_JSON},
+        Error, Str,
+    };
+
+    fn fetcher(server: &TestServer) -> ConfigurationFetcher {
+        ConfigurationFetcher::new(server.fetcher_config())
+    }
+
+    #[test]
+    fn validates_api_key() {
+        assert!(validate_api_key("api-key").is_ok());
+        assert!(matches!(
+            validate_api_key(""),
+            Err(Error::InvalidConfiguration(_))
+        ));
+        assert!(matches!(
+            validate_api_key("  \t"),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn validates_base_url() {
+        assert!(validate_base_url(DEFAULT_BASE_URL).is_ok());
+        assert!(validate_base_url("http://localhost:8080/api").is_ok());
+        assert!(matches!(
+            validate_base_url("not a url"),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
 
-        Ok(configuration)
+    #[test]
+    fn validates_query_params() {
+        assert!(validate_query_params(&[("wrapperName".to_owned(), "sdk".to_owned())]).is_ok());
+        assert!(matches!(
+            validate_query_params(&[("sdkName".to_owned(), "sdk".to_owned())]),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn sends_cache_validators_from_previous_response() {
+        let server = TestServer::start(|request| {
+            if request.header("if-none-match") == Some("\"v1\"") {
+                TestResponse::new(304)
+            } else {
+                TestResponse::new(200)
+                    .with_header("ETag", "\"v1\"")
+                    .with_header("Last-Modified", "Thu, 18 Jul 2024 00:00:00 GMT")
+                    .with_body(UFC_JSON)
+            }
+        });
+        let mut fetcher = fetcher(&server);
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Ok(FetchResult::Modified(_))
+        ));
+        let request = server.next_request();
+        assert_eq!(request.header("if-none-match"), None);
+        assert_eq!(request.header("if-modified-since"), None);
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Ok(FetchResult::NotModified)
+        ));
+        let request = server.next_request();
+        assert_eq!(request.header("if-none-match"), Some("\"v1\""));
+        assert_eq!(
+            request.header("if-modified-since"),
+            Some("Thu, 18 Jul 2024 00:00:00 GMT")
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_base_url() {
+        let primary = TestServer::start(|_| TestResponse::new(503));
+        let secondary = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            base_urls: vec![
+                // Nothing is listening on port 1, so connection fails.
+                "http://127.0.0.1:1/api".to_owned(),
+                primary.base_url().to_owned(),
+                secondary.base_url().to_owned(),
+            ],
+            ..primary.fetcher_config()
+        });
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Ok(FetchResult::Modified(_))
+        ));
+        primary.next_request();
+        secondary.next_request();
+
+        // The last working URL is tried first.
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Ok(FetchResult::Modified(_))
+        ));
+        secondary.next_request();
+        assert!(primary.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn does_not_fail_over_when_unauthorized() {
+        let primary = TestServer::start(|_| TestResponse::new(401));
+        let secondary = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            base_urls: vec![
+                primary.base_url().to_owned(),
+                secondary.base_url().to_owned(),
+            ],
+            ..primary.fetcher_config()
+        });
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Err(Error::Unauthorized)
+        ));
+        primary.next_request();
+        assert!(secondary.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn uses_provided_client() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        // Default headers stand in for application middleware configured on a shared client.
+        let client = reqwest::Client::builder()
+            .default_headers(HeaderMap::from_iter([(
+                HeaderName::from_static("x-middleware"),
+                HeaderValue::from_static("shared-client"),
+            )]))
+            .build()
+            .unwrap();
+        let mut fetcher = ConfigurationFetcher::with_client(
+            client,
+            ConfigurationFetcherConfig {
+                headers: vec![("X-Gateway".to_owned(), "gateway".to_owned())],
+                ..server.fetcher_config()
+            },
+        );
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Ok(FetchResult::Modified(_))
+        ));
+
+        let request = server.next_request();
+        assert_eq!(request.header("x-middleware"), Some("shared-client"));
+        assert_eq!(request.header("x-gateway"), Some("gateway"));
+        assert!(request.path.contains("apiKey=api-key"));
+        assert!(request.path.contains("sdkName=test"));
+    }
+
+    #[tokio::test]
+    async fn applies_request_timeout_to_provided_client() {
+        let server = TestServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(500));
+            TestResponse::new(200).with_body(UFC_JSON)
+        });
+        let mut fetcher = ConfigurationFetcher::with_client(
+            reqwest::Client::new(),
+            ConfigurationFetcherConfig {
+                request_timeout: Duration::from_millis(50),
+                ..server.fetcher_config()
+            },
+        );
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Err(Error::NetworkError { status: None, source, .. }) if source.is_timeout()
+        ));
+    }
+
+    #[tokio::test]
+    async fn skips_bandits_request_without_bandit_references() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let mut fetcher = fetcher(&server);
+
+        let Ok(FetchResult::Modified(configuration)) = fetcher.fetch_configuration().await else {
+            panic!("configuration should be fetched");
+        };
+        assert!(configuration.bandits.is_none());
+
+        assert!(server.next_request().path.contains(UFC_ENDPOINT));
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn fetches_bandits_when_referenced() {
+        let server = TestServer::start(|request| {
+            if request.path.contains(BANDIT_ENDPOINT) {
+                TestResponse::new(200).with_body(BANDITS_JSON)
+            } else {
+                TestResponse::new(200).with_body(UFC_WITH_BANDITS_JSON)
+            }
+        });
+        let mut fetcher = fetcher(&server);
+
+        let Ok(FetchResult::Modified(configuration)) = fetcher.fetch_configuration().await else {
+            panic!("configuration should be fetched");
+        };
+        assert!(configuration.bandits.is_some());
+
+        assert!(server.next_request().path.contains(UFC_ENDPOINT));
+        assert!(server.next_request().path.contains(BANDIT_ENDPOINT));
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn applies_response_limit_to_bandits() {
+        let server = TestServer::start(|request| {
+            if request.path.contains(BANDIT_ENDPOINT) {
+                TestResponse::new(200).with_body(vec![b' '; 1024])
+            } else {
+                TestResponse::new(200).with_body(UFC_WITH_BANDITS_JSON)
+            }
+        });
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            max_response_bytes: UFC_WITH_BANDITS_JSON.len(),
+            ..server.fetcher_config()
+        });
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Err(Error::ResponseTooLarge {
+                actual_hint: Some(1024),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn reuses_bandits_until_refresh_is_due() {
+        async fn fetch(fetcher: &mut ConfigurationFetcher) -> Arc<BanditResponse> {
+            let Ok(FetchResult::Modified(configuration)) = fetcher.fetch_configuration().await
+            else {
+                panic!("configuration should be fetched");
+            };
+            configuration.bandits.unwrap()
+        }
+
+        let server = TestServer::start(|request| {
+            if request.path.contains(BANDIT_ENDPOINT) {
+                TestResponse::new(200).with_body(BANDITS_JSON)
+            } else {
+                TestResponse::new(200).with_body(UFC_WITH_BANDITS_JSON)
+            }
+        });
+        let mut fetcher = fetcher(&server);
+
+        let first = fetch(&mut fetcher).await;
+        assert!(server.next_request().path.contains(UFC_ENDPOINT));
+        assert!(server.next_request().path.contains(BANDIT_ENDPOINT));
+
+        fetcher.set_refresh_bandits(false);
+        let second = fetch(&mut fetcher).await;
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(server.next_request().path.contains(UFC_ENDPOINT));
+        assert!(server.try_next_request().is_none());
+
+        fetcher.set_refresh_bandits(true);
+        let third = fetch(&mut fetcher).await;
+        assert!(!Arc::ptr_eq(&first, &third));
+        assert!(server.next_request().path.contains(UFC_ENDPOINT));
+        assert!(server.next_request().path.contains(BANDIT_ENDPOINT));
+    }
+
+    #[tokio::test]
+    async fn refetches_bandits_when_references_change() {
+        let flag_requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let flag_requests = flag_requests.clone();
+            TestServer::start(move |request| {
+                if request.path.contains(BANDIT_ENDPOINT) {
+                    return TestResponse::new(200).with_body(BANDITS_JSON);
+                }
+                let ufc = match flag_requests.fetch_add(1, Ordering::SeqCst) {
+                    0 => UFC_WITH_BANDITS_JSON.to_owned(),
+                    // A new model version is published.
+                    1 => UFC_WITH_BANDITS_JSON
+                        .replace(r#""modelVersion": "v1""#, r#""modelVersion": "v2""#),
+                    // A new bandit is referenced.
+                    _ => UFC_WITH_BANDITS_JSON.replace("banner-bandit", "other-bandit"),
+                };
+                TestResponse::new(200).with_body(ufc)
+            })
+        };
+        let mut fetcher = fetcher(&server);
+
+        assert!(fetcher.fetch_configuration().await.is_ok());
+        assert!(server.next_request().path.contains(UFC_ENDPOINT));
+        assert!(server.next_request().path.contains(BANDIT_ENDPOINT));
+
+        fetcher.set_refresh_bandits(false);
+        for _ in 0..2 {
+            assert!(fetcher.fetch_configuration().await.is_ok());
+            assert!(server.next_request().path.contains(UFC_ENDPOINT));
+            assert!(server.next_request().path.contains(BANDIT_ENDPOINT));
+        }
+    }
+
+    #[tokio::test]
+    async fn always_fetches_bandits_when_requested() {
+        let server = TestServer::start(|request| {
+            if request.path.contains(BANDIT_ENDPOINT) {
+                TestResponse::new(200).with_body(BANDITS_JSON)
+            } else {
+                TestResponse::new(200).with_body(UFC_JSON)
+            }
+        });
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            always_fetch_bandits: true,
+            query_params: Vec::new(),
+            ..server.fetcher_config()
+        });
+
+        let Ok(FetchResult::Modified(configuration)) = fetcher.fetch_configuration().await else {
+            panic!("configuration should be fetched");
+        };
+        assert!(configuration.bandits.is_some());
+
+        assert!(server.next_request().path.contains(UFC_ENDPOINT));
+        assert!(server.next_request().path.contains(BANDIT_ENDPOINT));
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn sends_additional_query_params() {
+        let server = TestServer::start(|request| {
+            if request.path.contains(BANDIT_ENDPOINT) {
+                TestResponse::new(200).with_body(BANDITS_JSON)
+            } else {
+                TestResponse::new(200).with_body(UFC_WITH_BANDITS_JSON)
+            }
+        });
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            query_params: vec![
+                ("wrapperName".to_owned(), "platform-sdk".to_owned()),
+                ("wrapperVersion".to_owned(), "1.2.3".to_owned()),
+                ("apiKey".to_owned(), "overridden".to_owned()),
+            ],
+            ..server.fetcher_config()
+        });
+
+        fetcher.fetch_configuration().await.unwrap();
+
+        for request in [server.next_request(), server.next_request()] {
+            let query = request.path.split_once('?').unwrap().1;
+            let params = url::form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect::<Vec<_>>();
+            let get = |name: &str| {
+                params
+                    .iter()
+                    .filter(|(key, _)| key == name)
+                    .map(|(_, value)| value.as_str())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(get("apiKey"), ["api-key"]);
+            assert_eq!(get("sdkName"), ["test"]);
+            assert_eq!(get("sdkVersion"), ["0.1.0"]);
+            assert_eq!(get("coreVersion"), [env!("CARGO_PKG_VERSION")]);
+            assert_eq!(get("wrapperName"), ["platform-sdk"]);
+            assert_eq!(get("wrapperVersion"), ["1.2.3"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_api_key_in_query_param_by_default() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let mut fetcher = fetcher(&server);
+
+        fetcher.fetch_configuration().await.unwrap();
+
+        let request = server.next_request();
+        assert!(request.path.contains("apiKey=api-key"));
+        assert_eq!(request.header("authorization"), None);
+    }
+
+    #[tokio::test]
+    async fn sends_api_key_in_authorization_header() {
+        let server = TestServer::start(|request| {
+            if request.path.contains(BANDIT_ENDPOINT) {
+                TestResponse::new(200).with_body(BANDITS_JSON)
+            } else {
+                TestResponse::new(200).with_body(UFC_WITH_BANDITS_JSON)
+            }
+        });
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            auth_mode: AuthMode::BearerHeader,
+            // Reserved parameter cannot be used to add the key back.
+            query_params: vec![("apiKey".to_owned(), "api-key".to_owned())],
+            ..server.fetcher_config()
+        });
+
+        fetcher.fetch_configuration().await.unwrap();
+
+        for endpoint in [UFC_ENDPOINT, BANDIT_ENDPOINT] {
+            let request = server.next_request();
+            assert!(request.path.contains(endpoint));
+            assert!(
+                !request.path.contains("api-key"),
+                "API key leaked into {}",
+                request.path
+            );
+            assert!(request.path.contains("sdkName=test"));
+            assert_eq!(request.header("authorization"), Some("Bearer api-key"));
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_api_key_that_is_not_a_valid_header() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            api_key: "api\nkey".to_owned(),
+            auth_mode: AuthMode::BearerHeader,
+            ..server.fetcher_config()
+        });
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Err(Error::InvalidHeader(name)) if name == "authorization"
+        ));
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn fetches_obfuscated_configuration_when_requested() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+
+        fetcher(&server).fetch_configuration().await.unwrap();
+        let request = server.next_request();
+        assert!(request.path.starts_with("/api/flag-config/v1/config?"));
+
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            obfuscated: true,
+            ..server.fetcher_config()
+        });
+        fetcher.fetch_configuration().await.unwrap();
+        let request = server.next_request();
+        assert!(request
+            .path
+            .starts_with("/api/flag-config/v1/obfuscated-config?"));
+    }
+
+    #[tokio::test]
+    async fn classifies_errors() {
+        async fn fetch_with_status(status: u16) -> Error {
+            let server = TestServer::start(move |_| TestResponse::new(status));
+            fetcher(&server).fetch_configuration().await.unwrap_err()
+        }
+
+        let err = fetch_with_status(401).await;
+        assert!(matches!(err, Error::Unauthorized));
+        assert!(!err.is_retriable());
+
+        for status in [403, 404] {
+            let err = fetch_with_status(status).await;
+            assert!(
+                matches!(
+                    &err,
+                    Error::NetworkError { status: Some(s), url, .. }
+                        if s.as_u16() == status && url.ends_with(UFC_ENDPOINT)
+                ),
+                "unexpected error for {status}: {err:?}"
+            );
+            assert!(err.is_retriable(), "{status} should be retriable");
+        }
+
+        let err = fetch_with_status(429).await;
+        assert!(matches!(err, Error::RateLimited { .. }));
+        assert!(err.is_retriable());
+
+        let err = fetch_with_status(500).await;
+        assert!(matches!(
+            err,
+            Error::NetworkError { status: Some(status), .. } if status.as_u16() == 500
+        ));
+        assert!(err.is_retriable());
+
+        // Nothing is listening on port 1, so connection is refused.
+        let err = ConfigurationFetcher::new(ConfigurationFetcherConfig::for_tests(
+            "http://127.0.0.1:1/api",
+        ))
+        .fetch_configuration()
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::NetworkError { status: None, source, .. } if source.is_connect()
+        ));
+        assert!(err.is_retriable());
+    }
+
+    #[tokio::test]
+    async fn network_error_does_not_expose_api_key() {
+        let server = TestServer::start(|_| TestResponse::new(500));
+        let err = fetcher(&server).fetch_configuration().await.unwrap_err();
+
+        let Error::NetworkError { url, .. } = &err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(url, &format!("{}{UFC_ENDPOINT}", server.base_url()));
+        assert!(!err.to_string().contains("api-key"));
+        assert!(!format!("{err:?}").contains("api-key"));
+    }
+
+    #[tokio::test]
+    async fn rejects_response_with_large_content_length() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            max_response_bytes: 16,
+            ..server.fetcher_config()
+        });
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Err(Error::ResponseTooLarge {
+                limit: 16,
+                actual_hint: Some(length)
+            }) if length == UFC_JSON.len() as u64
+        ));
+    }
+
+    #[tokio::test]
+    async fn aborts_streaming_response_exceeding_limit() {
+        // 64MB body without Content-Length. If the fetcher buffered the whole body, the test
+        // server would finish writing it; instead the connection is dropped early.
+        let (written, written_receiver) = std::sync::mpsc::channel();
+        let server = TestServer::start(move |_| {
+            TestResponse::new(200)
+                .without_content_length()
+                .with_body(vec![b' '; 64 * 1024 * 1024])
+                .on_written(written.clone())
+        });
+        let mut fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            max_response_bytes: 1024,
+            ..server.fetcher_config()
+        });
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Err(Error::ResponseTooLarge {
+                limit: 1024,
+                actual_hint: None
+            })
+        ));
+        drop(fetcher);
+        // Wait on a blocking thread, so the runtime can close the connection meanwhile.
+        let written = tokio::task::spawn_blocking(move || {
+            written_receiver.recv_timeout(Duration::from_secs(10))
+        })
+        .await
+        .unwrap()
+        .expect("server should finish writing response");
+        assert!(!written, "response should not be read completely");
+    }
+
+    #[tokio::test]
+    async fn reports_rate_limiting() {
+        let server =
+            TestServer::start(|_| TestResponse::new(429).with_header("Retry-After", "120"));
+        let mut fetcher = fetcher(&server);
+
+        assert!(matches!(
+            fetcher.fetch_configuration().await,
+            Err(Error::RateLimited {
+                retry_after: Some(retry_after)
+            }) if retry_after == Duration::from_secs(120)
+        ));
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, value.parse().unwrap());
+            headers
+        };
+
+        assert_eq!(parse_retry_after(&HeaderMap::new()), None);
+        assert_eq!(
+            parse_retry_after(&headers("30")),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+        let in_a_minute = (Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        assert!(parse_retry_after(&headers(&in_a_minute))
+            .is_some_and(|it| it > Duration::from_secs(50) && it <= Duration::from_secs(60)));
+        assert_eq!(parse_retry_after(&headers("soon")), None);
+    }
+
+    #[tokio::test]
+    async fn does_not_send_validators_without_etag() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let mut fetcher = fetcher(&server);
+
+        for _ in 0..2 {
+            assert!(matches!(
+                fetcher.fetch_configuration().await,
+                Ok(FetchResult::Modified(_))
+            ));
+            assert_eq!(server.next_request().header("if-none-match"), None);
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn decompresses_gzip_response() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(UFC_JSON.as_bytes()).unwrap();
+        let body = encoder.finish().unwrap();
+
+        let server = TestServer::start(move |_| {
+            TestResponse::new(200)
+                .with_header("Content-Encoding", "gzip")
+                .with_body(body.clone())
+        });
+        let mut fetcher = fetcher(&server);
+
+        let result = fetcher.fetch_configuration().await;
+
+        let Ok(FetchResult::Modified(configuration)) = result else {
+            panic!("expected configuration, got {result:?}");
+        };
+        assert_eq!(
+            configuration.flags.compiled.environment.name,
+            Str::from("test")
+        );
+        let accept_encoding = server
+            .next_request()
+            .header("accept-encoding")
+            .map(str::to_owned);
+        assert!(
+            accept_encoding
+                .as_deref()
+                .is_some_and(|it| it.contains("gzip") && it.contains("br")),
+            "unexpected Accept-Encoding: {accept_encoding:?}"
+        );
     }
 }
@@ -0,0 +1,242 @@
+//! Persisting configuration across process restarts.
+//!
+//! Short-lived processes (e.g., serverless functions) pay for a full configuration fetch on every
+//! cold start. With [`ConfigurationPersistence`], the poller saves the last successfully fetched
+//! configuration (along with HTTP cache validators), and loads it at startup. This makes the
+//! client usable before the first fetch completes, and allows the server to answer the first
+//! fetch with 304 Not Modified.
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::{
+    bandits::BanditResponse, ufc::UniversalFlagConfig, Configuration, Result, SdkMetadata,
+};
+
+/// A store for the last fetched configuration.
+///
+/// The payload is opaque to the store. Implementations must be thread-safe as they are called from
+/// the poller.
+pub trait ConfigurationPersistence: Send + Sync {
+    /// Save `bytes`, replacing previously saved payload.
+    fn save(&self, bytes: &[u8]) -> std::io::Result<()>;
+
+    /// Load previously saved payload. Returns `None` if nothing has been saved yet.
+    fn load(&self) -> std::io::Result<Option<Vec<u8>>>;
+}
+
+/// [`ConfigurationPersistence`] that stores configuration in a file on the local filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileConfigurationPersistence {
+    path: PathBuf,
+}
+
+impl FileConfigurationPersistence {
+    /// Create a persistence that stores configuration at `path`. The parent directory must exist.
+    pub fn new(path: impl Into<PathBuf>) -> FileConfigurationPersistence {
+        FileConfigurationPersistence { path: path.into() }
+    }
+
+    /// Path to the file with the saved configuration.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ConfigurationPersistence for FileConfigurationPersistence {
+    fn save(&self, bytes: &[u8]) -> std::io::Result<()> {
+        // Write to a temporary file and rename it, so a concurrent reader (or a crash mid-write)
+        // never observes a partially written file. The temporary file is unique per process, as
+        // several processes may write to the same path.
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(format!(".{}.tmp", std::process::id()));
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+
+    fn load(&self) -> std::io::Result<Option<Vec<u8>>> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Payload written to [`ConfigurationPersistence`].
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PersistedConfiguration {
+    pub fetched_at: DateTime<Utc>,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// Raw flags configuration response.
+    #[serde_as(as = "serde_with::base64::Base64")]
+    pub flags: Vec<u8>,
+    #[serde(default)]
+    pub bandits: Option<Arc<BanditResponse>>,
+}
+
+impl PersistedConfiguration {
+    pub fn new(
+        configuration: &Configuration,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    ) -> PersistedConfiguration {
+        PersistedConfiguration {
+            fetched_at: configuration.fetched_at,
+            etag,
+            last_modified,
+            flags: configuration.flags.to_json().to_vec(),
+            bandits: configuration.bandits.clone(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<PersistedConfiguration> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Parse persisted flags. Configuration keeps the original `fetched_at`, so its age reflects
+    /// when it was actually fetched from the server.
+    pub fn to_configuration(&self, sdk_metadata: SdkMetadata) -> Result<Configuration> {
+        let flags = UniversalFlagConfig::from_json(sdk_metadata, self.flags.clone())?;
+        Ok(Configuration {
+            fetched_at: self.fetched_at,
+            flags,
+            bandits: self.bandits.clone(),
+        })
+    }
+}
+
+/// Save `persisted` to `persistence`. Errors are logged and otherwise ignored as persistence is an
+/// optimization.
+pub(crate) fn save(persistence: &dyn ConfigurationPersistence, persisted: &PersistedConfiguration) {
+    if let Err(err) = persisted
+        .to_bytes()
+        .and_then(|bytes| Ok(persistence.save(&bytes)?))
+    {
+        log::warn!(target: "eppo", "failed to persist configuration: {err}");
+    }
+}
+
+/// Load configuration previously saved by the poller to `persistence`.
+///
+/// Returns `None` if nothing was saved or the saved payload cannot be read or parsed (a warning
+/// is logged in that case).
+pub fn load_configuration(
+    persistence: &dyn ConfigurationPersistence,
+    sdk_metadata: SdkMetadata,
+) -> Option<Configuration> {
+    let (_, configuration) = load(persistence, sdk_metadata)?;
+    Some(configuration)
+}
+
+pub(crate) fn load(
+    persistence: &dyn ConfigurationPersistence,
+    sdk_metadata: SdkMetadata,
+) -> Option<(PersistedConfiguration, Configuration)> {
+    let bytes = match persistence.load() {
+        Ok(Some(bytes)) => bytes,
+        Ok(None) => return None,
+        Err(err) => {
+            log::warn!(target: "eppo", "failed to load persisted configuration: {err}");
+            return None;
+        }
+    };
+    match PersistedConfiguration::from_bytes(&bytes)
+        .and_then(|persisted| Ok((persisted.to_configuration(sdk_metadata)?, persisted)))
+    {
+        Ok((configuration, persisted)) => {
+            log::debug!(target: "eppo", "loaded persisted configuration fetched at {}", persisted.fetched_at);
+            Some((persisted, configuration))
+        }
+        Err(err) => {
+            log::warn!(target: "eppo", "ignoring corrupt persisted configuration: {err}");
+            None
+        }
+    }
+}
+
+/// In-memory persistence for tests.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct MemoryPersistence(pub std::sync::Mutex<Option<Vec<u8>>>);
+
+#[cfg(test)]
+impl ConfigurationPersistence for MemoryPersistence {
+    fn save(&self, bytes: &[u8]) -> std::io::Result<()> {
+        *self.0.lock().unwrap() = Some(bytes.to_vec());
+        Ok(())
+    }
+
+    fn load(&self) -> std::io::Result<Option<Vec<u8>>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::{
+        test_server::{SDK_METADATA, UFC_JSON},
+        Configuration,
+    };
+
+    use super::{
+        load, load_configuration, ConfigurationPersistence, FileConfigurationPersistence,
+        MemoryPersistence, PersistedConfiguration,
+    };
+
+    fn configuration() -> Configuration {
+        Configuration::from_ufc_json_for_tests(UFC_JSON)
+    }
+
+    #[test]
+    fn round_trips_configuration_and_cache_validators() {
+        let persistence = MemoryPersistence::default();
+        let configuration = configuration();
+        let persisted =
+            PersistedConfiguration::new(&configuration, Some("\"v1\"".to_owned()), None);
+        persistence.save(&persisted.to_bytes().unwrap()).unwrap();
+
+        let (persisted, restored) = load(&persistence, SDK_METADATA).unwrap();
+
+        assert_eq!(persisted.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(restored.fetched_at, configuration.fetched_at);
+        assert_eq!(restored.flags.to_json(), UFC_JSON.as_bytes());
+    }
+
+    #[test]
+    fn ignores_corrupt_payload() {
+        let persistence = MemoryPersistence(Mutex::new(Some(b"not json".to_vec())));
+
+        assert!(load_configuration(&persistence, SDK_METADATA).is_none());
+    }
+
+    #[test]
+    fn file_persistence_saves_and_loads() {
+        let path =
+            std::env::temp_dir().join(format!("eppo-{}-file-persistence.json", std::process::id()));
+        let persistence = FileConfigurationPersistence::new(&path);
+        assert_eq!(persistence.load().unwrap(), None);
+
+        persistence.save(b"first").unwrap();
+        persistence.save(b"second").unwrap();
+
+        assert_eq!(persistence.load().unwrap(), Some(b"second".to_vec()));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,106 @@
+//! Pluggable sources of configuration for the poller.
+//!
+//! [`ConfigurationFetcher`] fetches configuration from Eppo servers over HTTP. Applications that
+//! distribute configuration by other means (e.g., a sidecar or a local socket) can implement
+//! [`ConfigurationSource`] and reuse the poller (jitter, retries, persistence, and
+//! `wait_for_configuration()`) with their own transport.
+use std::future::Future;
+
+use crate::{
+    configuration_fetcher::{ConfigurationFetcher, ConfigurationFile, FetchResult},
+    configuration_persistence::{self, ConfigurationPersistence, PersistedConfiguration},
+    Configuration, Result, SdkMetadata,
+};
+
+/// A source of configuration that is periodically polled by
+/// [`PollerThread`](crate::poller_thread::PollerThread).
+pub trait ConfigurationSource: Send + 'static {
+    /// Metadata of the SDK the configuration is parsed for.
+    fn sdk_metadata(&self) -> SdkMetadata;
+
+    /// Fetch the latest configuration.
+    ///
+    /// Return [`FetchResult::NotModified`] if configuration hasn't changed since the last
+    /// successful fetch. Errors are handled the same way as for HTTP fetches: the poller retries
+    /// with a backoff if [`Error::is_retriable()`](crate::Error::is_retriable) and stops
+    /// otherwise.
+    fn fetch(&mut self) -> impl Future<Output = Result<FetchResult>> + Send;
+
+    /// Called by the poller before every [`ConfigurationSource::fetch()`] to tell whether bandit
+    /// models are due for a refresh. When `refresh` is `false`, the source may reuse bandit models
+    /// from the previous fetch. See
+    /// [`PollerThreadConfig::bandit_poll_every_n_cycles`](crate::poller_thread::PollerThreadConfig::bandit_poll_every_n_cycles).
+    ///
+    /// Defaults to ignoring the hint.
+    fn set_refresh_bandits(&mut self, _refresh: bool) {}
+
+    /// Size (in bytes) of the payload received by the last successful fetch. Reported in poller
+    /// stats.
+    ///
+    /// Defaults to zero.
+    fn last_payload_bytes(&self) -> usize {
+        0
+    }
+
+    /// Read configuration from local files.
+    fn read_configuration_file(&self, file: &ConfigurationFile) -> Result<Configuration> {
+        file.read(self.sdk_metadata())
+    }
+
+    /// Save `configuration` to `persistence`. Errors are logged and otherwise ignored.
+    fn persist_configuration(
+        &self,
+        configuration: &Configuration,
+        persistence: &dyn ConfigurationPersistence,
+    ) {
+        configuration_persistence::save(
+            persistence,
+            &PersistedConfiguration::new(configuration, None, None),
+        );
+    }
+
+    /// Load configuration saved by [`ConfigurationSource::persist_configuration()`].
+    fn restore_persisted_configuration(
+        &mut self,
+        persistence: &dyn ConfigurationPersistence,
+    ) -> Option<Configuration> {
+        configuration_persistence::load_configuration(persistence, self.sdk_metadata())
+    }
+}
+
+impl ConfigurationSource for ConfigurationFetcher {
+    fn sdk_metadata(&self) -> SdkMetadata {
+        ConfigurationFetcher::sdk_metadata(self)
+    }
+
+    fn fetch(&mut self) -> impl Future<Output = Result<FetchResult>> + Send {
+        self.fetch_configuration()
+    }
+
+    fn set_refresh_bandits(&mut self, refresh: bool) {
+        ConfigurationFetcher::set_refresh_bandits(self, refresh)
+    }
+
+    fn last_payload_bytes(&self) -> usize {
+        ConfigurationFetcher::last_payload_bytes(self)
+    }
+
+    fn read_configuration_file(&self, file: &ConfigurationFile) -> Result<Configuration> {
+        ConfigurationFetcher::read_configuration_file(self, file)
+    }
+
+    fn persist_configuration(
+        &self,
+        configuration: &Configuration,
+        persistence: &dyn ConfigurationPersistence,
+    ) {
+        ConfigurationFetcher::persist_configuration(self, configuration, persistence)
+    }
+
+    fn restore_persisted_configuration(
+        &mut self,
+        persistence: &dyn ConfigurationPersistence,
+    ) -> Option<Configuration> {
+        ConfigurationFetcher::restore_persisted_configuration(self, persistence)
+    }
+}
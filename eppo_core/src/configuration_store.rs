@@ -1,17 +1,96 @@
 //! A thread-safe in-memory storage for currently active configuration. [`ConfigurationStore`]
 //! provides a concurrent access for readers (e.g., flag evaluation) and writers (e.g., periodic
 //! configuration fetcher).
-use std::sync::{Arc, RwLock};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+use arc_swap::ArcSwapOption;
+use chrono::{DateTime, Utc};
+use tokio::sync::watch;
 
 use crate::Configuration;
 
+/// Storage for the currently-active configuration used by
+/// [`Evaluator`](crate::eval::Evaluator) and the pollers.
+///
+/// [`ConfigurationStore`] (in-memory) is the default implementation. Other implementations can
+/// share configuration between processes (e.g., see
+/// [`FileConfigurationStore`](crate::file_configuration_store::FileConfigurationStore)), so only
+/// one process needs to poll the server. [`Configuration::to_bytes()`] and
+/// [`Configuration::from_bytes()`] can be used to serialize configuration for out-of-process
+/// storage.
+///
+/// [`ConfigurationStoreBackend::get()`] is called on every evaluation, so it should be cheap.
+pub trait ConfigurationStoreBackend: Send + Sync {
+    /// Get currently-active configuration. Returns None if configuration hasn't been stored yet.
+    fn get(&self) -> Option<Arc<Configuration>>;
+
+    /// Set new configuration.
+    fn set(&self, configuration: Arc<Configuration>);
+
+    /// Set new configuration only if it is newer than the currently-active one. Returns `true` if
+    /// configuration was replaced. See [`ConfigurationStore::set_configuration_if_newer()`].
+    ///
+    /// The default implementation is not atomic: a concurrent `set()` may be overwritten.
+    fn set_if_newer(&self, configuration: Arc<Configuration>) -> bool {
+        if self
+            .get()
+            .is_some_and(|current| !is_newer(&configuration, &current))
+        {
+            return false;
+        }
+        self.set(configuration);
+        true
+    }
+
+    /// A counter incremented every time configuration is replaced. See
+    /// [`ConfigurationStore::generation()`].
+    ///
+    /// Defaults to zero for backends that don't track generations.
+    fn generation(&self) -> u64 {
+        0
+    }
+
+    /// Subscribe to configuration changes. See [`ConfigurationStore::subscribe()`].
+    ///
+    /// Returns `None` for backends that don't notify about changes (the default). The poller then
+    /// publishes configuration it stores itself.
+    fn subscribe(&self) -> Option<ConfigurationWatcher> {
+        None
+    }
+}
+
+/// Returns `true` if `configuration` is newer than `current`: its flags were published later or,
+/// if flags were published at the same time, its bandit models were updated later.
+pub(crate) fn is_newer(configuration: &Configuration, current: &Configuration) -> bool {
+    fn version(configuration: &Configuration) -> impl Ord {
+        (
+            configuration.flags.compiled.created_at,
+            configuration.bandits.as_ref().map(|it| it.updated_at),
+        )
+    }
+
+    version(configuration) > version(current)
+}
+
 /// `ConfigurationStore` provides a thread-safe (`Sync`) storage for Eppo configuration that allows
 /// concurrent access for readers and writers.
 ///
-/// `Configuration` itself is always immutable and can only be replaced completely.
-#[derive(Default)]
+/// `Configuration` itself is always immutable and can only be replaced completely. Use
+/// [`ConfigurationStore::subscribe()`] to get notified when it's replaced.
 pub struct ConfigurationStore {
-    configuration: RwLock<Option<Arc<Configuration>>>,
+    /// Read on every evaluation, so reads are lock-free.
+    configuration: ArcSwapOption<Configuration>,
+    /// Holds the same configuration as `configuration` and notifies subscribers. Writers update
+    /// both while holding the channel's lock, so subscribers always observe the same configuration
+    /// as readers, even with concurrent writers.
+    notifier: watch::Sender<Option<Arc<Configuration>>>,
+    // Both are updated while holding the channel's lock, so they are consistent with the stored
+    // configuration.
+    generation: AtomicU64,
+    last_updated_at: Mutex<Option<DateTime<Utc>>>,
 }
 
 impl ConfigurationStore {
@@ -23,24 +102,143 @@ impl ConfigurationStore {
     /// Get currently-active configuration. Returns None if configuration hasn't been fetched/stored
     /// yet.
     pub fn get_configuration(&self) -> Option<Arc<Configuration>> {
-        // self.configuration.read() should always return Ok(). Err() is possible only if the lock
-        // is poisoned (writer panicked while holding the lock), which should never happen.
-        let configuration = self
-            .configuration
-            .read()
-            .expect("thread holding configuration lock should not panic");
-
-        configuration.clone()
+        self.configuration.load_full()
     }
 
     /// Set new configuration.
+    ///
+    /// Subscribers are notified after the configuration is stored. This never waits for
+    /// subscribers to process the notification.
     pub fn set_configuration(&self, config: Arc<Configuration>) {
-        let mut configuration_slot = self
-            .configuration
-            .write()
-            .expect("thread holding configuration lock should not panic");
+        self.notifier.send_modify(|current| {
+            *current = Some(config);
+            self.record_update(current);
+        });
+    }
+
+    /// Set new configuration only if it is newer than the currently-active one. Returns `true` if
+    /// configuration was replaced.
+    ///
+    /// Configuration is newer if its flags were published later or, if flags were published at the
+    /// same time, its bandit models were updated later. Configuration with the same timestamps is
+    /// not replaced.
+    ///
+    /// This protects against a slow fetch overwriting configuration that was set in the meantime.
+    /// Use [`ConfigurationStore::set_configuration()`] to override configuration unconditionally.
+    pub fn set_configuration_if_newer(&self, config: Arc<Configuration>) -> bool {
+        self.notifier.send_if_modified(|current| {
+            let is_newer = current
+                .as_ref()
+                .map_or(true, |current| is_newer(&config, current));
+            if is_newer {
+                *current = Some(config);
+                self.record_update(current);
+            }
+            is_newer
+        })
+    }
+
+    /// A counter incremented every time configuration is replaced. Zero if configuration hasn't
+    /// been stored yet.
+    ///
+    /// This is a cheap way to detect configuration changes (e.g., to invalidate caches or as an
+    /// ETag for responses computed from configuration). Read the generation *before* reading
+    /// configuration: configuration is then guaranteed to be at least as new as the generation.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Time when configuration was last replaced. Returns `None` if configuration hasn't been
+    /// stored yet.
+    pub fn last_updated_at(&self) -> Option<DateTime<Utc>> {
+        *self
+            .last_updated_at
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
 
-        *configuration_slot = Some(config);
+    /// Publish `configuration` to readers. Must be called while holding the channel's lock.
+    fn record_update(&self, configuration: &Option<Arc<Configuration>>) {
+        // Store configuration before incrementing generation, so readers that observe the new
+        // generation also observe the new configuration.
+        self.configuration.store(configuration.clone());
+        self.generation.fetch_add(1, Ordering::Release);
+        *self
+            .last_updated_at
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(Utc::now());
+    }
+
+    /// Subscribe to configuration changes.
+    ///
+    /// The watcher is notified after every [`ConfigurationStore::set_configuration()`] call. If
+    /// configuration is replaced several times before the watcher checks for changes, it only
+    /// observes the latest one. Dropping the watcher unsubscribes it.
+    pub fn subscribe(&self) -> ConfigurationWatcher {
+        ConfigurationWatcher {
+            receiver: self.notifier.subscribe(),
+        }
+    }
+}
+
+impl ConfigurationStoreBackend for ConfigurationStore {
+    fn get(&self) -> Option<Arc<Configuration>> {
+        self.get_configuration()
+    }
+
+    fn set(&self, configuration: Arc<Configuration>) {
+        self.set_configuration(configuration)
+    }
+
+    fn set_if_newer(&self, configuration: Arc<Configuration>) -> bool {
+        self.set_configuration_if_newer(configuration)
+    }
+
+    fn generation(&self) -> u64 {
+        ConfigurationStore::generation(self)
+    }
+
+    fn subscribe(&self) -> Option<ConfigurationWatcher> {
+        Some(ConfigurationStore::subscribe(self))
+    }
+}
+
+impl Default for ConfigurationStore {
+    fn default() -> Self {
+        ConfigurationStore {
+            configuration: ArcSwapOption::empty(),
+            notifier: watch::channel(None).0,
+            generation: AtomicU64::new(0),
+            last_updated_at: Mutex::new(None),
+        }
+    }
+}
+
+/// A subscription to configuration changes in [`ConfigurationStore`]. Created by
+/// [`ConfigurationStore::subscribe()`].
+pub struct ConfigurationWatcher {
+    pub(crate) receiver: watch::Receiver<Option<Arc<Configuration>>>,
+}
+
+impl ConfigurationWatcher {
+    /// Wait until configuration is replaced and return the new configuration.
+    ///
+    /// Changes made before the watcher was created, or already returned by this method, are not
+    /// reported again. Returns `None` if the store has been dropped.
+    pub async fn changed(&mut self) -> Option<Arc<Configuration>> {
+        self.receiver.changed().await.ok()?;
+        self.receiver.borrow_and_update().clone()
+    }
+
+    /// Returns `true` if configuration has been replaced since the last
+    /// [`ConfigurationWatcher::changed()`] call (or since the watcher was created).
+    pub fn has_changed(&self) -> bool {
+        self.receiver.has_changed().unwrap_or(false)
+    }
+
+    /// Get currently-active configuration without marking it as seen.
+    pub fn get_configuration(&self) -> Option<Arc<Configuration>> {
+        self.receiver.borrow().clone()
     }
 }
 
@@ -48,11 +246,12 @@ impl ConfigurationStore {
 mod tests {
     use std::{collections::HashMap, sync::Arc};
 
-    use chrono::Utc;
+    use chrono::{DateTime, Utc};
 
     use super::ConfigurationStore;
     use crate::{
-        ufc::{CompiledFlagsConfig, Environment, UniversalFlagConfig},
+        bandits::BanditResponse,
+        ufc::{CompiledFlagsConfig, Environment, ParseDiagnostics, UniversalFlagConfig},
         Configuration,
     };
 
@@ -73,9 +272,15 @@ mod tests {
                             environment: Environment {
                                 name: "test".into(),
                             },
+                            obfuscated: false,
                             flags: HashMap::new(),
                             flag_to_bandit_associations: HashMap::new(),
+                            bandit_model_versions: HashMap::new(),
+                            hashed_flag_keys: Default::default(),
+                            diagnostics: ParseDiagnostics::default(),
+                            flags_bytes: 0,
                         },
+                        diagnostics: Default::default(),
                     },
                     None,
                 )))
@@ -85,4 +290,221 @@ mod tests {
 
         assert!(store.get_configuration().is_some());
     }
+
+    fn configuration(created_at: DateTime<Utc>) -> Arc<Configuration> {
+        Arc::new(Configuration::from_server_response(
+            UniversalFlagConfig {
+                wire_json: b"test-bytes".to_vec(),
+                compiled: CompiledFlagsConfig {
+                    created_at,
+                    environment: Environment {
+                        name: "test".into(),
+                    },
+                    obfuscated: false,
+                    flags: HashMap::new(),
+                    flag_to_bandit_associations: HashMap::new(),
+                    bandit_model_versions: HashMap::new(),
+                    hashed_flag_keys: Default::default(),
+                    diagnostics: ParseDiagnostics::default(),
+                    flags_bytes: 0,
+                },
+                diagnostics: Default::default(),
+            },
+            None,
+        ))
+    }
+
+    #[test]
+    fn set_configuration_if_newer_ignores_out_of_order_configuration() {
+        let store = ConfigurationStore::new();
+        let older = configuration(Utc::now());
+        let newer = configuration(older.flags.compiled.created_at + chrono::Duration::seconds(1));
+
+        assert!(store.set_configuration_if_newer(newer.clone()));
+        assert!(!store.set_configuration_if_newer(older.clone()));
+        assert!(Arc::ptr_eq(&store.get_configuration().unwrap(), &newer));
+
+        // Plain setter still overrides.
+        store.set_configuration(older.clone());
+        assert!(Arc::ptr_eq(&store.get_configuration().unwrap(), &older));
+    }
+
+    #[test]
+    fn set_configuration_if_newer_keeps_configuration_with_equal_timestamps() {
+        let store = ConfigurationStore::new();
+        let created_at = Utc::now();
+        let first = configuration(created_at);
+        assert!(store.set_configuration_if_newer(first.clone()));
+
+        let watcher = store.subscribe();
+        assert!(!store.set_configuration_if_newer(configuration(created_at)));
+        assert!(Arc::ptr_eq(&store.get_configuration().unwrap(), &first));
+        // Subscribers are not notified if configuration is not replaced.
+        assert!(!watcher.has_changed());
+    }
+
+    #[test]
+    fn set_configuration_if_newer_compares_bandits_updated_at() {
+        let store = ConfigurationStore::new();
+        let created_at = Utc::now();
+        let with_bandits = |updated_at| {
+            let mut configuration = Arc::try_unwrap(configuration(created_at)).unwrap();
+            configuration.bandits = Some(Arc::new(BanditResponse {
+                bandits: HashMap::new(),
+                updated_at,
+            }));
+            Arc::new(configuration)
+        };
+
+        assert!(store.set_configuration_if_newer(configuration(created_at)));
+        assert!(store.set_configuration_if_newer(with_bandits(created_at)));
+        assert!(!store.set_configuration_if_newer(with_bandits(created_at)));
+        assert!(store
+            .set_configuration_if_newer(with_bandits(created_at + chrono::Duration::seconds(1))));
+        assert!(!store.set_configuration_if_newer(with_bandits(created_at)));
+    }
+
+    #[test]
+    fn generation_is_incremented_when_configuration_is_replaced() {
+        let store = ConfigurationStore::new();
+        assert_eq!(store.generation(), 0);
+        assert_eq!(store.last_updated_at(), None);
+
+        let created_at = Utc::now();
+        store.set_configuration(configuration(created_at));
+        assert_eq!(store.generation(), 1);
+        let updated_at = store.last_updated_at().unwrap();
+
+        assert!(!store.set_configuration_if_newer(configuration(created_at)));
+        assert_eq!(store.generation(), 1);
+        assert_eq!(store.last_updated_at(), Some(updated_at));
+
+        store.set_configuration(configuration(created_at));
+        assert_eq!(store.generation(), 2);
+        assert!(store.last_updated_at().unwrap() >= updated_at);
+    }
+
+    #[test]
+    fn configuration_is_at_least_as_new_as_generation() {
+        const WRITES: u32 = 2000;
+
+        let store = Arc::new(ConfigurationStore::new());
+        let base = Utc::now();
+        // Generation N stores configuration created at `base + N` seconds.
+        let writer = {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                for i in 1..=WRITES {
+                    store.set_configuration(configuration(
+                        base + chrono::Duration::seconds(i.into()),
+                    ));
+                }
+            })
+        };
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let store = store.clone();
+                std::thread::spawn(move || {
+                    let mut last_generation = 0;
+                    while last_generation < u64::from(WRITES) {
+                        let generation = store.generation();
+                        let configuration = store.get_configuration();
+                        assert!(generation >= last_generation);
+                        if generation > 0 {
+                            let created_at = configuration.unwrap().flags.compiled.created_at;
+                            assert!(
+                                created_at >= base + chrono::Duration::seconds(generation as i64)
+                            );
+                        }
+                        last_generation = generation;
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(store.generation(), u64::from(WRITES));
+    }
+
+    #[tokio::test]
+    async fn notifies_subscribers_of_new_configuration() {
+        let store = ConfigurationStore::new();
+        let mut watcher = store.subscribe();
+        assert!(!watcher.has_changed());
+
+        let configuration = configuration(Utc::now());
+        store.set_configuration(configuration.clone());
+
+        assert!(watcher.has_changed());
+        assert!(Arc::ptr_eq(
+            &watcher.changed().await.unwrap(),
+            &configuration
+        ));
+        assert!(!watcher.has_changed());
+
+        drop(store);
+        assert!(watcher.changed().await.is_none());
+    }
+
+    #[test]
+    fn subscribers_observe_latest_configuration_with_concurrent_writers() {
+        const WRITERS: usize = 4;
+        const WRITES: usize = 500;
+        const SUBSCRIBERS: usize = 4;
+
+        let store = Arc::new(ConfigurationStore::new());
+        let last = configuration(Utc::now() + chrono::Duration::days(1));
+
+        let subscribers: Vec<_> = (0..SUBSCRIBERS)
+            .map(|_| {
+                let mut watcher = store.subscribe();
+                let last = last.clone();
+                std::thread::spawn(move || {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .build()
+                        .unwrap();
+                    runtime.block_on(async {
+                        let mut notifications = 0;
+                        loop {
+                            let configuration = watcher.changed().await.unwrap();
+                            notifications += 1;
+                            if Arc::ptr_eq(&configuration, &last) {
+                                return notifications;
+                            }
+                        }
+                    })
+                })
+            })
+            .collect();
+        let dropped: Vec<_> = (0..SUBSCRIBERS).map(|_| store.subscribe()).collect();
+
+        let writers: Vec<_> = (0..WRITERS)
+            .map(|_| {
+                let store = store.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..WRITES {
+                        store.set_configuration(configuration(Utc::now()));
+                    }
+                })
+            })
+            .collect();
+        // Subscribers may go away while writers are active.
+        drop(dropped);
+        assert_eq!(store.notifier.receiver_count(), SUBSCRIBERS);
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        store.set_configuration(last.clone());
+
+        for subscriber in subscribers {
+            let notifications = subscriber.join().unwrap();
+            assert!((1..=WRITERS * WRITES + 1).contains(&notifications));
+        }
+        assert!(Arc::ptr_eq(&store.get_configuration().unwrap(), &last));
+        assert_eq!(store.notifier.receiver_count(), 0);
+    }
 }
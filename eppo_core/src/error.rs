@@ -1,8 +1,8 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use serde::{Deserialize, Serialize};
 
-use crate::ufc::VariationType;
+use crate::{ufc::VariationType, Str};
 
 /// Represents a result type for operations in the Eppo SDK.
 ///
@@ -21,26 +21,146 @@ pub enum Error {
     #[error(transparent)]
     EvaluationError(EvaluationError),
 
+    /// Configuration supplied by the user or received from the server cannot be parsed.
+    #[error("error parsing configuration")]
+    // serde_json::Error is not clonable, so we're wrapping it in an Arc.
+    ConfigurationParseError(#[source] Arc<serde_json::Error>),
+
+    /// Configuration uses constructs unknown to this SDK version. Only returned when parsing in
+    /// [`ParseMode::Strict`](crate::ufc::ParseMode::Strict).
+    #[error(
+        "configuration uses constructs unknown to this SDK version (operators: {unknown_operators:?}, fields: {unknown_fields:?})"
+    )]
+    UnsupportedConfiguration {
+        /// Unknown condition operators along with keys of flags using them.
+        unknown_operators: Vec<(Str, String)>,
+        /// Paths of unknown fields.
+        unknown_fields: Vec<String>,
+    },
+
+    /// JSON flag value cannot be deserialized into the requested type.
+    #[error("error deserializing value of JSON flag {flag_key:?}")]
+    JsonDeserialization {
+        /// Key of the flag.
+        flag_key: String,
+        /// Deserialization error.
+        // serde_json::Error is not clonable, so we're wrapping it in an Arc.
+        #[source]
+        source: Arc<serde_json::Error>,
+    },
+
+    /// Client configuration supplied by the user is invalid.
+    #[error("invalid configuration: {0}")]
+    InvalidConfiguration(String),
+
     /// Invalid base URL configuration.
     #[error("invalid base_url configuration")]
     InvalidBaseUrl(#[source] url::ParseError),
 
+    /// Invalid HTTP header name or value in configuration.
+    #[error("invalid HTTP header: {0}")]
+    InvalidHeader(String),
+
+    /// Poll interval is zero.
+    #[error("invalid poll interval, must be greater than zero")]
+    InvalidPollInterval,
+
     /// The request was unauthorized, possibly due to an invalid API key.
     #[error("unauthorized, api_key is likely invalid")]
     Unauthorized,
 
+    /// The server is throttling requests (responded with 429 Too Many Requests).
+    #[error("rate limited by the server (retry after: {retry_after:?})")]
+    RateLimited {
+        /// Delay requested by the server via the `Retry-After` header, if any.
+        retry_after: Option<Duration>,
+    },
+
+    /// Configuration response is larger than `max_response_bytes`. The response is aborted
+    /// without reading it completely.
+    #[error("configuration response exceeds the limit of {limit} bytes")]
+    ResponseTooLarge {
+        /// Configured limit in bytes.
+        limit: usize,
+        /// Response size reported by the server via `Content-Length`, if any.
+        actual_hint: Option<u64>,
+    },
+
+    /// Configuration has not been fetched within the requested timeout.
+    #[error("timed out waiting for configuration")]
+    ConfigurationWaitTimeout,
+
     /// Indicates that the poller thread panicked. This should normally never happen.
     #[error("poller thread panicked")]
     PollerThreadPanicked,
 
+    /// The poller is not running: it has been stopped, or it hasn't been started.
+    #[error("poller is not running")]
+    PollerStopped,
+
     /// An I/O error.
     #[error(transparent)]
     // std::io::Error is not clonable, so we're wrapping it in an Arc.
     Io(Arc<std::io::Error>),
 
-    /// Network error.
-    #[error(transparent)]
-    Network(Arc<reqwest::Error>),
+    /// A configuration request failed: the server could not be reached (e.g., DNS, connection,
+    /// TLS, or timeout error) or responded with an unsuccessful HTTP status.
+    #[error("{}", network_error_message(.status, .url))]
+    NetworkError {
+        /// HTTP status returned by the server. `None` if no response was received.
+        status: Option<reqwest::StatusCode>,
+        /// Requested URL without query parameters (they include API key).
+        url: String,
+        /// Underlying error.
+        #[source]
+        source: Arc<reqwest::Error>,
+    },
+}
+
+fn network_error_message(status: &Option<reqwest::StatusCode>, url: &str) -> String {
+    match status {
+        Some(status) => format!("request to {url} failed with HTTP status {status}"),
+        None => format!("request to {url} failed"),
+    }
+}
+
+impl Error {
+    /// Returns `true` if the operation that produced this error may succeed when retried (e.g., a
+    /// network failure or a server error). Returns `false` for errors that require user action,
+    /// like an invalid API key or a misconfigured base URL.
+    ///
+    /// The poller stops polling after a non-retriable error.
+    ///
+    /// ```
+    /// # use eppo_core::Error;
+    /// assert!(!Error::Unauthorized.is_retriable());
+    /// assert!(Error::RateLimited { retry_after: None }.is_retriable());
+    /// ```
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Error::Unauthorized
+            | Error::InvalidBaseUrl(_)
+            | Error::InvalidHeader(_)
+            | Error::InvalidConfiguration(_)
+            | Error::InvalidPollInterval
+            | Error::PollerThreadPanicked
+            | Error::PollerStopped
+            | Error::EvaluationError(_)
+            | Error::JsonDeserialization { .. } => false,
+            // No response (connection failures, timeouts) and server errors are transient. Other
+            // client errors (e.g., 403 Forbidden or 404 Not Found) are often caused by a CDN or
+            // proxy misconfiguration that gets fixed without restarting the SDK, so they are
+            // retried with backoff too. Only invalid API key (`Unauthorized`) stops the poller.
+            Error::NetworkError { .. }
+            | Error::RateLimited { .. }
+            | Error::ResponseTooLarge { .. }
+            | Error::ConfigurationWaitTimeout
+            // Server may publish a fixed configuration.
+            | Error::ConfigurationParseError(_)
+            | Error::UnsupportedConfiguration { .. }
+            | Error::Io(_) => true,
+        }
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -49,9 +169,28 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::ConfigurationParseError(Arc::new(value))
+    }
+}
+
 impl From<reqwest::Error> for Error {
     fn from(value: reqwest::Error) -> Self {
-        Error::Network(Arc::new(value.without_url()))
+        let url = value
+            .url()
+            .map(|url| {
+                let mut url = url.clone();
+                url.set_query(None);
+                url.to_string()
+            })
+            .unwrap_or_default();
+        Error::NetworkError {
+            status: value.status(),
+            url,
+            // The URL includes API key, so it must not be exposed.
+            source: Arc::new(value.without_url()),
+        }
     }
 }
 
@@ -1,10 +1,12 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use chrono::{DateTime, Utc};
 
 use crate::{
     error::{EvaluationError, EvaluationFailure},
     events::AssignmentEvent,
+    obfuscation::md5_hex,
+    sharder::Sharder,
     ufc::{
         Allocation, Assignment, AssignmentValue, CompiledFlagsConfig, Flag, Shard, Split,
         Timestamp, VariationType,
@@ -13,8 +15,9 @@ use crate::{
 };
 
 use super::{
-    eval_details::EvaluationResultWithDetails,
+    eval_details::{EvaluationResultWithDetails, FlagEvaluationCode},
     eval_details_builder::EvalDetailsBuilder,
+    eval_options::EvaluationOptions,
     eval_visitor::{
         EvalAllocationVisitor, EvalAssignmentVisitor, EvalRuleVisitor, EvalSplitVisitor,
         NoopEvalVisitor,
@@ -31,18 +34,49 @@ pub fn get_assignment(
     subject_attributes: &Arc<Attributes>,
     expected_type: Option<VariationType>,
     now: DateTime<Utc>,
+    options: &EvaluationOptions,
 ) -> Result<Option<Assignment>, EvaluationError> {
     get_assignment_with_visitor(
         configuration,
         &mut NoopEvalVisitor,
         flag_key,
-        subject_key,
-        subject_attributes,
+        &Subject::new(subject_key.clone(), subject_attributes.clone()),
         expected_type,
         now,
+        options,
     )
 }
 
+/// Evaluate the specified feature flag for the given subject and return assigned variation along
+/// with a [`FlagEvaluationCode`] explaining the result.
+///
+/// This is a cheaper alternative to [`get_assignment_details()`] when only the reason is needed.
+/// Evaluation errors are not returned but are reflected in the code (and produce no assignment).
+pub fn get_assignment_with_reason(
+    configuration: Option<&Configuration>,
+    flag_key: &str,
+    subject_key: &Str,
+    subject_attributes: &Arc<Attributes>,
+    expected_type: Option<VariationType>,
+    now: DateTime<Utc>,
+    options: &EvaluationOptions,
+) -> (Option<Assignment>, FlagEvaluationCode) {
+    let result = eval_assignment_with_visitor(
+        configuration,
+        &mut NoopEvalVisitor,
+        flag_key,
+        &Subject::new(subject_key.clone(), subject_attributes.clone()),
+        expected_type,
+        now,
+        options,
+    );
+
+    let code = FlagEvaluationCode::from(result.as_ref().map(|_| ()).map_err(Clone::clone));
+    let assignment = handle_assignment_result(flag_key, subject_key, result).unwrap_or_default();
+
+    (assignment, code)
+}
+
 /// Evaluate the specified feature flag for the given subject and return evaluation details.
 pub fn get_assignment_details(
     configuration: Option<&Configuration>,
@@ -51,6 +85,7 @@ pub fn get_assignment_details(
     subject_attributes: &Arc<Attributes>,
     expected_type: Option<VariationType>,
     now: DateTime<Utc>,
+    options: &EvaluationOptions,
 ) -> (
     EvaluationResultWithDetails<AssignmentValue>,
     Option<AssignmentEvent>,
@@ -65,10 +100,10 @@ pub fn get_assignment_details(
         configuration,
         &mut details_builder,
         flag_key,
-        subject_key,
-        subject_attributes,
+        &Subject::new(subject_key.clone(), subject_attributes.clone()),
         expected_type,
         now,
+        options,
     );
 
     let (value, mut event) = match result.unwrap_or_default() {
@@ -91,33 +126,119 @@ pub fn get_assignment_details(
     (result_with_details, event)
 }
 
+/// Evaluate all flags in the configuration for the given subject and return assignments keyed by
+/// flag key. If `flag_keys` is `Some`, only the specified flags are evaluated.
+///
+/// Flags that fail to evaluate or don't produce an assignment (e.g., disabled flags or subject not
+/// matching any allocation) are omitted from the result. Returned assignments include events for
+/// allocations that need to be logged.
+///
+/// For obfuscated configuration, flag keys are not known unless `flag_keys` are specified, so
+/// the result is keyed by md5 hashes of flag keys in that case.
+pub fn get_all_assignments(
+    configuration: Option<&Configuration>,
+    subject_key: &Str,
+    subject_attributes: &Arc<Attributes>,
+    flag_keys: Option<&[&str]>,
+    now: DateTime<Utc>,
+    options: &EvaluationOptions,
+) -> HashMap<Str, Assignment> {
+    let Some(configuration) = configuration else {
+        log::warn!(target: "eppo",
+                   subject_key;
+                   "evaluating flags before Eppo configuration has been fetched");
+        return HashMap::new();
+    };
+
+    let compiled = &configuration.flags.compiled;
+    // Subject is shared between all flag evaluations.
+    let subject = Subject::new(subject_key.clone(), subject_attributes.clone());
+
+    let eval = |(flag_key, flag): (&Str, &Result<Flag, EvaluationFailure>)| {
+        let result = flag
+            .as_ref()
+            .map_err(Clone::clone)
+            .and_then(|flag| flag.eval_subject(&mut NoopEvalVisitor, &subject, now, options))
+            .map(|assignment| compiled.deobfuscate_assignment(flag_key, assignment));
+        match result {
+            Ok(assignment) => Some((flag_key.clone(), assignment)),
+            Err(err) => {
+                log::trace!(target: "eppo",
+                            flag_key:% = flag_key,
+                            subject_key;
+                            "skipping flag in bulk evaluation because of: {err}");
+                None
+            }
+        }
+    };
+
+    match flag_keys {
+        Some(flag_keys) => flag_keys
+            .iter()
+            .filter_map(|flag_key| {
+                let flag = compiled.lookup_flag(flag_key)?;
+                eval((&Str::from(*flag_key), flag))
+            })
+            .collect(),
+        // Keys of obfuscated configuration are hashed, so they are returned as is.
+        None => compiled.flags.iter().filter_map(eval).collect(),
+    }
+}
+
 // Exposed for use in bandit evaluation.
 pub(super) fn get_assignment_with_visitor<V: EvalAssignmentVisitor>(
     configuration: Option<&Configuration>,
     visitor: &mut V,
     flag_key: &str,
-    subject_key: &Str,
-    subject_attributes: &Arc<Attributes>,
+    subject: &Subject,
     expected_type: Option<VariationType>,
     now: DateTime<Utc>,
+    options: &EvaluationOptions,
 ) -> Result<Option<Assignment>, EvaluationError> {
+    let result = eval_assignment_with_visitor(
+        configuration,
+        visitor,
+        flag_key,
+        subject,
+        expected_type,
+        now,
+        options,
+    );
+    handle_assignment_result(flag_key, subject.key(), result)
+}
+
+/// Evaluate the flag and report the result to `visitor` without remapping failures.
+fn eval_assignment_with_visitor<V: EvalAssignmentVisitor>(
+    configuration: Option<&Configuration>,
+    visitor: &mut V,
+    flag_key: &str,
+    subject: &Subject,
+    expected_type: Option<VariationType>,
+    now: DateTime<Utc>,
+    options: &EvaluationOptions,
+) -> Result<Assignment, EvaluationFailure> {
     let result = if let Some(config) = configuration {
         visitor.on_configuration(config);
 
-        config.flags.compiled.eval_flag(
-            visitor,
-            &flag_key,
-            &subject_key,
-            &subject_attributes,
-            expected_type,
-            now,
-        )
+        config
+            .flags
+            .compiled
+            .eval_flag(visitor, flag_key, subject, expected_type, now, options)
     } else {
         Err(EvaluationFailure::ConfigurationMissing)
     };
 
     visitor.on_result(&result);
 
+    result
+}
+
+/// Log evaluation result and convert evaluation failures into user-facing result.
+fn handle_assignment_result(
+    flag_key: &str,
+    subject_key: &Str,
+    result: Result<Assignment, EvaluationFailure>,
+) -> Result<Option<Assignment>, EvaluationError> {
     match result {
         Ok(assignment) => {
             log::trace!(target: "eppo",
@@ -163,10 +284,10 @@ impl CompiledFlagsConfig {
         &self,
         visitor: &mut V,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject: &Subject,
         expected_type: Option<VariationType>,
         now: DateTime<Utc>,
+        options: &EvaluationOptions,
     ) -> Result<Assignment, EvaluationFailure> {
         let flag = self.get_flag(flag_key)?;
 
@@ -176,18 +297,61 @@ impl CompiledFlagsConfig {
             flag.verify_type(ty)?;
         }
 
-        flag.eval(visitor, subject_key, subject_attributes, now)
+        flag.eval_subject(visitor, subject, now, options)
+            .map(|assignment| self.deobfuscate_assignment(flag_key, assignment))
     }
 
     fn get_flag<'a>(&'a self, flag_key: &str) -> Result<&'a Flag, EvaluationFailure> {
         let flag = self
-            .flags
-            .get(flag_key)
+            .lookup_flag(flag_key)
             .ok_or(EvaluationFailure::FlagUnrecognizedOrDisabled)?
             .as_ref()
             .map_err(Clone::clone)?;
         Ok(flag)
     }
+
+    /// Variation type of the flag. `None` if the flag is unknown, disabled, or failed to parse.
+    pub(super) fn flag_variation_type(&self, flag_key: &str) -> Option<VariationType> {
+        self.get_flag(flag_key).ok().map(|flag| flag.variation_type)
+    }
+
+    /// Find flag by its key. Obfuscated configuration is keyed by md5 hash of the flag key.
+    fn lookup_flag(&self, flag_key: &str) -> Option<&Result<Flag, EvaluationFailure>> {
+        if !self.obfuscated {
+            return self.flags.get(flag_key);
+        }
+
+        let hashed_flag_keys = self
+            .hashed_flag_keys
+            .read()
+            .unwrap_or_else(|err| err.into_inner());
+        if let Some(hash) = hashed_flag_keys.get(flag_key) {
+            return self.flags.get(hash);
+        }
+        drop(hashed_flag_keys);
+
+        let (hash, flag) = self.flags.get_key_value(md5_hex(flag_key).as_str())?;
+        self.hashed_flag_keys
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(flag_key.into(), hash.clone());
+        Some(flag)
+    }
+
+    /// Events of obfuscated configuration are compiled with hashed flag key, so replace it with
+    /// the flag key the assignment was requested with.
+    fn deobfuscate_assignment(&self, flag_key: &str, mut assignment: Assignment) -> Assignment {
+        if self.obfuscated {
+            if let Some(event) = &mut assignment.event {
+                if *event.base.feature_flag != *flag_key {
+                    let base = Arc::make_mut(&mut event.base);
+                    base.feature_flag = flag_key.into();
+                    base.experiment = format!("{flag_key}-{}", base.allocation);
+                }
+            }
+        }
+        assignment
+    }
 }
 
 impl Flag {
@@ -202,18 +366,16 @@ impl Flag {
         }
     }
 
-    fn eval<V: EvalAssignmentVisitor>(
+    fn eval_subject<V: EvalAssignmentVisitor>(
         &self,
         visitor: &mut V,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject: &Subject,
         now: DateTime<Utc>,
+        options: &EvaluationOptions,
     ) -> Result<Assignment, EvaluationFailure> {
-        let subject = Subject::new(subject_key.clone(), subject_attributes.clone());
-
         let Some(split) = self.allocations.iter().find_map(|allocation| {
             let mut visitor = visitor.visit_allocation(allocation);
-            let result = allocation.get_matching_split(&mut visitor, &subject, now);
+            let result = allocation.get_matching_split(&mut visitor, subject, now, options.sharder);
             visitor.on_result(result);
             result.ok()
         }) else {
@@ -226,8 +388,8 @@ impl Flag {
             value,
             event: event_base.map(|base| AssignmentEvent {
                 base,
-                subject: subject_key.clone(),
-                subject_attributes: subject_attributes.clone(),
+                subject: subject.key().clone(),
+                subject_attributes: subject.attributes().clone(),
                 timestamp: now,
                 evaluation_details: None,
             }),
@@ -249,6 +411,7 @@ impl Allocation {
         visitor: &mut V,
         subject: &Subject,
         now: Timestamp,
+        sharder: Option<&dyn Sharder>,
     ) -> Result<&Split, AllocationNonMatchReason> {
         if self.start_at.is_some_and(|t| now < t) {
             return Err(AllocationNonMatchReason::BeforeStartDate);
@@ -272,7 +435,7 @@ impl Allocation {
             .iter()
             .find(|split| {
                 let mut visitor = visitor.visit_split(split);
-                let matches = split.matches(&mut visitor, subject.key());
+                let matches = split.matches(&mut visitor, subject.key(), sharder);
                 visitor.on_result(matches);
                 matches
             })
@@ -284,17 +447,34 @@ impl Split {
     /// Return `true` if `subject_key` matches the given split.
     ///
     /// To match a split, subject must match all underlying shards.
-    fn matches<V: EvalSplitVisitor>(&self, visitor: &mut V, subject_key: &str) -> bool {
+    fn matches<V: EvalSplitVisitor>(
+        &self,
+        visitor: &mut V,
+        subject_key: &str,
+        sharder: Option<&dyn Sharder>,
+    ) -> bool {
         self.shards
             .iter()
-            .all(|shard| shard.matches(visitor, subject_key))
+            .all(|shard| shard.matches(visitor, subject_key, sharder))
     }
 }
 
 impl Shard {
     /// Return `true` if `subject_key` matches the given shard.
-    fn matches<V: EvalSplitVisitor>(&self, visitor: &mut V, subject_key: &str) -> bool {
-        let h = self.sharder.shard(&[subject_key]);
+    ///
+    /// If `sharder` is `None`, the default md5 sharder with pre-computed salt is used.
+    fn matches<V: EvalSplitVisitor>(
+        &self,
+        visitor: &mut V,
+        subject_key: &str,
+        sharder: Option<&dyn Sharder>,
+    ) -> bool {
+        let h = match sharder {
+            Some(sharder) => {
+                sharder.shard(&[&self.salt, "-", subject_key], self.sharder.total_shards())
+            }
+            None => self.sharder.shard(&[subject_key]),
+        };
 
         let matches = self.ranges.iter().any(|range| range.contains(h));
         visitor.on_shard_eval(self, h, matches);
@@ -309,6 +489,7 @@ mod tests {
         sync::Arc,
     };
 
+    use base64::{prelude::BASE64_STANDARD, Engine};
     use chrono::Utc;
     use serde::{Deserialize, Serialize};
 
@@ -317,10 +498,12 @@ mod tests {
             eval_details::{
                 AllocationEvaluationCode, AllocationEvaluationDetails, FlagEvaluationCode,
             },
-            get_assignment, get_assignment_details,
+            get_all_assignments, get_assignment, get_assignment_details,
+            get_assignment_with_reason, EvaluationOptions,
         },
-        ufc::{RuleWire, UniversalFlagConfig, ValueWire, VariationType},
-        Attributes, Configuration, SdkMetadata, Str,
+        obfuscation::md5_hex,
+        ufc::{AssignmentValue, RuleWire, ValueWire, VariationType},
+        Attributes, Configuration, Str,
     };
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -425,17 +608,35 @@ mod tests {
 
     #[test]
     fn evaluation_sdk_test_data() {
+        check_sdk_test_data("../sdk-test-data/ufc/flags-v1.json");
+    }
+
+    #[test]
+    fn evaluation_sdk_test_data_obfuscated() {
+        check_sdk_test_data("../sdk-test-data/ufc/flags-v1-obfuscated.json");
+    }
+
+    #[test]
+    fn evaluation_sdk_test_data_after_export() {
+        let config =
+            load_configuration(std::fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap());
+
+        let exported = load_configuration(config.to_ufc_json().to_vec());
+
+        check_configuration(&exported);
+    }
+
+    fn load_configuration(json: Vec<u8>) -> Configuration {
+        Configuration::from_ufc_json_for_tests(json)
+    }
+
+    fn check_sdk_test_data(config_path: &str) {
+        check_configuration(&load_configuration(std::fs::read(config_path).unwrap()));
+    }
+
+    fn check_configuration(config: &Configuration) {
         let _ = env_logger::builder().is_test(true).try_init();
 
-        let config = UniversalFlagConfig::from_json(
-            SdkMetadata {
-                name: "test",
-                version: "0.1.0",
-            },
-            std::fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap(),
-        )
-        .unwrap();
-        let config = Configuration::from_server_response(config, None);
         let now = Utc::now();
 
         for entry in fs::read_dir("../sdk-test-data/ufc/tests/").unwrap() {
@@ -452,12 +653,13 @@ mod tests {
             for subject in test_file.subjects {
                 print!("test subject {:?} ... ", subject.subject_key);
                 let result = get_assignment(
-                    Some(&config),
+                    Some(config),
                     &test_file.flag,
                     &subject.subject_key,
                     &subject.subject_attributes,
                     Some(test_file.variation_type),
                     now,
+                    &EvaluationOptions::default(),
                 )
                 .unwrap_or(None);
 
@@ -475,19 +677,350 @@ mod tests {
         }
     }
 
+    #[test]
+    fn evaluation_obfuscated_config() {
+        let b64 = |s: &str| BASE64_STANDARD.encode(s);
+        let json = format!(
+            r#"{{
+              "createdAt": "2024-07-18T00:00:00Z",
+              "format": "CLIENT",
+              "environment": {{"name": "test"}},
+              "flags": {{
+                "{flag_hash}": {{
+                  "key": "{flag_hash}",
+                  "enabled": true,
+                  "variationType": "INTEGER",
+                  "totalShards": 10000,
+                  "variations": {{
+                    "{on}": {{"key": "{on}", "value": "{value}"}}
+                  }},
+                  "allocations": [{{
+                    "key": "{allocation}",
+                    "startAt": "{start_at}",
+                    "rules": [{{"conditions": [{{
+                      "attribute": "{attribute}",
+                      "operator": "{one_of}",
+                      "value": ["{country}"]
+                    }}]}}],
+                    "splits": [{{"variationKey": "{on}", "shards": []}}],
+                    "doLog": true
+                  }}]
+                }}
+              }}
+            }}"#,
+            flag_hash = md5_hex("obfuscated-flag"),
+            on = b64("on"),
+            value = b64("42"),
+            allocation = b64("allocation-1"),
+            start_at = b64("2024-01-01T00:00:00Z"),
+            attribute = md5_hex("country"),
+            one_of = md5_hex("ONE_OF"),
+            country = md5_hex("US"),
+        );
+        let config = Configuration::from_ufc_json_for_tests(json);
+
+        let assignment = get_assignment(
+            Some(&config),
+            "obfuscated-flag",
+            &"subject".into(),
+            &Arc::new([("country".into(), "US".into())].into_iter().collect()),
+            Some(VariationType::Integer),
+            Utc::now(),
+            &EvaluationOptions::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(assignment.value, AssignmentValue::Integer(42));
+        let event = assignment.event.unwrap();
+        assert_eq!(&*event.base.feature_flag, "obfuscated-flag");
+        assert_eq!(&*event.base.allocation, "allocation-1");
+        assert_eq!(&*event.base.variation, "on");
+
+        let assignment = get_assignment(
+            Some(&config),
+            "obfuscated-flag",
+            &"subject".into(),
+            &Arc::new([("country".into(), "UK".into())].into_iter().collect()),
+            Some(VariationType::Integer),
+            Utc::now(),
+            &EvaluationOptions::default(),
+        )
+        .unwrap();
+        assert!(assignment.is_none());
+
+        // Hashes of existing flag keys are cached, so they are only computed once.
+        let assignment = get_assignment(
+            Some(&config),
+            "unknown-flag",
+            &"subject".into(),
+            &Arc::default(),
+            None,
+            Utc::now(),
+            &EvaluationOptions::default(),
+        )
+        .unwrap();
+        assert!(assignment.is_none());
+        let hashed_flag_keys = config.flags.compiled.hashed_flag_keys.read().unwrap();
+        assert_eq!(
+            hashed_flag_keys
+                .get("obfuscated-flag")
+                .map(|hash| hash.as_str()),
+            Some(md5_hex("obfuscated-flag").as_str())
+        );
+        assert_eq!(hashed_flag_keys.len(), 1);
+    }
+
+    #[test]
+    fn custom_sharder_selects_split() {
+        use std::sync::Mutex;
+
+        use crate::sharder::{Md5Sharder, Sharder};
+
+        /// Returns a fixed shard and records inputs.
+        struct FixedSharder {
+            shard: u32,
+            inputs: Mutex<Vec<(String, u32)>>,
+        }
+
+        impl Sharder for FixedSharder {
+            fn shard(&self, input: &[&str], total_shards: u32) -> u32 {
+                self.inputs
+                    .lock()
+                    .unwrap()
+                    .push((input.concat(), total_shards));
+                self.shard
+            }
+        }
+
+        let config = Configuration::from_ufc_json_for_tests(br#"{
+              "createdAt": "2024-07-18T00:00:00Z",
+              "format": "SERVER",
+              "environment": {"name": "test"},
+              "flags": {
+                "split-flag": {
+                  "key": "split-flag",
+                  "enabled": true,
+                  "variationType": "STRING",
+                  "totalShards": 10000,
+                  "variations": {
+                    "a": {"key": "a", "value": "a"},
+                    "b": {"key": "b", "value": "b"}
+                  },
+                  "allocations": [{
+                    "key": "allocation",
+                    "rules": [],
+                    "splits": [
+                      {"variationKey": "a", "shards": [{"salt": "split-salt", "ranges": [{"start": 0, "end": 5000}]}]},
+                      {"variationKey": "b", "shards": [{"salt": "split-salt", "ranges": [{"start": 5000, "end": 10000}]}]}
+                    ],
+                    "doLog": false
+                  }]
+                }
+              }
+            }"#
+            );
+        let assign = |subject_key: &str, sharder: Option<&dyn Sharder>| {
+            get_assignment(
+                Some(&config),
+                "split-flag",
+                &subject_key.into(),
+                &Arc::new(Attributes::new()),
+                Some(VariationType::String),
+                Utc::now(),
+                &EvaluationOptions {
+                    sharder,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .unwrap()
+            .value
+        };
+
+        let sharder = FixedSharder {
+            shard: 7000,
+            inputs: Mutex::new(Vec::new()),
+        };
+        assert_eq!(
+            assign("subject", Some(&sharder)),
+            AssignmentValue::String("b".into())
+        );
+        assert_eq!(
+            sharder.inputs.lock().unwrap().as_slice(),
+            [
+                ("split-salt-subject".to_owned(), 10000),
+                ("split-salt-subject".to_owned(), 10000)
+            ]
+        );
+
+        // The default sharder is md5 with pre-computed salt.
+        for subject_key in ["alice", "bob", "charlie", "dave", "eve"] {
+            assert_eq!(
+                assign(subject_key, None),
+                assign(subject_key, Some(&Md5Sharder))
+            );
+        }
+    }
+
+    #[test]
+    fn disabled_flags_are_distinguished_from_unknown_flags() {
+        let json = |format: &str, flag_key: &str| {
+            format!(
+                r#"{{
+                  "createdAt": "2024-07-18T00:00:00Z",
+                  "format": "{format}",
+                  "environment": {{"name": "test"}},
+                  "flags": {{
+                    "{flag_key}": {{
+                      "key": "{flag_key}",
+                      "enabled": false,
+                      "variationType": "STRING",
+                      "totalShards": 10000,
+                      "variations": {{}},
+                      "allocations": []
+                    }}
+                  }}
+                }}"#
+            )
+            .into_bytes()
+        };
+        let reason = |config: &Configuration, flag_key: &str| {
+            get_assignment_with_reason(
+                Some(config),
+                flag_key,
+                &"subject".into(),
+                &Arc::new(Attributes::new()),
+                None,
+                Utc::now(),
+                &EvaluationOptions::default(),
+            )
+            .1
+        };
+
+        let config = Configuration::from_ufc_json_for_tests(json("SERVER", "disabled-flag"));
+        assert_eq!(
+            reason(&config, "disabled-flag"),
+            FlagEvaluationCode::FlagDisabled
+        );
+        assert_eq!(
+            reason(&config, "unknown-flag"),
+            FlagEvaluationCode::FlagUnrecognizedOrDisabled
+        );
+        // Evaluation details keep the merged code.
+        let (details, _) = get_assignment_details(
+            Some(&config),
+            "disabled-flag",
+            &"subject".into(),
+            &Arc::new(Attributes::new()),
+            None,
+            Utc::now(),
+            &EvaluationOptions::default(),
+        );
+        assert_eq!(
+            details.flag_evaluation_code(),
+            Some(FlagEvaluationCode::FlagUnrecognizedOrDisabled)
+        );
+
+        // Obfuscated configuration does not reveal which flags exist.
+        let config =
+            Configuration::from_ufc_json_for_tests(json("CLIENT", &md5_hex("disabled-flag")));
+        assert_eq!(
+            reason(&config, "disabled-flag"),
+            FlagEvaluationCode::FlagUnrecognizedOrDisabled
+        );
+    }
+
+    #[test]
+    fn get_all_assignments_with_flag_keys_matches_individual_assignments() {
+        let config = Configuration::from_ufc_json_for_tests(br#"{
+              "createdAt": "2024-07-18T00:00:00Z",
+              "format": "SERVER",
+              "environment": {"name": "test"},
+              "flags": {
+                "color": {
+                  "key": "color",
+                  "enabled": true,
+                  "variationType": "STRING",
+                  "totalShards": 10000,
+                  "variations": {"blue": {"key": "blue", "value": "blue"}},
+                  "allocations": [{
+                    "key": "everyone",
+                    "rules": [],
+                    "splits": [{"variationKey": "blue", "shards": []}],
+                    "doLog": true
+                  }]
+                },
+                "size": {
+                  "key": "size",
+                  "enabled": true,
+                  "variationType": "INTEGER",
+                  "totalShards": 10000,
+                  "variations": {"large": {"key": "large", "value": 3}},
+                  "allocations": [{
+                    "key": "customers",
+                    "rules": [{"conditions": [{"attribute": "customer", "operator": "IS_NULL", "value": false}]}],
+                    "splits": [{"variationKey": "large", "shards": []}],
+                    "doLog": false
+                  }]
+                }
+              }
+            }"#
+            );
+        let subject_key = Str::from("subject");
+        let now = Utc::now();
+        let flag_keys = ["color", "size", "unknown-flag"];
+
+        for attributes in [
+            Attributes::new(),
+            [("customer".into(), true.into())].into_iter().collect(),
+        ] {
+            let attributes = Arc::new(attributes);
+            let assignments = get_all_assignments(
+                Some(&config),
+                &subject_key,
+                &attributes,
+                Some(&flag_keys),
+                now,
+                &EvaluationOptions::default(),
+            );
+
+            for flag_key in flag_keys {
+                let expected = get_assignment(
+                    Some(&config),
+                    flag_key,
+                    &subject_key,
+                    &attributes,
+                    None,
+                    now,
+                    &EvaluationOptions::default(),
+                )
+                .unwrap()
+                .map(|assignment| (assignment.value, assignment.event.is_some()));
+                let actual = assignments
+                    .get(flag_key)
+                    .map(|assignment| (assignment.value.clone(), assignment.event.is_some()));
+                assert_eq!(actual, expected, "flag {flag_key}");
+            }
+        }
+
+        assert!(get_all_assignments(
+            None,
+            &subject_key,
+            &Arc::new(Attributes::new()),
+            Some(&flag_keys),
+            now,
+            &EvaluationOptions::default(),
+        )
+        .is_empty());
+    }
+
     #[test]
     fn evaluation_details_sdk_test_data() {
         let _ = env_logger::builder().is_test(true).try_init();
 
-        let config = UniversalFlagConfig::from_json(
-            SdkMetadata {
-                name: "test",
-                version: "0.1.0",
-            },
+        let config = Configuration::from_ufc_json_for_tests(
             std::fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap(),
-        )
-        .unwrap();
-        let config = Configuration::from_server_response(config, None);
+        );
         let now = Utc::now();
 
         for entry in fs::read_dir("../sdk-test-data/ufc/tests/").unwrap() {
@@ -506,6 +1039,7 @@ mod tests {
                     &subject.subject_attributes,
                     Some(test_file.variation_type),
                     now,
+                    &EvaluationOptions::default(),
                 );
 
                 let actual = result.evaluation_details;
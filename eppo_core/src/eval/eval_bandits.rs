@@ -1,29 +1,37 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 use crate::bandits::{
-    BanditCategoricalAttributeCoefficient, BanditModelData, BanditNumericAttributeCoefficient,
+    BanditCategoricalAttributeCoefficient, BanditConfiguration, BanditModelData,
+    BanditNumericAttributeCoefficient,
 };
 use crate::error::EvaluationFailure;
 use crate::events::{AssignmentEvent, BanditEvent};
-use crate::sharder::get_md5_shard;
-use crate::ufc::{Assignment, AssignmentValue, VariationType};
+use crate::sharder::{Md5Sharder, Sharder};
+use crate::ufc::VariationType;
 use crate::{Configuration, EvaluationError, Str};
 use crate::{ContextAttributes, SdkMetadata};
 
 use super::eval_assignment::get_assignment_with_visitor;
 use super::eval_details::EvaluationDetails;
 use super::eval_details_builder::EvalDetailsBuilder;
-use super::eval_visitor::{EvalBanditVisitor, NoopEvalVisitor};
+use super::eval_options::EvaluationOptions;
+use super::eval_visitor::{
+    AllocationKeyVisitor, EvalAssignmentVisitor, EvalBanditVisitor, NoopEvalVisitor,
+};
+use super::subject::Subject;
 
 #[derive(Debug)]
-pub(super) struct BanditEvaluationDetails {
+pub(super) struct BanditEvaluationDetails<'a> {
     /// Selected action.
     pub(super) action_key: Str,
+    pub(super) action_attributes: &'a ContextAttributes,
     pub(super) action_weight: f64,
+    /// Score of the selected action.
+    pub(super) action_score: f64,
     /// Distance between best and selected actions' scores.
     pub(super) optimality_gap: f64,
 }
@@ -33,6 +41,95 @@ struct Action<'a> {
     attributes: &'a ContextAttributes,
 }
 
+/// Actions supplied to bandit evaluation.
+///
+/// Actions without contextual attributes can be supplied as a slice of keys, which avoids
+/// building a map of empty attributes for every evaluation. Both forms produce the same selection
+/// for the same actions.
+#[derive(Debug, Clone, Copy)]
+pub enum Actions<'a> {
+    /// Action keys. All actions have empty attributes. Duplicate keys are considered once.
+    Keys(&'a [Str]),
+    /// Action keys along with their attributes.
+    WithAttributes(&'a HashMap<Str, ContextAttributes>),
+}
+
+impl<'a> Actions<'a> {
+    /// Iterate over action keys and their attributes.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a Str, &'a ContextAttributes)> {
+        let (keys, with_attributes) = match *self {
+            Actions::Keys(keys) => (Some(keys), None),
+            Actions::WithAttributes(actions) => (None, Some(actions)),
+        };
+        keys.into_iter()
+            .flatten()
+            .map(|key| (key, empty_attributes()))
+            .chain(with_attributes.into_iter().flatten())
+    }
+
+    /// Get attributes of action `key`. Returns `None` if there is no such action.
+    pub fn get(&self, key: &str) -> Option<&'a ContextAttributes> {
+        match *self {
+            Actions::Keys(keys) => keys
+                .iter()
+                .any(|it| it.as_str() == key)
+                .then(empty_attributes),
+            Actions::WithAttributes(actions) => actions.get(key),
+        }
+    }
+}
+
+impl<'a> From<&'a [Str]> for Actions<'a> {
+    fn from(keys: &'a [Str]) -> Self {
+        Actions::Keys(keys)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [Str; N]> for Actions<'a> {
+    fn from(keys: &'a [Str; N]) -> Self {
+        Actions::Keys(keys)
+    }
+}
+
+impl<'a> From<&'a Vec<Str>> for Actions<'a> {
+    fn from(keys: &'a Vec<Str>) -> Self {
+        Actions::Keys(keys)
+    }
+}
+
+impl<'a> From<&'a HashMap<Str, ContextAttributes>> for Actions<'a> {
+    fn from(actions: &'a HashMap<Str, ContextAttributes>) -> Self {
+        Actions::WithAttributes(actions)
+    }
+}
+
+/// Attributes shared by all actions supplied without attributes.
+fn empty_attributes() -> &'static ContextAttributes {
+    static EMPTY: OnceLock<ContextAttributes> = OnceLock::new();
+    EMPTY.get_or_init(ContextAttributes::default)
+}
+
+/// Options controlling bandit evaluation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BanditEvaluationOptions {
+    /// How to handle actions the bandit model has no coefficients for.
+    pub unknown_action_policy: UnknownActionPolicy,
+}
+
+/// How to handle actions the bandit model has no coefficients for (e.g., actions added after
+/// the model was last trained).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownActionPolicy {
+    /// Score unknown actions with the model's default action score, so they can be explored.
+    #[default]
+    UseDefaultScore,
+    /// Never select unknown actions. If none of the supplied actions are known to the model,
+    /// bandit evaluation fails with
+    /// [`NoActionsSuppliedForBandit`](super::eval_details::BanditEvaluationCode::NoActionsSuppliedForBandit)
+    /// and the flag variation is returned without an action.
+    Exclude,
+}
+
 /// Result of evaluating a bandit.
 #[derive(Debug, Clone, Serialize)]
 pub struct BanditResult {
@@ -40,33 +137,47 @@ pub struct BanditResult {
     pub variation: Str,
     /// Selected action if any.
     pub action: Option<Str>,
+    /// Key of the flag allocation the variation was assigned by. `None` if the flag could not be
+    /// evaluated and `default_variation` is returned.
+    ///
+    /// Unlike `assignment_event`, this is also set for allocations that are not logged.
+    pub allocation_key: Option<Str>,
+    /// Experiment of the flag allocation the variation was assigned by (`"{flag_key}-{allocation_key}"`).
+    /// `None` if `allocation_key` is `None`.
+    pub experiment: Option<Str>,
     /// Flag assignment event that needs to be logged to analytics storage.
     pub assignment_event: Option<AssignmentEvent>,
     /// Bandit assignment event that needs to be logged to analytics storage.
     pub bandit_event: Option<BanditEvent>,
 }
 
+/// Bandit evaluation request: the flag to evaluate, the subject to evaluate it for, and the
+/// actions to choose from.
+#[derive(Debug, Clone, Copy)]
+pub struct BanditRequest<'a> {
+    pub flag_key: &'a str,
+    pub subject_key: &'a Str,
+    pub subject_attributes: &'a ContextAttributes,
+    pub actions: Actions<'a>,
+    /// Variation returned if the flag cannot be evaluated.
+    pub default_variation: &'a Str,
+}
+
 /// Evaluate the specified string feature flag for the given subject. If resulting variation is
 /// a bandit, evaluate the bandit to return the action.
 pub fn get_bandit_action(
     configuration: Option<&Configuration>,
-    flag_key: &str,
-    subject_key: &Str,
-    subject_attributes: &ContextAttributes,
-    actions: &HashMap<Str, ContextAttributes>,
-    default_variation: &Str,
+    request: &BanditRequest,
     now: DateTime<Utc>,
+    options: &EvaluationOptions,
     sdk_meta: &SdkMetadata,
 ) -> BanditResult {
     get_bandit_action_with_visitor(
         &mut NoopEvalVisitor,
         configuration,
-        flag_key,
-        subject_key,
-        subject_attributes,
-        actions,
-        default_variation,
+        request,
         now,
+        options,
         sdk_meta,
     )
 }
@@ -75,52 +186,50 @@ pub fn get_bandit_action(
 /// a bandit, evaluate the bandit to return the action. In addition, return evaluation details.
 pub fn get_bandit_action_details(
     configuration: Option<&Configuration>,
-    flag_key: &str,
-    subject_key: &Str,
-    subject_attributes: &ContextAttributes,
-    actions: &HashMap<Str, ContextAttributes>,
-    default_variation: &Str,
+    request: &BanditRequest,
     now: DateTime<Utc>,
+    options: &EvaluationOptions,
     sdk_meta: &SdkMetadata,
 ) -> (BanditResult, EvaluationDetails) {
     let mut builder = EvalDetailsBuilder::new(
-        flag_key.to_owned(),
-        subject_key.to_owned(),
-        subject_attributes.to_generic_attributes().into(),
+        request.flag_key.to_owned(),
+        request.subject_key.to_owned(),
+        request.subject_attributes.to_generic_attributes().into(),
         now,
     );
-    let result = get_bandit_action_with_visitor(
+    let mut result = get_bandit_action_with_visitor(
         &mut builder,
         configuration,
-        flag_key,
-        subject_key,
-        subject_attributes,
-        actions,
-        default_variation,
+        request,
         now,
+        options,
         sdk_meta,
     );
     let details = builder.build();
+
+    if let Some(event) = &mut result.assignment_event {
+        event.evaluation_details = Some(Arc::new(details.clone()));
+    }
+
     (result, details)
 }
 
 /// Evaluate the specified string feature flag for the given subject. If resulting variation is
 /// a bandit, evaluate the bandit to return the action.
-fn get_bandit_action_with_visitor<V: EvalBanditVisitor>(
+pub(super) fn get_bandit_action_with_visitor<V: EvalBanditVisitor>(
     visitor: &mut V,
     configuration: Option<&Configuration>,
-    flag_key: &str,
-    subject_key: &Str,
-    subject_attributes: &ContextAttributes,
-    actions: &HashMap<Str, ContextAttributes>,
-    default_variation: &Str,
+    request: &BanditRequest,
     now: DateTime<Utc>,
+    options: &EvaluationOptions,
     sdk_meta: &SdkMetadata,
 ) -> BanditResult {
     let Some(configuration) = configuration else {
         let result = BanditResult {
-            variation: default_variation.clone(),
+            variation: request.default_variation.clone(),
             action: None,
+            allocation_key: None,
+            experiment: None,
             assignment_event: None,
             bandit_event: None,
         };
@@ -130,34 +239,16 @@ fn get_bandit_action_with_visitor<V: EvalBanditVisitor>(
 
     visitor.on_configuration(configuration);
 
-    let assignment = get_assignment_with_visitor(
-        Some(configuration),
-        &mut visitor.visit_assignment(),
-        flag_key,
-        subject_key,
-        &Arc::new(subject_attributes.to_generic_attributes()),
-        Some(VariationType::String),
+    let mut result = evaluate_bandit_flag(
+        visitor.visit_assignment(),
+        configuration,
+        request,
         now,
-    )
-    .unwrap_or_default()
-    .unwrap_or_else(|| Assignment {
-        value: AssignmentValue::String(default_variation.clone()),
-        event: None,
-    });
-
-    let variation = assignment
-        .value
-        .to_string()
-        .expect("flag assignment in bandit evaluation is always a string");
+        options,
+    );
 
-    let Some(bandit_key) = configuration.get_bandit_key(flag_key, &variation) else {
+    let Some(bandit_key) = configuration.get_bandit_key(request.flag_key, &result.variation) else {
         // It's not a bandit variation, just return it.
-        let result = BanditResult {
-            variation,
-            action: None,
-            assignment_event: assignment.event,
-            bandit_event: None,
-        };
         visitor.on_result(Err(EvaluationFailure::NonBanditVariation), &result);
         return result;
     };
@@ -171,12 +262,6 @@ fn get_bandit_action_with_visitor<V: EvalBanditVisitor>(
         // This should normally never happen as it means that there's a mismatch between the
         // general UFC config and bandits config.
         log::warn!(target: "eppo", bandit_key; "unable to find bandit configuration");
-        let result = BanditResult {
-            variation,
-            action: None,
-            assignment_event: assignment.event,
-            bandit_event: None,
-        };
         visitor.on_result(
             Err(EvaluationFailure::Error(
                 EvaluationError::UnexpectedConfigurationError,
@@ -186,73 +271,307 @@ fn get_bandit_action_with_visitor<V: EvalBanditVisitor>(
         return result;
     };
 
-    let evaluation =
-        match bandit
-            .model_data
-            .evaluate(flag_key, subject_key, subject_attributes, actions)
-        {
-            Ok(evaluation) => evaluation,
-            Err(err) => {
-                // We've evaluated a flag but now bandit evaluation failed. (Likely to user supplying
-                // empty actions, or NaN attributes.)
-                //
-                // Abort evaluation and return default variant.
-                let result = BanditResult {
-                    variation,
-                    action: None,
-                    assignment_event: assignment.event,
-                    bandit_event: None,
-                };
-                visitor.on_result(Err(err), &result);
-                return result;
+    let evaluation = match bandit.model_data.evaluate(
+        visitor,
+        request.flag_key,
+        request.subject_key,
+        request.subject_attributes,
+        request.actions,
+        options,
+    ) {
+        Ok(evaluation) => evaluation,
+        Err(err) => {
+            // We've evaluated a flag but now bandit evaluation failed. (Likely to user supplying
+            // empty actions, or NaN attributes.)
+            //
+            // Abort evaluation and return default variant.
+            visitor.on_result(Err(err), &result);
+            return result;
+        }
+    };
+
+    let bandit_event = bandit_event(request, bandit_key, bandit, &evaluation, now, sdk_meta);
+
+    result.action = Some(evaluation.action_key);
+    result.bandit_event = Some(bandit_event);
+    visitor.on_result(Ok(()), &result);
+    result
+}
+
+/// Evaluate the specified string feature flag for the given subject. If resulting variation is
+/// a bandit, return up to `k` best actions for the subject, ranked by the bandit.
+///
+/// Actions are sampled without replacement: the first action is the same as returned by
+/// [`get_bandit_action()`], and every next action is selected from the remaining ones with
+/// their weights re-normalized. Selection is pseudo-random but deterministic, so the same inputs
+/// always produce the same ranking.
+///
+/// Every result carries its own bandit event, so all shown actions can be logged. The
+/// `action_probability` of an event is the probability of selecting the action among actions
+/// remaining at its rank. The flag assignment event is only attached to the first result.
+///
+/// If the variation is not a bandit or bandit evaluation fails, a single result without action
+/// is returned, same as [`get_bandit_action()`] does.
+pub fn get_bandit_actions_ranked(
+    configuration: Option<&Configuration>,
+    request: &BanditRequest,
+    k: usize,
+    now: DateTime<Utc>,
+    options: &EvaluationOptions,
+    sdk_meta: &SdkMetadata,
+) -> Vec<BanditResult> {
+    let Some(configuration) = configuration else {
+        return vec![BanditResult {
+            variation: request.default_variation.clone(),
+            action: None,
+            allocation_key: None,
+            experiment: None,
+            assignment_event: None,
+            bandit_event: None,
+        }];
+    };
+
+    let mut result = evaluate_bandit_flag(NoopEvalVisitor, configuration, request, now, options);
+
+    let bandit = configuration
+        .get_bandit_key(request.flag_key, &result.variation)
+        .and_then(|bandit_key| {
+            let bandit = configuration.get_bandit(bandit_key);
+            if bandit.is_none() {
+                log::warn!(target: "eppo", bandit_key; "unable to find bandit configuration");
             }
+            Some((bandit_key, bandit?))
+        });
+    let ranking = bandit.map(|(bandit_key, bandit)| {
+        let ranking = bandit.model_data.rank(
+            request.flag_key,
+            request.subject_key,
+            request.subject_attributes,
+            request.actions,
+            k,
+            options,
+        );
+        (bandit_key, bandit, ranking)
+    });
+    let Some((bandit_key, bandit, Ok(ranking))) = ranking else {
+        return vec![result];
+    };
+
+    let mut assignment_event = result.assignment_event.take();
+    ranking
+        .into_iter()
+        .map(|evaluation| {
+            let bandit_event =
+                bandit_event(request, bandit_key, bandit, &evaluation, now, sdk_meta);
+            BanditResult {
+                action: Some(evaluation.action_key),
+                assignment_event: assignment_event.take(),
+                bandit_event: Some(bandit_event),
+                ..result.clone()
+            }
+        })
+        .collect()
+}
+
+/// Evaluate the bandit flag as a string flag. Returns result without action, falling back to
+/// `default_variation` if the flag cannot be evaluated.
+fn evaluate_bandit_flag<V: EvalAssignmentVisitor>(
+    visitor: V,
+    configuration: &Configuration,
+    request: &BanditRequest,
+    now: DateTime<Utc>,
+    options: &EvaluationOptions,
+) -> BanditResult {
+    let flag_key = request.flag_key;
+    let mut visitor = AllocationKeyVisitor::new(visitor);
+    let assignment = get_assignment_with_visitor(
+        Some(configuration),
+        &mut visitor,
+        flag_key,
+        &Subject::new(
+            request.subject_key.clone(),
+            Arc::new(request.subject_attributes.to_generic_attributes()),
+        ),
+        Some(VariationType::String),
+        now,
+        options,
+    )
+    .unwrap_or_default();
+
+    let Some(assignment) = assignment else {
+        return BanditResult {
+            variation: request.default_variation.clone(),
+            action: None,
+            allocation_key: None,
+            experiment: None,
+            assignment_event: None,
+            bandit_event: None,
         };
+    };
+
+    let variation = assignment
+        .value
+        .to_string()
+        .expect("flag assignment in bandit evaluation is always a string");
+    let allocation_key = visitor.into_allocation_key();
+    let experiment = allocation_key
+        .as_ref()
+        .map(|allocation_key| format!("{flag_key}-{allocation_key}").into());
+
+    BanditResult {
+        variation,
+        action: None,
+        allocation_key,
+        experiment,
+        assignment_event: assignment.event,
+        bandit_event: None,
+    }
+}
 
-    let action_attributes = &actions[&evaluation.action_key];
-    let bandit_event = BanditEvent {
-        flag_key: flag_key.into(),
+fn bandit_event(
+    request: &BanditRequest,
+    bandit_key: &Str,
+    bandit: &BanditConfiguration,
+    evaluation: &BanditEvaluationDetails,
+    now: DateTime<Utc>,
+    sdk_meta: &SdkMetadata,
+) -> BanditEvent {
+    let action_attributes = evaluation.action_attributes;
+    BanditEvent {
+        flag_key: request.flag_key.into(),
         bandit_key: bandit_key.clone(),
-        subject: subject_key.clone(),
+        subject: request.subject_key.clone(),
         action: evaluation.action_key.clone(),
         action_probability: evaluation.action_weight,
         optimality_gap: evaluation.optimality_gap,
+        action_score: evaluation.action_score,
+        gamma: bandit.model_data.gamma,
         model_version: bandit.model_version.clone(),
         timestamp: now.to_rfc3339(),
-        subject_numeric_attributes: subject_attributes.numeric.clone(),
-        subject_categorical_attributes: subject_attributes.categorical.clone(),
+        subject_numeric_attributes: request.subject_attributes.numeric.clone(),
+        subject_categorical_attributes: request.subject_attributes.categorical.clone(),
         action_numeric_attributes: action_attributes.numeric.clone(),
         action_categorical_attributes: action_attributes.categorical.clone(),
         meta_data: sdk_meta.into(),
-    };
-
-    let result = BanditResult {
-        variation,
-        action: Some(evaluation.action_key),
-        assignment_event: assignment.event,
-        bandit_event: Some(bandit_event),
-    };
-    visitor.on_result(Ok(()), &result);
-    return result;
+    }
 }
 
 impl BanditModelData {
     // Exported to super, so we can use it in precomputed evaluation.
-    pub(super) fn evaluate(
+    pub(super) fn evaluate<'a, V: EvalBanditVisitor>(
         &self,
+        visitor: &mut V,
         flag_key: &str,
         subject_key: &str,
         subject_attributes: &ContextAttributes,
-        actions: &HashMap<Str, ContextAttributes>,
-    ) -> Result<BanditEvaluationDetails, EvaluationFailure> {
-        // total_shards is not configurable at the moment.
-        const TOTAL_SHARDS: u32 = 10_000;
+        actions: Actions<'a>,
+        options: &EvaluationOptions,
+    ) -> Result<BanditEvaluationDetails<'a>, EvaluationFailure> {
+        let sharder = options.sharder.unwrap_or(&Md5Sharder);
 
-        if actions.len() == 0 {
-            return Err(EvaluationFailure::NoActionsSuppliedForBandit);
+        let actions = self.weigh_subject_actions(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            actions,
+            options,
+        )?;
+        visitor.on_bandit_actions(&actions.scores, &actions.weights);
+
+        let selection_hash = selection_hash(sharder, &[flag_key, "-", subject_key]);
+        let selected_action =
+            select_action(&actions.shuffled, &actions.weights, 1.0, selection_hash)
+                .map(|i| actions.shuffled[i])
+                .ok_or_else(|| {
+                    debug_assert!(false, "shuffled actions should contain at least one action");
+                    EvaluationFailure::NoActionsSuppliedForBandit
+                })?;
+
+        Ok(actions.details(selected_action, actions.weights[selected_action]))
+    }
+
+    /// Rank up to `k` actions for the subject by sampling them without replacement. See
+    /// [`get_bandit_actions_ranked()`].
+    fn rank<'a>(
+        &self,
+        flag_key: &str,
+        subject_key: &str,
+        subject_attributes: &ContextAttributes,
+        actions: Actions<'a>,
+        k: usize,
+        options: &EvaluationOptions,
+    ) -> Result<Vec<BanditEvaluationDetails<'a>>, EvaluationFailure> {
+        let sharder = options.sharder.unwrap_or(&Md5Sharder);
+
+        let actions = self.weigh_subject_actions(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            actions,
+            options,
+        )?;
+
+        let mut remaining = actions.shuffled.clone();
+        let mut remaining_weight = 1.0;
+        let mut ranking = Vec::with_capacity(k.min(remaining.len()));
+        for rank in 0..k {
+            // The first action is selected the same way as in `evaluate()`.
+            let selection_hash = if rank == 0 {
+                selection_hash(sharder, &[flag_key, "-", subject_key])
+            } else {
+                selection_hash(
+                    sharder,
+                    &[flag_key, "-", subject_key, "-rank-", &rank.to_string()],
+                )
+            };
+            let Some(i) = select_action(
+                &remaining,
+                &actions.weights,
+                remaining_weight,
+                selection_hash,
+            ) else {
+                break;
+            };
+
+            let action_key = remaining.remove(i);
+            let weight = actions.weights[action_key];
+            let probability = if remaining_weight > 0.0 {
+                weight / remaining_weight
+            } else {
+                0.0
+            };
+            ranking.push(actions.details(action_key, probability));
+            remaining_weight -= weight;
         }
 
+        Ok(ranking)
+    }
+
+    /// Score and weigh `actions` (restricted to `options.allowed_actions` if any) for the
+    /// subject.
+    fn weigh_subject_actions<'a>(
+        &self,
+        flag_key: &str,
+        subject_key: &str,
+        subject_attributes: &ContextAttributes,
+        actions: Actions<'a>,
+        options: &EvaluationOptions,
+    ) -> Result<WeighedActions<'a>, EvaluationFailure> {
+        let sharder = options.sharder.unwrap_or(&Md5Sharder);
+        let mut attributes = HashMap::new();
         let scores = actions
             .iter()
+            .filter(|(key, _)| {
+                options
+                    .allowed_actions
+                    .map_or(true, |allowed| allowed.contains(*key))
+            })
+            .filter(|(key, _)| match options.bandit.unknown_action_policy {
+                UnknownActionPolicy::UseDefaultScore => true,
+                UnknownActionPolicy::Exclude => self.coefficients.contains_key(key.as_str()),
+            })
+            // Action keys may repeat, so make sure every action is scored once.
+            .filter(|&(key, action_attributes)| attributes.insert(key, action_attributes).is_none())
             .map(|(key, attributes)| {
                 (
                     key,
@@ -261,6 +580,10 @@ impl BanditModelData {
             })
             .collect::<HashMap<_, _>>();
 
+        if scores.is_empty() {
+            return Err(EvaluationFailure::NoActionsSuppliedForBandit);
+        }
+
         let best = scores
             .iter()
             .max_by(|a, b| {
@@ -281,65 +604,51 @@ impl BanditModelData {
                 EvaluationFailure::NoActionsSuppliedForBandit
             })?;
 
-        let weights = self.weigh_actions(&scores, best);
-
         // Pseudo-random deterministic shuffle of actions. Shuffling is unique per subject, so when
         // weights change slightly, large swatches of subjects are not reassign from one action to
         // the same other action (instead, if subject is pushed away from an action, it will get
         // assigned to a pseudo-random other action).
-        let shuffled_actions = {
-            let mut shuffled_actions = actions.keys().collect::<Vec<_>>();
+        let shuffled = {
+            let mut shuffled_actions = scores.keys().copied().collect::<Vec<_>>();
             // Sort actions by their shard value. Use action key as tie breaker.
             shuffled_actions.sort_by_cached_key(|&action_key| {
                 let hash =
-                    get_md5_shard(&[flag_key, "-", subject_key, "-", action_key], TOTAL_SHARDS);
+                    sharder.shard(&[flag_key, "-", subject_key, "-", action_key], TOTAL_SHARDS);
                 (hash, action_key)
             });
             shuffled_actions
         };
 
-        let selection_hash = (get_md5_shard(&[flag_key, "-", subject_key], TOTAL_SHARDS) as f64)
-            / (TOTAL_SHARDS as f64);
+        let weights = self.weigh_actions(&shuffled, &scores, best);
 
-        let selected_action = {
-            let mut cumulative_weight = 0.0;
-            *shuffled_actions
-                .iter()
-                .find(|&action_key| {
-                    cumulative_weight += weights[action_key];
-                    cumulative_weight > selection_hash
-                })
-                .or_else(|| shuffled_actions.last())
-                .ok_or_else(|| {
-                    debug_assert!(false, "shuffled_actions should contain at least one action");
-                    EvaluationFailure::NoActionsSuppliedForBandit
-                })?
-        };
-
-        let optimality_gap = best.1 - scores[selected_action];
-
-        Ok(BanditEvaluationDetails {
-            action_key: selected_action.to_owned(),
-            // action_attributes: actions[selected_action].to_owned(),
-            action_weight: weights[selected_action],
-            optimality_gap,
+        Ok(WeighedActions {
+            best_score: best.1,
+            attributes,
+            scores,
+            weights,
+            shuffled,
         })
     }
 
     /// Weigh actions depending on their scores. Higher-scored actions receive more weight, except
     /// best action which receive the remainder weight.
+    ///
+    /// Actions are visited in the order of `actions`, so the remainder weight is computed
+    /// deterministically (floating-point subtraction is order-dependent).
     fn weigh_actions<'a>(
         &self,
+        actions: &[&'a Str],
         scores: &HashMap<&'a Str, f64>,
         (best_action, best_score): (&'a Str, f64),
     ) -> HashMap<&'a Str, f64> {
         let mut weights = HashMap::<&Str, f64>::new();
 
-        let n_actions = scores.len() as f64;
+        let n_actions = actions.len() as f64;
 
         let mut remainder_weight = 1.0;
-        for (action, score) in scores {
-            if *action != best_action {
+        for &action in actions {
+            let score = scores[action];
+            if action != best_action {
                 let min_probability = self.action_probability_floor / n_actions;
                 let weight =
                     min_probability.max(1.0 / (n_actions + self.gamma * (best_score - score)));
@@ -361,7 +670,7 @@ impl BanditModelData {
 
         coefficients.intercept
             + score_attributes(
-                &action.attributes,
+                action.attributes,
                 &coefficients.action_numeric_coefficients,
                 &coefficients.action_categorical_coefficients,
             )
@@ -373,13 +682,62 @@ impl BanditModelData {
     }
 }
 
+/// Scores and weights of actions available to a subject.
+struct WeighedActions<'a> {
+    attributes: HashMap<&'a Str, &'a ContextAttributes>,
+    scores: HashMap<&'a Str, f64>,
+    weights: HashMap<&'a Str, f64>,
+    best_score: f64,
+    /// Actions in the pseudo-random order they are considered in during selection.
+    shuffled: Vec<&'a Str>,
+}
+
+impl<'a> WeighedActions<'a> {
+    fn details(&self, action_key: &Str, action_weight: f64) -> BanditEvaluationDetails<'a> {
+        BanditEvaluationDetails {
+            action_key: action_key.clone(),
+            action_attributes: self.attributes[action_key],
+            action_weight,
+            action_score: self.scores[action_key],
+            optimality_gap: self.best_score - self.scores[action_key],
+        }
+    }
+}
+
+// total_shards is not configurable at the moment.
+const TOTAL_SHARDS: u32 = 10_000;
+
+/// Map `input` to a pseudo-random number in `[0, 1)`.
+fn selection_hash(sharder: &dyn Sharder, input: &[&str]) -> f64 {
+    (sharder.shard(input, TOTAL_SHARDS) as f64) / (TOTAL_SHARDS as f64)
+}
+
+/// Select one of `candidates` with probability proportional to its weight, using
+/// `selection_hash` as the source of randomness. Returns index of the selected candidate, or
+/// `None` if there are no candidates.
+fn select_action(
+    candidates: &[&Str],
+    weights: &HashMap<&Str, f64>,
+    total_weight: f64,
+    selection_hash: f64,
+) -> Option<usize> {
+    let mut cumulative_weight = 0.0;
+    candidates
+        .iter()
+        .position(|action_key| {
+            cumulative_weight += weights[action_key];
+            cumulative_weight / total_weight > selection_hash
+        })
+        .or_else(|| candidates.len().checked_sub(1))
+}
+
 fn score_attributes(
     attributes: &ContextAttributes,
     numeric_coefficients: &[BanditNumericAttributeCoefficient],
     categorical_coefficients: &[BanditCategoricalAttributeCoefficient],
 ) -> f64 {
     numeric_coefficients
-        .into_iter()
+        .iter()
         .map(|coef| {
             attributes
                 .numeric
@@ -391,7 +749,7 @@ fn score_attributes(
                 .map(|value| value * coef.coefficient)
                 .unwrap_or(coef.missing_value_coefficient)
         })
-        .chain(categorical_coefficients.into_iter().map(|coef| {
+        .chain(categorical_coefficients.iter().map(|coef| {
             attributes
                 .categorical
                 .get(coef.attribute_key.as_str())
@@ -404,16 +762,382 @@ fn score_attributes(
 
 #[cfg(test)]
 mod tests {
-    use std::fs::{read_dir, File};
+    use std::{
+        collections::HashMap,
+        fs::{read_dir, File},
+    };
 
     use chrono::Utc;
     use serde::{Deserialize, Serialize};
 
     use crate::{
-        eval::get_bandit_action, ufc::UniversalFlagConfig, Configuration, ContextAttributes,
-        SdkMetadata, Str,
+        eval::{
+            eval_details::BanditEvaluationCode, get_bandit_action, get_bandit_action_details,
+            get_bandit_actions_ranked, Actions, BanditEvaluationOptions, BanditRequest,
+            EvaluationOptions, UnknownActionPolicy,
+        },
+        test_server::SDK_METADATA,
+        Configuration, ContextAttributes, Str,
     };
 
+    /// Configuration with `banner-bandit-flag` always assigning a bandit that prefers nike over
+    /// adidas over reebok over puma. Internal subjects (`internal` attribute is `"true"`) are
+    /// assigned by the `internal` allocation, which is not logged. Other subjects are assigned by
+    /// the logged `everyone` allocation.
+    fn bandit_configuration() -> Configuration {
+        let flags = br#"{
+              "createdAt": "2024-07-18T00:00:00Z",
+              "format": "SERVER",
+              "environment": {"name": "test"},
+              "flags": {
+                "banner-bandit-flag": {
+                  "key": "banner-bandit-flag",
+                  "enabled": true,
+                  "variationType": "STRING",
+                  "totalShards": 10000,
+                  "variations": {"banner-bandit": {"key": "banner-bandit", "value": "banner-bandit"}},
+                  "allocations": [{
+                    "key": "internal",
+                    "rules": [{"conditions": [{"attribute": "internal", "operator": "ONE_OF", "value": ["true"]}]}],
+                    "splits": [{"variationKey": "banner-bandit", "shards": []}],
+                    "doLog": false
+                  }, {
+                    "key": "everyone",
+                    "splits": [{"variationKey": "banner-bandit", "shards": []}],
+                    "doLog": true
+                  }]
+                }
+              },
+              "bandits": {
+                "banner-bandit": [{
+                  "key": "banner-bandit",
+                  "flagKey": "banner-bandit-flag",
+                  "variationKey": "banner-bandit",
+                  "variationValue": "banner-bandit"
+                }]
+              }
+            }"#;
+        let coefficients = [("nike", 3.0), ("adidas", 2.0), ("reebok", 1.0)]
+            .into_iter()
+            .map(|(key, intercept)| {
+                (
+                    key.to_owned(),
+                    serde_json::json!({
+                        "actionKey": key,
+                        "intercept": intercept,
+                        "subjectNumericCoefficients": [],
+                        "subjectCategoricalCoefficients": [],
+                        "actionNumericCoefficients": [],
+                        "actionCategoricalCoefficients": []
+                    }),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        let bandits = serde_json::to_vec(&serde_json::json!({
+            "updatedAt": "2024-07-18T00:00:00Z",
+            "bandits": {
+                "banner-bandit": {
+                    "banditKey": "banner-bandit",
+                    "modelName": "falcon",
+                    "modelVersion": "v1",
+                    "updatedAt": "2024-07-18T00:00:00Z",
+                    "modelData": {
+                        "gamma": 1.0,
+                        "defaultActionScore": 0.0,
+                        "actionProbabilityFloor": 0.1,
+                        "coefficients": coefficients
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        Configuration::from_json_for_tests(flags, Some(&bandits))
+    }
+
+    #[test]
+    fn ranked_actions_are_sampled_without_replacement() {
+        let configuration = bandit_configuration();
+        let actions = ["nike", "adidas", "reebok", "puma"]
+            .into_iter()
+            .map(|key| (key.into(), ContextAttributes::default()))
+            .collect::<HashMap<Str, _>>();
+        let now = Utc::now();
+        let rank = |subject_key: &Str, k| {
+            get_bandit_actions_ranked(
+                Some(&configuration),
+                &BanditRequest {
+                    flag_key: "banner-bandit-flag",
+                    subject_key,
+                    subject_attributes: &ContextAttributes::default(),
+                    actions: (&actions).into(),
+                    default_variation: &"default".into(),
+                },
+                k,
+                now,
+                &EvaluationOptions::default(),
+                &SDK_METADATA,
+            )
+        };
+        let summary = |results: &[super::BanditResult]| {
+            results
+                .iter()
+                .map(|result| {
+                    let event = result.bandit_event.as_ref().unwrap();
+                    assert_eq!(result.action.as_ref(), Some(&event.action));
+                    (event.action.clone(), event.action_probability)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for i in 0..100 {
+            let subject_key = Str::from(format!("subject-{i}"));
+
+            let ranked = rank(&subject_key, 3);
+            assert_eq!(ranked.len(), 3);
+            let ranking = summary(&ranked);
+
+            // Deterministic for the same inputs.
+            assert_eq!(summary(&rank(&subject_key, 3)), ranking);
+            // Ranking is a prefix of a longer ranking.
+            let full_ranking = summary(&rank(&subject_key, 10));
+            assert_eq!(full_ranking.len(), 4);
+            assert_eq!(full_ranking[..3], ranking);
+            // The last remaining action is always selected.
+            assert!((full_ranking[3].1 - 1.0).abs() < 1e-9);
+
+            // No action is repeated.
+            let mut keys = full_ranking.iter().map(|(key, _)| key).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            assert_eq!(keys.len(), 4);
+
+            // The best-ranked action is the one selected by `get_bandit_action()`.
+            let single = get_bandit_action(
+                Some(&configuration),
+                &BanditRequest {
+                    flag_key: "banner-bandit-flag",
+                    subject_key: &subject_key,
+                    subject_attributes: &ContextAttributes::default(),
+                    actions: (&actions).into(),
+                    default_variation: &"default".into(),
+                },
+                now,
+                &EvaluationOptions::default(),
+                &SDK_METADATA,
+            );
+            let event = single.bandit_event.unwrap();
+            assert_eq!(ranking[0], (event.action, event.action_probability));
+
+            // Assignment is only logged once.
+            assert!(ranked[0].assignment_event.is_some());
+            assert!(ranked[1..]
+                .iter()
+                .all(|result| result.assignment_event.is_none()));
+        }
+
+        assert!(rank(&"alice".into(), 0).is_empty());
+    }
+
+    #[test]
+    fn unknown_actions_are_excluded_when_requested() {
+        let configuration = bandit_configuration();
+        let evaluate = |actions: &[&str], unknown_action_policy| {
+            let actions = actions.iter().map(|&key| key.into()).collect::<Vec<Str>>();
+            (0..100)
+                .map(|i| {
+                    get_bandit_action_details(
+                        Some(&configuration),
+                        &BanditRequest {
+                            flag_key: "banner-bandit-flag",
+                            subject_key: &format!("subject-{i}").into(),
+                            subject_attributes: &ContextAttributes::default(),
+                            actions: (&actions).into(),
+                            default_variation: &"default".into(),
+                        },
+                        Utc::now(),
+                        &EvaluationOptions {
+                            bandit: BanditEvaluationOptions {
+                                unknown_action_policy,
+                            },
+                            ..Default::default()
+                        },
+                        &SDK_METADATA,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The model knows nike but not puma or converse.
+        let actions = ["nike", "puma", "converse"];
+
+        let results = evaluate(&actions, UnknownActionPolicy::UseDefaultScore);
+        assert!(results
+            .iter()
+            .any(|(result, _)| result.action.as_deref() != Some("nike")));
+        for (_, details) in &results {
+            assert_eq!(details.actions.len(), 3);
+        }
+
+        let results = evaluate(&actions, UnknownActionPolicy::Exclude);
+        for (result, details) in &results {
+            assert_eq!(result.variation.as_str(), "banner-bandit");
+            assert_eq!(result.action.as_deref(), Some("nike"));
+            let event = result.bandit_event.as_ref().unwrap();
+            assert_eq!(event.action_probability, 1.0);
+            assert_eq!(event.action_score, 3.0);
+            assert_eq!(event.gamma, 1.0);
+            assert_eq!(details.actions.len(), 1);
+        }
+
+        // None of the actions are known, so the variation is returned without an action.
+        let results = evaluate(&["puma", "converse"], UnknownActionPolicy::Exclude);
+        for (result, details) in &results {
+            assert_eq!(result.variation.as_str(), "banner-bandit");
+            assert_eq!(result.action, None);
+            assert!(result.bandit_event.is_none());
+            assert_eq!(
+                details.bandit_evaluation_code,
+                Some(BanditEvaluationCode::NoActionsSuppliedForBandit)
+            );
+        }
+    }
+
+    #[test]
+    fn action_keys_select_same_as_empty_attributes() {
+        let configuration = bandit_configuration();
+        let keys = ["nike", "adidas", "reebok", "puma"].map(Str::from);
+        let actions = keys
+            .iter()
+            .map(|key| (key.clone(), ContextAttributes::default()))
+            .collect::<HashMap<_, _>>();
+        let now = Utc::now();
+
+        for i in 0..100 {
+            let subject_key = Str::from(format!("subject-{i}"));
+            let evaluate = |actions: Actions| {
+                get_bandit_action(
+                    Some(&configuration),
+                    &BanditRequest {
+                        flag_key: "banner-bandit-flag",
+                        subject_key: &subject_key,
+                        subject_attributes: &ContextAttributes::default(),
+                        actions,
+                        default_variation: &"default".into(),
+                    },
+                    now,
+                    &EvaluationOptions::default(),
+                    &SDK_METADATA,
+                )
+                .bandit_event
+                .unwrap()
+            };
+
+            let event = evaluate(Actions::Keys(&keys));
+            let expected = evaluate(Actions::WithAttributes(&actions));
+
+            assert_eq!(event.action, expected.action);
+            assert_eq!(event.action_probability, expected.action_probability);
+            assert_eq!(event.optimality_gap, expected.optimality_gap);
+            assert!(event.action_numeric_attributes.is_empty());
+            assert!(event.action_categorical_attributes.is_empty());
+        }
+    }
+
+    #[test]
+    fn duplicate_action_keys_are_considered_once() {
+        let configuration = bandit_configuration();
+        let keys = ["nike", "adidas", "reebok", "puma"].map(Str::from);
+        let duplicated = ["nike", "adidas", "nike", "reebok", "puma", "puma"].map(Str::from);
+        let now = Utc::now();
+
+        for i in 0..100 {
+            let subject_key = Str::from(format!("subject-{i}"));
+            let rank = |actions: &[Str]| {
+                get_bandit_actions_ranked(
+                    Some(&configuration),
+                    &BanditRequest {
+                        flag_key: "banner-bandit-flag",
+                        subject_key: &subject_key,
+                        subject_attributes: &ContextAttributes::default(),
+                        actions: Actions::Keys(actions),
+                        default_variation: &"default".into(),
+                    },
+                    usize::MAX,
+                    now,
+                    &EvaluationOptions::default(),
+                    &SDK_METADATA,
+                )
+                .into_iter()
+                .map(|result| {
+                    let event = result.bandit_event.unwrap();
+                    (event.action, event.action_probability)
+                })
+                .collect::<Vec<_>>()
+            };
+
+            let ranking = rank(&duplicated);
+            assert_eq!(ranking, rank(&keys));
+            assert_eq!(ranking.len(), keys.len());
+        }
+    }
+
+    #[test]
+    fn bandit_result_includes_allocation_regardless_of_logging() {
+        let configuration = bandit_configuration();
+        let evaluate = |subject_attributes: &ContextAttributes| {
+            get_bandit_action(
+                Some(&configuration),
+                &BanditRequest {
+                    flag_key: "banner-bandit-flag",
+                    subject_key: &"alice".into(),
+                    subject_attributes,
+                    actions: Actions::Keys(&["nike".into(), "adidas".into()]),
+                    default_variation: &"default".into(),
+                },
+                Utc::now(),
+                &EvaluationOptions::default(),
+                &SDK_METADATA,
+            )
+        };
+
+        let logged = evaluate(&ContextAttributes::default());
+        assert!(logged.action.is_some());
+        assert_eq!(logged.allocation_key.as_deref(), Some("everyone"));
+        assert_eq!(
+            logged.experiment.as_deref(),
+            Some("banner-bandit-flag-everyone")
+        );
+        let event = logged.assignment_event.unwrap();
+        assert_eq!(logged.allocation_key, Some(event.base.allocation.clone()));
+        assert_eq!(logged.experiment.as_deref(), Some(&*event.base.experiment));
+
+        let not_logged = evaluate(&[("internal", "true")].into_iter().collect());
+        assert!(not_logged.action.is_some());
+        assert!(not_logged.assignment_event.is_none());
+        assert_eq!(not_logged.allocation_key.as_deref(), Some("internal"));
+        assert_eq!(
+            not_logged.experiment.as_deref(),
+            Some("banner-bandit-flag-internal")
+        );
+
+        let unknown_flag = get_bandit_action(
+            Some(&configuration),
+            &BanditRequest {
+                flag_key: "unknown-flag",
+                subject_key: &"alice".into(),
+                subject_attributes: &ContextAttributes::default(),
+                actions: Actions::Keys(&["nike".into()]),
+                default_variation: &"default".into(),
+            },
+            Utc::now(),
+            &EvaluationOptions::default(),
+            &SDK_METADATA,
+        );
+        assert_eq!(unknown_flag.variation.as_str(), "default");
+        assert_eq!(unknown_flag.allocation_key, None);
+        assert_eq!(unknown_flag.experiment, None);
+    }
+
     #[derive(Debug, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     struct TestFile {
@@ -448,20 +1172,10 @@ mod tests {
 
     #[test]
     fn sdk_test_data() {
-        let config = UniversalFlagConfig::from_json(
-            SdkMetadata {
-                name: "test",
-                version: "0.1.0",
-            },
+        let config = Configuration::from_json_for_tests(
             std::fs::read("../sdk-test-data/ufc/bandit-flags-v1.json").unwrap(),
-        )
-        .unwrap();
-        let bandits = serde_json::from_reader(
-            File::open("../sdk-test-data/ufc/bandit-models-v1.json").unwrap(),
-        )
-        .unwrap();
-
-        let config = Configuration::from_server_response(config, Some(bandits));
+            Some(&std::fs::read("../sdk-test-data/ufc/bandit-models-v1.json").unwrap()),
+        );
 
         for entry in read_dir("../sdk-test-data/ufc/bandit-tests/").unwrap() {
             let entry = entry.unwrap();
@@ -486,23 +1200,58 @@ mod tests {
                 let actions = subject
                     .actions
                     .into_iter()
-                    .map(|x| (x.action_key, x.attributes.into()))
-                    .collect();
+                    .map(|x| (x.action_key, x.attributes))
+                    .collect::<HashMap<_, _>>();
 
-                let result = get_bandit_action(
+                let subject_attributes = subject.subject_attributes;
+                let now = Utc::now();
+
+                let request = BanditRequest {
+                    flag_key: &test.flag,
+                    subject_key: &subject.subject_key,
+                    subject_attributes: &subject_attributes,
+                    actions: (&actions).into(),
+                    default_variation: &test.default_value,
+                };
+                let options = EvaluationOptions::default();
+
+                let result =
+                    get_bandit_action(Some(&config), &request, now, &options, &SDK_METADATA);
+                let (details_result, details) = get_bandit_action_details(
                     Some(&config),
-                    &test.flag,
-                    &subject.subject_key,
-                    &subject.subject_attributes.into(),
-                    &actions,
-                    &test.default_value,
-                    Utc::now(),
-                    &SdkMetadata {
-                        name: "test",
-                        version: "0.1.0",
-                    },
+                    &request,
+                    now,
+                    &options,
+                    &SDK_METADATA,
                 );
 
+                assert_eq!(details_result.action, result.action);
+                match &details_result.bandit_event {
+                    Some(event) => {
+                        assert_eq!(details.actions.len(), actions.len());
+                        assert!(details
+                            .actions
+                            .windows(2)
+                            .all(|pair| pair[0].score >= pair[1].score));
+                        let total_weight = details.actions.iter().map(|a| a.weight).sum::<f64>();
+                        assert!((total_weight - 1.0).abs() < 1e-9, "{total_weight}");
+                        let selected = details
+                            .actions
+                            .iter()
+                            .find(|action| action.key == event.action)
+                            .unwrap();
+                        assert_eq!(selected.weight, event.action_probability);
+                        assert_eq!(
+                            details.actions[0].score - selected.score,
+                            event.optimality_gap
+                        );
+                        assert!(event.action_score.is_finite());
+                        assert_eq!(selected.score, event.action_score);
+                        assert!(event.gamma.is_finite());
+                    }
+                    None => assert!(details.actions.is_empty()),
+                }
+
                 assert_eq!(
                     TestAssignment {
                         variation: result.variation,
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     error::{EvaluationError, EvaluationFailure},
-    ufc::{AssignmentValue, ConditionWire, Shard},
+    ufc::{AssignmentValue, ConditionWire, Shard, VariationType},
     AttributeValue, Attributes, Str,
 };
 
@@ -17,7 +17,15 @@ pub enum FlagEvaluationCode {
     /// Configuration has not been fetched yet.
     ConfigurationMissing,
     /// Flag does not exist or is not enabled for the environment in use.
+    ///
+    /// For unobfuscated configuration, disabled flags are reported as
+    /// [`FlagEvaluationCode::FlagDisabled`] instead, except in evaluation details.
     FlagUnrecognizedOrDisabled,
+    /// Flag exists in configuration but is disabled.
+    ///
+    /// Evaluation details report [`FlagEvaluationCode::FlagUnrecognizedOrDisabled`] instead for
+    /// parity with other Eppo SDKs.
+    FlagDisabled,
     /// Default allocation is matched and is also serving NULL, resulting in the default value being
     /// assigned.
     DefaultAllocationNull,
@@ -26,6 +34,9 @@ pub enum FlagEvaluationCode {
     /// Configuration received from the server is invalid for the SDK. This should normally never
     /// happen and is likely a signal that you should update SDK.
     UnexpectedConfigurationError,
+    /// An allocation was matched, but configuration is older than the maximum age configured by
+    /// the SDK (i.e., the SDK failed to refresh configuration). The assignment is still served.
+    ConfigurationStale,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -53,6 +64,19 @@ pub struct EvaluationResultWithDetails<T> {
 }
 
 impl<T> EvaluationResultWithDetails<T> {
+    /// Evaluation error that caused the default value to be returned, if any.
+    ///
+    /// Normal conditions (e.g., flag being disabled or subject not matching any allocation) are not
+    /// errors. Use [`EvaluationResultWithDetails::flag_evaluation_code()`] to distinguish them.
+    pub fn error(&self) -> Option<EvaluationError> {
+        self.evaluation_details.evaluation_error
+    }
+
+    /// Code describing the outcome of flag evaluation. `None` if the flag was not evaluated.
+    pub fn flag_evaluation_code(&self) -> Option<FlagEvaluationCode> {
+        self.evaluation_details.flag_evaluation_code
+    }
+
     /// Map `EvaluationResultWithDetails.variation` using the `f` function.
     pub fn map<T2, F: FnOnce(T) -> T2>(self, f: F) -> EvaluationResultWithDetails<T2> {
         EvaluationResultWithDetails {
@@ -85,18 +109,57 @@ pub struct EvaluationDetails {
     pub bandit_evaluation_code: Option<BanditEvaluationCode>,
     pub flag_evaluation_code: Option<FlagEvaluationCode>,
     pub flag_evaluation_description: String,
+    /// Error that occurred during evaluation, if any. Not serialized, as the error is already
+    /// reflected in `flag_evaluation_code` and `flag_evaluation_description`.
+    #[serde(skip)]
+    pub evaluation_error: Option<EvaluationError>,
 
     /// Key of the selected variation.
     pub variation_key: Option<Str>,
     /// Value of the selected variation. Could be `None` if no variation is selected, or selected
     /// value is absent in configuration (configuration error).
     pub variation_value: Option<AssignmentValue>,
+    /// Original type of the variation value if it was converted to the requested type by numeric
+    /// type coercion (see
+    /// [`EvaluatorConfig::coerce_numeric_types`](super::EvaluatorConfig::coerce_numeric_types)).
+    /// `variation_value` holds the original value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coerced_from: Option<VariationType>,
 
     pub bandit_key: Option<Str>,
     pub bandit_action: Option<Str>,
+    /// Scores and weights of all actions considered by the bandit, sorted by score (best
+    /// first). Only populated by bandit evaluation with details.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<BanditActionDetails>,
 
     /// Evaluation details for all allocations.
     pub allocations: Vec<AllocationEvaluationDetails>,
+
+    /// Set if the caller requested evaluation without logging the assignment event, so the
+    /// assignment was not recorded as an exposure.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub logging_suppressed: bool,
+}
+
+impl EvaluationDetails {
+    /// Returns `true` if configuration used for evaluation was fetched more than `max_age` before
+    /// the evaluation. See [`Configuration::is_stale()`](crate::Configuration::is_stale).
+    pub fn is_configuration_stale(&self, max_age: std::time::Duration) -> bool {
+        self.config_fetched_at.is_some_and(|fetched_at| {
+            crate::configuration::is_stale(fetched_at, max_age, self.timestamp)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BanditActionDetails {
+    pub key: Str,
+    /// Score assigned to the action by the bandit model.
+    pub score: f64,
+    /// Probability of the action being selected.
+    pub weight: f64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -172,7 +235,7 @@ impl From<EvaluationFailure> for FlagEvaluationCode {
         match value {
             EvaluationFailure::ConfigurationMissing => Self::ConfigurationMissing,
             EvaluationFailure::FlagUnrecognizedOrDisabled => Self::FlagUnrecognizedOrDisabled,
-            EvaluationFailure::FlagDisabled => Self::FlagUnrecognizedOrDisabled,
+            EvaluationFailure::FlagDisabled => Self::FlagDisabled,
             EvaluationFailure::DefaultAllocationNull => Self::DefaultAllocationNull,
             EvaluationFailure::Error(err) => err.into(),
             EvaluationFailure::NonBanditVariation
@@ -33,6 +33,7 @@ pub(crate) struct EvalDetailsBuilder {
     bandit_evaluation_failure: Option<Result<(), EvaluationFailure>>,
     bandit_key: Option<Str>,
     bandit_action: Option<Str>,
+    bandit_actions: Vec<BanditActionDetails>,
 
     /// Matched details on allocation and split if any.
     matched_details: Option<MatchedDetails>,
@@ -86,6 +87,7 @@ impl EvalDetailsBuilder {
             bandit_evaluation_failure: None,
             bandit_key: None,
             bandit_action: None,
+            bandit_actions: Vec::new(),
             matched_details: None,
             allocation_keys_order: Vec::new(),
             allocation_eval_results: HashMap::new(),
@@ -94,6 +96,12 @@ impl EvalDetailsBuilder {
 
     pub fn build(mut self) -> EvaluationDetails {
         let flag_evaluation_description = self.build_flag_evaluation_description();
+        let evaluation_error = [self.flag_evaluation_failure, self.bandit_evaluation_failure]
+            .into_iter()
+            .find_map(|failure| match failure {
+                Some(Err(EvaluationFailure::Error(err))) => Some(err),
+                _ => None,
+            });
         EvaluationDetails {
             flag_key: self.flag_key,
             subject_key: self.subject_key,
@@ -103,12 +111,23 @@ impl EvalDetailsBuilder {
             config_published_at: self.configuration_published_at,
             environment_name: self.environment_name,
             bandit_evaluation_code: self.bandit_evaluation_failure.map(|it| it.into()),
-            flag_evaluation_code: self.flag_evaluation_failure.map(|it| it.into()),
+            flag_evaluation_code: self.flag_evaluation_failure.map(|it| {
+                match it.into() {
+                    // Other SDKs don't distinguish disabled flags in evaluation details.
+                    FlagEvaluationCode::FlagDisabled => {
+                        FlagEvaluationCode::FlagUnrecognizedOrDisabled
+                    }
+                    code => code,
+                }
+            }),
             flag_evaluation_description,
+            evaluation_error,
             variation_key: self.variation_key,
             variation_value: self.variation_value,
+            coerced_from: None,
             bandit_key: self.bandit_key,
             bandit_action: self.bandit_action,
+            actions: self.bandit_actions,
             allocations: self
                 .allocation_keys_order
                 .into_iter()
@@ -124,9 +143,11 @@ impl EvalDetailsBuilder {
                     },
                 })
                 .collect(),
+            logging_suppressed: false,
         }
     }
 
+    #[allow(clippy::useless_format)]
     fn build_flag_evaluation_description(&self) -> String {
         if self.flag_evaluation_failure.is_none() {
             return "Flag evaluation was not attempted".to_owned();
@@ -219,6 +240,20 @@ impl EvalBanditVisitor for EvalDetailsBuilder {
         self.bandit_key = Some(key.clone());
     }
 
+    fn on_bandit_actions(&mut self, scores: &HashMap<&Str, f64>, weights: &HashMap<&Str, f64>) {
+        self.bandit_actions = scores
+            .iter()
+            .map(|(&key, &score)| BanditActionDetails {
+                key: key.clone(),
+                score,
+                weight: weights[key],
+            })
+            .collect();
+        // Ties are ordered by action key, so the order is deterministic.
+        self.bandit_actions
+            .sort_by(|a, b| f64::total_cmp(&b.score, &a.score).then_with(|| a.key.cmp(&b.key)));
+    }
+
     fn visit_assignment<'a>(&'a mut self) -> Self::AssignmentVisitor<'a> {
         self
     }
@@ -229,6 +264,7 @@ impl EvalBanditVisitor for EvalDetailsBuilder {
     }
 }
 
+#[allow(clippy::needless_lifetimes)]
 impl<'b> EvalAssignmentVisitor for &'b mut EvalDetailsBuilder {
     type AllocationVisitor<'a> =
         <EvalDetailsBuilder as EvalAssignmentVisitor>::AllocationVisitor<'a>
@@ -0,0 +1,22 @@
+use std::collections::HashSet;
+
+use crate::{sharder::Sharder, Str};
+
+use super::BanditEvaluationOptions;
+
+/// Options of a single flag or bandit evaluation.
+///
+/// `EvaluationOptions::default()` evaluates the same way as other Eppo SDKs: with the md5
+/// sharder, considering all supplied bandit actions, and with default bandit options.
+#[derive(Clone, Copy, Default)]
+pub struct EvaluationOptions<'a> {
+    /// Sharder used to assign subjects to splits and to shuffle bandit actions. `None` uses the
+    /// default md5 sharder, which is required to match assignments of other Eppo SDKs.
+    pub sharder: Option<&'a dyn Sharder>,
+    /// If `Some`, only bandit actions with keys in the set are considered, so temporarily
+    /// unavailable actions can be excluded without rebuilding the actions. An empty set behaves
+    /// the same as supplying no actions. Ignored by flag evaluation.
+    pub allowed_actions: Option<&'a HashSet<Str>>,
+    /// Options controlling bandit evaluation. Ignored by flag evaluation.
+    pub bandit: BanditEvaluationOptions,
+}
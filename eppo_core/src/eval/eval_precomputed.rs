@@ -1,13 +1,37 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
 
-use crate::eval::get_assignment;
-use crate::precomputed::{PrecomputedAssignment, PrecomputedBandit, PrecomputedConfiguration};
+use crate::precomputed::{
+    PrecomputedAssignment, PrecomputedAssignmentDetails, PrecomputedBandit,
+    PrecomputedConfiguration,
+};
 use crate::ufc::{ConfigurationFormat, ValueWire, VariationType};
 use crate::{Configuration, ContextAttributes, Str};
 
+use super::eval_assignment::get_assignment_with_visitor;
+use super::eval_details_builder::EvalDetailsBuilder;
+use super::eval_options::EvaluationOptions;
+use super::eval_visitor::NoopEvalVisitor;
+use super::subject::Subject;
+
+/// Options of [`get_precomputed_configuration()`].
+#[derive(Clone, Copy, Default)]
+pub struct PrecomputeOptions<'a> {
+    /// If provided, only flags with keys in the filter are evaluated (along with their bandits).
+    /// Other flags are absent from the result.
+    pub flag_filter: Option<&'a HashSet<Str>>,
+    /// If true, every assignment carries compact evaluation details (evaluation code, matched
+    /// allocation key, and variation key), which is useful for debugging. Collecting details is
+    /// expensive, so it should be off when serving client SDKs. Details are never included in
+    /// obfuscated configuration.
+    pub with_details: bool,
+    /// Options applied to every flag and bandit evaluation.
+    pub evaluation: EvaluationOptions<'a>,
+}
+
+/// Precompute assignments and bandit actions for a single subject.
 pub fn get_precomputed_configuration(
     configuration: Option<&Configuration>,
     subject_key: &Str,
@@ -17,6 +41,7 @@ pub fn get_precomputed_configuration(
         HashMap</* action_key: */ Str, ContextAttributes>,
     >,
     now: DateTime<Utc>,
+    options: &PrecomputeOptions,
 ) -> PrecomputedConfiguration {
     let Some(configuration) = configuration else {
         log::warn!(target: "eppo",
@@ -33,22 +58,49 @@ pub fn get_precomputed_configuration(
     };
 
     let generic_attributes = Arc::new(subject_attributes.to_generic_attributes());
+    // Subject is shared between all flag evaluations.
+    let subject = Subject::new(subject_key.clone(), generic_attributes.clone());
 
     let flags = configuration
         .flags
         .compiled
         .flags
         .keys()
+        .filter(|flag_key| {
+            options
+                .flag_filter
+                .map_or(true, |filter| filter.contains(*flag_key))
+        })
         .filter_map(|flag_key| {
-            get_assignment(
-                Some(configuration),
-                flag_key,
-                &subject_key,
-                &generic_attributes,
-                None,
-                now,
-            )
-            .unwrap_or_else(|err| {
+            let mut details_builder = options.with_details.then(|| {
+                EvalDetailsBuilder::new(
+                    flag_key.to_string(),
+                    subject_key.clone(),
+                    generic_attributes.clone(),
+                    now,
+                )
+            });
+            let result = match &mut details_builder {
+                Some(details_builder) => get_assignment_with_visitor(
+                    Some(configuration),
+                    details_builder,
+                    flag_key,
+                    &subject,
+                    None,
+                    now,
+                    &options.evaluation,
+                ),
+                None => get_assignment_with_visitor(
+                    Some(configuration),
+                    &mut NoopEvalVisitor,
+                    flag_key,
+                    &subject,
+                    None,
+                    now,
+                    &options.evaluation,
+                ),
+            };
+            let assignment = result.unwrap_or_else(|err| {
                 log::warn!(
                     target: "eppo",
                     subject_key,
@@ -57,8 +109,13 @@ pub fn get_precomputed_configuration(
                     "Failed to evaluate assignment"
                 );
                 None
-            })
-            .map(|assignment| ((flag_key.clone(), PrecomputedAssignment::from(assignment))))
+            })?;
+
+            let mut precomputed = PrecomputedAssignment::from(assignment);
+            precomputed.details = details_builder.map(|details_builder| {
+                PrecomputedAssignmentDetails::from(&details_builder.build())
+            });
+            Some((flag_key.clone(), precomputed))
         })
         .collect::<HashMap<_, _>>();
 
@@ -69,6 +126,8 @@ pub fn get_precomputed_configuration(
             flag_actions
                 .iter()
                 .filter_map(|(flag_key, actions)| {
+                    // Flags excluded by `flag_filter` are missing here, so their bandits are
+                    // skipped as well.
                     let flag = flags.get(flag_key)?;
 
                     // Skip non-string variations as they can't be bandits.
@@ -94,7 +153,14 @@ pub fn get_precomputed_configuration(
 
                             let bandit_evaluation = bandit_model
                                 .model_data
-                                .evaluate(flag_key, subject_key, subject_attributes, actions)
+                                .evaluate(
+                                    &mut NoopEvalVisitor,
+                                    flag_key,
+                                    subject_key,
+                                    subject_attributes,
+                                    actions.into(),
+                                    &options.evaluation,
+                                )
                                 .ok()?;
 
                             let selected_action = &actions[&bandit_evaluation.action_key];
@@ -128,10 +194,12 @@ pub fn get_precomputed_configuration(
                                     let bandit_evaluation = bandit_model
                                         .model_data
                                         .evaluate(
+                                            &mut NoopEvalVisitor,
                                             flag_key,
                                             subject_key,
                                             subject_attributes,
-                                            actions,
+                                            actions.into(),
+                                            &options.evaluation,
                                         )
                                         .ok()?;
 
@@ -181,11 +249,13 @@ pub fn get_precomputed_configuration(
 mod tests {
     use crate::Str;
     use chrono::Utc;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
 
     use crate::{
-        eval::get_precomputed_configuration, ufc::UniversalFlagConfig, Configuration,
-        ContextAttributes, SdkMetadata,
+        eval::{
+            eval_details::FlagEvaluationCode, get_precomputed_configuration, PrecomputeOptions,
+        },
+        Configuration, ContextAttributes,
     };
     use std::{fs, sync::Arc};
 
@@ -195,15 +265,9 @@ mod tests {
 
         let configuration = {
             // Load test configuration
-            let ufc_config = UniversalFlagConfig::from_json(
-                SdkMetadata {
-                    name: "test",
-                    version: "0.1.0",
-                },
+            Configuration::from_ufc_json_for_tests(
                 fs::read("../sdk-test-data/ufc/flags-v1.json").unwrap(),
             )
-            .unwrap();
-            Configuration::from_server_response(ufc_config, None)
         };
 
         let subject_key = "test-subject-1".into();
@@ -218,6 +282,7 @@ mod tests {
             &subject_attributes,
             &actions,
             now,
+            &PrecomputeOptions::default(),
         );
 
         assert!(
@@ -246,22 +311,10 @@ mod tests {
     fn test_precomputed_assignment_bandits() {
         let _ = env_logger::builder().is_test(true).try_init();
 
-        let configuration = {
-            // Load test configuration
-            let ufc_config = UniversalFlagConfig::from_json(
-                SdkMetadata {
-                    name: "test",
-                    version: "0.1.0",
-                },
-                fs::read("../sdk-test-data/ufc/bandit-flags-v1.json").unwrap(),
-            )
-            .unwrap();
-            let bandits_config = serde_json::from_slice(
-                &fs::read("../sdk-test-data/ufc/bandit-models-v1.json").unwrap(),
-            )
-            .unwrap();
-            Configuration::from_server_response(ufc_config, Some(bandits_config))
-        };
+        let configuration = Configuration::from_json_for_tests(
+            fs::read("../sdk-test-data/ufc/bandit-flags-v1.json").unwrap(),
+            Some(&fs::read("../sdk-test-data/ufc/bandit-models-v1.json").unwrap()),
+        );
 
         let subject_key = "test-subject-1".into();
         let subject_attributes = Default::default();
@@ -292,6 +345,7 @@ mod tests {
             &subject_attributes,
             &actions,
             now,
+            &PrecomputeOptions::default(),
         );
 
         assert!(
@@ -320,21 +374,10 @@ mod tests {
     fn test_precomputed_assignment_with_and_without_actions() {
         let _ = env_logger::builder().is_test(true).try_init();
 
-        let configuration = {
-            let ufc_config = UniversalFlagConfig::from_json(
-                SdkMetadata {
-                    name: "test",
-                    version: "0.1.0",
-                },
-                fs::read("../sdk-test-data/ufc/bandit-flags-v1.json").unwrap(),
-            )
-            .unwrap();
-            let bandits_config = serde_json::from_slice(
-                &fs::read("../sdk-test-data/ufc/bandit-models-v1.json").unwrap(),
-            )
-            .unwrap();
-            Configuration::from_server_response(ufc_config, Some(bandits_config))
-        };
+        let configuration = Configuration::from_json_for_tests(
+            fs::read("../sdk-test-data/ufc/bandit-flags-v1.json").unwrap(),
+            Some(&fs::read("../sdk-test-data/ufc/bandit-models-v1.json").unwrap()),
+        );
 
         let subject_key = "test-subject-1".into();
         let subject_attributes = Arc::new(ContextAttributes {
@@ -350,6 +393,7 @@ mod tests {
             &subject_attributes,
             &HashMap::new(),
             now,
+            &PrecomputeOptions::default(),
         );
         // Validate Case 1
         assert!(
@@ -376,6 +420,7 @@ mod tests {
             &subject_attributes,
             &car_only_actions,
             now,
+            &PrecomputeOptions::default(),
         );
         // Validate Case 2
         assert!(
@@ -412,6 +457,7 @@ mod tests {
             &subject_attributes,
             &banner_only_actions,
             now,
+            &PrecomputeOptions::default(),
         );
         // Validate Case 3
         assert!(
@@ -433,4 +479,93 @@ mod tests {
             "Should have UK banner bandit data when banner actions provided"
         );
     }
+
+    /// Configuration with a few string flags: `web-banner` (logged), `web-theme` (matching the
+    /// second allocation and not logged), `server-only`, and disabled `disabled-flag`.
+    fn test_configuration() -> Configuration {
+        Configuration::from_ufc_json_for_tests(include_bytes!(
+            "../../tests/fixtures/flags-precomputed.json"
+        ))
+    }
+
+    #[test]
+    fn flag_filter_skips_other_flags() {
+        let configuration = test_configuration();
+        let filter: HashSet<Str> = ["web-banner".into(), "web-theme".into(), "unknown".into()]
+            .into_iter()
+            .collect();
+
+        let precomputed = get_precomputed_configuration(
+            Some(&configuration),
+            &"subject".into(),
+            &Arc::new(ContextAttributes::default()),
+            &HashMap::new(),
+            Utc::now(),
+            &PrecomputeOptions {
+                flag_filter: Some(&filter),
+                ..Default::default()
+            },
+        );
+
+        let mut flag_keys = precomputed
+            .flags
+            .keys()
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>();
+        flag_keys.sort();
+        assert_eq!(flag_keys, ["web-banner", "web-theme"]);
+    }
+
+    #[test]
+    fn details_do_not_change_assignments() {
+        let configuration = test_configuration();
+        let precompute = |with_details| {
+            get_precomputed_configuration(
+                Some(&configuration),
+                &"subject".into(),
+                &Arc::new(ContextAttributes::default()),
+                &HashMap::new(),
+                "2024-11-18T00:00:00Z".parse().unwrap(),
+                &PrecomputeOptions {
+                    with_details,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let without_details = precompute(false);
+        let with_details = precompute(true);
+
+        assert!(without_details
+            .flags
+            .values()
+            .all(|assignment| assignment.details.is_none()));
+        let mut detailed_json = serde_json::to_value(&with_details).unwrap();
+        for assignment in detailed_json["flags"].as_object_mut().unwrap().values_mut() {
+            assert!(assignment
+                .as_object_mut()
+                .unwrap()
+                .remove("details")
+                .is_some());
+        }
+        assert_eq!(
+            detailed_json,
+            serde_json::to_value(&without_details).unwrap()
+        );
+
+        let details = with_details.flags["web-theme"].details.as_ref().unwrap();
+        assert_eq!(
+            details.flag_evaluation_code,
+            Some(FlagEvaluationCode::Match)
+        );
+        assert_eq!(details.allocation_key.as_deref(), Some("everyone"));
+        assert_eq!(details.variation_key.as_deref(), Some("on"));
+        assert!(!with_details.flags.contains_key("disabled-flag"));
+
+        // Obfuscated configuration never includes details.
+        assert_eq!(
+            serde_json::to_value(with_details.obfuscate_with_salt("salt")).unwrap(),
+            serde_json::to_value(without_details.obfuscate_with_salt("salt")).unwrap()
+        );
+    }
 }
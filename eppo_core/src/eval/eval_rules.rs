@@ -1,7 +1,9 @@
-use semver::Version;
-
 use crate::{
-    ufc::{Comparand, ComparisonOperator, Condition, ConditionCheck, RuleWire, TryParse},
+    obfuscation::md5_hex,
+    ufc::{
+        parse_timestamp, parse_version, Comparand, ComparisonOperator, Condition, ConditionCheck,
+        RuleWire, TryParse,
+    },
     AttributeValue,
 };
 
@@ -18,7 +20,11 @@ impl RuleWire {
 
 impl Condition {
     fn eval<V: EvalRuleVisitor>(&self, visitor: &mut V, subject: &Subject) -> bool {
-        let attribute = subject.get_attribute(self.attribute.as_ref());
+        let attribute = if self.obfuscated {
+            subject.get_obfuscated_attribute(self.attribute.as_ref())
+        } else {
+            subject.get_attribute(self.attribute.as_ref())
+        };
         let result = self.check.eval(attribute);
         visitor.on_condition_eval(self, attribute, result);
         result
@@ -41,7 +47,22 @@ impl ConditionCheck {
                 let attribute = attribute?.clone();
                 let ordering = match comparand {
                     Comparand::Version(comparand) => {
-                        let attribute = Version::parse(attribute.as_str()?).ok()?;
+                        // Build metadata is stripped, so this compares precedence.
+                        let attribute = parse_version(attribute.as_str()?)?;
+                        attribute.cmp(comparand)
+                    }
+                    Comparand::Timestamp(comparand) => {
+                        // Attributes are RFC 3339 timestamps or milliseconds since the epoch.
+                        let attribute = match attribute.as_str().and_then(|s| parse_timestamp(s)) {
+                            Some(timestamp) => timestamp,
+                            None => {
+                                let millis = attribute.coerce_to_number()?;
+                                if !millis.is_finite() {
+                                    return None;
+                                }
+                                chrono::DateTime::from_timestamp_millis(millis as i64)?
+                            }
+                        };
                         attribute.cmp(comparand)
                     }
                     Comparand::Number(comparand) => {
@@ -64,9 +85,19 @@ impl ConditionCheck {
                 expected_membership,
                 values,
             } => {
-                let s = attribute?.coerce_to_string()?;
-                let s = s.as_ref();
-                values.into_iter().any(|it| it.as_ref() == s) == *expected_membership
+                let is_member =
+                    any_value(attribute?, |s| values.iter().any(|it| it.as_ref() == s))?;
+                is_member == *expected_membership
+            }
+            ConditionCheck::ObfuscatedMembership {
+                expected_membership,
+                hashes,
+            } => {
+                let is_member = any_value(attribute?, |s| {
+                    let hash = md5_hex(s);
+                    hashes.iter().any(|it| **it == *hash)
+                })?;
+                is_member == *expected_membership
             }
             ConditionCheck::Null { expected_null } => {
                 let is_present = attribute.is_some_and(|it| !it.is_null());
@@ -79,13 +110,32 @@ impl ConditionCheck {
     }
 }
 
+/// Check if `predicate` holds for the attribute coerced to string. For arrays, check if it holds
+/// for any element (elements that cannot be coerced are skipped).
+fn any_value(attribute: &AttributeValue, predicate: impl Fn(&str) -> bool) -> Option<bool> {
+    match attribute.as_array() {
+        Some(elements) => Some(
+            elements
+                .iter()
+                .filter_map(AttributeValue::coerce_to_string)
+                .any(|s| predicate(&s)),
+        ),
+        None => Some(predicate(&attribute.coerce_to_string()?)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, sync::Arc};
 
     use crate::{
         eval::{eval_visitor::NoopEvalVisitor, subject::Subject},
-        ufc::{Comparand, ComparisonOperator, Condition, ConditionCheck, RuleWire},
+        obfuscation::md5_hex,
+        ufc::{
+            Comparand, ComparisonOperator, Condition, ConditionCheck, ConditionValue,
+            ConditionWire, RuleWire, ValueWire,
+        },
+        AttributeValue,
     };
 
     #[test]
@@ -162,6 +212,112 @@ mod tests {
         assert!(!false_check.eval(None));
     }
 
+    #[test]
+    fn one_of_array() {
+        let check = ConditionCheck::Membership {
+            expected_membership: true,
+            values: ["pro".into(), "42".into()].into(),
+        };
+        assert!(check.eval(Some(&AttributeValue::array(["free", "pro"]))));
+        assert!(check.eval(Some(&AttributeValue::array([42.0]))));
+        assert!(!check.eval(Some(&AttributeValue::array(["free", "beta"]))));
+        assert!(!check.eval(Some(&AttributeValue::array::<AttributeValue>([]))));
+        // Nested arrays and nulls are never members.
+        assert!(!check.eval(Some(&AttributeValue::array([
+            AttributeValue::array(["pro"]),
+            AttributeValue::null(),
+        ]))));
+    }
+
+    #[test]
+    fn not_one_of_array() {
+        let check = ConditionCheck::Membership {
+            expected_membership: false,
+            values: ["pro".into()].into(),
+        };
+        assert!(!check.eval(Some(&AttributeValue::array(["free", "pro"]))));
+        assert!(check.eval(Some(&AttributeValue::array(["free", "beta"]))));
+        assert!(check.eval(Some(&AttributeValue::array::<AttributeValue>([]))));
+    }
+
+    #[test]
+    fn obfuscated_one_of_array() {
+        let check = ConditionCheck::ObfuscatedMembership {
+            expected_membership: true,
+            hashes: [md5_hex("pro").into()].into(),
+        };
+        assert!(check.eval(Some(&AttributeValue::array(["free", "pro"]))));
+        assert!(!check.eval(Some(&AttributeValue::array(["free"]))));
+    }
+
+    #[test]
+    fn other_operators_do_not_match_arrays() {
+        let array = AttributeValue::array(["10", "test"]);
+        for check in [
+            ConditionCheck::Regex {
+                expected_match: true,
+                regex: "^test".try_into().unwrap(),
+            },
+            ConditionCheck::Regex {
+                expected_match: false,
+                regex: "^test".try_into().unwrap(),
+            },
+            ConditionCheck::Comparison {
+                operator: ComparisonOperator::Gte,
+                comparand: Comparand::Number(1.0),
+            },
+            ConditionCheck::Comparison {
+                operator: ComparisonOperator::Lt,
+                comparand: Comparand::Number(100.0),
+            },
+        ] {
+            assert!(!check.eval(Some(&array)), "{check:?}");
+        }
+    }
+
+    #[test]
+    fn array_attributes_deserialize_from_json() {
+        let attribute: AttributeValue = serde_json::from_str(r#"["pro", 42, true]"#).unwrap();
+        assert_eq!(
+            attribute,
+            AttributeValue::array([
+                AttributeValue::categorical("pro"),
+                AttributeValue::numeric(42.0),
+                AttributeValue::categorical(true),
+            ])
+        );
+        assert_eq!(
+            serde_json::to_string(&attribute).unwrap(),
+            r#"["pro",42.0,true]"#
+        );
+    }
+
+    #[test]
+    fn non_finite_numbers_are_null() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let attribute = AttributeValue::from(value);
+            assert!(attribute.is_null(), "{value}");
+            assert!(AttributeValue::categorical(value).is_null(), "{value}");
+
+            for operator in [
+                ComparisonOperator::Gte,
+                ComparisonOperator::Gt,
+                ComparisonOperator::Lte,
+                ComparisonOperator::Lt,
+            ] {
+                let check = ConditionCheck::Comparison {
+                    operator,
+                    comparand: Comparand::Number(0.0),
+                };
+                assert!(!check.eval(Some(&attribute)), "{operator:?} {value}");
+            }
+            assert!(ConditionCheck::Null {
+                expected_null: true
+            }
+            .eval(Some(&attribute)));
+        }
+    }
+
     #[test]
     fn is_null() {
         assert!(ConditionCheck::Null {
@@ -336,6 +492,150 @@ mod tests {
         .eval(Some(&"0.9.9".into())));
     }
 
+    #[test]
+    fn comparison_table() {
+        use ComparisonOperator::{Gt, Gte, Lt, Lte};
+
+        fn condition(operator: ComparisonOperator, value: ValueWire) -> Condition {
+            Condition::try_from(ConditionWire {
+                attribute: "version".into(),
+                operator: operator.into(),
+                value: ConditionValue::Single(value),
+            })
+            .unwrap()
+        }
+
+        let string = |s: &str| ValueWire::String(s.into());
+        let cases: &[(ComparisonOperator, ValueWire, AttributeValue, bool)] = &[
+            // Pre-release precedence per SemVer 2.0.
+            (Lt, string("1.0.0"), "1.0.0-rc.1".into(), true),
+            (Lt, string("1.0.0-rc.1"), "1.0.0-beta.2".into(), true),
+            (Lt, string("1.0.0-rc.10"), "1.0.0-rc.2".into(), true),
+            (Gt, string("1.0.0-rc.2"), "1.0.0-rc.10".into(), true),
+            (Gt, string("1.0.0-alpha"), "1.0.0-alpha.1".into(), true),
+            (Gte, string("2.3.1-rc.2"), "2.3.1".into(), true),
+            (Lte, string("2.3.1-rc.2"), "2.3.1-rc.1".into(), true),
+            (Gt, string("2.3.0"), "2.3.1-rc.2".into(), true),
+            // Build metadata is ignored.
+            (Gte, string("2.3.1"), "2.3.1+build.55".into(), true),
+            (Lte, string("2.3.1"), "2.3.1+build.55".into(), true),
+            (Gt, string("2.3.1"), "2.3.1+build.55".into(), false),
+            (
+                Lte,
+                string("2.3.1-rc.2+build.1"),
+                "2.3.1-rc.2+build.55".into(),
+                true,
+            ),
+            (
+                Gte,
+                string("2.3.1-rc.2+build.1"),
+                "2.3.1-rc.2+build.55".into(),
+                true,
+            ),
+            (Gte, string("2.3.1"), "2.3.1+build_55".into(), true),
+            // Two-segment values are numbers.
+            (Gt, string("2.3"), "2.10".into(), false),
+            (Gt, string("2.3"), "2.4".into(), true),
+            (Gte, string("2.3"), 2.3.into(), true),
+            (Lt, ValueWire::Number(2.3), "2.25".into(), true),
+            // Versions and numbers are not compared with each other.
+            (Gte, string("2.3.0"), "2.4".into(), false),
+            (Lt, string("2.3.0"), "2.2".into(), false),
+            (Gte, string("2.3.0"), 3.0.into(), false),
+            (Gt, string("2.3"), "2.4.0".into(), false),
+            (Gt, ValueWire::Number(2.0), "2.4.0".into(), false),
+        ];
+        for (operator, value, attribute, expected) in cases {
+            let condition = condition(*operator, value.clone());
+            assert_eq!(
+                condition.check.eval(Some(attribute)),
+                *expected,
+                "{operator:?} {value:?} with attribute {attribute:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn timestamp_comparison_table() {
+        use ComparisonOperator::{Gt, Gte, Lt, Lte};
+
+        fn condition(operator: ComparisonOperator, value: &str) -> Condition {
+            Condition::try_from(ConditionWire {
+                attribute: "created_at".into(),
+                operator: operator.into(),
+                value: ConditionValue::Single(ValueWire::String(value.into())),
+            })
+            .unwrap()
+        }
+
+        // 2024-01-01T00:00:00Z
+        const NEW_YEAR_MILLIS: f64 = 1704067200000.0;
+
+        let cases: &[(ComparisonOperator, &str, AttributeValue, bool)] = &[
+            (Lt, "2024-01-01", "2023-12-31T23:59:59Z".into(), true),
+            (Lt, "2024-01-01", "2024-01-01T00:00:00Z".into(), false),
+            (Lte, "2024-01-01", "2024-01-01T00:00:00Z".into(), true),
+            (Gte, "2024-01-01T00:00:00Z", "2024-01-01".into(), true),
+            (
+                Gt,
+                "2024-01-01T00:00:00Z",
+                "2024-06-15T08:30:00.123Z".into(),
+                true,
+            ),
+            // Timezone offsets are taken into account.
+            (
+                Lt,
+                "2024-01-01T00:00:00Z",
+                "2024-01-01T01:00:00+02:00".into(),
+                true,
+            ),
+            (
+                Gt,
+                "2024-01-01T00:00:00+02:00",
+                "2023-12-31T23:00:00Z".into(),
+                true,
+            ),
+            (
+                Lte,
+                "2024-01-01T00:00:00-05:00",
+                "2024-01-01T05:00:00Z".into(),
+                true,
+            ),
+            (
+                Gte,
+                "2024-01-01T00:00:00-05:00",
+                "2024-01-01T05:00:00Z".into(),
+                true,
+            ),
+            // Numeric attributes are milliseconds since the epoch.
+            (Gte, "2024-01-01", NEW_YEAR_MILLIS.into(), true),
+            (Gt, "2024-01-01", NEW_YEAR_MILLIS.into(), false),
+            (Lt, "2024-01-01", (NEW_YEAR_MILLIS - 1.0).into(), true),
+            (Lt, "2024-01-01", "1704067199999".into(), true),
+            (
+                Gt,
+                "2023-12-31T23:00:00-01:00",
+                NEW_YEAR_MILLIS.into(),
+                false,
+            ),
+            // Unparseable attributes never match.
+            (Lt, "2024-01-01", "yesterday".into(), false),
+            (Gt, "2024-01-01", "yesterday".into(), false),
+            (Lt, "2024-01-01", "2023-13-01".into(), false),
+            (Lt, "2024-01-01", true.into(), false),
+            (Lt, "2024-01-01", f64::NAN.into(), false),
+            (Gt, "2024-01-01", f64::INFINITY.into(), false),
+        ];
+        for (operator, value, attribute, expected) in cases {
+            let condition = condition(*operator, value);
+            assert_eq!(
+                condition.check.eval(Some(attribute)),
+                *expected,
+                "{operator:?} {value:?} with attribute {attribute:?}"
+            );
+        }
+    }
+
     #[test]
     fn empty_rule() {
         let rule = RuleWire { conditions: vec![] };
@@ -350,6 +650,7 @@ mod tests {
         let rule = RuleWire {
             conditions: vec![Condition {
                 attribute: "age".into(),
+                obfuscated: false,
                 check: ConditionCheck::Comparison {
                     operator: ComparisonOperator::Gt,
                     comparand: 10.0.into(),
@@ -372,6 +673,7 @@ mod tests {
             conditions: vec![
                 Condition {
                     attribute: "age".into(),
+                    obfuscated: false,
                     check: ConditionCheck::Comparison {
                         operator: ComparisonOperator::Gt,
                         comparand: 18.0.into(),
@@ -380,6 +682,7 @@ mod tests {
                 .into(),
                 Condition {
                     attribute: "age".into(),
+                    obfuscated: false,
                     check: ConditionCheck::Comparison {
                         operator: ComparisonOperator::Lt,
                         comparand: 100.0.into(),
@@ -416,6 +719,7 @@ mod tests {
         let rule = RuleWire {
             conditions: vec![Condition {
                 attribute: "age".into(),
+                obfuscated: false,
                 check: ConditionCheck::Comparison {
                     operator: ComparisonOperator::Gt,
                     comparand: 10.0.into(),
@@ -431,4 +735,68 @@ mod tests {
             )
         ));
     }
+
+    #[test]
+    fn obfuscated_one_of() {
+        let check = ConditionCheck::ObfuscatedMembership {
+            expected_membership: true,
+            hashes: [md5_hex("alice").into(), md5_hex("42").into()].into(),
+        };
+        assert!(check.eval(Some(&"alice".into())));
+        assert!(check.eval(Some(&42.0.into())));
+        assert!(!check.eval(Some(&"bob".into())));
+        assert!(!check.eval(None));
+    }
+
+    #[test]
+    fn obfuscated_attribute() {
+        let rule = RuleWire {
+            conditions: vec![Condition {
+                attribute: md5_hex("age").into(),
+                obfuscated: true,
+                check: ConditionCheck::Comparison {
+                    operator: ComparisonOperator::Gt,
+                    comparand: 10.0.into(),
+                },
+            }
+            .into()],
+        };
+        assert!(rule.eval(
+            &mut NoopEvalVisitor,
+            &Subject::new(
+                "key".into(),
+                Arc::new(HashMap::from([("age".into(), 11.0.into())]))
+            )
+        ));
+        assert!(!rule.eval(
+            &mut NoopEvalVisitor,
+            &Subject::new(
+                "key".into(),
+                Arc::new(HashMap::from([("age".into(), 9.0.into())]))
+            )
+        ));
+    }
+
+    #[test]
+    fn obfuscated_id_falls_back_to_subject_key() {
+        let rule = RuleWire {
+            conditions: vec![Condition {
+                attribute: md5_hex("id").into(),
+                obfuscated: true,
+                check: ConditionCheck::ObfuscatedMembership {
+                    expected_membership: true,
+                    hashes: [md5_hex("alice").into()].into(),
+                },
+            }
+            .into()],
+        };
+        assert!(rule.eval(
+            &mut NoopEvalVisitor,
+            &Subject::new("alice".into(), Default::default())
+        ));
+        assert!(!rule.eval(
+            &mut NoopEvalVisitor,
+            &Subject::new("bob".into(), Default::default())
+        ));
+    }
 }
@@ -1,10 +1,15 @@
+use std::collections::HashMap;
+
 use crate::{
     error::EvaluationFailure,
     ufc::{Allocation, Assignment, Condition, Flag, RuleWire, Shard, Split},
     AttributeValue, Configuration, Str,
 };
 
-use super::{eval_assignment::AllocationNonMatchReason, eval_bandits::BanditResult};
+use super::{
+    eval_assignment::AllocationNonMatchReason, eval_bandits::BanditResult,
+    eval_details::FlagEvaluationCode,
+};
 
 pub(super) trait EvalBanditVisitor {
     type AssignmentVisitor<'a>: EvalAssignmentVisitor + 'a
@@ -19,6 +24,9 @@ pub(super) trait EvalBanditVisitor {
     /// Called when bandit key is known.
     fn on_bandit_key(&mut self, key: &Str);
 
+    /// Called with scores and weights of all actions once the bandit model has scored them.
+    fn on_bandit_actions(&mut self, scores: &HashMap<&Str, f64>, weights: &HashMap<&Str, f64>);
+
     /// Called when result of bandit evaluation is known.
     ///
     /// Note that unlike assignment evaluation, bandit evaluation still returns a variation in case
@@ -98,7 +106,10 @@ impl EvalBanditVisitor for NoopEvalVisitor {
     fn on_bandit_key(&mut self, _key: &Str) {}
 
     #[inline]
-    fn visit_assignment<'a>(&'a mut self) -> NoopEvalVisitor {
+    fn on_bandit_actions(&mut self, _scores: &HashMap<&Str, f64>, _weights: &HashMap<&Str, f64>) {}
+
+    #[inline]
+    fn visit_assignment(&mut self) -> NoopEvalVisitor {
         NoopEvalVisitor
     }
 
@@ -164,3 +175,160 @@ impl EvalSplitVisitor for NoopEvalVisitor {
     #[inline]
     fn on_result(&mut self, _matches: bool) {}
 }
+
+/// Visitor that only records the outcome of flag evaluation. This is much cheaper than collecting
+/// full evaluation details.
+#[derive(Default)]
+pub(super) struct OutcomeEvalVisitor {
+    /// Outcome of flag evaluation. `None` until the outcome is known.
+    pub(super) code: Option<FlagEvaluationCode>,
+}
+
+impl OutcomeEvalVisitor {
+    pub(super) fn visit_assignment(&mut self) -> OutcomeAssignmentVisitor<'_> {
+        OutcomeAssignmentVisitor(&mut self.code)
+    }
+}
+
+impl EvalBanditVisitor for OutcomeEvalVisitor {
+    type AssignmentVisitor<'a> = OutcomeAssignmentVisitor<'a>;
+
+    #[inline]
+    fn on_configuration(&mut self, _configuration: &Configuration) {}
+
+    #[inline]
+    fn on_bandit_key(&mut self, _key: &Str) {}
+
+    #[inline]
+    fn on_bandit_actions(&mut self, _scores: &HashMap<&Str, f64>, _weights: &HashMap<&Str, f64>) {}
+
+    #[inline]
+    fn visit_assignment<'a>(&'a mut self) -> OutcomeAssignmentVisitor<'a> {
+        OutcomeEvalVisitor::visit_assignment(self)
+    }
+
+    #[inline]
+    fn on_result(&mut self, failure: Result<(), EvaluationFailure>, _result: &BanditResult) {
+        // Flag outcome is recorded by the assignment visitor. The only case when the flag is not
+        // evaluated is missing configuration.
+        if self.code.is_none() && failure == Err(EvaluationFailure::ConfigurationMissing) {
+            self.code = Some(FlagEvaluationCode::ConfigurationMissing);
+        }
+    }
+}
+
+pub(super) struct OutcomeAssignmentVisitor<'a>(&'a mut Option<FlagEvaluationCode>);
+
+impl EvalAssignmentVisitor for OutcomeAssignmentVisitor<'_> {
+    type AllocationVisitor<'b>
+        = NoopEvalVisitor
+    where
+        Self: 'b;
+
+    #[inline]
+    fn visit_allocation(&mut self, _allocation: &Allocation) -> NoopEvalVisitor {
+        NoopEvalVisitor
+    }
+
+    #[inline]
+    fn on_configuration(&mut self, _configuration: &Configuration) {}
+
+    #[inline]
+    fn on_flag_configuration(&mut self, _flag: &Flag) {}
+
+    #[inline]
+    fn on_result(&mut self, result: &Result<Assignment, EvaluationFailure>) {
+        *self.0 = Some(result.as_ref().map(|_| ()).map_err(Clone::clone).into());
+    }
+}
+
+/// Assignment visitor that records key of the matched allocation and forwards all calls to the
+/// wrapped visitor.
+///
+/// Used by bandit evaluation, as the allocation is otherwise only known from the assignment event,
+/// which is missing for allocations that are not logged.
+pub(super) struct AllocationKeyVisitor<V> {
+    inner: V,
+    allocation_key: Option<Str>,
+}
+
+impl<V> AllocationKeyVisitor<V> {
+    pub(super) fn new(inner: V) -> AllocationKeyVisitor<V> {
+        AllocationKeyVisitor {
+            inner,
+            allocation_key: None,
+        }
+    }
+
+    /// Key of the matched allocation. `None` if no allocation has matched.
+    pub(super) fn into_allocation_key(self) -> Option<Str> {
+        self.allocation_key
+    }
+}
+
+impl<V: EvalAssignmentVisitor> EvalAssignmentVisitor for AllocationKeyVisitor<V> {
+    type AllocationVisitor<'a>
+        = AllocationKeyAllocationVisitor<'a, V::AllocationVisitor<'a>>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn visit_allocation<'a>(&'a mut self, allocation: &Allocation) -> Self::AllocationVisitor<'a> {
+        AllocationKeyAllocationVisitor {
+            inner: self.inner.visit_allocation(allocation),
+            allocation_key: allocation.key.clone(),
+            matched_key: &mut self.allocation_key,
+        }
+    }
+
+    #[inline]
+    fn on_configuration(&mut self, configuration: &Configuration) {
+        self.inner.on_configuration(configuration);
+    }
+
+    #[inline]
+    fn on_flag_configuration(&mut self, flag: &Flag) {
+        self.inner.on_flag_configuration(flag);
+    }
+
+    #[inline]
+    fn on_result(&mut self, result: &Result<Assignment, EvaluationFailure>) {
+        self.inner.on_result(result);
+    }
+}
+
+pub(super) struct AllocationKeyAllocationVisitor<'a, V> {
+    inner: V,
+    allocation_key: Str,
+    matched_key: &'a mut Option<Str>,
+}
+
+impl<V: EvalAllocationVisitor> EvalAllocationVisitor for AllocationKeyAllocationVisitor<'_, V> {
+    type RuleVisitor<'a>
+        = V::RuleVisitor<'a>
+    where
+        Self: 'a;
+
+    type SplitVisitor<'a>
+        = V::SplitVisitor<'a>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn visit_rule<'a>(&'a mut self, rule: &RuleWire) -> Self::RuleVisitor<'a> {
+        self.inner.visit_rule(rule)
+    }
+
+    #[inline]
+    fn visit_split<'a>(&'a mut self, split: &Split) -> Self::SplitVisitor<'a> {
+        self.inner.visit_split(split)
+    }
+
+    #[inline]
+    fn on_result(&mut self, result: Result<&Split, AllocationNonMatchReason>) {
+        if result.is_ok() {
+            *self.matched_key = Some(self.allocation_key.clone());
+        }
+        self.inner.on_result(result);
+    }
+}
@@ -1,24 +1,69 @@
-use std::{collections::HashMap, sync::Arc};
-
-use chrono::Utc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    configuration_store::ConfigurationStore,
+    configuration_store::ConfigurationStoreBackend,
     events::AssignmentEvent,
     precomputed::PrecomputedConfiguration,
+    sharder::Sharder,
+    timestamp::Clock,
     ufc::{Assignment, AssignmentValue, VariationType},
     Attributes, Configuration, ContextAttributes, EvaluationError, SdkMetadata, Str,
 };
 
 use super::{
-    eval_details::{EvaluationDetails, EvaluationResultWithDetails},
-    get_assignment, get_assignment_details, get_bandit_action, get_bandit_action_details,
-    get_precomputed_configuration, BanditResult,
+    eval_assignment::get_assignment_with_visitor,
+    eval_bandits::get_bandit_action_with_visitor,
+    eval_details::{EvaluationDetails, EvaluationResultWithDetails, FlagEvaluationCode},
+    eval_visitor::OutcomeEvalVisitor,
+    get_all_assignments, get_assignment, get_assignment_details, get_assignment_with_reason,
+    get_bandit_action, get_bandit_action_details, get_bandit_actions_ranked,
+    get_precomputed_configuration,
+    subject::Subject,
+    Actions, BanditEvaluationOptions, BanditRequest, BanditResult, EvaluationOptions,
+    PrecomputeOptions,
 };
 
 pub struct EvaluatorConfig {
-    pub configuration_store: Arc<ConfigurationStore>,
+    pub configuration_store: Arc<dyn ConfigurationStoreBackend>,
     pub sdk_metadata: SdkMetadata,
+    /// Clock used to get the current time for evaluation. Use
+    /// [`SystemClock`](crate::timestamp::SystemClock) to evaluate at current system time.
+    pub clock: Arc<dyn Clock>,
+    /// Optional observer notified about every flag evaluation. See [`EvaluationObserver`].
+    pub observer: Option<Arc<dyn EvaluationObserver>>,
+    /// Sharder used to assign subjects to splits and to shuffle bandit actions. `None` uses the
+    /// default md5 sharder, which is required to match assignments of other Eppo SDKs.
+    pub sharder: Option<Arc<dyn Sharder>>,
+    /// Attributes added to every evaluated subject (e.g., region or app version). Attributes
+    /// passed to evaluation take precedence over default attributes with the same name.
+    pub default_attributes: Arc<Attributes>,
+    /// Allow evaluating Integer flags as Numeric and Numeric flags as Integer. Numeric values are
+    /// only converted to Integer if they are integral, so a value like `3.5` still produces
+    /// [`EvaluationError::TypeMismatch`]. Conversions are noted in
+    /// [`EvaluationDetails::coerced_from`].
+    pub coerce_numeric_types: bool,
+    /// Options applied to every bandit evaluation. See [`BanditEvaluationOptions`].
+    pub bandit_evaluation_options: BanditEvaluationOptions,
+}
+
+/// Observer notified about flag evaluations, e.g., to export latency and outcome metrics.
+///
+/// The observer is invoked for [`Evaluator::get_assignment()`] and
+/// [`Evaluator::get_bandit_action()`]. Only the outcome of flag evaluation is tracked, which is
+/// much cheaper than collecting full evaluation details. When no observer is configured, evaluation
+/// is not timed at all.
+///
+/// Callbacks are invoked on the thread performing the evaluation, so they should be fast.
+pub trait EvaluationObserver: Send + Sync {
+    /// Called after a flag has been evaluated.
+    ///
+    /// `duration` is the time spent evaluating the flag (excluding event logging). For bandits,
+    /// `outcome` describes evaluation of the flag itself.
+    fn on_evaluation(&self, flag_key: &str, duration: Duration, outcome: FlagEvaluationCode);
 }
 
 /// Evaluator simplifies calling into evaluation functions and automatically adds necessary metadata
@@ -39,15 +84,77 @@ impl Evaluator {
         subject_attributes: &Arc<Attributes>,
         expected_type: Option<VariationType>,
     ) -> Result<Option<Assignment>, EvaluationError> {
+        let merged_attributes = self.merge_default_attributes(subject_attributes);
+        let subject_attributes = merged_attributes.as_ref().unwrap_or(subject_attributes);
+
+        let config = self.get_configuration();
+        let coerced_from = self.coerced_from(config.as_deref(), flag_key, expected_type);
+
+        let result = if let Some(observer) = &self.config.observer {
+            let start = Instant::now();
+            let mut visitor = OutcomeEvalVisitor::default();
+            let result = get_assignment_with_visitor(
+                config.as_ref().map(AsRef::as_ref),
+                &mut visitor.visit_assignment(),
+                flag_key,
+                &Subject::new(subject_key.clone(), subject_attributes.clone()),
+                coerced_from.or(expected_type),
+                self.config.clock.now(),
+                &self.evaluation_options(None),
+            );
+            Self::notify(observer, flag_key, start, visitor);
+            result
+        } else {
+            get_assignment(
+                config.as_ref().map(AsRef::as_ref),
+                flag_key,
+                subject_key,
+                subject_attributes,
+                coerced_from.or(expected_type),
+                self.config.clock.now(),
+                &self.evaluation_options(None),
+            )
+        };
+
+        match (coerced_from, expected_type, result) {
+            (Some(_), Some(ty), Ok(Some(assignment))) => {
+                coerce_assignment(flag_key, assignment, ty).map(Some)
+            }
+            (_, _, result) => result,
+        }
+    }
+
+    pub fn get_assignment_with_reason(
+        &self,
+        flag_key: &str,
+        subject_key: &Str,
+        subject_attributes: &Arc<Attributes>,
+        expected_type: Option<VariationType>,
+    ) -> (Option<Assignment>, FlagEvaluationCode) {
+        let merged_attributes = self.merge_default_attributes(subject_attributes);
+        let subject_attributes = merged_attributes.as_ref().unwrap_or(subject_attributes);
+
         let config = self.get_configuration();
-        get_assignment(
+        let coerced_from = self.coerced_from(config.as_deref(), flag_key, expected_type);
+        let (assignment, code) = get_assignment_with_reason(
             config.as_ref().map(AsRef::as_ref),
-            &flag_key,
-            &subject_key,
-            &subject_attributes,
-            expected_type,
-            Utc::now(),
-        )
+            flag_key,
+            subject_key,
+            subject_attributes,
+            coerced_from.or(expected_type),
+            self.config.clock.now(),
+            &self.evaluation_options(None),
+        );
+
+        match (coerced_from, expected_type, assignment) {
+            (Some(_), Some(ty), Some(assignment)) => {
+                match coerce_assignment(flag_key, assignment, ty) {
+                    Ok(assignment) => (Some(assignment), code),
+                    Err(err) => (None, err.into()),
+                }
+            }
+            (_, _, assignment) => (assignment, code),
+        }
     }
 
     pub fn get_assignment_details(
@@ -60,59 +167,201 @@ impl Evaluator {
         EvaluationResultWithDetails<AssignmentValue>,
         Option<AssignmentEvent>,
     ) {
+        let merged_attributes = self.merge_default_attributes(subject_attributes);
+        let subject_attributes = merged_attributes.as_ref().unwrap_or(subject_attributes);
+
         let config = self.get_configuration();
-        get_assignment_details(
+        let coerced_from = self.coerced_from(config.as_deref(), flag_key, expected_type);
+        let (mut result, mut event) = get_assignment_details(
             config.as_ref().map(AsRef::as_ref),
-            &flag_key,
-            &subject_key,
-            &subject_attributes,
-            expected_type,
-            Utc::now(),
-        )
+            flag_key,
+            subject_key,
+            subject_attributes,
+            coerced_from.or(expected_type),
+            self.config.clock.now(),
+            &self.evaluation_options(None),
+        );
+
+        let (Some(found), Some(ty)) = (coerced_from, expected_type) else {
+            return (result, event);
+        };
+        let Some(value) = result.variation.take() else {
+            return (result, event);
+        };
+
+        // Detach details from the event first, so they are not shared and don't need to be cloned.
+        if let Some(event) = &mut event {
+            event.evaluation_details = None;
+        }
+        let details = Arc::make_mut(&mut result.evaluation_details);
+        match value.coerce_to(ty) {
+            Some(value) => {
+                result.variation = Some(value);
+                details.coerced_from = Some(found);
+                details.flag_evaluation_description = format!(
+                    "{} Variation value was converted from {found:?} to {ty:?}.",
+                    details.flag_evaluation_description
+                );
+            }
+            None => {
+                let err = EvaluationError::TypeMismatch {
+                    expected: ty,
+                    found,
+                };
+                details.flag_evaluation_code = Some(err.into());
+                details.evaluation_error = Some(err);
+                details.flag_evaluation_description = format!(
+                    "Variation value cannot be converted from {found:?} to {ty:?} without loss for flag {flag_key}"
+                );
+                event = None;
+            }
+        }
+        if let Some(event) = &mut event {
+            event.evaluation_details = Some(result.evaluation_details.clone());
+        }
+
+        (result, event)
     }
 
-    pub fn get_bandit_action(
+    pub fn get_bandit_action<'a>(
         &self,
         flag_key: &str,
         subject_key: &Str,
         subject_attributes: &ContextAttributes,
-        actions: &HashMap<Str, ContextAttributes>,
+        actions: impl Into<Actions<'a>>,
+        allowed_actions: Option<&HashSet<Str>>,
         default_variation: &Str,
     ) -> BanditResult {
-        let configuration = self.get_configuration();
-        get_bandit_action(
-            configuration.as_ref().map(|it| it.as_ref()),
+        let merged_attributes = self.merge_default_context_attributes(subject_attributes);
+        let subject_attributes = merged_attributes.as_ref().unwrap_or(subject_attributes);
+
+        let request = BanditRequest {
             flag_key,
             subject_key,
             subject_attributes,
-            actions,
+            actions: actions.into(),
             default_variation,
-            Utc::now(),
+        };
+        let options = self.evaluation_options(allowed_actions);
+
+        let Some(observer) = &self.config.observer else {
+            let configuration = self.get_configuration();
+            return get_bandit_action(
+                configuration.as_ref().map(|it| it.as_ref()),
+                &request,
+                self.config.clock.now(),
+                &options,
+                &self.config.sdk_metadata,
+            );
+        };
+
+        let start = Instant::now();
+        let configuration = self.get_configuration();
+        let mut visitor = OutcomeEvalVisitor::default();
+        let result = get_bandit_action_with_visitor(
+            &mut visitor,
+            configuration.as_ref().map(|it| it.as_ref()),
+            &request,
+            self.config.clock.now(),
+            &options,
             &self.config.sdk_metadata,
-        )
+        );
+        Self::notify(observer, flag_key, start, visitor);
+
+        result
+    }
+
+    fn notify(
+        observer: &Arc<dyn EvaluationObserver>,
+        flag_key: &str,
+        start: Instant,
+        visitor: OutcomeEvalVisitor,
+    ) {
+        let duration = start.elapsed();
+        let outcome = visitor
+            .code
+            .unwrap_or(FlagEvaluationCode::UnexpectedConfigurationError);
+        observer.on_evaluation(flag_key, duration, outcome);
     }
 
-    pub fn get_bandit_action_details(
+    pub fn get_bandit_action_details<'a>(
         &self,
         flag_key: &str,
         subject_key: &Str,
         subject_attributes: &ContextAttributes,
-        actions: &HashMap<Str, ContextAttributes>,
+        actions: impl Into<Actions<'a>>,
+        allowed_actions: Option<&HashSet<Str>>,
         default_variation: &Str,
     ) -> (BanditResult, EvaluationDetails) {
+        let merged_attributes = self.merge_default_context_attributes(subject_attributes);
+        let subject_attributes = merged_attributes.as_ref().unwrap_or(subject_attributes);
+
         let configuration = self.get_configuration();
         get_bandit_action_details(
             configuration.as_ref().map(|it| it.as_ref()),
-            flag_key,
+            &BanditRequest {
+                flag_key,
+                subject_key,
+                subject_attributes,
+                actions: actions.into(),
+                default_variation,
+            },
+            self.config.clock.now(),
+            &self.evaluation_options(allowed_actions),
+            &self.config.sdk_metadata,
+        )
+    }
+
+    /// Rank up to `k` bandit actions for the subject. If `allowed_actions` is `Some`, only actions
+    /// with keys in the set are ranked. See [`get_bandit_actions_ranked()`].
+    pub fn get_bandit_actions_ranked(
+        &self,
+        request: &BanditRequest,
+        k: usize,
+        allowed_actions: Option<&HashSet<Str>>,
+    ) -> Vec<BanditResult> {
+        let merged_attributes = self.merge_default_context_attributes(request.subject_attributes);
+        let request = BanditRequest {
+            subject_attributes: merged_attributes
+                .as_ref()
+                .unwrap_or(request.subject_attributes),
+            ..*request
+        };
+
+        let configuration = self.get_configuration();
+        get_bandit_actions_ranked(
+            configuration.as_ref().map(|it| it.as_ref()),
+            &request,
+            k,
+            self.config.clock.now(),
+            &self.evaluation_options(allowed_actions),
+            &self.config.sdk_metadata,
+        )
+    }
+
+    pub fn get_all_assignments(
+        &self,
+        subject_key: &Str,
+        subject_attributes: &Arc<Attributes>,
+        flag_keys: Option<&[&str]>,
+    ) -> HashMap<Str, Assignment> {
+        let merged_attributes = self.merge_default_attributes(subject_attributes);
+        let subject_attributes = merged_attributes.as_ref().unwrap_or(subject_attributes);
+
+        let configuration = self.get_configuration();
+        get_all_assignments(
+            configuration.as_ref().map(AsRef::as_ref),
             subject_key,
             subject_attributes,
-            actions,
-            default_variation,
-            Utc::now(),
-            &self.config.sdk_metadata,
+            flag_keys,
+            self.config.clock.now(),
+            &self.evaluation_options(None),
         )
     }
 
+    /// Precompute assignments and bandit actions for a single subject. If `flag_filter` is
+    /// provided, only flags with keys in the filter are evaluated. If `with_details` is true,
+    /// assignments carry compact evaluation details (see [`get_precomputed_configuration()`]).
     pub fn get_precomputed_configuration(
         &self,
         subject_key: &Str,
@@ -121,18 +370,493 @@ impl Evaluator {
             /* flag_key: */ Str,
             HashMap</* action_key: */ Str, ContextAttributes>,
         >,
+        flag_filter: Option<&HashSet<Str>>,
+        with_details: bool,
     ) -> PrecomputedConfiguration {
+        let merged_attributes = self
+            .merge_default_context_attributes(subject_attributes)
+            .map(Arc::new);
+        let subject_attributes = merged_attributes.as_ref().unwrap_or(subject_attributes);
+
         let configuration = self.get_configuration();
         get_precomputed_configuration(
             configuration.as_ref().map(AsRef::as_ref),
             subject_key,
             subject_attributes,
             flag_actions,
-            Utc::now(),
+            self.config.clock.now(),
+            &PrecomputeOptions {
+                flag_filter,
+                with_details,
+                evaluation: self.evaluation_options(None),
+            },
         )
     }
 
+    /// Generation of the configuration used for evaluation. See
+    /// [`ConfigurationStoreBackend::generation()`].
+    ///
+    /// Read it before evaluating (e.g., to emit as an ETag for precomputed assignments), so the
+    /// result is computed from configuration at least as new as the returned generation.
+    pub fn configuration_generation(&self) -> u64 {
+        self.config.configuration_store.generation()
+    }
+
     fn get_configuration(&self) -> Option<Arc<Configuration>> {
-        self.config.configuration_store.get_configuration()
+        self.config.configuration_store.get()
+    }
+
+    fn evaluation_options<'a>(
+        &'a self,
+        allowed_actions: Option<&'a HashSet<Str>>,
+    ) -> EvaluationOptions<'a> {
+        EvaluationOptions {
+            sharder: self.config.sharder.as_deref(),
+            allowed_actions,
+            bandit: self.config.bandit_evaluation_options,
+        }
+    }
+
+    /// Returns the variation type of `flag_key` if the flag has to be evaluated as that type and
+    /// converted to `expected_type` afterwards, i.e., numeric type coercion is enabled and the
+    /// flag is Integer while Numeric is expected (or vice versa).
+    fn coerced_from(
+        &self,
+        configuration: Option<&Configuration>,
+        flag_key: &str,
+        expected_type: Option<VariationType>,
+    ) -> Option<VariationType> {
+        if !self.config.coerce_numeric_types {
+            return None;
+        }
+        let found = configuration?
+            .flags
+            .compiled
+            .flag_variation_type(flag_key)?;
+        match (expected_type?, found) {
+            (VariationType::Integer, VariationType::Numeric)
+            | (VariationType::Numeric, VariationType::Integer) => Some(found),
+            _ => None,
+        }
+    }
+
+    /// Merge default attributes under `subject_attributes`. Returns `None` if there are no default
+    /// attributes, so `subject_attributes` can be used as is without copying.
+    fn merge_default_attributes(&self, subject_attributes: &Attributes) -> Option<Arc<Attributes>> {
+        let defaults = &self.config.default_attributes;
+        if defaults.is_empty() {
+            return None;
+        }
+
+        let mut merged = Attributes::clone(defaults);
+        merged.extend(
+            subject_attributes
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        Some(Arc::new(merged))
+    }
+
+    /// Same as [`Evaluator::merge_default_attributes()`] but for bandit context attributes.
+    /// Default attributes are overridden by both numeric and categorical attributes of the
+    /// subject.
+    fn merge_default_context_attributes(
+        &self,
+        subject_attributes: &ContextAttributes,
+    ) -> Option<ContextAttributes> {
+        let defaults = &self.config.default_attributes;
+        if defaults.is_empty() {
+            return None;
+        }
+
+        let merged = defaults
+            .iter()
+            .filter(|(key, _)| {
+                !subject_attributes.numeric.contains_key(*key)
+                    && !subject_attributes.categorical.contains_key(*key)
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .chain(subject_attributes.to_generic_attributes())
+            .collect();
+        Some(merged)
+    }
+}
+
+/// Convert value of `assignment` to `ty`. The assignment (and its event) is dropped if the value
+/// cannot be converted without loss.
+fn coerce_assignment(
+    flag_key: &str,
+    assignment: Assignment,
+    ty: VariationType,
+) -> Result<Assignment, EvaluationError> {
+    let found = assignment.value.variation_type();
+    match assignment.value.coerce_to(ty) {
+        Some(value) => Ok(Assignment {
+            value,
+            ..assignment
+        }),
+        None => {
+            log::warn!(target: "eppo",
+                       flag_key;
+                       "variation value cannot be converted from {found:?} to {ty:?} without loss");
+            Err(EvaluationError::TypeMismatch {
+                expected: ty,
+                found,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use crate::{
+        configuration_store::ConfigurationStore,
+        eval::eval_details::FlagEvaluationCode,
+        test_server::SDK_METADATA,
+        timestamp::{FixedClock, SystemClock, Timestamp},
+        ufc::{AssignmentValue, VariationType},
+        Attributes, Configuration, ContextAttributes, EvaluationError,
+    };
+
+    use super::{Evaluator, EvaluatorConfig};
+
+    fn configuration_store(flags_json: &[u8]) -> Arc<ConfigurationStore> {
+        let store = Arc::new(ConfigurationStore::new());
+        store.set_configuration(Arc::new(Configuration::from_ufc_json_for_tests(flags_json)));
+        store
+    }
+
+    /// Evaluator serving a flag that returns "on" during January 2024 and "off" otherwise.
+    fn evaluator(now: &str) -> Evaluator {
+        let store = configuration_store(
+            br#"{
+              "createdAt": "2024-01-01T00:00:00Z",
+              "format": "SERVER",
+              "environment": {"name": "test"},
+              "flags": {
+                "promo": {
+                  "key": "promo",
+                  "enabled": true,
+                  "variationType": "STRING",
+                  "totalShards": 10000,
+                  "variations": {
+                    "on": {"key": "on", "value": "on"},
+                    "off": {"key": "off", "value": "off"}
+                  },
+                  "allocations": [
+                    {
+                      "key": "january",
+                      "startAt": "2024-01-01T00:00:00Z",
+                      "endAt": "2024-02-01T00:00:00Z",
+                      "splits": [{"variationKey": "on", "shards": []}],
+                      "doLog": true
+                    },
+                    {
+                      "key": "default",
+                      "splits": [{"variationKey": "off", "shards": []}],
+                      "doLog": true
+                    }
+                  ]
+                }
+              }
+            }"#,
+        );
+        Evaluator::new(EvaluatorConfig {
+            configuration_store: store,
+            sdk_metadata: SDK_METADATA,
+            clock: Arc::new(FixedClock(now.parse::<Timestamp>().unwrap())),
+            observer: None,
+            sharder: None,
+            default_attributes: Arc::default(),
+            coerce_numeric_types: false,
+            bandit_evaluation_options: Default::default(),
+        })
+    }
+
+    #[test]
+    fn fixed_clock_controls_allocation_window() {
+        for (now, expected) in [
+            ("2023-12-31T23:59:59Z", "off"),
+            ("2024-01-01T00:00:00Z", "on"),
+            ("2024-01-15T12:00:00Z", "on"),
+            ("2024-02-01T00:00:01Z", "off"),
+        ] {
+            let evaluator = evaluator(now);
+
+            let assignment = evaluator
+                .get_assignment(
+                    "promo",
+                    &"alice".into(),
+                    &Arc::new(Attributes::new()),
+                    Some(VariationType::String),
+                )
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                assignment.value,
+                AssignmentValue::String(expected.into()),
+                "at {now}"
+            );
+
+            let precomputed = evaluator.get_precomputed_configuration(
+                &"alice".into(),
+                &Arc::new(ContextAttributes::default()),
+                &HashMap::new(),
+                None,
+                false,
+            );
+            assert_eq!(
+                precomputed.flags["promo"].variation_key.as_deref(),
+                Some(expected),
+                "at {now}"
+            );
+
+            let bandit = evaluator.get_bandit_action(
+                "promo",
+                &"alice".into(),
+                &ContextAttributes::default(),
+                &HashMap::new(),
+                None,
+                &"default".into(),
+            );
+            assert_eq!(&*bandit.variation, expected, "at {now}");
+        }
+    }
+
+    #[test]
+    fn default_attributes_are_merged_under_subject_attributes() {
+        let evaluator = Evaluator::new(EvaluatorConfig {
+            configuration_store: configuration_store(
+                br#"{
+                  "createdAt": "2024-01-01T00:00:00Z",
+                  "format": "SERVER",
+                  "environment": {"name": "test"},
+                  "flags": {
+                    "regional": {
+                      "key": "regional",
+                      "enabled": true,
+                      "variationType": "STRING",
+                      "totalShards": 10000,
+                      "variations": {
+                        "eu": {"key": "eu", "value": "eu"},
+                        "other": {"key": "other", "value": "other"}
+                      },
+                      "allocations": [
+                        {
+                          "key": "eu",
+                          "rules": [{"conditions": [{"attribute": "region", "operator": "ONE_OF", "value": ["eu"]}]}],
+                          "splits": [{"variationKey": "eu", "shards": []}],
+                          "doLog": true
+                        },
+                        {
+                          "key": "other",
+                          "splits": [{"variationKey": "other", "shards": []}],
+                          "doLog": true
+                        }
+                      ]
+                    }
+                  }
+                }"#,
+            ),
+            sdk_metadata: SDK_METADATA,
+            clock: Arc::new(SystemClock),
+            observer: None,
+            sharder: None,
+            default_attributes: Arc::new(
+                [
+                    ("region".into(), "eu".into()),
+                    ("platform".into(), "ios".into()),
+                ]
+                .into(),
+            ),
+            coerce_numeric_types: false,
+            bandit_evaluation_options: Default::default(),
+        });
+        let assign = |attributes: &Arc<Attributes>| {
+            evaluator
+                .get_assignment("regional", &"alice".into(), attributes, None)
+                .unwrap()
+                .unwrap()
+        };
+
+        let assignment = assign(&Arc::new(Attributes::new()));
+        assert_eq!(assignment.value, AssignmentValue::String("eu".into()));
+        assert_eq!(
+            *assignment.event.unwrap().subject_attributes,
+            Attributes::from([
+                ("region".into(), "eu".into()),
+                ("platform".into(), "ios".into()),
+            ])
+        );
+
+        // Subject attributes win over default attributes and are not modified.
+        let attributes = Arc::new(Attributes::from([("region".into(), "us".into())]));
+        let assignment = assign(&attributes);
+        assert_eq!(assignment.value, AssignmentValue::String("other".into()));
+        assert_eq!(
+            *assignment.event.unwrap().subject_attributes,
+            Attributes::from([
+                ("region".into(), "us".into()),
+                ("platform".into(), "ios".into()),
+            ])
+        );
+        assert_eq!(
+            *attributes,
+            Attributes::from([("region".into(), "us".into())])
+        );
+
+        let bandit = evaluator.get_bandit_action(
+            "regional",
+            &"alice".into(),
+            &ContextAttributes::from_iter([("region", "us")]),
+            &HashMap::new(),
+            None,
+            &"default".into(),
+        );
+        assert_eq!(&*bandit.variation, "other");
+        let bandit = evaluator.get_bandit_action(
+            "regional",
+            &"alice".into(),
+            &ContextAttributes::default(),
+            &HashMap::new(),
+            None,
+            &"default".into(),
+        );
+        assert_eq!(&*bandit.variation, "eu");
+    }
+
+    #[test]
+    fn numeric_types_are_coerced_when_enabled() {
+        let variation = |ty: &str, value: &str| {
+            format!(
+                r#"{{
+                  "key": "flag",
+                  "enabled": true,
+                  "variationType": "{ty}",
+                  "totalShards": 10000,
+                  "variations": {{"value": {{"key": "value", "value": {value}}}}},
+                  "allocations": [{{
+                    "key": "everyone",
+                    "splits": [{{"variationKey": "value", "shards": []}}],
+                    "doLog": true
+                  }}]
+                }}"#
+            )
+        };
+        let flags_json = format!(
+            r#"{{
+              "createdAt": "2024-01-01T00:00:00Z",
+              "format": "SERVER",
+              "environment": {{"name": "test"}},
+              "flags": {{
+                "whole": {},
+                "fraction": {},
+                "count": {}
+              }}
+            }}"#,
+            variation("NUMERIC", "3.0"),
+            variation("NUMERIC", "3.5"),
+            variation("INTEGER", "7"),
+        );
+        let evaluator = |coerce_numeric_types| {
+            Evaluator::new(EvaluatorConfig {
+                configuration_store: configuration_store(flags_json.as_bytes()),
+                sdk_metadata: SDK_METADATA,
+                clock: Arc::new(SystemClock),
+                observer: None,
+                sharder: None,
+                default_attributes: Arc::default(),
+                coerce_numeric_types,
+                bandit_evaluation_options: Default::default(),
+            })
+        };
+        let assign = |evaluator: &Evaluator, flag_key, ty| {
+            evaluator
+                .get_assignment(
+                    flag_key,
+                    &"alice".into(),
+                    &Arc::new(Attributes::new()),
+                    Some(ty),
+                )
+                .map(|assignment| assignment.map(|assignment| assignment.value))
+        };
+
+        let strict = evaluator(false);
+        assert_eq!(
+            assign(&strict, "whole", VariationType::Integer),
+            Err(EvaluationError::TypeMismatch {
+                expected: VariationType::Integer,
+                found: VariationType::Numeric
+            })
+        );
+
+        let evaluator = evaluator(true);
+        assert_eq!(
+            assign(&evaluator, "whole", VariationType::Integer),
+            Ok(Some(AssignmentValue::Integer(3)))
+        );
+        assert_eq!(
+            assign(&evaluator, "count", VariationType::Numeric),
+            Ok(Some(AssignmentValue::Numeric(7.0)))
+        );
+        assert_eq!(
+            assign(&evaluator, "fraction", VariationType::Numeric),
+            Ok(Some(AssignmentValue::Numeric(3.5)))
+        );
+        // Lossy conversion is still a type mismatch.
+        assert_eq!(
+            assign(&evaluator, "fraction", VariationType::Integer),
+            Err(EvaluationError::TypeMismatch {
+                expected: VariationType::Integer,
+                found: VariationType::Numeric
+            })
+        );
+        // Only numeric types are coerced.
+        assert!(assign(&evaluator, "count", VariationType::String).is_err());
+
+        let (assignment, code) = evaluator.get_assignment_with_reason(
+            "fraction",
+            &"alice".into(),
+            &Arc::new(Attributes::new()),
+            Some(VariationType::Integer),
+        );
+        assert!(assignment.is_none());
+        assert_eq!(code, FlagEvaluationCode::TypeMismatch);
+
+        let details = |flag_key, ty| {
+            evaluator.get_assignment_details(
+                flag_key,
+                &"alice".into(),
+                &Arc::new(Attributes::new()),
+                Some(ty),
+            )
+        };
+
+        let (result, event) = details("whole", VariationType::Integer);
+        assert_eq!(result.variation, Some(AssignmentValue::Integer(3)));
+        assert_eq!(
+            result.evaluation_details.coerced_from,
+            Some(VariationType::Numeric)
+        );
+        assert_eq!(
+            result.evaluation_details.variation_value,
+            Some(AssignmentValue::Numeric(3.0))
+        );
+        let event = event.unwrap();
+        assert!(Arc::ptr_eq(
+            event.evaluation_details.as_ref().unwrap(),
+            &result.evaluation_details
+        ));
+
+        let (result, event) = details("fraction", VariationType::Integer);
+        assert_eq!(result.variation, None);
+        assert_eq!(
+            result.flag_evaluation_code(),
+            Some(FlagEvaluationCode::TypeMismatch)
+        );
+        assert_eq!(result.evaluation_details.coerced_from, None);
+        assert!(event.is_none());
     }
 }
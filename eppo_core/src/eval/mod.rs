@@ -1,6 +1,7 @@
 mod eval_assignment;
 mod eval_bandits;
 mod eval_details_builder;
+mod eval_options;
 mod eval_precomputed;
 mod eval_rules;
 mod eval_visitor;
@@ -9,7 +10,13 @@ mod subject;
 
 pub mod eval_details;
 
-pub use eval_assignment::{get_assignment, get_assignment_details};
-pub use eval_bandits::{get_bandit_action, get_bandit_action_details, BanditResult};
-pub use eval_precomputed::get_precomputed_configuration;
-pub use evaluator::{Evaluator, EvaluatorConfig};
+pub use eval_assignment::{
+    get_all_assignments, get_assignment, get_assignment_details, get_assignment_with_reason,
+};
+pub use eval_bandits::{
+    get_bandit_action, get_bandit_action_details, get_bandit_actions_ranked, Actions,
+    BanditEvaluationOptions, BanditRequest, BanditResult, UnknownActionPolicy,
+};
+pub use eval_options::EvaluationOptions;
+pub use eval_precomputed::{get_precomputed_configuration, PrecomputeOptions};
+pub use evaluator::{EvaluationObserver, Evaluator, EvaluatorConfig};
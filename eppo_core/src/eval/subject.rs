@@ -1,6 +1,10 @@
-use std::sync::Arc;
+use std::{
+    cell::OnceCell,
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+};
 
-use crate::Str;
+use crate::{obfuscation::md5_hex, Str};
 
 use crate::{AttributeValue, Attributes};
 
@@ -11,6 +15,9 @@ pub(super) struct Subject {
     /// done to allow returning subject key as an attribute when rule references "id".
     key: AttributeValue,
     attributes: Arc<Attributes>,
+    /// Attribute names keyed by md5 hashes of the names. Only needed to evaluate obfuscated
+    /// configuration, so it's built on first use (once per subject).
+    hashed_attribute_names: OnceCell<HashMap<String, Str>>,
 }
 
 impl Subject {
@@ -18,6 +25,7 @@ impl Subject {
         Subject {
             key: AttributeValue::from(key),
             attributes,
+            hashed_attribute_names: OnceCell::new(),
         }
     }
 
@@ -28,6 +36,10 @@ impl Subject {
         s
     }
 
+    pub fn attributes(&self) -> &Arc<Attributes> {
+        &self.attributes
+    }
+
     /// Get subject attribute.
     ///
     /// If attribute `name` is `"id"` and there's no explicit attribute with this name, return
@@ -44,4 +56,31 @@ impl Subject {
 
         None
     }
+
+    /// Get subject attribute by md5 hash of its name. This is used to evaluate obfuscated
+    /// configuration.
+    ///
+    /// Same as [`Subject::get_attribute`], falls back to subject key for `"id"` attribute.
+    pub fn get_obfuscated_attribute(&self, name_hash: &str) -> Option<&AttributeValue> {
+        static ID_HASH: OnceLock<String> = OnceLock::new();
+
+        let names = self.hashed_attribute_names.get_or_init(|| {
+            self.attributes
+                .keys()
+                .map(|name| (md5_hex(name), name.clone()))
+                .collect()
+        });
+        let value = names
+            .get(name_hash)
+            .and_then(|name| self.attributes.get(name));
+        if value.is_some() {
+            return value;
+        }
+
+        if ID_HASH.get_or_init(|| md5_hex("id")) == name_hash {
+            return Some(&self.key);
+        }
+
+        None
+    }
 }
@@ -5,6 +5,7 @@ use serde::Serialize;
 use crate::{
     attributes::{Attributes, CategoricalAttribute, NumericAttribute},
     eval::eval_details::EvaluationDetails,
+    ufc::{ValueWire, VariationType},
     SdkMetadata, Str,
 };
 
@@ -18,7 +19,7 @@ pub struct Events {
 }
 
 /// Common fields for the same split.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AssignmentEventBase {
     /// The key of the feature flag being assigned.
@@ -29,6 +30,10 @@ pub struct AssignmentEventBase {
     pub experiment: String,
     /// The specific variation assigned to the subject.
     pub variation: Str,
+    /// The type of the flag's variations.
+    pub variation_type: VariationType,
+    /// The value of the assigned variation, as it appears in the flag configuration.
+    pub variation_value: ValueWire,
     /// Additional metadata such as SDK language and version.
     pub meta_data: EventMetaData,
     /// Additional user-defined logging fields for capturing extra information related to the
@@ -66,6 +71,10 @@ pub struct BanditEvent {
     pub action: Str,
     pub action_probability: f64,
     pub optimality_gap: f64,
+    /// Score of the selected action according to the bandit model.
+    pub action_score: f64,
+    /// Exploration parameter of the bandit model at the time of evaluation.
+    pub gamma: f64,
     pub model_version: Str,
     pub timestamp: String,
     pub subject_numeric_attributes: Arc<HashMap<Str, NumericAttribute>>,
@@ -99,6 +108,139 @@ impl From<&SdkMetadata> for EventMetaData {
     }
 }
 
+/// Canonical JSON representation of [`AssignmentEvent`], shared with other Eppo SDKs.
+///
+/// This is decoupled from `AssignmentEvent`'s own `Serialize` implementation, so that the exported
+/// schema stays stable even if the struct changes.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AssignmentEventJson<'a> {
+    feature_flag: &'a str,
+    allocation: &'a str,
+    experiment: &'a str,
+    variation: &'a str,
+    variation_type: VariationType,
+    variation_value: &'a ValueWire,
+    subject: &'a str,
+    subject_attributes: &'a Attributes,
+    timestamp: String,
+    meta_data: &'a EventMetaData,
+    extra_logging: &'a HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    evaluation_details: Option<&'a EvaluationDetails>,
+}
+
+impl AssignmentEvent {
+    /// Convert event to JSON using the canonical schema expected by Eppo's ingestion:
+    ///
+    /// ```json
+    /// {
+    ///   "featureFlag": "...",
+    ///   "allocation": "...",
+    ///   "experiment": "...",
+    ///   "variation": "...",
+    ///   "variationType": "STRING",
+    ///   "variationValue": "...",
+    ///   "subject": "...",
+    ///   "subjectAttributes": {...},
+    ///   "timestamp": "2024-07-18T00:00:00.000Z",
+    ///   "metaData": {"sdkName": "...", "sdkVersion": "...", "coreVersion": "..."},
+    ///   "extraLogging": {...},
+    ///   "evaluationDetails": {...}
+    /// }
+    /// ```
+    ///
+    /// `evaluationDetails` is only present if the event has evaluation details attached. Use
+    /// [`AssignmentEvent::to_json_without_details()`] to always omit it.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.to_json_impl(self.evaluation_details.as_deref())
+    }
+
+    /// Same as [`AssignmentEvent::to_json()`] but never includes `evaluationDetails`.
+    pub fn to_json_without_details(&self) -> serde_json::Value {
+        self.to_json_impl(None)
+    }
+
+    fn to_json_impl(&self, evaluation_details: Option<&EvaluationDetails>) -> serde_json::Value {
+        serde_json::to_value(AssignmentEventJson {
+            feature_flag: &self.base.feature_flag,
+            allocation: &self.base.allocation,
+            experiment: &self.base.experiment,
+            variation: &self.base.variation,
+            variation_type: self.base.variation_type,
+            variation_value: &self.base.variation_value,
+            subject: &self.subject,
+            subject_attributes: &self.subject_attributes,
+            timestamp: self
+                .timestamp
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            meta_data: &self.base.meta_data,
+            extra_logging: &self.base.extra_logging,
+            evaluation_details,
+        })
+        .expect("AssignmentEvent should always be serializable to JSON")
+    }
+}
+
+/// Canonical JSON representation of [`BanditEvent`], shared with other Eppo SDKs.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BanditEventJson<'a> {
+    flag_key: &'a str,
+    bandit_key: &'a str,
+    subject: &'a str,
+    action: &'a str,
+    action_probability: f64,
+    optimality_gap: f64,
+    model_version: &'a str,
+    timestamp: &'a str,
+    subject_numeric_attributes: &'a HashMap<Str, NumericAttribute>,
+    subject_categorical_attributes: &'a HashMap<Str, CategoricalAttribute>,
+    action_numeric_attributes: &'a HashMap<Str, NumericAttribute>,
+    action_categorical_attributes: &'a HashMap<Str, CategoricalAttribute>,
+    meta_data: &'a EventMetaData,
+}
+
+impl BanditEvent {
+    /// Convert event to JSON using the canonical schema expected by Eppo's ingestion:
+    ///
+    /// ```json
+    /// {
+    ///   "flagKey": "...",
+    ///   "banditKey": "...",
+    ///   "subject": "...",
+    ///   "action": "...",
+    ///   "actionProbability": 0.5,
+    ///   "optimalityGap": 0.0,
+    ///   "modelVersion": "...",
+    ///   "timestamp": "...",
+    ///   "subjectNumericAttributes": {...},
+    ///   "subjectCategoricalAttributes": {...},
+    ///   "actionNumericAttributes": {...},
+    ///   "actionCategoricalAttributes": {...},
+    ///   "metaData": {"sdkName": "...", "sdkVersion": "...", "coreVersion": "..."}
+    /// }
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(BanditEventJson {
+            flag_key: &self.flag_key,
+            bandit_key: &self.bandit_key,
+            subject: &self.subject,
+            action: &self.action,
+            action_probability: self.action_probability,
+            optimality_gap: self.optimality_gap,
+            model_version: &self.model_version,
+            timestamp: &self.timestamp,
+            subject_numeric_attributes: &self.subject_numeric_attributes,
+            subject_categorical_attributes: &self.subject_categorical_attributes,
+            action_numeric_attributes: &self.action_numeric_attributes,
+            action_categorical_attributes: &self.action_categorical_attributes,
+            meta_data: &self.meta_data,
+        })
+        .expect("BanditEvent should always be serializable to JSON")
+    }
+}
+
 #[cfg(feature = "pyo3")]
 mod pyo3_impl {
     use pyo3::{PyObject, PyResult, Python};
@@ -144,3 +286,117 @@ mod magnus_impl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use chrono::{TimeZone, Utc};
+
+    use crate::{
+        ufc::{ValueWire, VariationType},
+        AttributeValue, CategoricalAttribute, NumericAttribute,
+    };
+
+    use super::{AssignmentEvent, AssignmentEventBase, BanditEvent, EventMetaData};
+
+    const META_DATA: EventMetaData = EventMetaData {
+        sdk_name: "test",
+        sdk_version: "1.0.0",
+        core_version: "2.0.0",
+    };
+
+    fn assignment_event() -> AssignmentEvent {
+        AssignmentEvent {
+            base: Arc::new(AssignmentEventBase {
+                feature_flag: "flag".into(),
+                allocation: "allocation".into(),
+                experiment: "flag-allocation".to_owned(),
+                variation: "variation".into(),
+                variation_type: VariationType::String,
+                variation_value: ValueWire::String("hello".into()),
+                meta_data: META_DATA,
+                extra_logging: HashMap::from([("holdout".to_owned(), "h1".to_owned())]),
+            }),
+            subject: "subject".into(),
+            subject_attributes: Arc::new(HashMap::from([
+                ("age".into(), AttributeValue::numeric(42.0)),
+                ("country".into(), AttributeValue::categorical("DE")),
+            ])),
+            timestamp: Utc.with_ymd_and_hms(2024, 7, 18, 12, 30, 0).unwrap(),
+            evaluation_details: None,
+        }
+    }
+
+    #[test]
+    fn assignment_event_to_json() {
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/golden/assignment_event.json")).unwrap();
+
+        assert_eq!(assignment_event().to_json(), golden);
+        assert_eq!(assignment_event().to_json_without_details(), golden);
+    }
+
+    #[test]
+    fn bandit_event_to_json() {
+        let event = BanditEvent {
+            flag_key: "flag".into(),
+            bandit_key: "bandit".into(),
+            subject: "subject".into(),
+            action: "action".into(),
+            action_probability: 0.5,
+            optimality_gap: 0.25,
+            action_score: 1.5,
+            gamma: 1.0,
+            model_version: "v1".into(),
+            timestamp: "2024-07-18T12:30:00.000Z".to_owned(),
+            subject_numeric_attributes: Arc::new(HashMap::from([(
+                "age".into(),
+                NumericAttribute::from(42.0),
+            )])),
+            subject_categorical_attributes: Arc::new(HashMap::from([(
+                "country".into(),
+                CategoricalAttribute::from("DE"),
+            )])),
+            action_numeric_attributes: Arc::new(HashMap::new()),
+            action_categorical_attributes: Arc::new(HashMap::from([(
+                "brand".into(),
+                CategoricalAttribute::from("nike"),
+            )])),
+            meta_data: META_DATA,
+        };
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/golden/bandit_event.json")).unwrap();
+
+        assert_eq!(event.to_json(), golden);
+    }
+
+    #[test]
+    fn non_finite_attributes_are_serialized_as_null() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut event = assignment_event();
+            event.subject_attributes = Arc::new(HashMap::from([
+                ("age".into(), AttributeValue::numeric(value)),
+                ("score".into(), AttributeValue::categorical(value)),
+                ("weight".into(), value.into()),
+            ]));
+
+            assert_eq!(
+                event.to_json()["subjectAttributes"],
+                serde_json::json!({"age": null, "score": null, "weight": null}),
+                "{value}"
+            );
+
+            // Numeric attributes of bandit events are not normalized, but are still serialized as
+            // valid JSON.
+            assert_eq!(
+                serde_json::to_value(NumericAttribute::from(value)).unwrap(),
+                serde_json::Value::Null
+            );
+            assert_eq!(
+                serde_json::to_value(CategoricalAttribute::from(value)).unwrap(),
+                serde_json::Value::Null
+            );
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! A [`ConfigurationStoreBackend`] that shares configuration between processes on the same host
+//! through a file.
+//!
+//! One process (e.g., a sidecar or a leader worker) runs the poller with a
+//! [`FileConfigurationStore`], and other processes create a `FileConfigurationStore` with the
+//! same path and only evaluate flags. They pick up configuration changes without polling the
+//! server themselves.
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock, Weak,
+    },
+    time::{Duration, SystemTime},
+};
+
+use arc_swap::ArcSwapOption;
+
+use crate::{
+    configuration_persistence::{ConfigurationPersistence, FileConfigurationPersistence},
+    configuration_store::ConfigurationStoreBackend,
+    Configuration, SdkMetadata,
+};
+
+/// A [`ConfigurationStoreBackend`] that stores configuration in a file.
+///
+/// This is a reference implementation for out-of-process backends. Configuration is cached in
+/// memory, so evaluation never hits the filesystem. The file is loaded on the first
+/// [`get()`](ConfigurationStoreBackend::get) and then checked for changes by a background thread
+/// every [`FileConfigurationStore::with_refresh_interval()`]. The thread exits after the store is
+/// dropped.
+pub struct FileConfigurationStore {
+    inner: Arc<Inner>,
+    watcher: OnceLock<()>,
+}
+
+struct Inner {
+    file: FileConfigurationPersistence,
+    sdk_metadata: SdkMetadata,
+    refresh_interval: Duration,
+    configuration: ArcSwapOption<Configuration>,
+    /// Version of the file `configuration` was loaded from. The lock also serializes file access
+    /// between `refresh()` and `set()`.
+    version: Mutex<Option<FileVersion>>,
+    generation: AtomicU64,
+}
+
+/// Identifies file contents without reading it. The file is replaced on every write, so a change
+/// of any of these means that configuration has changed. (On Unix, the inode number is
+/// different for every write. On other platforms, changes within the resolution of modification
+/// time that keep the same file size may be missed.)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileVersion {
+    modified: SystemTime,
+    len: u64,
+    #[cfg(unix)]
+    inode: u64,
+}
+
+impl FileVersion {
+    fn of(path: &Path) -> std::io::Result<FileVersion> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(FileVersion {
+            modified: metadata.modified()?,
+            len: metadata.len(),
+            #[cfg(unix)]
+            inode: std::os::unix::fs::MetadataExt::ino(&metadata),
+        })
+    }
+}
+
+impl FileConfigurationStore {
+    /// Default value for [`FileConfigurationStore::with_refresh_interval()`].
+    pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Shorter refresh intervals are rounded up to this value, so the background thread doesn't
+    /// spin.
+    pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Create a store that keeps configuration at `path`. The parent directory must exist.
+    ///
+    /// `sdk_metadata` is used to parse configuration written by other processes.
+    pub fn new(path: impl Into<PathBuf>, sdk_metadata: SdkMetadata) -> FileConfigurationStore {
+        FileConfigurationStore {
+            inner: Arc::new(Inner {
+                file: FileConfigurationPersistence::new(path),
+                sdk_metadata,
+                refresh_interval: FileConfigurationStore::DEFAULT_REFRESH_INTERVAL,
+                configuration: ArcSwapOption::empty(),
+                version: Mutex::new(None),
+                generation: AtomicU64::new(0),
+            }),
+            watcher: OnceLock::new(),
+        }
+    }
+
+    /// Update how often the file is checked for changes made by other processes.
+    ///
+    /// Must be called before the store is used.
+    pub fn with_refresh_interval(mut self, interval: Duration) -> FileConfigurationStore {
+        if let Some(inner) = Arc::get_mut(&mut self.inner) {
+            inner.refresh_interval = interval.max(FileConfigurationStore::MIN_REFRESH_INTERVAL);
+        }
+        self
+    }
+
+    /// Path to the file with configuration.
+    pub fn path(&self) -> &Path {
+        self.inner.file.path()
+    }
+
+    /// Load the file and start a thread that watches it for changes. Runs once, on the first
+    /// `get()`.
+    fn start_watcher(&self) {
+        self.inner.refresh();
+
+        let inner = Arc::downgrade(&self.inner);
+        let interval = self.inner.refresh_interval;
+        let result = std::thread::Builder::new()
+            .name("eppo-file-store".to_owned())
+            .spawn(move || Inner::watch(inner, interval));
+        if let Err(err) = result {
+            log::warn!(target: "eppo", "failed to start configuration file watcher: {err}");
+        }
+    }
+}
+
+impl Inner {
+    fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Refresh configuration every `interval` until the store is dropped.
+    fn watch(inner: Weak<Inner>, interval: Duration) {
+        loop {
+            std::thread::sleep(interval);
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+            inner.refresh();
+        }
+    }
+
+    /// Reload configuration if the file has changed since it was last loaded.
+    fn refresh(&self) {
+        let mut current_version = self.lock_version();
+
+        let version = match FileVersion::of(self.path()) {
+            Ok(version) => version,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return,
+            Err(err) => {
+                log::warn!(target: "eppo", "failed to check configuration file {}: {err}", self.path().display());
+                return;
+            }
+        };
+        if *current_version == Some(version) {
+            return;
+        }
+
+        // Remember the version even if the file cannot be parsed, so we don't try to parse it
+        // again until it's replaced.
+        *current_version = Some(version);
+        let configuration = match self.file.load() {
+            Ok(Some(bytes)) => Configuration::from_bytes(self.sdk_metadata, &bytes),
+            // Removed after we checked it.
+            Ok(None) => return,
+            Err(err) => Err(err.into()),
+        };
+        match configuration {
+            Ok(configuration) => {
+                self.configuration.store(Some(Arc::new(configuration)));
+                self.generation.fetch_add(1, Ordering::Release);
+            }
+            Err(err) => {
+                log::warn!(target: "eppo", "failed to load configuration from {}: {err}", self.path().display());
+            }
+        }
+    }
+
+    fn lock_version(&self) -> std::sync::MutexGuard<'_, Option<FileVersion>> {
+        self.version.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl ConfigurationStoreBackend for FileConfigurationStore {
+    fn get(&self) -> Option<Arc<Configuration>> {
+        self.watcher.get_or_init(|| self.start_watcher());
+        self.inner.configuration.load_full()
+    }
+
+    /// Write `configuration` to the file. Errors are logged, but configuration is still served to
+    /// this process.
+    fn set(&self, configuration: Arc<Configuration>) {
+        let inner = &self.inner;
+        let mut version = inner.lock_version();
+        match configuration
+            .to_bytes()
+            .and_then(|bytes| Ok(inner.file.save(&bytes)?))
+        {
+            Ok(()) => {
+                *version = FileVersion::of(inner.path()).ok();
+            }
+            Err(err) => {
+                log::warn!(target: "eppo", "failed to save configuration to {}: {err}", inner.path().display());
+            }
+        }
+        inner.configuration.store(Some(configuration));
+        inner.generation.fetch_add(1, Ordering::Release);
+    }
+
+    fn generation(&self) -> u64 {
+        self.inner.generation.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        configuration_store::ConfigurationStoreBackend,
+        test_server::{SDK_METADATA, UFC_JSON},
+        Configuration,
+    };
+
+    use super::FileConfigurationStore;
+
+    fn configuration(flags: &str) -> Arc<Configuration> {
+        Arc::new(Configuration::from_ufc_json_for_tests(flags))
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("eppo-{}-{name}.json", std::process::id()))
+    }
+
+    /// Store that doesn't refresh in the background, so tests can call `refresh()` explicitly.
+    fn store(path: &Path) -> FileConfigurationStore {
+        FileConfigurationStore::new(path, SDK_METADATA)
+            .with_refresh_interval(Duration::from_secs(3600))
+    }
+
+    #[test]
+    fn shares_configuration_between_stores() {
+        let path = temp_path("file-store-shares");
+        let writer = store(&path);
+        let reader = store(&path);
+        assert!(reader.get().is_none());
+        assert_eq!(reader.generation(), 0);
+
+        writer.set(configuration(UFC_JSON));
+        // `get()` doesn't touch the file, changes are picked up by refresh.
+        assert!(reader.get().is_none());
+        reader.inner.refresh();
+
+        let first = reader.get().unwrap();
+        assert_eq!(first.flags.to_json(), UFC_JSON.as_bytes());
+        assert_eq!(reader.generation(), 1);
+        // Unchanged file is not reloaded.
+        reader.inner.refresh();
+        assert!(Arc::ptr_eq(&reader.get().unwrap(), &first));
+        assert_eq!(reader.generation(), 1);
+
+        let updated = UFC_JSON.replace("2024-07-18", "2024-07-19");
+        writer.set(configuration(&updated));
+        reader.inner.refresh();
+        assert_eq!(reader.get().unwrap().flags.to_json(), updated.as_bytes());
+        assert_eq!(reader.generation(), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn keeps_configuration_if_file_is_corrupt() {
+        let path = temp_path("file-store-corrupt");
+        let store = store(&path);
+        store.set(configuration(UFC_JSON));
+
+        std::fs::write(&path, b"not json").unwrap();
+        store.inner.refresh();
+
+        assert_eq!(store.get().unwrap().flags.to_json(), UFC_JSON.as_bytes());
+        assert_eq!(store.generation(), 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loads_file_on_first_get() {
+        let path = temp_path("file-store-first-get");
+        store(&path).set(configuration(UFC_JSON));
+
+        let reader = store(&path);
+        assert_eq!(reader.get().unwrap().flags.to_json(), UFC_JSON.as_bytes());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn watcher_picks_up_changes() {
+        let path = temp_path("file-store-watcher");
+        let writer = store(&path);
+        let reader = FileConfigurationStore::new(&path, SDK_METADATA)
+            .with_refresh_interval(FileConfigurationStore::MIN_REFRESH_INTERVAL);
+        assert!(reader.get().is_none());
+
+        writer.set(configuration(UFC_JSON));
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while reader.get().is_none() {
+            assert!(Instant::now() < deadline, "configuration was not picked up");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(reader.generation(), 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -16,7 +16,9 @@
 //! central authority on what configuration is currently active. Whenever configuration changes, it
 //! is replaced completely. When a reader gets a configuration, it receives a *snapshot* that is not
 //! affected by further writes—to provide a consistent response to user, it is important that
-//! reader uses the same `Configuration` snapshot throughout the operation.
+//! reader uses the same `Configuration` snapshot throughout the operation. Other storage (e.g., to
+//! share configuration between processes) can be plugged in by implementing
+//! [`ConfigurationStoreBackend`](configuration_store::ConfigurationStoreBackend).
 //!
 //! [`ConfigurationFetcher`](configuration_fetcher::ConfigurationFetcher) is an HTTP client that
 //! knows how to fetch [`Configuration`] from the server. It's best to save and reuse the same
@@ -25,6 +27,10 @@
 //! [`PollerThread`](poller_thread::PollerThread) launches a background thread that periodically
 //! fetches a new `Configuration` (using `ConfigurationFetcher`) and updates
 //! `ConfigurationStore`. This is the simplest way to keep SDK configuration up-to-date.
+//! [`PollerTask`](poller_task::PollerTask) does the same as a task on an existing tokio runtime.
+//! Both can save fetched configuration to a
+//! [`ConfigurationPersistence`](configuration_persistence::ConfigurationPersistence), so it
+//! survives process restarts.
 //!
 //! [`eval`] module contains functions for flag and bandit evaluation. It also supports evaluation
 //! with [details](eval::eval_details::EvaluationDetails). These functions return evaluation results
@@ -40,7 +46,23 @@
 //! global state), they are a bit tedious to call directly. [`Evaluator`](eval::Evaluator) is a
 //! helper to simplify SDK code and pass repeated parameters automatically.
 //!
-//! Most SDKs are built from a `ConfigurationStore`, a `PollerThread`, and an `Evaluator`.
+//! Most SDKs are built from a `ConfigurationStore`, a `PollerThread`, and an `Evaluator`. To serve
+//! several Eppo environments from one process, use one of each per environment, with stores kept in
+//! a [`MultiConfigurationStore`](multi_configuration_store::MultiConfigurationStore).
+//!
+//! # Features
+//!
+//! - `tls-rustls` (default): use rustls for HTTPS connections when fetching configuration.
+//! - `tls-native`: use platform-native TLS implementation (OpenSSL on Linux).
+//! - `vendored`: same as `tls-native` but builds and statically links OpenSSL.
+//! - `compression` (default): request gzip/brotli-compressed configuration responses.
+//! - `xxhash`: `sharder::Xxh3Sharder`, a faster sharder that changes assignments (see
+//!   [`sharder`]).
+//! - `server`: request and response types to serve precomputed assignments (see
+//!   `precomputed_server` module).
+//! - `pyo3`, `magnus`: conversions for Python and Ruby SDKs respectively.
+//!
+//! If both `tls-rustls` and `tls-native` are enabled, the native implementation is used.
 //!
 //! # Versioning
 //!
@@ -49,13 +71,21 @@
 
 #![warn(rustdoc::missing_crate_level_docs)]
 
+pub mod assignment_cache;
 pub mod attributes;
 pub mod bandits;
 pub mod configuration_fetcher;
+pub mod configuration_persistence;
+pub mod configuration_source;
 pub mod configuration_store;
 pub mod eval;
 pub mod events;
+pub mod file_configuration_store;
+pub mod multi_configuration_store;
+pub mod poller_task;
 pub mod poller_thread;
+#[cfg(feature = "server")]
+pub mod precomputed_server;
 #[cfg(feature = "pyo3")]
 pub mod pyo3;
 pub mod sharder;
@@ -64,15 +94,22 @@ pub mod ufc;
 
 mod configuration;
 mod error;
+mod memory_usage;
 mod obfuscation;
 mod precomputed;
 mod sdk_metadata;
 mod str;
+#[cfg(test)]
+mod test_server;
 
 pub use crate::str::Str;
 pub use attributes::{
-    AttributeValue, Attributes, CategoricalAttribute, ContextAttributes, NumericAttribute,
+    AttributeKind, AttributeValue, Attributes, CategoricalAttribute, ContextAttributes,
+    NumericAttribute,
+};
+pub use configuration::{
+    Configuration, ConfigurationDiff, ConfigurationMemoryStats, ConfigurationTimestamps,
 };
-pub use configuration::Configuration;
 pub use error::{Error, EvaluationError, Result};
+pub use precomputed::{ObfuscatedPrecomputedConfiguration, PrecomputedConfiguration};
 pub use sdk_metadata::SdkMetadata;
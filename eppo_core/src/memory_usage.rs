@@ -0,0 +1,260 @@
+//! Approximate accounting of memory occupied by configuration.
+//!
+//! Estimates count string lengths and collection capacities. They ignore allocator overhead,
+//! sharing of reference-counted values, and internals of third-party types (e.g., compiled
+//! regexes), so they should only be used to track the order of magnitude and growth of memory
+//! usage.
+use std::{collections::HashMap, mem::size_of, sync::Arc};
+
+use crate::{
+    bandits::{
+        BanditCategoricalAttributeCoefficient, BanditCoefficients, BanditConfiguration,
+        BanditModelData, BanditNumericAttributeCoefficient, BanditResponse,
+    },
+    error::EvaluationFailure,
+    events::AssignmentEventBase,
+    ufc::{
+        Allocation, AssignmentValue, BanditVariationWire, Condition, ConditionCheck, Flag,
+        RuleWire, Shard, Split, TryParse, ValueWire,
+    },
+    Str,
+};
+
+/// A value that owns heap memory.
+pub(crate) trait MemoryUsage {
+    /// Approximate number of bytes this value owns on the heap, not including `size_of::<Self>()`.
+    fn heap_bytes(&self) -> usize;
+
+    /// Approximate number of bytes occupied by this value, including its own size.
+    fn total_bytes(&self) -> usize
+    where
+        Self: Sized,
+    {
+        size_of::<Self>() + self.heap_bytes()
+    }
+}
+
+macro_rules! no_heap {
+    ($($ty:ty),*) => {
+        $(impl MemoryUsage for $ty {
+            fn heap_bytes(&self) -> usize {
+                0
+            }
+        })*
+    };
+}
+
+no_heap!(bool, u32, u64, usize, i64, f64);
+
+impl MemoryUsage for Str {
+    fn heap_bytes(&self) -> usize {
+        self.len()
+    }
+}
+
+impl MemoryUsage for String {
+    fn heap_bytes(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl MemoryUsage for Box<str> {
+    fn heap_bytes(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for Vec<T> {
+    fn heap_bytes(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(T::heap_bytes).sum::<usize>()
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for Box<[T]> {
+    fn heap_bytes(&self) -> usize {
+        self.iter().map(T::total_bytes).sum()
+    }
+}
+
+impl<K: MemoryUsage, V: MemoryUsage, S> MemoryUsage for HashMap<K, V, S> {
+    fn heap_bytes(&self) -> usize {
+        // hashbrown stores one control byte per bucket.
+        self.capacity() * (size_of::<K>() + size_of::<V>() + 1)
+            + self
+                .iter()
+                .map(|(key, value)| key.heap_bytes() + value.heap_bytes())
+                .sum::<usize>()
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for Option<T> {
+    fn heap_bytes(&self) -> usize {
+        self.as_ref().map_or(0, T::heap_bytes)
+    }
+}
+
+impl<T: MemoryUsage, E: MemoryUsage> MemoryUsage for Result<T, E> {
+    fn heap_bytes(&self) -> usize {
+        match self {
+            Ok(value) => value.heap_bytes(),
+            Err(err) => err.heap_bytes(),
+        }
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for Arc<T> {
+    fn heap_bytes(&self) -> usize {
+        // Reference counts. Shared values are counted for every reference.
+        2 * size_of::<usize>() + T::total_bytes(self)
+    }
+}
+
+impl<A: MemoryUsage, B: MemoryUsage> MemoryUsage for (A, B) {
+    fn heap_bytes(&self) -> usize {
+        self.0.heap_bytes() + self.1.heap_bytes()
+    }
+}
+
+impl MemoryUsage for Flag {
+    fn heap_bytes(&self) -> usize {
+        self.allocations.heap_bytes()
+    }
+}
+
+impl MemoryUsage for Allocation {
+    fn heap_bytes(&self) -> usize {
+        self.key.heap_bytes() + self.rules.heap_bytes() + self.splits.heap_bytes()
+    }
+}
+
+impl MemoryUsage for RuleWire {
+    fn heap_bytes(&self) -> usize {
+        self.conditions.heap_bytes()
+    }
+}
+
+impl<T: MemoryUsage> MemoryUsage for TryParse<T> {
+    fn heap_bytes(&self) -> usize {
+        match self {
+            TryParse::Parsed(value) => value.heap_bytes(),
+            // Only kept until compilation.
+            TryParse::ParseFailed(_) => 0,
+        }
+    }
+}
+
+impl MemoryUsage for Condition {
+    fn heap_bytes(&self) -> usize {
+        self.attribute.heap_bytes()
+            + match &self.check {
+                // Compiled regex is usually larger than its pattern.
+                ConditionCheck::Regex { regex, .. } => regex.as_str().len(),
+                ConditionCheck::Membership { values, .. } => values.heap_bytes(),
+                ConditionCheck::ObfuscatedMembership { hashes, .. } => hashes.heap_bytes(),
+                ConditionCheck::Comparison { .. } | ConditionCheck::Null { .. } => 0,
+            }
+    }
+}
+
+impl MemoryUsage for Split {
+    fn heap_bytes(&self) -> usize {
+        self.shards.heap_bytes() + self.variation_key.heap_bytes() + self.result.heap_bytes()
+    }
+}
+
+impl MemoryUsage for Shard {
+    fn heap_bytes(&self) -> usize {
+        self.salt.heap_bytes() + self.ranges.len() * size_of::<crate::ufc::ShardRange>()
+    }
+}
+
+impl MemoryUsage for AssignmentValue {
+    fn heap_bytes(&self) -> usize {
+        match self {
+            AssignmentValue::String(value) => value.heap_bytes(),
+            // Parsed value takes roughly as much memory as its JSON representation.
+            AssignmentValue::Json { raw, .. } => 2 * raw.heap_bytes(),
+            AssignmentValue::Integer(_)
+            | AssignmentValue::Numeric(_)
+            | AssignmentValue::Boolean(_) => 0,
+        }
+    }
+}
+
+impl MemoryUsage for ValueWire {
+    fn heap_bytes(&self) -> usize {
+        match self {
+            ValueWire::String(value) => value.heap_bytes(),
+            ValueWire::Boolean(_) | ValueWire::Number(_) => 0,
+        }
+    }
+}
+
+impl MemoryUsage for AssignmentEventBase {
+    fn heap_bytes(&self) -> usize {
+        self.feature_flag.heap_bytes()
+            + self.allocation.heap_bytes()
+            + self.experiment.heap_bytes()
+            + self.variation.heap_bytes()
+            + self.variation_value.heap_bytes()
+            + self.extra_logging.heap_bytes()
+    }
+}
+
+impl MemoryUsage for EvaluationFailure {
+    fn heap_bytes(&self) -> usize {
+        0
+    }
+}
+
+impl MemoryUsage for BanditVariationWire {
+    fn heap_bytes(&self) -> usize {
+        self.key.heap_bytes()
+            + self.flag_key.heap_bytes()
+            + self.variation_key.heap_bytes()
+            + self.variation_value.heap_bytes()
+    }
+}
+
+impl MemoryUsage for BanditResponse {
+    fn heap_bytes(&self) -> usize {
+        self.bandits.heap_bytes()
+    }
+}
+
+impl MemoryUsage for BanditConfiguration {
+    fn heap_bytes(&self) -> usize {
+        self.bandit_key.heap_bytes()
+            + self.model_name.heap_bytes()
+            + self.model_version.heap_bytes()
+            + self.model_data.heap_bytes()
+    }
+}
+
+impl MemoryUsage for BanditModelData {
+    fn heap_bytes(&self) -> usize {
+        self.coefficients.heap_bytes()
+    }
+}
+
+impl MemoryUsage for BanditCoefficients {
+    fn heap_bytes(&self) -> usize {
+        self.action_key.heap_bytes()
+            + self.subject_numeric_coefficients.heap_bytes()
+            + self.subject_categorical_coefficients.heap_bytes()
+            + self.action_numeric_coefficients.heap_bytes()
+            + self.action_categorical_coefficients.heap_bytes()
+    }
+}
+
+impl MemoryUsage for BanditNumericAttributeCoefficient {
+    fn heap_bytes(&self) -> usize {
+        self.attribute_key.heap_bytes()
+    }
+}
+
+impl MemoryUsage for BanditCategoricalAttributeCoefficient {
+    fn heap_bytes(&self) -> usize {
+        self.attribute_key.heap_bytes() + self.value_coefficients.heap_bytes()
+    }
+}
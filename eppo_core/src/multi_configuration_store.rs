@@ -0,0 +1,242 @@
+//! Configuration for several Eppo environments (e.g., staging and production) served by the same
+//! process.
+//!
+//! [`MultiConfigurationStore`] holds a separate [`ConfigurationStore`] per environment. Each
+//! environment is fed by its own poller (each environment has its own SDK key) and evaluated by
+//! its own [`Evaluator`](crate::eval::Evaluator), both bound to
+//! [`MultiConfigurationStore::store()`] of that environment:
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use eppo_core::{
+//! #     eval::{Evaluator, EvaluatorConfig},
+//! #     multi_configuration_store::MultiConfigurationStore,
+//! #     timestamp::SystemClock,
+//! #     SdkMetadata,
+//! # };
+//! let stores = MultiConfigurationStore::new();
+//! let staging = Evaluator::new(EvaluatorConfig {
+//!     configuration_store: stores.store("staging"),
+//!     sdk_metadata: SdkMetadata { name: "example", version: "0.1.0" },
+//!     clock: Arc::new(SystemClock),
+//!     observer: None,
+//!     sharder: None,
+//!     default_attributes: Arc::default(),
+//!     coerce_numeric_types: false,
+//!     bandit_evaluation_options: Default::default(),
+//! });
+//! // Start a poller with `stores.store("staging")` to feed the evaluator.
+//! ```
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use crate::{configuration_store::ConfigurationStore, Configuration, Str};
+
+/// A thread-safe collection of configuration stores keyed by environment.
+///
+/// Environments are isolated: setting configuration for one environment never affects other
+/// environments.
+#[derive(Default)]
+pub struct MultiConfigurationStore {
+    stores: RwLock<HashMap<Str, Arc<ConfigurationStore>>>,
+}
+
+impl MultiConfigurationStore {
+    /// Create a new store without any environments.
+    pub fn new() -> MultiConfigurationStore {
+        MultiConfigurationStore::default()
+    }
+
+    /// Get the configuration store of `environment`, creating an empty one if the environment is
+    /// not known yet.
+    ///
+    /// The same store is returned for the same environment, so it can be shared with a poller and
+    /// an evaluator.
+    pub fn store(&self, environment: &str) -> Arc<ConfigurationStore> {
+        if let Some(store) = self.read_stores().get(environment) {
+            return store.clone();
+        }
+        self.stores
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(environment.into())
+            .or_default()
+            .clone()
+    }
+
+    /// Get currently-active configuration of `environment`. Returns `None` if configuration for
+    /// the environment hasn't been fetched or set yet.
+    pub fn get(&self, environment: &str) -> Option<Arc<Configuration>> {
+        self.read_stores().get(environment)?.get_configuration()
+    }
+
+    /// Set configuration of `environment`.
+    pub fn set(&self, environment: &str, configuration: Arc<Configuration>) {
+        self.store(environment).set_configuration(configuration);
+    }
+
+    /// Remove `environment` and its configuration. Returns the removed store, if any.
+    ///
+    /// Pollers and evaluators holding the removed store keep working with it, but it is no longer
+    /// returned by [`MultiConfigurationStore::store()`].
+    pub fn remove(&self, environment: &str) -> Option<Arc<ConfigurationStore>> {
+        self.stores
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(environment)
+    }
+
+    /// Returns all known environments, sorted alphabetically.
+    pub fn environments(&self) -> Vec<Str> {
+        let mut environments = self.read_stores().keys().cloned().collect::<Vec<_>>();
+        environments.sort();
+        environments
+    }
+
+    fn read_stores(&self) -> std::sync::RwLockReadGuard<'_, HashMap<Str, Arc<ConfigurationStore>>> {
+        self.stores.read().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        configuration_fetcher::FetchResult,
+        configuration_source::ConfigurationSource,
+        eval::{Evaluator, EvaluatorConfig},
+        poller_thread::PollerThread,
+        test_server::SDK_METADATA,
+        timestamp::SystemClock,
+        ufc::{AssignmentValue, VariationType},
+        Attributes, Configuration, Result, SdkMetadata, Str,
+    };
+
+    use super::MultiConfigurationStore;
+
+    // Configurations with a single string flag `color` that returns the environment name.
+    const STAGING_JSON: &[u8] = include_bytes!("../tests/fixtures/color-staging.json");
+    const PRODUCTION_JSON: &[u8] = include_bytes!("../tests/fixtures/color-production.json");
+
+    /// Returns the same configuration on every fetch.
+    struct FixedSource(&'static [u8]);
+
+    impl ConfigurationSource for FixedSource {
+        fn sdk_metadata(&self) -> SdkMetadata {
+            SDK_METADATA
+        }
+
+        async fn fetch(&mut self) -> Result<FetchResult> {
+            Ok(FetchResult::Modified(Box::new(
+                Configuration::from_ufc_json_for_tests(self.0),
+            )))
+        }
+    }
+
+    fn color(evaluator: &Evaluator) -> Option<AssignmentValue> {
+        evaluator
+            .get_assignment(
+                "color",
+                &"subject".into(),
+                &Arc::new(Attributes::new()),
+                Some(VariationType::String),
+            )
+            .unwrap()
+            .map(|assignment| assignment.value)
+    }
+
+    #[test]
+    fn environments_are_isolated() {
+        let stores = MultiConfigurationStore::new();
+        assert!(stores.get("staging").is_none());
+
+        stores.set(
+            "staging",
+            Arc::new(Configuration::from_ufc_json_for_tests(STAGING_JSON)),
+        );
+
+        assert_eq!(stores.get("staging").unwrap().environment_name(), "staging");
+        assert!(stores.get("production").is_none());
+
+        stores.set(
+            "production",
+            Arc::new(Configuration::from_ufc_json_for_tests(PRODUCTION_JSON)),
+        );
+
+        assert_eq!(stores.get("staging").unwrap().environment_name(), "staging");
+        assert_eq!(
+            stores.get("production").unwrap().environment_name(),
+            "production"
+        );
+        assert_eq!(
+            stores.environments(),
+            [Str::from("production"), Str::from("staging")]
+        );
+    }
+
+    #[test]
+    fn store_is_shared_for_the_same_environment() {
+        let stores = MultiConfigurationStore::new();
+
+        let store = stores.store("staging");
+        store.set_configuration(Arc::new(Configuration::from_ufc_json_for_tests(
+            STAGING_JSON,
+        )));
+
+        assert!(Arc::ptr_eq(&store, &stores.store("staging")));
+        assert!(stores.get("staging").is_some());
+        assert!(!Arc::ptr_eq(&store, &stores.store("production")));
+    }
+
+    #[test]
+    fn pollers_feed_evaluators_of_their_environment() {
+        let stores = MultiConfigurationStore::new();
+        let evaluator = |environment: &str| {
+            Evaluator::new(EvaluatorConfig {
+                configuration_store: stores.store(environment),
+                sdk_metadata: SDK_METADATA,
+                clock: Arc::new(SystemClock),
+                observer: None,
+                sharder: None,
+                default_attributes: Arc::default(),
+                coerce_numeric_types: false,
+                bandit_evaluation_options: Default::default(),
+            })
+        };
+        let staging = evaluator("staging");
+        let production = evaluator("production");
+
+        let staging_poller =
+            PollerThread::start(FixedSource(STAGING_JSON), stores.store("staging")).unwrap();
+        staging_poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+
+        assert_eq!(
+            color(&staging),
+            Some(AssignmentValue::String("staging".into()))
+        );
+        assert_eq!(color(&production), None);
+
+        let production_poller =
+            PollerThread::start(FixedSource(PRODUCTION_JSON), stores.store("production")).unwrap();
+        production_poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+
+        assert_eq!(
+            color(&staging),
+            Some(AssignmentValue::String("staging".into()))
+        );
+        assert_eq!(
+            color(&production),
+            Some(AssignmentValue::String("production".into()))
+        );
+
+        staging_poller.shutdown().unwrap();
+        production_poller.shutdown().unwrap();
+    }
+}
@@ -24,6 +24,12 @@ impl From<md5::Digest> for Md5HashedStr {
     }
 }
 
+/// Hex-encoded md5 hash of `input`. This is how obfuscated client configuration hashes flag keys,
+/// attribute names, and values.
+pub(crate) fn md5_hex(input: &str) -> String {
+    format!("{:x}", md5::compute(input))
+}
+
 /// Same as [`Str`] but serializes as base64-encoded string.
 #[serde_as]
 #[derive(
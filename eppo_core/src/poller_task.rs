@@ -0,0 +1,271 @@
+//! A background poller task that periodically requests configuration from the server and stores
+//! it in a configuration store.
+//!
+//! This is an async alternative to [`PollerThread`](crate::poller_thread::PollerThread) for hosts
+//! that already run a tokio runtime. It does not spawn a dedicated OS thread and does not create a
+//! nested runtime. Both run the same poller loop, so they behave the same way.
+use std::sync::{Arc, Mutex};
+
+use tokio::{sync::watch, task::JoinHandle};
+
+use crate::configuration_source::ConfigurationSource;
+use crate::configuration_store::ConfigurationStoreBackend;
+use crate::poller_thread::{
+    run_poller, ConfigurationReceiver, FetchStats, PollerShared, PollerState, PollerStatus,
+    PollerThreadConfig,
+};
+use crate::{Error, Result};
+
+/// A configuration poller task.
+///
+/// The poller task polls the server periodically to fetch the latest configuration using
+/// [`ConfigurationFetcher`](crate::configuration_fetcher::ConfigurationFetcher) and stores it in
+/// [`ConfigurationStoreBackend`]. Any other [`ConfigurationSource`] can be polled instead of the
+/// server.
+///
+/// The task is stopped when `PollerTask` is dropped.
+pub struct PollerTask {
+    /// Taken on shutdown.
+    join_handle: Mutex<Option<JoinHandle<()>>>,
+
+    /// Used to send a stop command to the poller task.
+    stop_sender: watch::Sender<bool>,
+
+    shared: Arc<PollerShared>,
+}
+
+impl PollerTask {
+    /// Spawns the configuration poller task on the current tokio runtime.
+    ///
+    /// The runtime must have the time driver enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime.
+    pub fn start(
+        source: impl ConfigurationSource,
+        store: Arc<dyn ConfigurationStoreBackend>,
+    ) -> PollerTask {
+        PollerTask::start_with_config(source, store, PollerThreadConfig::default())
+    }
+
+    /// Spawns the configuration poller task on the current tokio runtime with the provided
+    /// configuration.
+    ///
+    /// The runtime must have the time driver enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime.
+    pub fn start_with_config(
+        mut source: impl ConfigurationSource,
+        store: Arc<dyn ConfigurationStoreBackend>,
+        config: PollerThreadConfig,
+    ) -> PollerTask {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+
+        // Load persisted configuration synchronously, so it is available as soon as the poller is
+        // started.
+        let restored = config.restore_persisted_configuration(&mut source, &*store);
+        let shared = Arc::new(PollerShared::new(restored, &config, &*store));
+
+        let join_handle = tokio::spawn(run_poller(
+            source,
+            store,
+            config,
+            Arc::clone(&shared),
+            stop_receiver,
+            restored,
+        ));
+
+        PollerTask {
+            join_handle: Mutex::new(Some(join_handle)),
+            stop_sender,
+            shared,
+        }
+    }
+
+    /// Returns the current status of the poller without blocking. See
+    /// [`PollerThread::status()`](crate::poller_thread::PollerThread::status).
+    pub fn status(&self) -> PollerStatus {
+        self.shared.status()
+    }
+
+    /// Returns the current health of the poller without blocking.
+    pub fn state(&self) -> PollerState {
+        self.shared.state()
+    }
+
+    /// Returns the error that stopped the poller, if any. See
+    /// [`PollerThread::terminal_error()`](crate::poller_thread::PollerThread::terminal_error).
+    pub fn terminal_error(&self) -> Option<Error> {
+        self.shared.terminal_error()
+    }
+
+    /// Returns metadata about configuration fetches (e.g., when the last successful fetch
+    /// happened and how long it took).
+    pub fn stats(&self) -> FetchStats {
+        self.shared.stats()
+    }
+
+    /// Subscribe to configuration updates. See
+    /// [`PollerThread::watch()`](crate::poller_thread::PollerThread::watch).
+    pub fn watch(&self) -> ConfigurationReceiver {
+        self.shared.watch()
+    }
+
+    /// Waits for the configuration to be fetched.
+    ///
+    /// Returns the terminal error immediately if the poller has stopped because of one (see
+    /// [`PollerTask::terminal_error()`]), even if configuration was fetched before.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail with the following errors:
+    ///
+    /// - [`Error::PollerThreadPanicked`] if the task panicked or was aborted before fetching
+    ///   configuration.
+    /// - [`Error::PollerStopped`] if the task was stopped before fetching configuration.
+    /// - [`Error::Unauthorized`]
+    /// - [`Error::InvalidBaseUrl`]
+    /// - [`Error::InvalidHeader`]
+    /// - [`Error::RateLimited`] if the server throttled requests before configuration was fetched.
+    ///   The poller keeps retrying, so a later call may succeed.
+    /// - [`Error::Io`] or [`Error::ConfigurationParseError`] if
+    ///   [`PollerThreadConfig::configuration_file`] cannot be loaded and configuration hasn't been
+    ///   fetched from the server yet.
+    pub async fn wait_for_configuration(&self) -> Result<()> {
+        self.shared.wait_for_configuration_async().await
+    }
+
+    /// Stop the poller task. An in-flight configuration request is cancelled.
+    ///
+    /// This function does not wait for the task to actually stop.
+    pub fn stop(&self) {
+        // `send_replace()` never fails, even if the task has exited already and dropped the
+        // receiver.
+        self.stop_sender.send_replace(true);
+    }
+
+    /// Stop the poller task and wait for it to exit.
+    ///
+    /// Calling `shutdown()` on an already shut down poller task is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PollerThreadPanicked`] if the task has panicked.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.stop();
+
+        let join_handle = self
+            .join_handle
+            .lock()
+            .map_err(|_| Error::PollerThreadPanicked)?
+            .take();
+
+        if let Some(join_handle) = join_handle {
+            join_handle.await.map_err(|_| Error::PollerThreadPanicked)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use crate::{
+        configuration_fetcher::{ConfigurationFetcher, ConfigurationFetcherConfig},
+        configuration_store::ConfigurationStore,
+        poller_thread::{PollerState, PollerThreadConfig},
+        test_server::{TestResponse, TestServer, UFC_JSON},
+        Error,
+    };
+
+    use super::PollerTask;
+
+    fn fetcher(base_url: &str) -> ConfigurationFetcher {
+        ConfigurationFetcher::new(ConfigurationFetcherConfig::for_tests(base_url))
+    }
+
+    #[tokio::test]
+    async fn reports_unrecoverable_error() {
+        let fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig::for_tests("not a url"));
+        let store = Arc::new(ConfigurationStore::new());
+
+        let poller = PollerTask::start(fetcher, store.clone());
+
+        assert!(matches!(
+            poller.wait_for_configuration().await,
+            Err(Error::InvalidBaseUrl(_))
+        ));
+        assert!(store.get_configuration().is_none());
+    }
+    #[tokio::test]
+    async fn reports_fetch_stats() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let fetcher = ConfigurationFetcher::new(server.fetcher_config());
+
+        let poller = PollerTask::start(fetcher, Arc::new(ConfigurationStore::new()));
+        poller.wait_for_configuration().await.unwrap();
+
+        let stats = poller.stats();
+        assert_eq!(stats.consecutive_failures, 0);
+        assert_eq!(stats.last_payload_bytes, Some(UFC_JSON.len()));
+        assert!(stats.last_success_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn retries_with_backoff_like_poller_thread() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let requests = requests.clone();
+            TestServer::start(move |_| {
+                if requests.fetch_add(1, Ordering::SeqCst) < 2 {
+                    TestResponse::new(503)
+                } else {
+                    TestResponse::new(200).with_body(UFC_JSON)
+                }
+            })
+        };
+        let config = PollerThreadConfig::new()
+            .with_interval(Duration::from_secs(3600))
+            .with_initial_retry_backoff(Duration::from_millis(10));
+
+        let poller = PollerTask::start_with_config(
+            fetcher(server.base_url()),
+            Arc::new(ConfigurationStore::new()),
+            config,
+        );
+        poller.wait_for_configuration().await.unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(matches!(poller.state(), PollerState::Healthy));
+        poller.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stop_releases_waiters() {
+        let poller = PollerTask::start(
+            fetcher("http://localhost:1/api"),
+            Arc::new(ConfigurationStore::new()),
+        );
+
+        poller.stop();
+
+        assert!(matches!(
+            poller.wait_for_configuration().await,
+            Err(Error::PollerStopped)
+        ));
+        poller.shutdown().await.unwrap();
+        // Shutting down again is a no-op.
+        poller.shutdown().await.unwrap();
+    }
+}
@@ -1,22 +1,44 @@
 //! A background poller thread that periodically requests configuration from the server and stores
 //! it in a configuration store.
 use std::{
-    sync::{mpsc::RecvTimeoutError, Arc, Condvar, Mutex},
-    time::Duration,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
 };
 
+use chrono::{DateTime, Utc};
 use rand::{thread_rng, Rng};
+use tokio::sync::watch;
 
-use crate::configuration_fetcher::ConfigurationFetcher;
-use crate::configuration_store::ConfigurationStore;
-use crate::{Error, Result};
+use crate::configuration_fetcher::{ConfigurationFile, FetchResult};
+use crate::configuration_persistence::ConfigurationPersistence;
+use crate::configuration_source::ConfigurationSource;
+use crate::configuration_store::ConfigurationStoreBackend;
+use crate::{Configuration, Error, Result};
 
-/// Configuration for [`PollerThread`].
+/// A callback invoked by the poller after it stores a configuration that is newer than the previous
+/// one.
+///
+/// The callback receives an `Arc`, so it can cheaply hold on to the new configuration.
+pub type ConfigurationChangeCallback = Arc<dyn Fn(&Arc<Configuration>) + Send + Sync>;
+
+/// A receiver of configurations stored by the poller. See [`PollerThread::watch()`].
+pub type ConfigurationReceiver = watch::Receiver<Option<Arc<Configuration>>>;
+
+/// A callback invoked by the poller for every failed fetch.
+pub type ErrorCallback = Arc<dyn Fn(&Error) + Send + Sync>;
+
+/// A callback invoked by the poller when its [`PollerState`] changes.
+pub type PollerStateChangeCallback = Arc<dyn Fn(&PollerState) + Send + Sync>;
+
+/// Configuration for [`PollerThread`] and [`PollerTask`](crate::poller_task::PollerTask).
 // Not implementing `Copy` as we may add non-copyable fields in the future.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct PollerThreadConfig {
     /// Interval to wait between requests for configuration.
     ///
+    /// When the server rate limits requests, the delay it asks for via `Retry-After` is honored
+    /// up to this interval, so a misbehaving server can't stall polling indefinitely.
+    ///
     /// Defaults to [`PollerThreadConfig::DEFAULT_POLL_INTERVAL`].
     pub interval: Duration,
     /// Jitter applies a randomized duration to wait between requests for configuration. This helps
@@ -24,6 +46,80 @@ pub struct PollerThreadConfig {
     ///
     /// Defaults to [`PollerThreadConfig::DEFAULT_POLL_JITTER`].
     pub jitter: Duration,
+    /// Jitter as a fraction of the poll interval (e.g., `0.1` for 10%), so it scales with the
+    /// interval. Overrides [`PollerThreadConfig::jitter`] when set.
+    ///
+    /// Defaults to `None`.
+    pub jitter_percent: Option<f64>,
+    /// Delay before the first retry after a failed fetch. Subsequent retries double the delay
+    /// until a fetch succeeds. Every retry delay is randomly reduced by up to half, so servers that
+    /// failed together don't retry in lockstep, but it is never shorter than
+    /// [`PollerThreadConfig::MIN_RETRY_BACKOFF`].
+    ///
+    /// Defaults to [`PollerThreadConfig::DEFAULT_INITIAL_RETRY_BACKOFF`].
+    pub initial_retry_backoff: Duration,
+    /// Maximum delay between retries after failed fetches. The delay never exceeds
+    /// [`PollerThreadConfig::interval`] either.
+    ///
+    /// Defaults to [`PollerThreadConfig::DEFAULT_MAX_RETRY_BACKOFF`].
+    pub max_retry_backoff: Duration,
+    /// Configuration file to load before fetching configuration from the server.
+    ///
+    /// The file serves as the initial configuration until a fresh one is fetched. Errors reading
+    /// the file are reported by `wait_for_configuration()`, but the poller keeps polling the
+    /// server.
+    ///
+    /// Defaults to `None`.
+    pub configuration_file: Option<ConfigurationFile>,
+    /// Storage to save fetched configuration to, so it survives process restarts.
+    ///
+    /// Saved configuration is loaded when the poller starts, before the first fetch. It takes
+    /// precedence over [`PollerThreadConfig::configuration_file`]. Saved configuration that
+    /// cannot be parsed is ignored with a warning.
+    ///
+    /// Defaults to `None`.
+    pub persistence: Option<Arc<dyn ConfigurationPersistence>>,
+    /// Callback to invoke when a newly fetched configuration is newer than the previous one (i.e.,
+    /// it was published later). See [`ConfigurationStoreBackend::set_if_newer()`].
+    ///
+    /// The callback is invoked from the poller after the configuration is stored and is not
+    /// holding any configuration store locks. Panics inside the callback are caught and logged.
+    ///
+    /// Defaults to `None`.
+    pub on_configuration_change: Option<ConfigurationChangeCallback>,
+    /// Callback to invoke when [`PollerState`] of the poller changes, including every new failure
+    /// while [`PollerState::Degraded`].
+    ///
+    /// The callback is invoked from the poller. Panics inside the callback are caught and logged.
+    ///
+    /// Defaults to `None`.
+    pub on_state_change: Option<PollerStateChangeCallback>,
+    /// Callback to invoke for every failed fetch, e.g., to forward errors to an error reporter.
+    ///
+    /// The error is classified: [`Error::is_retriable()`] tells whether the poller keeps retrying
+    /// after it. The callback is invoked from the poller before the retry is scheduled. Panics
+    /// inside the callback are caught and logged, so they don't stop the poller.
+    ///
+    /// Defaults to `None`.
+    pub on_error: Option<ErrorCallback>,
+    /// Re-fetch bandit models only every Nth poll. Bandit models from the previous fetch are
+    /// reused in between, as they are large and change far less frequently than flags
+    /// configuration. The first poll always fetches bandit models. Zero is treated as one.
+    ///
+    /// Defaults to 1 (bandit models are fetched together with every flags configuration).
+    pub bandit_poll_every_n_cycles: u32,
+    /// Block [`PollerThread::start_with_config()`] until the first fetch completes (or the
+    /// timeout elapses), so configuration is in the store when the poller is returned.
+    ///
+    /// If the fetch fails with a non-retriable error (e.g., [`Error::Unauthorized`]), the poller
+    /// is stopped and the error is returned. If it fails with a retriable error or doesn't
+    /// complete within the timeout, a warning is logged and the poller keeps retrying in the
+    /// background.
+    ///
+    /// Ignored by [`PollerTask`](crate::poller_task::PollerTask), which can be awaited instead.
+    ///
+    /// Defaults to `None` (don't block).
+    pub block_on_initial_fetch: Option<Duration>,
 }
 
 impl PollerThreadConfig {
@@ -31,6 +127,12 @@ impl PollerThreadConfig {
     pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
     /// Default value for [`PollerThreadConfig::jitter`].
     pub const DEFAULT_POLL_JITTER: Duration = Duration::from_secs(3);
+    /// Default value for [`PollerThreadConfig::initial_retry_backoff`].
+    pub const DEFAULT_INITIAL_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+    /// Default value for [`PollerThreadConfig::max_retry_backoff`].
+    pub const DEFAULT_MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+    /// Lower bound for delays between retries, so a zero backoff doesn't retry in a tight loop.
+    pub const MIN_RETRY_BACKOFF: Duration = Duration::from_millis(10);
 
     /// Create a new `PollerThreadConfig` using default configuration.
     pub fn new() -> PollerThreadConfig {
@@ -46,8 +148,164 @@ impl PollerThreadConfig {
     /// Update poll interval jitter with `jitter`.
     pub fn with_jitter(mut self, jitter: Duration) -> PollerThreadConfig {
         self.jitter = jitter;
+        self.jitter_percent = None;
+        self
+    }
+
+    /// Update poll interval jitter to a fraction of the poll interval (e.g., `0.1` for 10%), so it
+    /// scales with the interval.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`] if `percent` is not within `[0, 1]`.
+    pub fn with_jitter_percent(mut self, percent: f64) -> Result<PollerThreadConfig> {
+        if !(0.0..=1.0).contains(&percent) {
+            return Err(Error::InvalidConfiguration(format!(
+                "jitter percent must be within [0, 1], got {percent}"
+            )));
+        }
+        self.jitter_percent = Some(percent);
+        Ok(self)
+    }
+
+    /// Re-fetch bandit models only every `n`th poll. See
+    /// [`PollerThreadConfig::bandit_poll_every_n_cycles`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`] if `n` is zero.
+    pub fn with_bandit_poll_every_n_cycles(mut self, n: u32) -> Result<PollerThreadConfig> {
+        if n == 0 {
+            return Err(Error::InvalidConfiguration(
+                "bandit_poll_every_n_cycles must be positive".to_owned(),
+            ));
+        }
+        self.bandit_poll_every_n_cycles = n;
+        Ok(self)
+    }
+
+    /// Block [`PollerThread::start_with_config()`] until the first fetch completes, for at most
+    /// `timeout`. See [`PollerThreadConfig::block_on_initial_fetch`].
+    pub fn with_block_on_initial_fetch(mut self, timeout: Duration) -> PollerThreadConfig {
+        self.block_on_initial_fetch = Some(timeout);
+        self
+    }
+
+    /// Update delay before the first retry after a failed fetch.
+    pub fn with_initial_retry_backoff(mut self, backoff: Duration) -> PollerThreadConfig {
+        self.initial_retry_backoff = backoff;
+        self
+    }
+
+    /// Update maximum delay between retries after failed fetches.
+    pub fn with_max_retry_backoff(mut self, backoff: Duration) -> PollerThreadConfig {
+        self.max_retry_backoff = backoff;
+        self
+    }
+
+    /// Delay before the next fetch after `consecutive_failures` failed fetches in a row.
+    ///
+    /// Returns a (jittered) poll interval if the last fetch succeeded. Otherwise, returns an
+    /// exponential backoff starting at `initial_retry_backoff` and capped at `max_retry_backoff`
+    /// and `interval`, randomly reduced by up to half and no shorter than `MIN_RETRY_BACKOFF`.
+    pub(crate) fn next_delay(&self, consecutive_failures: u32) -> Duration {
+        if consecutive_failures == 0 {
+            let poll_jitter = match self.jitter_percent {
+                Some(percent) => Jitter::Percent(percent),
+                None => Jitter::Absolute(self.jitter),
+            };
+            return jitter(self.interval, poll_jitter);
+        }
+
+        jitter(
+            self.retry_backoff(consecutive_failures),
+            Jitter::Percent(RETRY_BACKOFF_JITTER),
+        )
+        .max(PollerThreadConfig::MIN_RETRY_BACKOFF)
+    }
+
+    /// Exponential backoff (before jitter) after `consecutive_failures` failed fetches in a row.
+    fn retry_backoff(&self, consecutive_failures: u32) -> Duration {
+        let cap = self.max_retry_backoff.min(self.interval);
+        // Saturate the multiplier to avoid overflow after many failures; the result is capped
+        // anyway.
+        let multiplier = 2u32.saturating_pow(consecutive_failures.saturating_sub(1));
+        self.initial_retry_backoff
+            .checked_mul(multiplier)
+            .map_or(cap, |backoff| backoff.min(cap))
+    }
+
+    /// Load initial configuration from `file` before fetching configuration from the server.
+    pub fn with_configuration_file(mut self, file: ConfigurationFile) -> PollerThreadConfig {
+        self.configuration_file = Some(file);
+        self
+    }
+
+    /// Save fetched configuration to `persistence` and load it on startup.
+    pub fn with_persistence(
+        mut self,
+        persistence: Arc<dyn ConfigurationPersistence>,
+    ) -> PollerThreadConfig {
+        self.persistence = Some(persistence);
+        self
+    }
+
+    /// Load configuration saved to [`PollerThreadConfig::persistence`] into `store`.
+    ///
+    /// Returns `true` if configuration was loaded.
+    pub(crate) fn restore_persisted_configuration(
+        &self,
+        source: &mut impl ConfigurationSource,
+        store: &dyn ConfigurationStoreBackend,
+    ) -> bool {
+        let Some(configuration) = self
+            .persistence
+            .as_ref()
+            .and_then(|persistence| source.restore_persisted_configuration(&**persistence))
+        else {
+            return false;
+        };
+        update_configuration(store, configuration, self.on_configuration_change.as_ref());
+        true
+    }
+
+    /// Set a callback to invoke when configuration changes.
+    pub fn with_on_configuration_change(
+        mut self,
+        callback: impl Fn(&Arc<Configuration>) + Send + Sync + 'static,
+    ) -> PollerThreadConfig {
+        self.on_configuration_change = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set a callback to invoke when poller state changes.
+    pub fn with_on_state_change(
+        mut self,
+        callback: impl Fn(&PollerState) + Send + Sync + 'static,
+    ) -> PollerThreadConfig {
+        self.on_state_change = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set a callback to invoke for every failed fetch.
+    pub fn with_on_error(
+        mut self,
+        callback: impl Fn(&Error) + Send + Sync + 'static,
+    ) -> PollerThreadConfig {
+        self.on_error = Some(Arc::new(callback));
         self
     }
+
+    /// Notify `on_error` callback about a failed fetch.
+    pub(crate) fn report_error(&self, error: &Error) {
+        let Some(callback) = &self.on_error else {
+            return;
+        };
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(error)));
+        if result.is_err() {
+            log::warn!(target: "eppo", "error callback panicked");
+        }
+    }
 }
 
 impl Default for PollerThreadConfig {
@@ -55,179 +313,440 @@ impl Default for PollerThreadConfig {
         PollerThreadConfig {
             interval: PollerThreadConfig::DEFAULT_POLL_INTERVAL,
             jitter: PollerThreadConfig::DEFAULT_POLL_JITTER,
+            jitter_percent: None,
+            initial_retry_backoff: PollerThreadConfig::DEFAULT_INITIAL_RETRY_BACKOFF,
+            max_retry_backoff: PollerThreadConfig::DEFAULT_MAX_RETRY_BACKOFF,
+            configuration_file: None,
+            persistence: None,
+            on_configuration_change: None,
+            on_state_change: None,
+            on_error: None,
+            bandit_poll_every_n_cycles: 1,
+            block_on_initial_fetch: None,
         }
     }
 }
 
-/// A configuration poller thread.
+impl std::fmt::Debug for PollerThreadConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PollerThreadConfig")
+            .field("interval", &self.interval)
+            .field("jitter", &self.jitter)
+            .field("jitter_percent", &self.jitter_percent)
+            .field("initial_retry_backoff", &self.initial_retry_backoff)
+            .field("max_retry_backoff", &self.max_retry_backoff)
+            .field("configuration_file", &self.configuration_file)
+            .field("persistence", &self.persistence.as_ref().map(|_| ".."))
+            .field(
+                "on_configuration_change",
+                &self.on_configuration_change.as_ref().map(|_| ".."),
+            )
+            .field(
+                "on_state_change",
+                &self.on_state_change.as_ref().map(|_| ".."),
+            )
+            .field("on_error", &self.on_error.as_ref().map(|_| ".."))
+            .field(
+                "bandit_poll_every_n_cycles",
+                &self.bandit_poll_every_n_cycles,
+            )
+            .field("block_on_initial_fetch", &self.block_on_initial_fetch)
+            .finish()
+    }
+}
+
+/// Store `configuration` if it is newer than the stored one and notify `on_configuration_change`
+/// callback if it was stored. Returns the configuration if it was stored.
 ///
-/// The poller thread polls the server periodically to fetch the latest configuration using
-/// [`ConfigurationFetcher`] and stores it in [`ConfigurationStore`].
-pub struct PollerThread {
-    join_handle: std::thread::JoinHandle<()>,
+/// Older configuration is ignored, so a slow fetch doesn't overwrite configuration that was set
+/// manually in the meantime.
+pub(crate) fn update_configuration(
+    store: &dyn ConfigurationStoreBackend,
+    configuration: Configuration,
+    on_configuration_change: Option<&ConfigurationChangeCallback>,
+) -> Option<Arc<Configuration>> {
+    let configuration = Arc::new(configuration);
+    // Only needed to log the diff.
+    let previous = on_configuration_change.and_then(|_| store.get());
+    if !store.set_if_newer(configuration.clone()) {
+        log::debug!(target: "eppo", "ignoring configuration that is not newer than the stored one");
+        return None;
+    }
+
+    if let Some(callback) = on_configuration_change {
+        if let Some(previous) = previous {
+            log::info!(target: "eppo", "configuration changed: {}", previous.diff(&configuration));
+        }
+
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(&configuration)));
+        if result.is_err() {
+            log::warn!(target: "eppo", "configuration change callback panicked");
+        }
+    }
+
+    Some(configuration)
+}
+
+/// Current state of the poller. Can be used for readiness probes.
+#[derive(Debug, Clone)]
+pub enum PollerStatus {
+    /// The poller hasn't finished fetching the first configuration yet.
+    Pending,
+    /// The last configuration fetch succeeded.
+    Ready {
+        /// Time of the last successful fetch.
+        last_success: DateTime<Utc>,
+    },
+    /// The last configuration fetch failed.
+    ///
+    /// The poller keeps retrying unless `error` is unrecoverable (see [`Error::is_retriable()`]), in
+    /// which case the poller has stopped and the status doesn't change anymore. If configuration
+    /// was fetched before, the client continues serving it.
+    Failed {
+        /// The last error.
+        error: Error,
+        /// Number of failed fetches since the last successful one.
+        consecutive_failures: u32,
+        /// Time of the last successful fetch, if any.
+        last_success: Option<DateTime<Utc>>,
+    },
+}
+
+impl PollerStatus {
+    /// Returns `true` if the poller has fetched configuration at least once.
+    pub fn has_configuration(&self) -> bool {
+        match self {
+            PollerStatus::Pending => false,
+            PollerStatus::Ready { .. } => true,
+            PollerStatus::Failed { last_success, .. } => last_success.is_some(),
+        }
+    }
+
+    /// Returns the poller state derived from this status.
+    pub fn state(&self) -> PollerState {
+        match self {
+            PollerStatus::Pending => PollerState::Starting,
+            PollerStatus::Ready { .. } => PollerState::Healthy,
+            PollerStatus::Failed { error, .. } if !error.is_retriable() => PollerState::Failed {
+                error: error.clone(),
+            },
+            PollerStatus::Failed {
+                consecutive_failures,
+                ..
+            } => PollerState::Degraded {
+                consecutive_failures: *consecutive_failures,
+            },
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        matches!(self, PollerStatus::Failed { error, .. } if !error.is_retriable())
+    }
+
+    pub(crate) fn record_success(&mut self) {
+        if self.is_terminal() {
+            return;
+        }
+        *self = PollerStatus::Ready {
+            last_success: Utc::now(),
+        };
+    }
+
+    pub(crate) fn record_failure(&mut self, error: Error) {
+        if self.is_terminal() {
+            return;
+        }
+        let (consecutive_failures, last_success) = match self {
+            PollerStatus::Pending => (1, None),
+            PollerStatus::Ready { last_success } => (1, Some(*last_success)),
+            PollerStatus::Failed {
+                consecutive_failures,
+                last_success,
+                ..
+            } => (consecutive_failures.saturating_add(1), *last_success),
+        };
+        *self = PollerStatus::Failed {
+            error,
+            consecutive_failures,
+            last_success,
+        };
+    }
+}
+
+/// Health of the poller. A coarser view of [`PollerStatus`] (see [`PollerStatus::state()`]) that
+/// only moves along `Starting -> Healthy <-> Degraded -> Failed`.
+#[derive(Debug, Clone)]
+pub enum PollerState {
+    /// The poller hasn't finished the first fetch yet.
+    Starting,
+    /// The last fetch succeeded.
+    Healthy,
+    /// The last fetches failed with retriable errors and the poller is backing off.
+    Degraded {
+        /// Number of failed fetches since the last successful one.
+        consecutive_failures: u32,
+    },
+    /// The poller has stopped because of an unrecoverable error. This state is terminal.
+    Failed {
+        /// The error that stopped the poller.
+        error: Error,
+    },
+}
+
+impl PollerState {
+    /// Returns `true` if the states are the same, ignoring errors.
+    fn same_as(&self, other: &PollerState) -> bool {
+        match (self, other) {
+            (PollerState::Starting, PollerState::Starting)
+            | (PollerState::Healthy, PollerState::Healthy)
+            | (PollerState::Failed { .. }, PollerState::Failed { .. }) => true,
+            (
+                PollerState::Degraded {
+                    consecutive_failures: a,
+                },
+                PollerState::Degraded {
+                    consecutive_failures: b,
+                },
+            ) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Metadata about configuration fetches performed by the poller. Useful for debugging stale
+/// configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchStats {
+    /// Time when the last fetch was started.
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    /// Time when the last successful fetch completed.
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// How long the last fetch (successful or not) took.
+    pub last_duration: Option<Duration>,
+    /// Size (in bytes) of decompressed response bodies received by the last successful fetch.
+    /// This is zero if the server reported that configuration has not been modified.
+    pub last_payload_bytes: Option<usize>,
+    /// Number of failed fetches since the last successful one.
+    pub consecutive_failures: u32,
+}
+
+impl FetchStats {
+    pub(crate) fn record_success(
+        &mut self,
+        attempt_at: DateTime<Utc>,
+        duration: Duration,
+        payload_bytes: usize,
+    ) {
+        self.last_attempt_at = Some(attempt_at);
+        self.last_success_at = Some(Utc::now());
+        self.last_duration = Some(duration);
+        self.last_payload_bytes = Some(payload_bytes);
+        self.consecutive_failures = 0;
+    }
+
+    pub(crate) fn record_failure(&mut self, attempt_at: DateTime<Utc>, duration: Duration) {
+        self.last_attempt_at = Some(attempt_at);
+        self.last_duration = Some(duration);
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+    }
+}
+
+/// Coordinates on-demand fetches requested with [`PollerThread::poll_now()`].
+#[derive(Default)]
+struct Refresh {
+    /// Wakes up the poller while it waits for the next fetch.
+    notify: tokio::sync::Notify,
+    state: Mutex<RefreshState>,
+    completed: Condvar,
+}
 
-    /// Used to send a stop command to the poller thread.
-    // TODO: take a look at `std::thread::park_timeout()`. It could be used to build simpler
-    // synchronization.
-    stop_sender: std::sync::mpsc::SyncSender<()>,
+#[derive(Default)]
+struct RefreshState {
+    /// Id of the last requested fetch. Requests made before the fetch starts share the id.
+    requested: u64,
+    /// Value of `requested` when the current (or last) fetch started.
+    started: u64,
+    /// Value of `started` of the last completed fetch.
+    completed: u64,
+    /// Result of the last completed fetch.
+    result: Option<Result<()>>,
+    /// Set when the poller exits.
+    stopped: bool,
+}
+
+impl Refresh {
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, RefreshState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Request a fetch and block until it completes.
+    fn request(&self) -> Result<()> {
+        let mut state = self.lock_state();
+        if state.stopped {
+            return Err(Error::PollerStopped);
+        }
+        // Join the pending request, if any. Otherwise, a fetch that is already in flight may have
+        // started before the caller wanted it, so request a new one.
+        if state.requested == state.started {
+            state.requested += 1;
+            self.notify.notify_one();
+        }
+        let id = state.requested;
+        let state = self
+            .completed
+            .wait_while(state, |state| state.completed < id && !state.stopped)
+            .unwrap_or_else(|err| err.into_inner());
+        if state.completed < id {
+            return Err(Error::PollerStopped);
+        }
+        state.result.clone().unwrap_or(Ok(()))
+    }
+
+    /// Returns `true` if there are requests that are not served by the last started fetch.
+    fn is_pending(&self) -> bool {
+        let state = self.lock_state();
+        state.requested > state.started
+    }
+
+    /// Record that a fetch has started and return its id.
+    fn start_fetch(&self) -> u64 {
+        let mut state = self.lock_state();
+        state.started = state.requested;
+        state.started
+    }
+
+    fn complete_fetch(&self, id: u64, result: std::result::Result<(), &Error>) {
+        let mut state = self.lock_state();
+        state.completed = id;
+        state.result = Some(result.map_err(Error::clone));
+        self.completed.notify_all();
+    }
+
+    fn stop(&self) {
+        self.lock_state().stopped = true;
+        self.completed.notify_all();
+    }
 
+    /// Resolves when a fetch is requested that hasn't started yet.
+    async fn requested(&self) {
+        loop {
+            self.notify.notified().await;
+            if self.is_pending() {
+                return;
+            }
+        }
+    }
+}
+
+/// State shared between the poller loop and its handle ([`PollerThread`] or
+/// [`PollerTask`](crate::poller_task::PollerTask)).
+pub(crate) struct PollerShared {
     /// Holds `None` if configuration hasn't been fetched yet. Holds `Some(Ok(()))` if configuration
     /// has been fetches successfully. Holds `Some(Err(...))` if there was an error fetching the
     /// first configuration.
-    result: Arc<(Mutex<Option<Result<()>>>, Condvar)>,
+    result: Mutex<Option<Result<()>>>,
+    /// Wakes up threads waiting for `result`.
+    result_changed: Condvar,
+    /// Wakes up tasks waiting for `result`.
+    result_notify: tokio::sync::Notify,
+
+    /// Status of the last fetch. Updated after every fetch. [`PollerState`] is derived from it.
+    status: Mutex<PollerStatus>,
+    /// Invoked when the derived [`PollerState`] changes.
+    on_state_change: Option<PollerStateChangeCallback>,
+
+    /// Fetch metadata. Updated after every fetch.
+    stats: Mutex<FetchStats>,
+
+    /// Used to request an immediate fetch.
+    refresh: Refresh,
+
+    /// Publishes every configuration stored by the poller. Only used if the store doesn't notify
+    /// about changes itself, so every update is published once.
+    configuration_sender: Option<watch::Sender<Option<Arc<Configuration>>>>,
+    /// Cloned by `watch()`. Subscribed to the store or to `configuration_sender`.
+    configuration_receiver: ConfigurationReceiver,
 }
 
-impl PollerThread {
-    /// Starts the configuration poller thread.
-    ///
-    /// # Returns
-    ///
-    /// Returns a `Result` with the `PollerThread` instance if successful, or an `Error` if an issue
-    /// occurs.
-    ///
-    /// # Errors
-    ///
-    /// This method can return the following errors:
-    /// - IO Error if poller thread failed to start.
-    pub fn start(
-        fetcher: ConfigurationFetcher,
-        store: Arc<ConfigurationStore>,
-    ) -> std::io::Result<PollerThread> {
-        PollerThread::start_with_config(fetcher, store, PollerThreadConfig::default())
+impl PollerShared {
+    /// `restored` tells whether persisted configuration has been loaded before the poller started.
+    pub(crate) fn new(
+        restored: bool,
+        config: &PollerThreadConfig,
+        store: &dyn ConfigurationStoreBackend,
+    ) -> PollerShared {
+        let (configuration_sender, configuration_receiver) = match store.subscribe() {
+            Some(watcher) => (None, watcher.receiver),
+            None => {
+                let (sender, receiver) = watch::channel(store.get());
+                (Some(sender), receiver)
+            }
+        };
+        PollerShared {
+            result: Mutex::new(restored.then_some(Ok(()))),
+            result_changed: Condvar::new(),
+            result_notify: tokio::sync::Notify::new(),
+            status: Mutex::new(PollerStatus::Pending),
+            stats: Mutex::new(FetchStats::default()),
+            on_state_change: config.on_state_change.clone(),
+            refresh: Refresh::default(),
+            configuration_sender,
+            configuration_receiver,
+        }
     }
 
-    /// Starts the configuration poller thread with the provided configuration.
-    ///
-    /// # Returns
-    ///
-    /// Returns a `Result` with the `PollerThread` instance if successful, or an `Error` if an issue
-    /// occurs.
-    ///
-    /// # Errors
-    ///
-    /// This method can return the following errors:
-    /// - IO Error if poller thread failed to start.
-    pub fn start_with_config(
-        mut fetcher: ConfigurationFetcher,
-        store: Arc<ConfigurationStore>,
-        config: PollerThreadConfig,
-    ) -> std::io::Result<PollerThread> {
-        // Using `sync_channel` here as it makes `stop_sender` `Sync` (shareable between
-        // threads). Buffer size of 1 should be enough for our use case as we're sending a stop
-        // command, and we can simply `try_send()` and ignore if the buffer is full (another thread
-        // has sent a stop command already).
-        let (stop_sender, stop_receiver) = std::sync::mpsc::sync_channel::<()>(1);
+    pub(crate) fn watch(&self) -> ConfigurationReceiver {
+        let mut receiver = self.configuration_receiver.clone();
+        // Only configuration published after subscribing counts as a change.
+        receiver.borrow_and_update();
+        receiver
+    }
 
-        let result = Arc::new((Mutex::new(None), Condvar::new()));
+    fn publish(&self, configuration: Option<Arc<Configuration>>) {
+        if let (Some(sender), Some(configuration)) = (&self.configuration_sender, configuration) {
+            sender.send_replace(Some(configuration));
+        }
+    }
 
-        let join_handle = {
-            // Cloning Arc for move into thread
-            let result = Arc::clone(&result);
-            let update_result = move |value| {
-                *result.0.lock().unwrap() = Some(value);
-                result.1.notify_all();
-            };
+    pub(crate) fn status(&self) -> PollerStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
 
-            std::thread::Builder::new()
-                .name("eppo-poller".to_owned())
-                .spawn(move || {
-                    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                        let runtime = match tokio::runtime::Builder::new_current_thread()
-                            .enable_all()
-                            .build()
-                        {
-                            Ok(runtime) => runtime,
-                            Err(err) => {
-                                update_result(Err(Error::from(err)));
-                                return;
-                            }
-                        };
+    pub(crate) fn state(&self) -> PollerState {
+        self.status
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .state()
+    }
 
-                        loop {
-                            log::debug!(target: "eppo", "fetching new configuration");
-                            let result = runtime.block_on(fetcher.fetch_configuration());
-                            match result {
-                                Ok(configuration) => {
-                                    store.set_configuration(Arc::new(configuration));
-                                    update_result(Ok(()))
-                                }
-                                Err(err @ (Error::Unauthorized | Error::InvalidBaseUrl(_))) => {
-                                    // Unrecoverable errors
-                                    update_result(Err(err));
-                                    return;
-                                }
-                                _ => {
-                                    // Other errors are retrievable.
-                                }
-                            };
-
-                            let timeout = jitter(config.interval, config.jitter);
-                            match stop_receiver.recv_timeout(timeout) {
-                                Err(RecvTimeoutError::Timeout) => {
-                                    // Timed out. Loop back to fetch a new configuration.
-                                }
-                                Ok(()) => {
-                                    log::debug!(target: "eppo", "poller thread received stop command");
-                                    // Stop command received, break out of the loop to end the thread.
-                                    return;
-                                }
-                                Err(RecvTimeoutError::Disconnected) => {
-                                    // When the other end of channel disconnects, calls to
-                                    // .recv_timeout() return immediately.
-                                    // Stop the thread.
-                                    log::debug!(target: "eppo", "poller thread received disconnected");
-                                    return;
-                                }
-                            }
-                        }
-                    }));
-
-                    // If catch_unwind returns Err, it means a panic occurred.
-                    if let Err(_panic_info) = result {
-                        // Handle the panic gracefully by updating the result with an error.
-                        update_result(Err(Error::PollerThreadPanicked));
-                    }
-                })?
-        };
+    pub(crate) fn stats(&self) -> FetchStats {
+        self.stats
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
 
-        Ok(PollerThread {
-            join_handle,
-            stop_sender,
-            result,
-        })
+    pub(crate) fn terminal_error(&self) -> Option<Error> {
+        match self.state() {
+            PollerState::Failed { error } => Some(error),
+            _ => None,
+        }
     }
 
-    /// Waits for the configuration to be fetched.
-    ///
-    /// This method blocks until the poller thread has fetched the configuration.
-    ///
-    /// # Returns
-    ///
-    /// Returns `Result<()>` where `Ok(())` indicates successful configuration fetch and any
-    /// error that occurred during the process.
-    ///
-    /// # Errors
-    ///
-    /// This method can fail with the following errors:
-    ///
-    /// - [`Error::PollerThreadPanicked`]
-    /// - [`Error::Unauthorized`]
-    /// - [`Error::InvalidBaseUrl`]
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// # fn test(mut poller_thread: eppo_core::poller_thread::PollerThread) {
-    /// match poller_thread.wait_for_configuration() {
-    ///     Ok(()) => println!("Configuration fetched successfully."),
-    ///     Err(err) => eprintln!("Cannot fetch configuration: {:?}", err),
-    /// }
-    /// # }
-    /// ```
-    pub fn wait_for_configuration(&self) -> Result<()> {
+    pub(crate) fn poll_now(&self) -> Result<()> {
+        self.refresh.request()
+    }
+
+    pub(crate) fn wait_for_configuration(&self) -> Result<()> {
+        if let Some(error) = self.terminal_error() {
+            return Err(error);
+        }
         let mut lock = self
             .result
-            .0
             .lock()
             .map_err(|_| Error::PollerThreadPanicked)?;
         loop {
@@ -240,8 +759,7 @@ impl PollerThread {
                 None => {
                     // Block waiting for configuration to get fetched.
                     lock = self
-                        .result
-                        .1
+                        .result_changed
                         .wait(lock)
                         .map_err(|_| Error::PollerThreadPanicked)?;
                 }
@@ -249,57 +767,1951 @@ impl PollerThread {
         }
     }
 
-    /// Stop the poller thread.
-    ///
-    /// This function does not wait for the thread to actually stop.
-    pub fn stop(&self) {
-        // Error means that the receiver was dropped (thread exited) or the channel buffer is
-        // full. First case can be ignored it as there's nothing useful we can do—thread is already
-        // stopped. Second case can be ignored as it indicates that another thread already sent a
-        // stop command and the thread will stop anyway.
-        let _ = self.stop_sender.try_send(());
+    pub(crate) fn wait_for_configuration_timeout(&self, timeout: Duration) -> Result<()> {
+        if let Some(error) = self.terminal_error() {
+            return Err(error);
+        }
+        let lock = self
+            .result
+            .lock()
+            .map_err(|_| Error::PollerThreadPanicked)?;
+        let (lock, _) = self
+            .result_changed
+            .wait_timeout_while(lock, timeout, |result| result.is_none())
+            .map_err(|_| Error::PollerThreadPanicked)?;
+        match &*lock {
+            Some(result) => result.clone(),
+            None => Err(Error::ConfigurationWaitTimeout),
+        }
     }
 
-    /// Stop the poller thread and block waiting for it to exit.
-    ///
-    /// If you don't need to wait for the thread to exit, use [`PollerThread::stop`] instead.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error of type [`Error`] in the following cases:
-    ///
-    /// - [`Error::PollerThreadPanicked`] if the thread has panicked.
-    ///
-    /// # Examples
-    ///
+    pub(crate) async fn wait_for_configuration_async(&self) -> Result<()> {
+        if let Some(error) = self.terminal_error() {
+            return Err(error);
+        }
+        loop {
+            // Created before checking the result, so a notification sent in between is not lost.
+            let notified = self.result_notify.notified();
+            let result = self
+                .result
+                .lock()
+                .map_err(|_| Error::PollerThreadPanicked)?
+                .clone();
+            if let Some(result) = result {
+                return result;
+            }
+            notified.await;
+        }
+    }
+
+    fn set_result(&self, value: Result<()>) {
+        *self.result.lock().unwrap_or_else(|err| err.into_inner()) = Some(value);
+        self.result_changed.notify_all();
+        self.result_notify.notify_waiters();
+    }
+
+    /// Update the status and notify the state change callback if the derived state has changed.
+    fn record_status(&self, value: std::result::Result<(), &Error>) {
+        let (previous, state) = {
+            let mut status = self.status.lock().unwrap_or_else(|err| err.into_inner());
+            let previous = status.state();
+            match value {
+                Ok(()) => status.record_success(),
+                Err(err) => status.record_failure(err.clone()),
+            }
+            (previous, status.state())
+        };
+        if state.same_as(&previous) {
+            return;
+        }
+
+        let Some(callback) = &self.on_state_change else {
+            return;
+        };
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(&state)));
+        if result.is_err() {
+            log::warn!(target: "eppo", "poller state change callback panicked");
+        }
+    }
+
+    fn record_stats(&self, attempt_at: DateTime<Utc>, started: Instant, result: Option<usize>) {
+        let mut stats = self.stats.lock().unwrap_or_else(|err| err.into_inner());
+        match result {
+            Some(payload_bytes) => {
+                stats.record_success(attempt_at, started.elapsed(), payload_bytes)
+            }
+            None => stats.record_failure(attempt_at, started.elapsed()),
+        }
+    }
+
+    /// Report an unrecoverable error that stopped the poller.
+    pub(crate) fn fail(&self, error: Error) {
+        self.record_status(Err(&error));
+        self.set_result(Err(error));
+    }
+
+    /// Release callers that are still waiting for the poller after it has exited.
+    pub(crate) fn finish(&self) {
+        self.refresh.stop();
+        let mut result = self.result.lock().unwrap_or_else(|err| err.into_inner());
+        if result.is_none() {
+            *result = Some(Err(Error::PollerStopped));
+            self.result_changed.notify_all();
+            self.result_notify.notify_waiters();
+        }
+    }
+}
+
+/// Finishes the poller when the loop exits. If the loop hasn't returned, it has panicked (or the
+/// task running it was aborted).
+struct ExitGuard<'a> {
+    shared: &'a PollerShared,
+    returned: bool,
+}
+
+impl Drop for ExitGuard<'_> {
+    fn drop(&mut self) {
+        if !self.returned {
+            self.shared.fail(Error::PollerThreadPanicked);
+        }
+        self.shared.finish();
+    }
+}
+
+/// Run the poller loop until a stop command is received or an unrecoverable error occurs.
+///
+/// This is shared by [`PollerThread`] (which runs it on its own runtime) and
+/// [`PollerTask`](crate::poller_task::PollerTask) (which runs it on the ambient runtime), so their
+/// behavior doesn't drift.
+pub(crate) async fn run_poller(
+    source: impl ConfigurationSource,
+    store: Arc<dyn ConfigurationStoreBackend>,
+    config: PollerThreadConfig,
+    shared: Arc<PollerShared>,
+    stop_receiver: watch::Receiver<bool>,
+    restored: bool,
+) {
+    let mut guard = ExitGuard {
+        shared: &shared,
+        returned: false,
+    };
+    poll(source, &store, &config, &shared, stop_receiver, restored).await;
+    guard.returned = true;
+}
+
+async fn poll(
+    mut source: impl ConfigurationSource,
+    store: &Arc<dyn ConfigurationStoreBackend>,
+    config: &PollerThreadConfig,
+    shared: &PollerShared,
+    mut stop_receiver: watch::Receiver<bool>,
+    restored: bool,
+) {
+    let mut consecutive_failures: u32 = 0;
+    let mut has_configuration = restored;
+    let bandit_poll_every_n_cycles = u64::from(config.bandit_poll_every_n_cycles.max(1));
+    let mut cycle: u64 = 0;
+    // Bandit models are only re-downloaded along with modified flags configuration, so a due
+    // refresh stays pending until a fetch returns modified configuration.
+    let mut bandit_refresh_pending = false;
+
+    // Persisted configuration is newer than the configuration file.
+    if let Some(file) = config.configuration_file.clone().filter(|_| !restored) {
+        let Some((returned, result)) = run_blocking(move || {
+            let result = source.read_configuration_file(&file);
+            (source, result)
+        })
+        .await
+        else {
+            return;
+        };
+        source = returned;
+        match result {
+            Ok(configuration) => {
+                let Some(updated) = update_configuration_blocking(
+                    store,
+                    configuration,
+                    config.on_configuration_change.as_ref(),
+                )
+                .await
+                else {
+                    return;
+                };
+                shared.publish(updated);
+                has_configuration = true;
+                shared.record_status(Ok(()));
+                shared.set_result(Ok(()));
+            }
+            Err(err) => {
+                log::warn!(target: "eppo", "failed to read configuration file: {err}");
+                // Report the error to waiters but keep polling the server.
+                shared.record_status(Err(&err));
+                shared.set_result(Err(err));
+            }
+        }
+    }
+    loop {
+        log::debug!(target: "eppo", "fetching new configuration");
+        bandit_refresh_pending |= cycle % bandit_poll_every_n_cycles == 0;
+        source.set_refresh_bandits(bandit_refresh_pending);
+        cycle += 1;
+        let attempt_at = Utc::now();
+        let started = Instant::now();
+        let fetch_id = shared.refresh.start_fetch();
+        let result = tokio::select! {
+            result = source.fetch() => result,
+            () = stop_requested(&mut stop_receiver) => {
+                log::debug!(target: "eppo", "poller received stop command during fetch");
+                // Dropping the fetch future aborts the request.
+                return;
+            }
+        };
+        shared.record_stats(
+            attempt_at,
+            started,
+            result.is_ok().then(|| source.last_payload_bytes()),
+        );
+        if let Err(err) = &result {
+            config.report_error(err);
+        }
+        shared
+            .refresh
+            .complete_fetch(fetch_id, result.as_ref().map(|_| ()));
+        // Minimum delay before the next request as requested by the server.
+        let mut retry_after = Duration::ZERO;
+        match result {
+            Ok(result) => {
+                // Not-modified configuration is already in the store, so there's nothing to
+                // update.
+                if let FetchResult::Modified(mut configuration) = result {
+                    bandit_refresh_pending = false;
+                    if let Some(persistence) = config.persistence.clone() {
+                        let Some(returned) = run_blocking(move || {
+                            source.persist_configuration(&configuration, &*persistence);
+                            (source, configuration)
+                        })
+                        .await
+                        else {
+                            return;
+                        };
+                        (source, configuration) = returned;
+                    }
+                    let Some(updated) = update_configuration_blocking(
+                        store,
+                        *configuration,
+                        config.on_configuration_change.as_ref(),
+                    )
+                    .await
+                    else {
+                        return;
+                    };
+                    shared.publish(updated);
+                }
+                consecutive_failures = 0;
+                has_configuration = true;
+                shared.record_status(Ok(()));
+                shared.set_result(Ok(()));
+            }
+            Err(err) if !err.is_retriable() => {
+                // Unrecoverable errors
+                shared.record_status(Err(&err));
+                shared.set_result(Err(err));
+                return;
+            }
+            Err(err @ Error::RateLimited { retry_after: delay }) => {
+                consecutive_failures = consecutive_failures.saturating_add(1);
+                retry_after = delay.unwrap_or_default().min(config.interval);
+                shared.record_status(Err(&err));
+                if !has_configuration {
+                    // Let callers waiting for the first configuration know that they are being
+                    // throttled. The poller keeps retrying.
+                    shared.set_result(Err(err));
+                }
+            }
+            Err(err) => {
+                // Other errors are retrievable.
+                consecutive_failures = consecutive_failures.saturating_add(1);
+                shared.record_status(Err(&err));
+            }
+        };
+
+        let timeout = config.next_delay(consecutive_failures).max(retry_after);
+        // Fetches requested with `poll_now()` still honor the delay requested by the server.
+        let retry_at = tokio::time::Instant::now() + retry_after;
+        tokio::select! {
+            () = tokio::time::sleep(timeout) => {}
+            () = async {
+                shared.refresh.requested().await;
+                tokio::time::sleep_until(retry_at).await;
+            } => {}
+            () = stop_requested(&mut stop_receiver) => {
+                log::debug!(target: "eppo", "poller received stop command");
+                return;
+            }
+        }
+        // Timed out. Loop back to fetch a new configuration.
+    }
+}
+
+/// Run blocking `f` on tokio's blocking thread pool, so slow filesystem or storage doesn't stall
+/// the runtime the poller runs on (which is the application's runtime for
+/// [`PollerTask`](crate::poller_task::PollerTask)).
+///
+/// Panics inside `f` are propagated to the caller. Returns `None` if the runtime is shutting down.
+async fn run_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(value) => Some(value),
+        Err(err) => match err.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            Err(_) => None,
+        },
+    }
+}
+
+/// Same as [`update_configuration()`] but runs on the blocking pool, as store backends may do I/O
+/// (e.g., [`FileConfigurationStore`](crate::file_configuration_store::FileConfigurationStore)
+/// writes a file). Returns `None` if the runtime is shutting down.
+async fn update_configuration_blocking(
+    store: &Arc<dyn ConfigurationStoreBackend>,
+    configuration: Configuration,
+    on_configuration_change: Option<&ConfigurationChangeCallback>,
+) -> Option<Option<Arc<Configuration>>> {
+    let store = Arc::clone(store);
+    let callback = on_configuration_change.cloned();
+    run_blocking(move || update_configuration(&*store, configuration, callback.as_ref())).await
+}
+
+/// A configuration poller thread.
+///
+/// The poller thread polls the server periodically to fetch the latest configuration using
+/// [`ConfigurationFetcher`](crate::configuration_fetcher::ConfigurationFetcher) and stores it in
+/// [`ConfigurationStoreBackend`]. Any other [`ConfigurationSource`] can be polled instead of the
+/// server.
+pub struct PollerThread {
+    /// Taken on shutdown.
+    join_handle: Mutex<Option<std::thread::JoinHandle<()>>>,
+
+    /// Used to send a stop command to the poller thread. The thread watches it both while waiting
+    /// for the next fetch and during a fetch, so an in-flight request is cancelled promptly.
+    stop_sender: watch::Sender<bool>,
+
+    shared: Arc<PollerShared>,
+}
+
+impl PollerThread {
+    /// Starts the configuration poller thread.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` with the `PollerThread` instance if successful, or an `Error` if an issue
+    /// occurs.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// - IO Error if poller thread failed to start.
+    pub fn start(
+        source: impl ConfigurationSource,
+        store: Arc<dyn ConfigurationStoreBackend>,
+    ) -> Result<PollerThread> {
+        PollerThread::start_with_config(source, store, PollerThreadConfig::default())
+    }
+
+    /// Starts the configuration poller thread with the provided configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` with the `PollerThread` instance if successful, or an `Error` if an issue
+    /// occurs.
+    ///
+    /// # Errors
+    ///
+    /// This method can return the following errors:
+    /// - IO Error if poller thread or its runtime failed to start.
+    /// - Non-retriable error of the first fetch if [`PollerThreadConfig::block_on_initial_fetch`]
+    ///   is set. This blocks the calling thread, so avoid it in async code.
+    pub fn start_with_config(
+        mut source: impl ConfigurationSource,
+        store: Arc<dyn ConfigurationStoreBackend>,
+        config: PollerThreadConfig,
+    ) -> Result<PollerThread> {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let block_on_initial_fetch = config.block_on_initial_fetch;
+
+        // Load persisted configuration synchronously, so it is available as soon as the poller is
+        // started.
+        let restored = config.restore_persisted_configuration(&mut source, &*store);
+        let shared = Arc::new(PollerShared::new(restored, &config, &*store));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        let join_handle = {
+            let shared = Arc::clone(&shared);
+            std::thread::Builder::new()
+                .name("eppo-poller".to_owned())
+                .spawn(move || {
+                    // Callbacks catch their own panics. Anything else that panics (e.g., the
+                    // configuration source) is a bug: `run_poller()` reports it to waiters and the
+                    // panic is left to propagate, so `shutdown()` returns
+                    // `Error::PollerThreadPanicked` the same way `PollerTask` does.
+                    runtime.block_on(run_poller(
+                        source,
+                        store,
+                        config,
+                        shared,
+                        stop_receiver,
+                        restored,
+                    ));
+                    // Configuration may still be parsing on a blocking thread if the poller was
+                    // stopped during a fetch, so don't wait for it.
+                    runtime.shutdown_background();
+                })?
+        };
+
+        let poller = PollerThread {
+            join_handle: Mutex::new(Some(join_handle)),
+            stop_sender,
+            shared,
+        };
+
+        if let Some(timeout) = block_on_initial_fetch {
+            match poller.wait_for_configuration_timeout(timeout) {
+                Ok(()) => {}
+                Err(err) if !err.is_retriable() => {
+                    // The poller has already stopped on its own, so this only joins the thread.
+                    let _ = poller.shutdown();
+                    return Err(err);
+                }
+                Err(err) => {
+                    log::warn!(target: "eppo", "initial configuration fetch has not completed, continuing in the background: {err}");
+                }
+            }
+        }
+
+        Ok(poller)
+    }
+
+    /// Returns the current status of the poller without blocking.
+    ///
+    /// Unlike [`PollerThread::wait_for_configuration`], this reflects the result of the most
+    /// recent fetch, so it can be used for readiness probes.
+    pub fn status(&self) -> PollerStatus {
+        self.shared.status()
+    }
+
+    /// Fetch configuration immediately instead of waiting for the next poll, and block until the
+    /// fetch completes. Useful to pick up urgent changes (e.g., a kill switch) without waiting up
+    /// to [`PollerThreadConfig::interval`].
+    ///
+    /// Concurrent calls made before the fetch starts share the same fetch. If the server asked to
+    /// slow down (see [`Error::RateLimited`]), the fetch is delayed accordingly.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the fetch if it failed. Returns [`Error::PollerStopped`] if the poller
+    /// has stopped (including after an unrecoverable error) before the fetch completed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn test(poller_thread: eppo_core::poller_thread::PollerThread) {
+    /// if let Err(err) = poller_thread.poll_now() {
+    ///     eprintln!("failed to refresh configuration: {err}");
+    /// }
+    /// # }
+    /// ```
+    pub fn poll_now(&self) -> Result<()> {
+        self.shared.poll_now()
+    }
+
+    /// Returns the current health of the poller without blocking.
+    ///
+    /// See [`PollerThreadConfig::on_state_change`] to get notified about state changes instead.
+    pub fn state(&self) -> PollerState {
+        self.shared.state()
+    }
+
+    /// Returns the error that stopped the poller, if any (e.g., [`Error::Unauthorized`]).
+    ///
+    /// The poller stops after an unrecoverable error (see [`Error::is_retriable()`]), and
+    /// configuration is not updated anymore. The error stays available after the thread has
+    /// exited, so it can be checked at any time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn test(poller_thread: eppo_core::poller_thread::PollerThread) {
+    /// if let Some(err) = poller_thread.terminal_error() {
+    ///     eprintln!("configuration is not updated anymore: {err}");
+    /// }
+    /// # }
+    /// ```
+    pub fn terminal_error(&self) -> Option<Error> {
+        self.shared.terminal_error()
+    }
+
+    /// Returns metadata about configuration fetches (e.g., when the last successful fetch
+    /// happened and how long it took).
+    pub fn stats(&self) -> FetchStats {
+        self.shared.stats()
+    }
+
+    /// Subscribe to configuration updates.
+    ///
+    /// The receiver starts with the current configuration and is notified every time it is
+    /// replaced. If the store notifies about changes (see
+    /// [`ConfigurationStoreBackend::subscribe()`]), this is the store's channel, so configuration
+    /// set on the store directly is published too. Otherwise, the poller publishes configuration it
+    /// stores.
+    ///
+    /// The channel is closed when the store is dropped or, for stores that don't notify about
+    /// changes, when the poller is dropped.
+    ///
+    /// # Example
+    ///
+    /// Wait for the first configuration without blocking a thread:
+    ///
+    /// ```
+    /// # async fn test(poller_thread: eppo_core::poller_thread::PollerThread) {
+    /// let mut configurations = poller_thread.watch();
+    /// let fetched_at = match configurations.wait_for(|configuration| configuration.is_some()).await {
+    ///     Ok(configuration) => configuration.as_ref().unwrap().fetched_at,
+    ///     // The store has been dropped.
+    ///     Err(_) => return,
+    /// };
+    /// println!("received configuration fetched at {fetched_at}");
+    /// # }
+    /// ```
+    pub fn watch(&self) -> ConfigurationReceiver {
+        self.shared.watch()
+    }
+
+    /// Waits for the configuration to be fetched.
+    ///
+    /// Returns the terminal error immediately if the poller has stopped because of one (see
+    /// [`PollerThread::terminal_error()`]), even if configuration was fetched before.
+    ///
+    /// This method blocks until the poller thread has fetched the configuration.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Result<()>` where `Ok(())` indicates successful configuration fetch and any
+    /// error that occurred during the process.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail with the following errors:
+    ///
+    /// - [`Error::PollerThreadPanicked`]
+    /// - [`Error::PollerStopped`] if the poller has been stopped before fetching configuration.
+    /// - [`Error::Unauthorized`]
+    /// - [`Error::InvalidBaseUrl`]
+    /// - [`Error::InvalidHeader`]
+    /// - [`Error::RateLimited`] if the server throttled requests before configuration was fetched.
+    ///   The poller keeps retrying, so a later call may succeed.
+    /// - [`Error::Io`] or [`Error::ConfigurationParseError`] if
+    ///   [`PollerThreadConfig::configuration_file`] cannot be loaded and configuration hasn't been
+    ///   fetched from the server yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn test(mut poller_thread: eppo_core::poller_thread::PollerThread) {
+    /// match poller_thread.wait_for_configuration() {
+    ///     Ok(()) => println!("Configuration fetched successfully."),
+    ///     Err(err) => eprintln!("Cannot fetch configuration: {:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub fn wait_for_configuration(&self) -> Result<()> {
+        self.shared.wait_for_configuration()
+    }
+
+    /// Waits for the configuration to be fetched, but no longer than `timeout`.
+    ///
+    /// This is the same as [`PollerThread::wait_for_configuration`] but it doesn't block forever
+    /// if the poller keeps receiving retriable errors (e.g., because of network issues).
+    ///
+    /// # Errors
+    ///
+    /// This method can fail with the following errors:
+    ///
+    /// - [`Error::ConfigurationWaitTimeout`] if configuration wasn't fetched within `timeout`.
+    /// - [`Error::PollerThreadPanicked`]
+    /// - [`Error::PollerStopped`] if the poller has been stopped before fetching configuration.
+    /// - [`Error::Unauthorized`]
+    /// - [`Error::InvalidBaseUrl`]
+    /// - [`Error::InvalidHeader`]
+    /// - [`Error::RateLimited`] if the server throttled requests before configuration was fetched.
+    ///   The poller keeps retrying, so a later call may succeed.
+    /// - [`Error::Io`] or [`Error::ConfigurationParseError`] if
+    ///   [`PollerThreadConfig::configuration_file`] cannot be loaded and configuration hasn't been
+    ///   fetched from the server yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # fn test(mut poller_thread: eppo_core::poller_thread::PollerThread) {
+    /// match poller_thread.wait_for_configuration_timeout(Duration::from_secs(5)) {
+    ///     Ok(()) => println!("Configuration fetched successfully."),
+    ///     Err(err) => eprintln!("Cannot fetch configuration: {:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub fn wait_for_configuration_timeout(&self, timeout: Duration) -> Result<()> {
+        self.shared.wait_for_configuration_timeout(timeout)
+    }
+
+    /// Stop the poller thread. An in-flight configuration request is cancelled.
+    ///
+    /// This function does not wait for the thread to actually stop.
+    pub fn stop(&self) {
+        // `send_replace()` never fails, even if the thread has exited already and dropped the
+        // receiver.
+        self.stop_sender.send_replace(true);
+    }
+
+    /// Stop the poller thread and block waiting for it to exit.
+    ///
+    /// If you don't need to wait for the thread to exit, use [`PollerThread::stop`] instead.
+    ///
+    /// An in-flight configuration request is cancelled, so this returns promptly even if the
+    /// server is slow to respond.
+    ///
+    /// Calling `shutdown()` on an already shut down poller thread is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of type [`Error`] in the following cases:
+    ///
+    /// - [`Error::PollerThreadPanicked`] if the thread has panicked.
+    ///
+    /// # Examples
+    ///
     /// ```
     /// # fn test(poller_thread: eppo_core::poller_thread::PollerThread) {
     /// poller_thread.shutdown().expect("Failed to shut down the poller thread");
     /// # }
     /// ```
-    pub fn shutdown(self) -> Result<()> {
+    pub fn shutdown(&self) -> Result<()> {
         // Send stop signal in case it wasn't sent before.
         self.stop();
 
-        // Error means that the thread has panicked and there's nothing useful we can do in that
-        // case.
-        self.join_handle
-            .join()
-            .map_err(|_| Error::PollerThreadPanicked)?;
+        let join_handle = self
+            .join_handle
+            .lock()
+            .map_err(|_| Error::PollerThreadPanicked)?
+            .take();
+
+        if let Some(join_handle) = join_handle {
+            // Error means that the thread has panicked and there's nothing useful we can do in
+            // that case.
+            join_handle
+                .join()
+                .map_err(|_| Error::PollerThreadPanicked)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves when a stop command is received or the [`PollerThread`] handle is dropped.
+async fn stop_requested(stop_receiver: &mut watch::Receiver<bool>) {
+    // Error means that the sender was dropped, which also stops the thread.
+    let _ = stop_receiver.wait_for(|stop| *stop).await;
+}
+
+/// Fraction of the retry backoff that is randomized. See
+/// [`PollerThreadConfig::initial_retry_backoff`].
+const RETRY_BACKOFF_JITTER: f64 = 0.5;
+
+/// Randomized reduction of the poll interval. See [`PollerThreadConfig::jitter`] and
+/// [`PollerThreadConfig::jitter_percent`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Jitter {
+    /// Reduce the interval by up to the given duration.
+    Absolute(Duration),
+    /// Reduce the interval by up to the given fraction of it (e.g., `0.1` for 10%). Values
+    /// outside of `[0, 1]` are clamped.
+    Percent(f64),
+}
+
+impl From<Duration> for Jitter {
+    fn from(value: Duration) -> Jitter {
+        Jitter::Absolute(value)
+    }
+}
+
+impl Jitter {
+    /// Maximum reduction of `interval`.
+    fn max_for(self, interval: Duration) -> Duration {
+        match self {
+            Jitter::Absolute(jitter) => jitter,
+            // `mul_f64()` panics on NaN.
+            Jitter::Percent(percent) if percent.is_nan() => Duration::ZERO,
+            Jitter::Percent(percent) => interval.mul_f64(percent.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+/// Apply randomized `jitter` to `interval`.
+pub(crate) fn jitter(interval: Duration, jitter: impl Into<Jitter>) -> Duration {
+    let jitter = jitter.into().max_for(interval);
+    Duration::saturating_sub(interval, thread_rng().gen_range(Duration::ZERO..=jitter))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, VecDeque},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    use chrono::{DateTime, Utc};
+
+    use crate::{
+        configuration_fetcher::{ConfigurationFetcher, ConfigurationFetcherConfig},
+        configuration_persistence::{MemoryPersistence, PersistedConfiguration},
+        configuration_source::ConfigurationSource,
+        configuration_store::{ConfigurationStore, ConfigurationStoreBackend},
+        eval::{Evaluator, EvaluatorConfig},
+        file_configuration_store::FileConfigurationStore,
+        test_server::{TestResponse, TestServer, SDK_METADATA, UFC_JSON},
+        timestamp::SystemClock,
+        ufc::{CompiledFlagsConfig, Environment, ParseDiagnostics, UniversalFlagConfig},
+        Configuration, ContextAttributes, Error, Result, SdkMetadata, Str,
+    };
+
+    use super::{
+        update_configuration, ConfigurationChangeCallback, FetchResult, FetchStats, PollerState,
+        PollerStatus, PollerThread, PollerThreadConfig,
+    };
+
+    fn configuration(created_at: DateTime<Utc>) -> Configuration {
+        Configuration::from_server_response(
+            UniversalFlagConfig {
+                wire_json: b"test-bytes".to_vec(),
+                compiled: CompiledFlagsConfig {
+                    created_at,
+                    environment: Environment {
+                        name: "test".into(),
+                    },
+                    obfuscated: false,
+                    flags: HashMap::new(),
+                    flag_to_bandit_associations: HashMap::new(),
+                    bandit_model_versions: HashMap::new(),
+                    hashed_flag_keys: Default::default(),
+                    diagnostics: ParseDiagnostics::default(),
+                    flags_bytes: 0,
+                },
+                diagnostics: Default::default(),
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn configuration_change_callback_is_invoked_on_change() {
+        let store = ConfigurationStore::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let callback: ConfigurationChangeCallback = {
+            let calls = calls.clone();
+            Arc::new(move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+        let first = Utc::now();
+        let second = first + chrono::Duration::seconds(1);
+
+        update_configuration(&store, configuration(first), Some(&callback));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        update_configuration(&store, configuration(first), Some(&callback));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        update_configuration(&store, configuration(second), Some(&callback));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn fetched_configuration_does_not_override_newer_configuration() {
+        let store = ConfigurationStore::new();
+        let fetched = Utc::now();
+        // Configuration set manually while the fetch was in flight.
+        let manual = Arc::new(configuration(fetched + chrono::Duration::seconds(1)));
+        store.set_configuration(manual.clone());
+
+        update_configuration(&store, configuration(fetched), None);
+
+        assert!(Arc::ptr_eq(&store.get_configuration().unwrap(), &manual));
+    }
+
+    #[test]
+    fn configuration_change_callback_panic_is_caught() {
+        let store = ConfigurationStore::new();
+        let callback: ConfigurationChangeCallback = Arc::new(|_| panic!("callback panicked"));
+
+        update_configuration(&store, configuration(Utc::now()), Some(&callback));
+
+        assert!(store.get_configuration().is_some());
+    }
+
+    #[test]
+    fn wait_for_configuration_timeout_returns_timeout_error() {
+        // The server never answers.
+        let source = ScriptedSource::new([]).with_delay(Duration::from_secs(3600));
+        let poller = PollerThread::start(source, Arc::new(ConfigurationStore::new())).unwrap();
+
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::from_millis(50)),
+            Err(Error::ConfigurationWaitTimeout)
+        ));
+        assert!(matches!(poller.status(), PollerStatus::Pending));
 
-        Ok(())
+        // The in-flight fetch is cancelled.
+        poller.shutdown().unwrap();
     }
-}
 
-/// Apply randomized `jitter` to `interval`.
-fn jitter(interval: Duration, jitter: Duration) -> Duration {
-    Duration::saturating_sub(interval, thread_rng().gen_range(Duration::ZERO..=jitter))
+    #[test]
+    fn wait_for_configuration_timeout_returns_fatal_error() {
+        let fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig::for_tests("not a url"));
+        let poller = PollerThread::start(fetcher, Arc::new(ConfigurationStore::new())).unwrap();
+
+        // Invalid base url is reported well within the timeout.
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::from_secs(60)),
+            Err(Error::InvalidBaseUrl(_))
+        ));
+        assert!(matches!(
+            poller.status(),
+            PollerStatus::Failed {
+                error: Error::InvalidBaseUrl(_),
+                consecutive_failures: 1,
+                last_success: None,
+            }
+        ));
+    }
+
+    #[test]
+    fn poller_status_tracks_consecutive_failures() {
+        let mut status = PollerStatus::Pending;
+        assert!(!status.has_configuration());
+
+        status.record_failure(Error::ConfigurationWaitTimeout);
+        status.record_failure(Error::ConfigurationWaitTimeout);
+        assert!(matches!(
+            status,
+            PollerStatus::Failed {
+                consecutive_failures: 2,
+                last_success: None,
+                ..
+            }
+        ));
+
+        status.record_success();
+        assert!(matches!(status, PollerStatus::Ready { .. }));
+        assert!(status.has_configuration());
+
+        status.record_failure(Error::ConfigurationWaitTimeout);
+        assert!(matches!(
+            status,
+            PollerStatus::Failed {
+                consecutive_failures: 1,
+                last_success: Some(_),
+                ..
+            }
+        ));
+        assert!(status.has_configuration());
+    }
+
+    #[test]
+    fn fetch_stats_track_attempts() {
+        let mut stats = FetchStats::default();
+        let attempt_at = Utc::now();
+
+        stats.record_failure(attempt_at, Duration::from_millis(10));
+        stats.record_failure(attempt_at, Duration::from_millis(20));
+        assert_eq!(stats.consecutive_failures, 2);
+        assert_eq!(stats.last_attempt_at, Some(attempt_at));
+        assert_eq!(stats.last_duration, Some(Duration::from_millis(20)));
+        assert_eq!(stats.last_success_at, None);
+        assert_eq!(stats.last_payload_bytes, None);
+
+        stats.record_success(attempt_at, Duration::from_millis(5), 100);
+        assert_eq!(stats.consecutive_failures, 0);
+        assert!(stats.last_success_at.is_some());
+        assert_eq!(stats.last_duration, Some(Duration::from_millis(5)));
+        assert_eq!(stats.last_payload_bytes, Some(100));
+
+        stats.record_failure(attempt_at, Duration::from_millis(10));
+        assert_eq!(stats.consecutive_failures, 1);
+        // Last successful fetch is still reported.
+        assert_eq!(stats.last_payload_bytes, Some(100));
+        assert!(stats.last_success_at.is_some());
+    }
+
+    #[test]
+    fn poller_reports_fetch_stats() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let requests = requests.clone();
+            TestServer::start(move |_| {
+                if requests.fetch_add(1, Ordering::SeqCst) < 1 {
+                    TestResponse::new(503)
+                } else {
+                    TestResponse::new(200).with_body(UFC_JSON)
+                }
+            })
+        };
+        let fetcher = test_fetcher(&server);
+        let config = PollerThreadConfig::new()
+            .with_interval(Duration::from_secs(3600))
+            .with_initial_retry_backoff(Duration::from_millis(10));
+        let started_at = Utc::now();
+        let poller =
+            PollerThread::start_with_config(fetcher, Arc::new(ConfigurationStore::new()), config)
+                .unwrap();
+
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+
+        let stats = poller.stats();
+        assert_eq!(stats.consecutive_failures, 0);
+        assert_eq!(stats.last_payload_bytes, Some(UFC_JSON.len()));
+        assert!(stats.last_attempt_at.is_some_and(|t| t >= started_at));
+        assert!(stats.last_success_at >= stats.last_attempt_at);
+        assert!(stats.last_duration.is_some());
+    }
+
+    #[test]
+    fn invalid_header_is_unrecoverable() {
+        let fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            headers: vec![("invalid header".to_owned(), "value".to_owned())],
+            ..ConfigurationFetcherConfig::for_tests("http://localhost:1/api")
+        });
+        let poller = PollerThread::start(fetcher, Arc::new(ConfigurationStore::new())).unwrap();
+
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::from_secs(60)),
+            Err(Error::InvalidHeader(name)) if name == "invalid header"
+        ));
+    }
+
+    #[test]
+    fn block_on_initial_fetch_populates_store() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let store = Arc::new(ConfigurationStore::new());
+
+        let poller = PollerThread::start_with_config(
+            test_fetcher(&server),
+            store.clone(),
+            PollerThreadConfig::new().with_block_on_initial_fetch(Duration::from_secs(10)),
+        )
+        .unwrap();
+
+        assert!(store.get_configuration().is_some());
+        poller.shutdown().unwrap();
+    }
+
+    #[test]
+    fn block_on_initial_fetch_returns_unrecoverable_error() {
+        let server = TestServer::start(|_| TestResponse::new(401));
+
+        let result = PollerThread::start_with_config(
+            test_fetcher(&server),
+            Arc::new(ConfigurationStore::new()),
+            PollerThreadConfig::new().with_block_on_initial_fetch(Duration::from_secs(10)),
+        );
+
+        assert!(matches!(result, Err(Error::Unauthorized)));
+    }
+
+    #[test]
+    fn block_on_initial_fetch_continues_in_background_after_timeout() {
+        let store = Arc::new(ConfigurationStore::new());
+        let source =
+            ScriptedSource::new([Ok(FetchResult::Modified(Box::new(ufc_configuration())))])
+                .with_delay(Duration::from_millis(200));
+
+        let poller = PollerThread::start_with_config(
+            source,
+            store.clone(),
+            PollerThreadConfig::new().with_block_on_initial_fetch(Duration::from_millis(10)),
+        )
+        .unwrap();
+
+        assert!(store.get_configuration().is_none());
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert!(store.get_configuration().is_some());
+        poller.shutdown().unwrap();
+    }
+
+    #[test]
+    fn terminal_error_is_available_after_thread_exits() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let requests = requests.clone();
+            TestServer::start(move |_| {
+                if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                    TestResponse::new(200).with_body(UFC_JSON)
+                } else {
+                    TestResponse::new(401)
+                }
+            })
+        };
+        let errors = Arc::new(AtomicUsize::new(0));
+        let config = {
+            let errors = errors.clone();
+            PollerThreadConfig::new()
+                .with_interval(Duration::from_millis(10))
+                .with_jitter(Duration::ZERO)
+                .with_on_error(move |err| {
+                    assert!(matches!(err, Error::Unauthorized));
+                    errors.fetch_add(1, Ordering::SeqCst);
+                })
+        };
+        let poller = PollerThread::start_with_config(
+            test_fetcher(&server),
+            Arc::new(ConfigurationStore::new()),
+            config,
+        )
+        .unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert!(poller.terminal_error().is_none());
+
+        while poller.terminal_error().is_none() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        poller.shutdown().unwrap();
+
+        assert!(matches!(poller.terminal_error(), Some(Error::Unauthorized)));
+        // The first successful fetch is not reported anymore.
+        assert!(matches!(
+            poller.wait_for_configuration(),
+            Err(Error::Unauthorized)
+        ));
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::ZERO),
+            Err(Error::Unauthorized)
+        ));
+        assert!(matches!(
+            poller.status(),
+            PollerStatus::Failed {
+                error: Error::Unauthorized,
+                ..
+            }
+        ));
+        assert_eq!(errors.load(Ordering::SeqCst), 1);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn retries_with_backoff_after_transient_failure() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let requests = requests.clone();
+            TestServer::start(move |_| {
+                if requests.fetch_add(1, Ordering::SeqCst) < 2 {
+                    TestResponse::new(503)
+                } else {
+                    TestResponse::new(200).with_body(UFC_JSON)
+                }
+            })
+        };
+        let fetcher = test_fetcher(&server);
+        // Poll interval is way longer than the test timeout, so configuration can only be fetched
+        // by retrying.
+        let config = PollerThreadConfig::new()
+            .with_interval(Duration::from_secs(3600))
+            .with_initial_retry_backoff(Duration::from_millis(10));
+        let poller =
+            PollerThread::start_with_config(fetcher, Arc::new(ConfigurationStore::new()), config)
+                .unwrap();
+
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn rate_limiting_is_reported_and_respected() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let requests = requests.clone();
+            TestServer::start(move |_| {
+                requests.fetch_add(1, Ordering::SeqCst);
+                TestResponse::new(429).with_header("Retry-After", "3600")
+            })
+        };
+        let fetcher = test_fetcher(&server);
+        let config = PollerThreadConfig::new()
+            .with_interval(Duration::from_secs(3600))
+            .with_jitter(Duration::ZERO)
+            .with_initial_retry_backoff(Duration::from_millis(10));
+        let poller =
+            PollerThread::start_with_config(fetcher, Arc::new(ConfigurationStore::new()), config)
+                .unwrap();
+
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::from_secs(10)),
+            Err(Error::RateLimited {
+                retry_after: Some(retry_after)
+            }) if retry_after == Duration::from_secs(3600)
+        ));
+
+        // Regular backoff would have retried many times by now.
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn retry_after_is_capped_at_poll_interval() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let requests = requests.clone();
+            TestServer::start(move |_| {
+                requests.fetch_add(1, Ordering::SeqCst);
+                TestResponse::new(429).with_header("Retry-After", "3600")
+            })
+        };
+        let fetcher = test_fetcher(&server);
+        let config = PollerThreadConfig::new()
+            .with_interval(Duration::from_millis(20))
+            .with_jitter(Duration::ZERO)
+            .with_initial_retry_backoff(Duration::from_millis(10));
+        let _poller =
+            PollerThread::start_with_config(fetcher, Arc::new(ConfigurationStore::new()), config)
+                .unwrap();
+
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(requests.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn retry_backoff_is_exponential_and_capped() {
+        let config = PollerThreadConfig::new()
+            .with_interval(Duration::from_secs(30))
+            .with_jitter(Duration::ZERO)
+            .with_initial_retry_backoff(Duration::from_secs(1))
+            .with_max_retry_backoff(Duration::from_secs(10));
+
+        let backoffs: Vec<_> = (1..6)
+            .map(|failures| config.retry_backoff(failures))
+            .collect();
+        assert_eq!(backoffs, [1, 2, 4, 8, 10].map(Duration::from_secs).to_vec());
+        assert_eq!(config.retry_backoff(u32::MAX), Duration::from_secs(10));
+        assert_eq!(config.next_delay(0), Duration::from_secs(30));
+
+        // Backoff never exceeds the poll interval.
+        let config = config.with_interval(Duration::from_secs(3));
+        assert_eq!(config.retry_backoff(5), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn retry_backoff_is_jittered_and_bounded_below() {
+        let config = PollerThreadConfig::new()
+            .with_jitter(Duration::ZERO)
+            .with_initial_retry_backoff(Duration::from_secs(8));
+        let delays: Vec<_> = (0..100).map(|_| config.next_delay(1)).collect();
+        assert!(delays
+            .iter()
+            .all(|&delay| (Duration::from_secs(4)..=Duration::from_secs(8)).contains(&delay)));
+        assert!(delays.iter().any(|&delay| delay != delays[0]));
+
+        // Zero backoff doesn't retry in a tight loop.
+        let config = config.with_initial_retry_backoff(Duration::ZERO);
+        assert_eq!(config.next_delay(1), PollerThreadConfig::MIN_RETRY_BACKOFF);
+    }
+
+    #[test]
+    fn not_modified_response_keeps_stored_configuration() {
+        let server = TestServer::start(|request| {
+            if request.header("if-none-match").is_some() {
+                TestResponse::new(304)
+            } else {
+                TestResponse::new(200)
+                    .with_header("ETag", "\"v1\"")
+                    .with_body(UFC_JSON)
+            }
+        });
+        let fetcher = test_fetcher(&server);
+        let store = Arc::new(ConfigurationStore::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = {
+            let calls = calls.clone();
+            PollerThreadConfig::new()
+                .with_interval(Duration::from_millis(10))
+                .with_jitter(Duration::ZERO)
+                .with_on_configuration_change(move |_| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                })
+        };
+        let poller = PollerThread::start_with_config(fetcher, store.clone(), config).unwrap();
+
+        poller.wait_for_configuration().unwrap();
+        let first = store.get_configuration().unwrap();
+
+        // Wait for a few more polls answered with 304 Not Modified.
+        assert_eq!(server.next_request().header("if-none-match"), None);
+        for _ in 0..3 {
+            let request = server.next_request();
+            assert_eq!(
+                request.path.split('?').next(),
+                Some("/api/flag-config/v1/config")
+            );
+            assert_eq!(request.header("if-none-match"), Some("\"v1\""));
+        }
+        poller.stop();
+
+        let current = store.get_configuration().unwrap();
+        assert!(Arc::ptr_eq(&first, &current));
+        assert_eq!(current.fetched_at, first.fetched_at);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(poller.status(), PollerStatus::Ready { .. }));
+    }
+
+    fn test_fetcher(server: &TestServer) -> ConfigurationFetcher {
+        ConfigurationFetcher::new(server.fetcher_config())
+    }
+
+    #[test]
+    fn bandit_models_are_refreshed_every_n_cycles() {
+        let flag_requests = Arc::new(AtomicUsize::new(0));
+        let bandit_requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let flag_requests = flag_requests.clone();
+            let bandit_requests = bandit_requests.clone();
+            TestServer::start(move |request| {
+                if request.path.contains("/bandits") {
+                    bandit_requests.fetch_add(1, Ordering::SeqCst);
+                    return TestResponse::new(200).with_body(BANDIT_MODELS_JSON);
+                }
+                // Every response is newer, so it replaces the stored configuration.
+                let n = flag_requests.fetch_add(1, Ordering::SeqCst);
+                TestResponse::new(200).with_body(
+                    BANDIT_FLAGS_JSON
+                        .replace("2024-07-18T00:00:00Z", &format!("2024-07-18T00:00:{n:02}Z")),
+                )
+            })
+        };
+        let store = Arc::new(ConfigurationStore::new());
+        let poller = PollerThread::start_with_config(
+            test_fetcher(&server),
+            store.clone(),
+            PollerThreadConfig::new()
+                .with_interval(Duration::from_secs(3600))
+                .with_bandit_poll_every_n_cycles(3)
+                .unwrap(),
+        )
+        .unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(bandit_requests.load(Ordering::SeqCst), 1);
+
+        let evaluator = Evaluator::new(EvaluatorConfig {
+            configuration_store: store.clone(),
+            sdk_metadata: SDK_METADATA,
+            clock: Arc::new(SystemClock),
+            observer: None,
+            sharder: None,
+            default_attributes: Arc::default(),
+            coerce_numeric_types: false,
+            bandit_evaluation_options: Default::default(),
+        });
+        let actions = HashMap::from([(Str::from("action"), ContextAttributes::default())]);
+        let get_bandit_action = || {
+            evaluator.get_bandit_action(
+                "banner-bandit-flag",
+                &"subject".into(),
+                &ContextAttributes::default(),
+                &actions,
+                None,
+                &"default".into(),
+            )
+        };
+
+        for _ in 0..2 {
+            poller.poll_now().unwrap();
+            // Bandit models of the first fetch are carried forward to the new configuration.
+            let result = get_bandit_action();
+            assert_eq!(result.variation, Str::from("banner-bandit"));
+            assert_eq!(result.action.as_deref(), Some("action"));
+        }
+        assert_eq!(flag_requests.load(Ordering::SeqCst), 3);
+        assert_eq!(bandit_requests.load(Ordering::SeqCst), 1);
+
+        poller.poll_now().unwrap();
+        assert_eq!(flag_requests.load(Ordering::SeqCst), 4);
+        assert_eq!(bandit_requests.load(Ordering::SeqCst), 2);
+        assert_eq!(get_bandit_action().action.as_deref(), Some("action"));
+
+        poller.shutdown().unwrap();
+    }
+
+    #[test]
+    fn due_bandit_refresh_survives_not_modified_fetch() {
+        let flag_requests = Arc::new(AtomicUsize::new(0));
+        let bandit_requests = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let flag_requests = flag_requests.clone();
+            let bandit_requests = bandit_requests.clone();
+            TestServer::start(move |request| {
+                if request.path.contains("/bandits") {
+                    bandit_requests.fetch_add(1, Ordering::SeqCst);
+                    return TestResponse::new(200).with_body(BANDIT_MODELS_JSON);
+                }
+                let n = flag_requests.fetch_add(1, Ordering::SeqCst);
+                // Flags configuration is unchanged when the bandit refresh is due.
+                if n == 2 {
+                    return TestResponse::new(304);
+                }
+                TestResponse::new(200).with_body(
+                    BANDIT_FLAGS_JSON
+                        .replace("2024-07-18T00:00:00Z", &format!("2024-07-18T00:00:{n:02}Z")),
+                )
+            })
+        };
+        let poller = PollerThread::start_with_config(
+            test_fetcher(&server),
+            Arc::new(ConfigurationStore::new()),
+            PollerThreadConfig::new()
+                .with_interval(Duration::from_secs(3600))
+                .with_bandit_poll_every_n_cycles(2)
+                .unwrap(),
+        )
+        .unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(bandit_requests.load(Ordering::SeqCst), 1);
+
+        // Not due: bandit models are reused.
+        poller.poll_now().unwrap();
+        assert_eq!(bandit_requests.load(Ordering::SeqCst), 1);
+        // Due but not modified: there's nothing to attach bandit models to.
+        poller.poll_now().unwrap();
+        assert_eq!(bandit_requests.load(Ordering::SeqCst), 1);
+        // Not due anymore but the refresh is still pending.
+        poller.poll_now().unwrap();
+        assert_eq!(flag_requests.load(Ordering::SeqCst), 4);
+        assert_eq!(bandit_requests.load(Ordering::SeqCst), 2);
+
+        poller.shutdown().unwrap();
+    }
+
+    /// Flags configuration with a flag that always assigns `banner-bandit` bandit.
+    const BANDIT_FLAGS_JSON: &str = r#"{
+      "createdAt": "2024-07-18T00:00:00Z",
+      "format": "SERVER",
+      "environment": {"name": "test"},
+      "flags": {
+        "banner-bandit-flag": {
+          "key": "banner-bandit-flag",
+          "enabled": true,
+          "variationType": "STRING",
+          "totalShards": 10000,
+          "variations": {"banner-bandit": {"key": "banner-bandit", "value": "banner-bandit"}},
+          "allocations": [{
+            "key": "everyone",
+            "rules": [],
+            "splits": [{"variationKey": "banner-bandit", "shards": []}],
+            "doLog": true
+          }]
+        }
+      },
+      "bandits": {
+        "banner-bandit": [{
+          "key": "banner-bandit",
+          "flagKey": "banner-bandit-flag",
+          "variationKey": "banner-bandit",
+          "variationValue": "banner-bandit"
+        }]
+      }
+    }"#;
+
+    const BANDIT_MODELS_JSON: &str = r#"{
+      "updatedAt": "2024-07-18T00:00:00Z",
+      "bandits": {
+        "banner-bandit": {
+          "banditKey": "banner-bandit",
+          "modelName": "falcon",
+          "modelVersion": "v1",
+          "updatedAt": "2024-07-18T00:00:00Z",
+          "modelData": {
+            "gamma": 1.0,
+            "defaultActionScore": 0.0,
+            "actionProbabilityFloor": 0.0,
+            "coefficients": {}
+          }
+        }
+      }
+    }"#;
+
+    #[test]
+    fn shutdown_cancels_in_flight_fetch() {
+        let (received, received_receiver) = std::sync::mpsc::channel();
+        // The server never responds within the test.
+        let server = TestServer::start(move |_| {
+            let _ = received.send(());
+            std::thread::sleep(Duration::from_secs(60));
+            TestResponse::new(503)
+        });
+        let config = ConfigurationFetcherConfig {
+            request_timeout: Duration::from_secs(60),
+            ..server.fetcher_config()
+        };
+        let poller = PollerThread::start(
+            ConfigurationFetcher::new(config),
+            Arc::new(ConfigurationStore::new()),
+        )
+        .unwrap();
+        received_receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("fetch should start");
+
+        let started = std::time::Instant::now();
+        poller.shutdown().unwrap();
+
+        assert!(
+            started.elapsed() < Duration::from_secs(1),
+            "shutdown took {:?}",
+            started.elapsed()
+        );
+    }
+
+    #[test]
+    fn serves_persisted_configuration_before_first_fetch() {
+        let server = TestServer::start(|_| TestResponse::new(503));
+        let fetched_at = Utc::now() - chrono::Duration::hours(1);
+        let persisted = PersistedConfiguration::new(
+            &Configuration {
+                fetched_at,
+                ..Configuration::from_ufc_json_for_tests(UFC_JSON)
+            },
+            Some("\"v1\"".to_owned()),
+            None,
+        );
+        let persistence = Arc::new(MemoryPersistence::default());
+        *persistence.0.lock().unwrap() = Some(persisted.to_bytes().unwrap());
+        let store = Arc::new(ConfigurationStore::new());
+
+        let poller = PollerThread::start_with_config(
+            test_fetcher(&server),
+            store.clone(),
+            PollerThreadConfig::new().with_persistence(persistence),
+        )
+        .unwrap();
+
+        assert_eq!(store.get_configuration().unwrap().fetched_at, fetched_at);
+        assert!(poller
+            .wait_for_configuration_timeout(Duration::ZERO)
+            .is_ok());
+        // Cache validators are restored too.
+        assert_eq!(
+            server.next_request().header("if-none-match"),
+            Some("\"v1\"")
+        );
+    }
+
+    #[test]
+    fn ignores_corrupt_persisted_configuration() {
+        let server = TestServer::start(|_| TestResponse::new(200).with_body(UFC_JSON));
+        let persistence = Arc::new(MemoryPersistence(Mutex::new(Some(b"{}".to_vec()))));
+        let store = Arc::new(ConfigurationStore::new());
+
+        let poller = PollerThread::start_with_config(
+            test_fetcher(&server),
+            store.clone(),
+            PollerThreadConfig::new().with_persistence(persistence),
+        )
+        .unwrap();
+
+        assert!(store.get_configuration().is_none());
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert!(store.get_configuration().is_some());
+    }
+
+    #[test]
+    fn persists_fetched_configuration() {
+        let server = TestServer::start(|_| {
+            TestResponse::new(200)
+                .with_header("ETag", "\"v1\"")
+                .with_body(UFC_JSON)
+        });
+        let persistence = Arc::new(MemoryPersistence::default());
+
+        let poller = PollerThread::start_with_config(
+            test_fetcher(&server),
+            Arc::new(ConfigurationStore::new()),
+            PollerThreadConfig::new().with_persistence(persistence.clone()),
+        )
+        .unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+
+        let bytes = persistence.0.lock().unwrap().clone().unwrap();
+        let persisted = PersistedConfiguration::from_bytes(&bytes).unwrap();
+        assert_eq!(persisted.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(persisted.flags, UFC_JSON.as_bytes());
+    }
+
+    /// A source that replays scripted fetch results and reports "not modified" afterwards.
+    struct ScriptedSource {
+        script: VecDeque<Result<FetchResult>>,
+        fetches: Arc<AtomicUsize>,
+        /// How long every fetch takes.
+        delay: Duration,
+    }
+
+    impl ScriptedSource {
+        fn new(script: impl IntoIterator<Item = Result<FetchResult>>) -> ScriptedSource {
+            ScriptedSource {
+                script: script.into_iter().collect(),
+                fetches: Arc::new(AtomicUsize::new(0)),
+                delay: Duration::ZERO,
+            }
+        }
+
+        fn with_delay(mut self, delay: Duration) -> ScriptedSource {
+            self.delay = delay;
+            self
+        }
+    }
+
+    impl ConfigurationSource for ScriptedSource {
+        fn sdk_metadata(&self) -> SdkMetadata {
+            SDK_METADATA
+        }
+
+        async fn fetch(&mut self) -> Result<FetchResult> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.script
+                .pop_front()
+                .unwrap_or(Ok(FetchResult::NotModified))
+        }
+    }
+
+    fn ufc_configuration() -> Configuration {
+        Configuration::from_ufc_json_for_tests(UFC_JSON)
+    }
+
+    #[test]
+    fn polls_custom_configuration_source() {
+        let source = ScriptedSource::new([
+            Err(Error::from(std::io::Error::other("socket closed"))),
+            Ok(FetchResult::Modified(Box::new(ufc_configuration()))),
+        ]);
+        let fetches = source.fetches.clone();
+        let store = Arc::new(ConfigurationStore::new());
+        let config = PollerThreadConfig::new()
+            .with_interval(Duration::from_millis(10))
+            .with_jitter(Duration::ZERO)
+            .with_initial_retry_backoff(Duration::from_millis(10));
+
+        let poller = PollerThread::start_with_config(source, store.clone(), config).unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+
+        assert_eq!(
+            store.get_configuration().unwrap().flags.to_json(),
+            UFC_JSON.as_bytes()
+        );
+        // Keeps polling after the script is exhausted.
+        while fetches.load(Ordering::SeqCst) < 4 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        poller.shutdown().unwrap();
+        assert!(matches!(poller.status(), PollerStatus::Ready { .. }));
+        assert_eq!(poller.stats().consecutive_failures, 0);
+    }
+
+    #[test]
+    fn source_panic_is_reported() {
+        struct PanickingSource;
+
+        impl ConfigurationSource for PanickingSource {
+            fn sdk_metadata(&self) -> SdkMetadata {
+                SDK_METADATA
+            }
+
+            async fn fetch(&mut self) -> Result<FetchResult> {
+                panic!("source panicked")
+            }
+        }
+
+        let poller =
+            PollerThread::start(PanickingSource, Arc::new(ConfigurationStore::new())).unwrap();
+
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::from_secs(10)),
+            Err(Error::PollerThreadPanicked)
+        ));
+        assert!(matches!(
+            poller.shutdown(),
+            Err(Error::PollerThreadPanicked)
+        ));
+    }
+
+    /// Describes `state` for comparison in tests.
+    fn state_name(state: &PollerState) -> String {
+        match state {
+            PollerState::Starting => "starting".to_owned(),
+            PollerState::Healthy => "healthy".to_owned(),
+            PollerState::Degraded {
+                consecutive_failures,
+            } => format!("degraded({consecutive_failures})"),
+            PollerState::Failed { .. } => "failed".to_owned(),
+        }
+    }
+
+    /// Start a poller for `source` and return it with a log of its state changes.
+    fn start_recording_states(source: ScriptedSource) -> (PollerThread, Arc<Mutex<Vec<String>>>) {
+        let states = Arc::new(Mutex::new(Vec::new()));
+        let config = {
+            let states = states.clone();
+            PollerThreadConfig::new()
+                .with_interval(Duration::from_millis(10))
+                .with_jitter(Duration::ZERO)
+                .with_initial_retry_backoff(Duration::from_millis(1))
+                .with_on_state_change(move |state| states.lock().unwrap().push(state_name(state)))
+        };
+        let poller =
+            PollerThread::start_with_config(source, Arc::new(ConfigurationStore::new()), config)
+                .unwrap();
+        (poller, states)
+    }
+
+    #[test]
+    fn poller_state_recovers_after_transient_failures() {
+        let source = ScriptedSource::new([
+            Err(Error::from(std::io::Error::other("socket closed"))),
+            Err(Error::from(std::io::Error::other("socket closed"))),
+            Ok(FetchResult::Modified(Box::new(ufc_configuration()))),
+            Err(Error::from(std::io::Error::other("socket closed"))),
+        ]);
+        let fetches = source.fetches.clone();
+        let (poller, states) = start_recording_states(source);
+
+        while fetches.load(Ordering::SeqCst) < 5 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        poller.shutdown().unwrap();
+
+        assert_eq!(
+            *states.lock().unwrap(),
+            [
+                "degraded(1)",
+                "degraded(2)",
+                "healthy",
+                "degraded(1)",
+                "healthy"
+            ]
+        );
+        assert!(matches!(poller.state(), PollerState::Healthy));
+    }
+
+    #[test]
+    fn poller_state_fails_on_unrecoverable_error() {
+        let (poller, states) = start_recording_states(ScriptedSource::new([
+            Err(Error::from(std::io::Error::other("socket closed"))),
+            Err(Error::Unauthorized),
+        ]));
+        assert!(matches!(
+            poller.state(),
+            PollerState::Starting | PollerState::Degraded { .. }
+        ));
+
+        let _ = poller.wait_for_configuration_timeout(Duration::from_secs(10));
+        poller.shutdown().unwrap();
+
+        assert_eq!(*states.lock().unwrap(), ["degraded(1)", "failed"]);
+        assert!(matches!(
+            poller.state(),
+            PollerState::Failed {
+                error: Error::Unauthorized
+            }
+        ));
+    }
+
+    #[test]
+    fn error_callback_is_invoked_for_every_failed_fetch() {
+        let source = ScriptedSource::new([
+            Err(Error::from(std::io::Error::other("socket closed"))),
+            Err(Error::from(std::io::Error::other("socket closed"))),
+            Ok(FetchResult::Modified(Box::new(ufc_configuration()))),
+            Err(Error::Unauthorized),
+        ]);
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let config = {
+            let errors = errors.clone();
+            PollerThreadConfig::new()
+                .with_interval(Duration::from_millis(10))
+                .with_jitter(Duration::ZERO)
+                .with_initial_retry_backoff(Duration::from_millis(1))
+                .with_on_error(move |err| {
+                    errors.lock().unwrap().push(err.is_retriable());
+                    // Panics don't stop the poller.
+                    panic!("error callback panicked");
+                })
+        };
+        let poller =
+            PollerThread::start_with_config(source, Arc::new(ConfigurationStore::new()), config)
+                .unwrap();
+
+        while !matches!(poller.state(), PollerState::Failed { .. }) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        poller.shutdown().unwrap();
+
+        assert_eq!(*errors.lock().unwrap(), [true, true, false]);
+        assert!(matches!(
+            poller.state(),
+            PollerState::Failed {
+                error: Error::Unauthorized
+            }
+        ));
+    }
+
+    #[test]
+    fn poller_state_is_terminal_once_failed() {
+        let mut status = PollerStatus::Pending;
+        status.record_failure(Error::from(std::io::Error::other("socket closed")));
+        assert!(matches!(
+            status.state(),
+            PollerState::Degraded {
+                consecutive_failures: 1
+            }
+        ));
+
+        status.record_failure(Error::Unauthorized);
+        assert!(matches!(status.state(), PollerState::Failed { .. }));
+        status.record_success();
+        assert!(matches!(status.state(), PollerState::Failed { .. }));
+    }
+
+    #[test]
+    fn poll_now_fetches_immediately() {
+        let source = ScriptedSource::new([
+            Ok(FetchResult::Modified(Box::new(ufc_configuration()))),
+            Err(Error::from(std::io::Error::other("socket closed"))),
+        ]);
+        let fetches = source.fetches.clone();
+        let poller = PollerThread::start_with_config(
+            source,
+            Arc::new(ConfigurationStore::new()),
+            PollerThreadConfig::new().with_interval(Duration::from_secs(3600)),
+        )
+        .unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        assert!(matches!(poller.poll_now(), Err(Error::Io(_))));
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        poller.poll_now().unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+
+        poller.shutdown().unwrap();
+        assert!(matches!(poller.poll_now(), Err(Error::PollerStopped)));
+    }
+
+    #[test]
+    fn watch_publishes_stored_configuration() {
+        let store = Arc::new(ConfigurationStore::new());
+        let poller = PollerThread::start_with_config(
+            ScriptedSource::new([Ok(FetchResult::Modified(Box::new(ufc_configuration())))]),
+            store.clone(),
+            PollerThreadConfig::new().with_interval(Duration::from_secs(3600)),
+        )
+        .unwrap();
+        let mut receiver = poller.watch();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let published = runtime.block_on(async {
+            tokio::time::timeout(
+                Duration::from_secs(10),
+                receiver.wait_for(|configuration| configuration.is_some()),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .clone()
+            .unwrap()
+        });
+        assert!(Arc::ptr_eq(&published, &store.get_configuration().unwrap()));
+
+        // New subscribers see the latest configuration immediately.
+        assert!(poller.watch().borrow().is_some());
+
+        poller.shutdown().unwrap();
+        // This is the store's channel, so it publishes configuration set on the store directly.
+        store.set_configuration(Arc::new(ufc_configuration()));
+        assert!(receiver.has_changed().unwrap());
+        receiver.borrow_and_update();
+
+        drop(poller);
+        drop(store);
+        assert!(runtime.block_on(receiver.changed()).is_err());
+    }
+
+    #[test]
+    fn watch_publishes_configuration_for_stores_without_subscriptions() {
+        let path = std::env::temp_dir().join(format!(
+            "eppo-{}-poller-watch-file-store.json",
+            std::process::id()
+        ));
+        let poller = PollerThread::start_with_config(
+            ScriptedSource::new([Ok(FetchResult::Modified(Box::new(ufc_configuration())))]),
+            Arc::new(FileConfigurationStore::new(&path, SDK_METADATA)),
+            PollerThreadConfig::new().with_interval(Duration::from_secs(3600)),
+        )
+        .unwrap();
+        let mut receiver = poller.watch();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            tokio::time::timeout(
+                Duration::from_secs(10),
+                receiver.wait_for(|configuration| configuration.is_some()),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        });
+
+        poller.shutdown().unwrap();
+        drop(poller);
+        // The poller has dropped the sender.
+        assert!(runtime.block_on(receiver.changed()).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn concurrent_poll_now_calls_share_a_fetch() {
+        let source = ScriptedSource::new([]).with_delay(Duration::from_millis(500));
+        let fetches = source.fetches.clone();
+        let poller = Arc::new(
+            PollerThread::start_with_config(
+                source,
+                Arc::new(ConfigurationStore::new()),
+                PollerThreadConfig::new().with_interval(Duration::from_secs(3600)),
+            )
+            .unwrap(),
+        );
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+
+        let first = {
+            let poller = poller.clone();
+            std::thread::spawn(move || poller.poll_now())
+        };
+        while fetches.load(Ordering::SeqCst) < 2 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        // The second fetch is in flight, so these calls wait for the third one together.
+        let callers: Vec<_> = (0..5)
+            .map(|_| {
+                let poller = poller.clone();
+                std::thread::spawn(move || poller.poll_now())
+            })
+            .collect();
+        first.join().unwrap().unwrap();
+        for caller in callers {
+            caller.join().unwrap().unwrap();
+        }
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+        poller.shutdown().unwrap();
+    }
+
+    #[test]
+    fn poller_shares_configuration_through_file_store() {
+        let path = std::env::temp_dir().join(format!(
+            "eppo-{}-poller-file-store.json",
+            std::process::id()
+        ));
+        let poller = PollerThread::start(
+            ScriptedSource::new([Ok(FetchResult::Modified(Box::new(ufc_configuration())))]),
+            Arc::new(FileConfigurationStore::new(&path, SDK_METADATA)),
+        )
+        .unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        poller.shutdown().unwrap();
+
+        // Another process would only read configuration.
+        let reader = FileConfigurationStore::new(&path, SDK_METADATA);
+        assert_eq!(reader.get().unwrap().flags.to_json(), UFC_JSON.as_bytes());
+        // Configuration with the same timestamp is not written again.
+        assert!(!reader.set_if_newer(Arc::new(ufc_configuration())));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn custom_configuration_source_is_persisted() {
+        let persistence = Arc::new(MemoryPersistence::default());
+        let config = PollerThreadConfig::new().with_persistence(persistence.clone());
+
+        let poller = PollerThread::start_with_config(
+            ScriptedSource::new([Ok(FetchResult::Modified(Box::new(ufc_configuration())))]),
+            Arc::new(ConfigurationStore::new()),
+            config.clone(),
+        )
+        .unwrap();
+        poller
+            .wait_for_configuration_timeout(Duration::from_secs(10))
+            .unwrap();
+        poller.shutdown().unwrap();
+
+        // A source that never succeeds still serves the persisted configuration.
+        let store = Arc::new(ConfigurationStore::new());
+        let _poller = PollerThread::start_with_config(
+            ScriptedSource::new([Err(Error::Unauthorized)]),
+            store.clone(),
+            config,
+        )
+        .unwrap();
+        assert_eq!(
+            store.get_configuration().unwrap().flags.to_json(),
+            UFC_JSON.as_bytes()
+        );
+    }
 }
 
 #[cfg(test)]
 mod jitter_tests {
     use std::time::Duration;
 
+    use super::{Jitter, PollerThreadConfig};
+    use crate::Error;
+
     #[test]
     fn jitter_is_subtractive() {
         let interval = Duration::from_secs(30);
@@ -329,4 +2741,56 @@ mod jitter_tests {
 
         assert_eq!(result, Duration::from_secs(30));
     }
+
+    #[test]
+    fn percent_jitter_scales_with_interval() {
+        for interval in [Duration::from_secs(5), Duration::from_secs(600)] {
+            for _ in 0..100 {
+                let result = super::jitter(interval, Jitter::Percent(0.1));
+
+                assert!(result <= interval, "{result:?} must be <= {interval:?}");
+                assert!(
+                    result >= interval.mul_f64(0.9),
+                    "{result:?} must be >= 90% of {interval:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn percent_jitter_works_with_zero_interval() {
+        assert_eq!(
+            super::jitter(Duration::ZERO, Jitter::Percent(0.5)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn percent_jitter_is_clamped() {
+        let interval = Duration::from_secs(30);
+
+        assert_eq!(super::jitter(interval, Jitter::Percent(0.0)), interval);
+        assert_eq!(super::jitter(interval, Jitter::Percent(-1.0)), interval);
+        assert_eq!(super::jitter(interval, Jitter::Percent(f64::NAN)), interval);
+        assert!(super::jitter(interval, Jitter::Percent(2.0)) <= interval);
+    }
+
+    #[test]
+    fn jitter_percent_is_validated() {
+        assert!(PollerThreadConfig::new().with_jitter_percent(0.1).is_ok());
+        assert!(PollerThreadConfig::new().with_jitter_percent(1.0).is_ok());
+        for percent in [-0.1, 1.5, f64::NAN] {
+            assert!(matches!(
+                PollerThreadConfig::new().with_jitter_percent(percent),
+                Err(Error::InvalidConfiguration(_))
+            ));
+        }
+        assert_eq!(
+            PollerThreadConfig::new()
+                .with_jitter_percent(0.25)
+                .unwrap()
+                .jitter_percent,
+            Some(0.25)
+        );
+    }
 }
@@ -6,11 +6,16 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::eval::eval_details::{AllocationEvaluationCode, EvaluationDetails, FlagEvaluationCode};
 use crate::obfuscation::{Base64Str, Md5HashedStr};
 use crate::timestamp::Timestamp;
 use crate::ufc::{Assignment, ConfigurationFormat, Environment, ValueWire, VariationType};
 use crate::{CategoricalAttribute, NumericAttribute, Str};
 
+/// Assignments and bandit actions precomputed for a single subject.
+///
+/// Client SDKs expect the obfuscated version. Use [`PrecomputedConfiguration::obfuscate()`] or
+/// [`PrecomputedConfiguration::to_obfuscated_json()`] before sending it over the wire.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PrecomputedConfiguration {
@@ -39,6 +44,37 @@ pub(crate) struct PrecomputedAssignment {
     pub(crate) variation_key: Option<Str>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) extra_logging: Option<HashMap<String, String>>,
+    /// Only present if configuration was precomputed with details. Never sent to client SDKs, as
+    /// obfuscated configuration omits it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) details: Option<PrecomputedAssignmentDetails>,
+}
+
+/// Compact evaluation details of a precomputed assignment, useful to debug why it differs from
+/// server-side evaluation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PrecomputedAssignmentDetails {
+    pub(crate) flag_evaluation_code: Option<FlagEvaluationCode>,
+    /// Key of the matched allocation.
+    pub(crate) allocation_key: Option<Str>,
+    pub(crate) variation_key: Option<Str>,
+}
+
+impl From<&EvaluationDetails> for PrecomputedAssignmentDetails {
+    fn from(details: &EvaluationDetails) -> PrecomputedAssignmentDetails {
+        PrecomputedAssignmentDetails {
+            flag_evaluation_code: details.flag_evaluation_code,
+            allocation_key: details
+                .allocations
+                .iter()
+                .find(|allocation| {
+                    allocation.allocation_evaluation_code == AllocationEvaluationCode::Match
+                })
+                .map(|allocation| allocation.key.clone()),
+            variation_key: details.variation_key.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,6 +99,7 @@ impl From<Assignment> for PrecomputedAssignment {
                 allocation_key: Some(event.base.allocation.clone()),
                 variation_key: Some(event.base.variation.clone()),
                 extra_logging: Some(event.base.extra_logging.clone()),
+                details: None,
             },
             None => PrecomputedAssignment {
                 variation_type: assignment.value.variation_type(),
@@ -71,11 +108,53 @@ impl From<Assignment> for PrecomputedAssignment {
                 allocation_key: None,
                 variation_key: None,
                 extra_logging: None,
+                details: None,
             },
         }
     }
 }
 
+/// Obfuscated version of [`PrecomputedConfiguration`] in the wire format expected by client SDKs.
+///
+/// Serialized as JSON, this is the response of the `/precomputed` endpoint:
+///
+/// ```json
+/// {
+///   "obfuscated": true,
+///   "format": "PRECOMPUTED",
+///   "salt": "c29kaXVtY2hsb3JpZGU",
+///   "createdAt": "2024-11-18T14:23:39.456Z",
+///   "environment": {"name": "Test"},
+///   "flags": {
+///     "<md5(salt + flag_key)>": {
+///       "variationType": "STRING",
+///       "variationValue": "<base64(value)>",
+///       "doLog": true,
+///       "allocationKey": "<base64(allocation_key)>",
+///       "variationKey": "<base64(variation_key)>",
+///       "extraLogging": {"<base64(key)>": "<base64(value)>"}
+///     }
+///   },
+///   "bandits": {
+///     "<md5(salt + flag_key)>": {
+///       "<md5(salt + variation_value)>": {
+///         "banditKey": "<base64(bandit_key)>",
+///         "action": "<base64(action)>",
+///         "actionProbability": 0.5,
+///         "optimalityGap": 0.0,
+///         "modelVersion": "<base64(model_version)>",
+///         "actionNumericAttributes": {"<base64(key)>": "<base64(value)>"},
+///         "actionCategoricalAttributes": {"<base64(key)>": "<base64(value)>"}
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// Flag keys and bandit variation values are hex-encoded md5 hashes of the salt followed by the
+/// key. Other strings are base64-encoded; booleans and numbers are converted to strings first.
+/// `allocationKey`, `variationKey`, and `extraLogging` are omitted if `doLog` is false.
+/// `environment` is null if configuration was absent during evaluation.
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -123,19 +202,37 @@ pub(crate) struct ObfuscatedPrecomputedBandit {
 }
 
 impl PrecomputedConfiguration {
+    /// Obfuscate configuration, hashing flag keys and encoding values with a random salt.
     pub fn obfuscate(self) -> ObfuscatedPrecomputedConfiguration {
         self.into()
     }
+
+    /// Obfuscate configuration, hashing flag keys and encoding values with the provided `salt`.
+    ///
+    /// Prefer [`PrecomputedConfiguration::obfuscate()`], which generates a new random salt. A fixed
+    /// salt is useful for reproducible output (e.g., in tests or for caching responses).
+    pub fn obfuscate_with_salt(self, salt: &str) -> ObfuscatedPrecomputedConfiguration {
+        ObfuscatedPrecomputedConfiguration::new(self, salt.into())
+    }
+
+    /// Obfuscate configuration and serialize it to the JSON wire format expected by client SDKs.
+    pub fn to_obfuscated_json(self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.obfuscate())
+    }
 }
 
 impl From<PrecomputedConfiguration> for ObfuscatedPrecomputedConfiguration {
     fn from(config: PrecomputedConfiguration) -> Self {
         let salt: Str = {
             let bytes = rand::thread_rng().gen::<[u8; 16]>();
-            base64::prelude::BASE64_STANDARD_NO_PAD
-                .encode(&bytes)
-                .into()
+            base64::prelude::BASE64_STANDARD_NO_PAD.encode(bytes).into()
         };
+        ObfuscatedPrecomputedConfiguration::new(config, salt)
+    }
+}
+
+impl ObfuscatedPrecomputedConfiguration {
+    fn new(config: PrecomputedConfiguration, salt: Str) -> Self {
         ObfuscatedPrecomputedConfiguration {
             obfuscated: serde_bool::True,
             format: ConfigurationFormat::Precomputed,
@@ -168,6 +265,11 @@ impl From<PrecomputedConfiguration> for ObfuscatedPrecomputedConfiguration {
             salt,
         }
     }
+
+    /// Salt used for hashing flag keys and bandit variation values.
+    pub fn salt(&self) -> &str {
+        &self.salt
+    }
 }
 
 impl From<PrecomputedAssignment> for ObfuscatedPrecomputedAssignment {
@@ -235,6 +337,7 @@ mod tests {
                             .into_iter()
                             .collect(),
                     ),
+                    details: None,
                 },
             )]
             .into_iter()
@@ -252,4 +355,30 @@ mod tests {
             r#"{"variationType":"STRING","variationValue":"aGVsbG8sIHdvcmxkIQ==","doLog":true,"allocationKey":"YWxsb2NhdGlvbi1rZXk=","variationKey":"dmFyaWF0aW9uLWtleQ==","extraLogging":{"aGVsbG8=":"d29ybGQ="}}"#
         );
     }
+
+    /// Read the precomputed configuration embedded in a configuration wire fixture from
+    /// sdk-test-data.
+    fn wire_response(path: &str) -> serde_json::Value {
+        let wire: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+        serde_json::from_str(wire["precomputed"]["response"].as_str().unwrap()).unwrap()
+    }
+
+    /// Both fixtures come from sdk-test-data (`configuration-wire/`) and are produced by the JS
+    /// SDK: `precomputed-v1.json` is what client SDKs receive, and
+    /// `precomputed-v1-deobfuscated.json` holds the same assignments before obfuscation.
+    #[test]
+    fn obfuscation_matches_sdk_test_data() {
+        let obfuscated = wire_response("../sdk-test-data/configuration-wire/precomputed-v1.json");
+        let configuration: PrecomputedConfiguration = serde_json::from_value(wire_response(
+            "../sdk-test-data/configuration-wire/precomputed-v1-deobfuscated.json",
+        ))
+        .unwrap();
+
+        let salt = obfuscated["salt"].as_str().unwrap();
+        let result = configuration.obfuscate_with_salt(salt);
+
+        assert_eq!(result.salt(), salt);
+        assert_eq!(serde_json::to_value(&result).unwrap(), obfuscated);
+    }
 }
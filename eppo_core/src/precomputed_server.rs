@@ -0,0 +1,160 @@
+//! Framework-agnostic helpers to serve precomputed assignments to client SDKs.
+//!
+//! A precomputed assignments service parses a [`PrecomputedRequest`] from the request body,
+//! calls [`handle_precomputed_request()`], and responds with the returned [`PrecomputedResponse`]
+//! serialized as JSON. Both types match the format used by Eppo client SDKs, so services built on
+//! different HTTP frameworks can't diverge.
+//!
+//! ```
+//! # use eppo_core::precomputed_server::{handle_precomputed_request, PrecomputedRequest};
+//! # let configuration = None;
+//! let body = br#"{
+//!   "subject_key": "user-1",
+//!   "subject_attributes": {
+//!     "numericAttributes": {"age": 42},
+//!     "categoricalAttributes": {"country": "DE"}
+//!   }
+//! }"#;
+//! let request: PrecomputedRequest = serde_json::from_slice(body).unwrap();
+//! let response = handle_precomputed_request(configuration, request);
+//! let json = serde_json::to_string(&response).unwrap();
+//! ```
+//!
+//! This module is only available with the `server` feature.
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    eval::{get_precomputed_configuration, PrecomputeOptions},
+    precomputed::ObfuscatedPrecomputedConfiguration,
+    timestamp, Configuration, ContextAttributes, Str,
+};
+
+/// Request for precomputed assignments of a single subject, as sent by client SDKs.
+///
+/// Unlike most Eppo JSON, top-level fields are snake_case (`subject_key`, `subject_attributes`,
+/// `bandit_actions`), while attributes use camelCase (`numericAttributes`,
+/// `categoricalAttributes`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrecomputedRequest {
+    pub subject_key: Str,
+    #[serde(default)]
+    pub subject_attributes: ContextAttributes,
+    /// Actions available to the subject, keyed by bandit flag key. Bandits are only precomputed
+    /// for flags listed here.
+    #[serde(default)]
+    pub bandit_actions:
+        HashMap</* flag_key: */ Str, HashMap</* action_key: */ Str, ContextAttributes>>,
+}
+
+/// Response to a [`PrecomputedRequest`]: obfuscated precomputed configuration in the format
+/// expected by client SDKs.
+pub type PrecomputedResponse = ObfuscatedPrecomputedConfiguration;
+
+/// Precompute assignments for the subject of `request` using `configuration`.
+///
+/// If `configuration` is `None` (e.g., it hasn't been fetched yet), the response has no flags.
+pub fn handle_precomputed_request(
+    configuration: Option<&Configuration>,
+    request: PrecomputedRequest,
+) -> PrecomputedResponse {
+    get_precomputed_configuration(
+        configuration,
+        &request.subject_key,
+        &Arc::new(request.subject_attributes),
+        &request.bandit_actions,
+        timestamp::now(),
+        &PrecomputeOptions::default(),
+    )
+    .obfuscate()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{obfuscation::Md5HashedStr, Configuration, Str};
+
+    use super::{handle_precomputed_request, PrecomputedRequest};
+
+    fn configuration() -> Configuration {
+        let json = br#"{
+          "createdAt": "2024-07-18T00:00:00Z",
+          "format": "SERVER",
+          "environment": {"name": "Test"},
+          "flags": {
+            "adults-only": {
+              "key": "adults-only",
+              "enabled": true,
+              "variationType": "BOOLEAN",
+              "totalShards": 10000,
+              "variations": {"on": {"key": "on", "value": true}},
+              "allocations": [{
+                "key": "adults",
+                "rules": [{"conditions": [{"attribute": "age", "operator": "GTE", "value": 18}]}],
+                "splits": [{"variationKey": "on", "shards": []}],
+                "doLog": true
+              }]
+            }
+          }
+        }"#;
+        Configuration::from_ufc_json_for_tests(json)
+    }
+
+    fn request(body: &str) -> PrecomputedRequest {
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn parses_client_request_format() {
+        // Request body in the format sent by Eppo client SDKs.
+        let request: PrecomputedRequest =
+            serde_json::from_slice(include_bytes!("../tests/fixtures/precomputed-request.json"))
+                .unwrap();
+
+        assert_eq!(request.subject_key, Str::from("user-1"));
+        assert!(request.subject_attributes.numeric.contains_key("age"));
+        assert!(request
+            .subject_attributes
+            .categorical
+            .contains_key("country"));
+        assert!(request.bandit_actions["bandit-flag"].contains_key("nike"));
+    }
+
+    #[test]
+    fn responds_with_obfuscated_assignments() {
+        let configuration = configuration();
+
+        let adult = handle_precomputed_request(
+            Some(&configuration),
+            request(
+                r#"{"subject_key": "user-1", "subject_attributes": {"numericAttributes": {"age": 42}, "categoricalAttributes": {}}}"#,
+            ),
+        );
+        let child = handle_precomputed_request(
+            Some(&configuration),
+            request(r#"{"subject_key": "user-2"}"#),
+        );
+
+        let flag_key = Md5HashedStr::new(adult.salt().as_bytes(), b"adults-only");
+        let json = serde_json::to_value(&adult).unwrap();
+        assert_eq!(json["obfuscated"], true);
+        assert_eq!(json["format"], "PRECOMPUTED");
+        assert!(
+            json["flags"][serde_json::to_value(&flag_key).unwrap().as_str().unwrap()].is_object()
+        );
+        assert_eq!(
+            serde_json::to_value(&child).unwrap()["flags"],
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn responds_without_flags_before_configuration_is_fetched() {
+        let response = handle_precomputed_request(None, request(r#"{"subject_key": "user-1"}"#));
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["flags"],
+            serde_json::json!({})
+        );
+    }
+}
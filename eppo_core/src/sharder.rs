@@ -1,6 +1,62 @@
 //! Sharder implementation.
+//!
+//! Subjects are assigned to shards by hashing their keys. Eppo uses md5 by default
+//! ([`Md5Sharder`]), but evaluation accepts a custom [`Sharder`] (e.g., to reproduce assignments
+//! made by another system).
+//!
+//! # Assignment compatibility
+//!
+//! All Eppo SDKs (including client SDKs evaluating obfuscated configuration) use md5, so the same
+//! subject gets the same variation and the same bandit action order everywhere. Any other sharder
+//! maps subjects to different shards: almost every subject of a flag with traffic splits moves to
+//! a different split, and bandit actions are shuffled differently.
+//!
+//! A faster sharder (e.g., `Xxh3Sharder` with the `xxhash` feature) is therefore only safe for
+//! deployments where this SDK is the only one evaluating the flags (e.g., a Rust service that also
+//! serves precomputed assignments to its clients). Switching the sharder of a running deployment
+//! reassigns subjects of all running experiments, so it should be done between experiments.
 use md5;
 
+/// Maps evaluation inputs to shards.
+///
+/// Sharders are used to select splits for subjects and to shuffle bandit actions. Implementations
+/// must be deterministic: the same input must always produce the same shard.
+pub trait Sharder: Send + Sync {
+    /// Compute shard in `0..total_shards` for the concatenation of `input` segments.
+    fn shard(&self, input: &[&str], total_shards: u32) -> u32;
+}
+
+/// The default sharder: big-endian value of the first 4 bytes of md5 hash, modulo total shards.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Md5Sharder;
+
+impl Sharder for Md5Sharder {
+    fn shard(&self, input: &[&str], total_shards: u32) -> u32 {
+        get_md5_shard(input, total_shards)
+    }
+}
+
+/// A faster non-cryptographic sharder: 64-bit xxh3 hash, modulo total shards.
+///
+/// Produces assignments that differ from other Eppo SDKs. See [assignment
+/// compatibility](self#assignment-compatibility) before using it.
+///
+/// Requires the `xxhash` feature.
+#[cfg(feature = "xxhash")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Xxh3Sharder;
+
+#[cfg(feature = "xxhash")]
+impl Sharder for Xxh3Sharder {
+    fn shard(&self, input: &[&str], total_shards: u32) -> u32 {
+        let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+        for i in input {
+            hasher.update(i.as_bytes());
+        }
+        (hasher.digest() % u64::from(total_shards)) as u32
+    }
+}
+
 /// A sharder that has part of its hash pre-computed with the given salt.
 #[derive(Clone)]
 pub struct PreSaltedSharder {
@@ -17,6 +73,10 @@ impl PreSaltedSharder {
         PreSaltedSharder { ctx, total_shards }
     }
 
+    pub fn total_shards(&self) -> u32 {
+        self.total_shards
+    }
+
     pub fn shard(&self, input: &[impl AsRef<[u8]>]) -> u32 {
         let mut ctx = self.ctx.clone();
         for i in input {
@@ -52,3 +112,48 @@ pub fn get_md5_shard(input: &[impl AsRef<[u8]>], total_shards: u32) -> u32 {
     let value = u32::from_be_bytes(hash[0..4].try_into().unwrap());
     value % total_shards
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{get_md5_shard, Md5Sharder, PreSaltedSharder, Sharder};
+
+    #[test]
+    fn md5_sharder_matches_pre_salted_sharder() {
+        let pre_salted = PreSaltedSharder::new(&["salt", "-"], 10_000);
+        for subject_key in ["alice", "bob", "charlie", ""] {
+            assert_eq!(
+                Md5Sharder.shard(&["salt", "-", subject_key], 10_000),
+                pre_salted.shard(&[subject_key])
+            );
+        }
+        assert_eq!(
+            Md5Sharder.shard(&["flag", "-", "alice"], 10_000),
+            get_md5_shard(&["flag-alice"], 10_000)
+        );
+    }
+
+    #[cfg(feature = "xxhash")]
+    #[test]
+    fn xxh3_sharder_hashes_concatenated_input() {
+        use super::Xxh3Sharder;
+
+        for subject_key in ["alice", "bob", "charlie", ""] {
+            let shard = Xxh3Sharder.shard(&["salt", "-", subject_key], 10_000);
+            assert!(shard < 10_000);
+            assert_eq!(
+                shard,
+                Xxh3Sharder.shard(&[&format!("salt-{subject_key}")], 10_000)
+            );
+        }
+        // Stable across versions: changing the hash would reassign subjects.
+        for (subject_key, expected) in
+            [("alice", 186), ("bob", 3275), ("charlie", 2643), ("", 8214)]
+        {
+            assert_eq!(
+                Xxh3Sharder.shard(&["salt", "-", subject_key], 10_000),
+                expected,
+                "subject {subject_key:?}"
+            );
+        }
+    }
+}
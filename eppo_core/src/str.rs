@@ -60,6 +60,12 @@ impl<'a> From<&'a str> for Str {
     }
 }
 
+impl<'a> From<&'a Str> for Str {
+    fn from(value: &'a Str) -> Str {
+        value.clone()
+    }
+}
+
 impl<'a> From<Cow<'a, str>> for Str {
     fn from(value: Cow<'a, str>) -> Str {
         match value {
@@ -104,7 +110,7 @@ impl std::borrow::Borrow<str> for Str {
 }
 
 impl log::kv::ToValue for Str {
-    fn to_value(&self) -> log::kv::Value {
+    fn to_value(&self) -> log::kv::Value<'_> {
         log::kv::Value::from_display(self)
     }
 }
@@ -126,7 +132,7 @@ mod pyo3_impl {
 
     impl ToPyObject for Str {
         fn to_object(&self, py: Python<'_>) -> PyObject {
-            PyString::new_bound(py, &self).into()
+            PyString::new_bound(py, self).into()
         }
     }
 }
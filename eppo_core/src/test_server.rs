@@ -0,0 +1,269 @@
+//! A minimal HTTP server for testing requests made to the Eppo API.
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::mpsc,
+    time::Duration,
+};
+
+use reqwest::StatusCode;
+
+use crate::{
+    configuration_fetcher::{
+        AuthMode, ConfigurationFetcherConfig, DEFAULT_MAX_RESPONSE_BYTES, DEFAULT_REQUEST_TIMEOUT,
+    },
+    SdkMetadata,
+};
+
+/// SDK metadata used by tests.
+pub(crate) const SDK_METADATA: SdkMetadata = SdkMetadata {
+    name: "test",
+    version: "0.1.0",
+};
+
+impl ConfigurationFetcherConfig {
+    /// Fetcher configuration with default settings that fetches from `base_url`. Tests override
+    /// the fields they exercise with struct update syntax.
+    pub(crate) fn for_tests(base_url: &str) -> ConfigurationFetcherConfig {
+        ConfigurationFetcherConfig {
+            base_url: base_url.to_owned(),
+            base_urls: Vec::new(),
+            api_key: "api-key".to_owned(),
+            sdk_metadata: SDK_METADATA,
+            headers: Vec::new(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            obfuscated: false,
+            always_fetch_bandits: false,
+            query_params: Vec::new(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            auth_mode: AuthMode::QueryParam,
+        }
+    }
+}
+
+/// A minimal valid response for the flags configuration endpoint.
+pub(crate) const UFC_JSON: &str = r#"{
+  "createdAt": "2024-07-18T00:00:00Z",
+  "format": "SERVER",
+  "environment": {"name": "test"},
+  "flags": {}
+}"#;
+
+/// A flags configuration that references version `v1` of a bandit, so bandit models need to be
+/// fetched.
+pub(crate) const UFC_WITH_BANDITS_JSON: &str = r#"{
+  "createdAt": "2024-07-18T00:00:00Z",
+  "format": "SERVER",
+  "environment": {"name": "test"},
+  "flags": {},
+  "bandits": {
+    "banner-bandit": [
+      {
+        "key": "banner-bandit",
+        "flagKey": "banner-bandit-flag",
+        "variationKey": "banner-bandit",
+        "variationValue": "banner-bandit"
+      }
+    ]
+  },
+  "banditReferences": {
+    "banner-bandit": {
+      "modelVersion": "v1",
+      "flagVariations": [
+        {
+          "key": "banner-bandit",
+          "flagKey": "banner-bandit-flag",
+          "variationKey": "banner-bandit",
+          "variationValue": "banner-bandit"
+        }
+      ]
+    }
+  }
+}"#;
+
+/// A minimal response for the bandit models endpoint with the bandit referenced by
+/// [`UFC_WITH_BANDITS_JSON`].
+pub(crate) const BANDITS_JSON: &str = r#"{
+  "updatedAt": "2024-07-18T00:00:00Z",
+  "bandits": {
+    "banner-bandit": {
+      "banditKey": "banner-bandit",
+      "modelName": "falcon",
+      "modelVersion": "v1",
+      "updatedAt": "2024-07-18T00:00:00Z",
+      "modelData": {
+        "gamma": 1.0,
+        "defaultActionScore": 0.0,
+        "actionProbabilityFloor": 0.0,
+        "coefficients": {}
+      }
+    }
+  }
+}"#;
+
+/// An HTTP request received by [`TestServer`].
+#[derive(Debug)]
+pub(crate) struct TestRequest {
+    pub path: String,
+    /// Header names are lowercased.
+    pub headers: Vec<(String, String)>,
+}
+
+impl TestRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// An HTTP response to be sent by [`TestServer`].
+pub(crate) struct TestResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+    /// If `false`, the body is delimited by closing the connection instead.
+    pub content_length: bool,
+    /// Receives `true` if the response was written completely and `false` if the client closed
+    /// the connection early.
+    pub on_written: Option<mpsc::Sender<bool>>,
+}
+
+impl TestResponse {
+    pub fn new(status: u16) -> TestResponse {
+        TestResponse {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            content_length: true,
+            on_written: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> TestResponse {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> TestResponse {
+        self.body = body.into();
+        self
+    }
+
+    pub fn without_content_length(mut self) -> TestResponse {
+        self.content_length = false;
+        self
+    }
+
+    pub fn on_written(mut self, sender: mpsc::Sender<bool>) -> TestResponse {
+        self.on_written = Some(sender);
+        self
+    }
+}
+
+/// A server that replies to every request with a response produced by the `respond` callback.
+///
+/// The server thread runs until the test process exits.
+pub(crate) struct TestServer {
+    base_url: String,
+    requests: mpsc::Receiver<TestRequest>,
+}
+
+impl TestServer {
+    pub fn start(respond: impl Fn(&TestRequest) -> TestResponse + Send + 'static) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("should bind test server");
+        let base_url = format!("http://{}/api", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+
+                // Serve a single request per connection and ask client to close it, so we don't
+                // need to handle keep-alive.
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                let path = request_line
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_owned();
+
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    match reader.read_line(&mut line) {
+                        Ok(0) | Err(_) => break,
+                        Ok(_) => {}
+                    }
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+                    }
+                }
+
+                let request = TestRequest { path, headers };
+                let response = respond(&request);
+                // The test may have finished already and dropped the receiver.
+                let _ = sender.send(request);
+
+                let reason = StatusCode::from_u16(response.status)
+                    .ok()
+                    .and_then(|status| status.canonical_reason())
+                    .unwrap_or("");
+                let mut head = format!("HTTP/1.1 {} {reason}\r\n", response.status);
+                for (name, value) in &response.headers {
+                    head.push_str(&format!("{name}: {value}\r\n"));
+                }
+                if response.content_length {
+                    head.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+                }
+                head.push_str("Connection: close\r\n\r\n");
+
+                let written = stream
+                    .write_all(head.as_bytes())
+                    .and_then(|()| stream.write_all(&response.body));
+                if let Some(on_written) = response.on_written {
+                    let _ = on_written.send(written.is_ok());
+                }
+            }
+        });
+
+        TestServer { base_url, requests }
+    }
+
+    /// Base URL to pass to the configuration fetcher.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Fetcher configuration with default settings that fetches from this server.
+    pub fn fetcher_config(&self) -> ConfigurationFetcherConfig {
+        ConfigurationFetcherConfig::for_tests(&self.base_url)
+    }
+
+    /// Wait for the next request received by the server.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no request is received within 10 seconds.
+    pub fn next_request(&self) -> TestRequest {
+        self.requests
+            .recv_timeout(Duration::from_secs(10))
+            .expect("test server should receive a request")
+    }
+
+    /// Return a request if the server has received one, without waiting.
+    pub fn try_next_request(&self) -> Option<TestRequest> {
+        self.requests.try_recv().ok()
+    }
+}
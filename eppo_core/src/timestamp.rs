@@ -6,3 +6,42 @@ pub type Timestamp = DateTime<Utc>;
 pub fn now() -> Timestamp {
     Utc::now()
 }
+
+/// A source of current time used for flag evaluation.
+///
+/// Allocations may have `start_at`/`end_at` windows, so the result of evaluation depends on the
+/// current time. Providing a custom clock allows testing such allocations or replaying historical
+/// traffic.
+///
+/// Closures returning [`Timestamp`] implement `Clock`. Use [`FixedClock`] to evaluate at a fixed
+/// point in time.
+pub trait Clock: Send + Sync {
+    /// Return current time.
+    fn now(&self) -> Timestamp;
+}
+
+/// A [`Clock`] that returns current system time. This is the default clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        now()
+    }
+}
+
+/// A [`Clock`] that always returns the same time. Useful for deterministic tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub Timestamp);
+
+impl Clock for FixedClock {
+    fn now(&self) -> Timestamp {
+        self.0
+    }
+}
+
+impl<F: Fn() -> Timestamp + Send + Sync> Clock for F {
+    fn now(&self) -> Timestamp {
+        self()
+    }
+}
@@ -329,6 +329,39 @@ impl AssignmentValue {
         }
     }
 
+    /// Converts the value to `ty`, allowing conversion between Integer and Numeric values.
+    ///
+    /// Integer values are always converted to Numeric. Numeric values are converted to Integer
+    /// only if they are integral and fit into `i64`, so the conversion never truncates.
+    ///
+    /// # Returns
+    /// - The converted value, or `None` if the value cannot be converted to `ty`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use eppo_core::ufc::{AssignmentValue, VariationType};
+    /// let value = AssignmentValue::Numeric(3.0);
+    /// assert_eq!(value.coerce_to(VariationType::Integer), Some(AssignmentValue::Integer(3)));
+    ///
+    /// let value = AssignmentValue::Numeric(3.5);
+    /// assert_eq!(value.coerce_to(VariationType::Integer), None);
+    /// ```
+    pub fn coerce_to(self, ty: VariationType) -> Option<AssignmentValue> {
+        match (self, ty) {
+            (value, ty) if value.variation_type() == ty => Some(value),
+            (AssignmentValue::Integer(i), VariationType::Numeric) => {
+                Some(AssignmentValue::Numeric(i as f64))
+            }
+            // `i64::MAX as f64` is 2^63, which is already out of range.
+            (AssignmentValue::Numeric(n), VariationType::Integer)
+                if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 =>
+            {
+                Some(AssignmentValue::Integer(n as i64))
+            }
+            _ => None,
+        }
+    }
+
     /// Returns the raw value of the variation.
     ///
     /// # Returns
@@ -1,17 +1,24 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     error::EvaluationFailure,
     events::{AssignmentEventBase, EventMetaData},
+    memory_usage::MemoryUsage,
     sharder::PreSaltedSharder,
     Error, EvaluationError, SdkMetadata, Str,
 };
 
 use super::{
-    AllocationWire, AssignmentValue, BanditVariationWire, Environment, FlagWire, RuleWire,
-    ShardRange, ShardWire, SplitWire, Timestamp, UniversalFlagConfigWire, VariationType,
+    obfuscated::{ObfuscatedFlagWire, ObfuscatedUniversalFlagConfigWire},
+    schema::{find_unknown_fields, unknown_operator},
+    AllocationWire, AssignmentValue, BanditVariationWire, ConditionOperator, ConditionValue,
+    ConditionWire, ConfigurationFormat, Environment, FlagWire, RuleWire, ShardRange, ShardWire,
+    SplitWire, Timestamp, TryParse, UniversalFlagConfigWire, ValueWire, VariationType,
 };
 
 #[derive(Debug)]
@@ -19,6 +26,9 @@ pub struct UniversalFlagConfig {
     /// Original JSON the configuration was compiled from.
     pub(crate) wire_json: Vec<u8>,
     pub(crate) compiled: CompiledFlagsConfig,
+    /// `compiled.diagnostics` along with unknown fields. Looked up on first use, as finding
+    /// unknown fields requires another pass over `wire_json`.
+    pub(crate) diagnostics: OnceLock<ParseDiagnostics>,
 }
 
 #[derive(Debug)]
@@ -27,6 +37,9 @@ pub(crate) struct CompiledFlagsConfig {
     pub created_at: Timestamp,
     /// Environment this configuration belongs to.
     pub environment: Environment,
+    /// If `true`, configuration was compiled from obfuscated (client) format. Flag keys are md5
+    /// hashes and conditions match hashed attributes.
+    pub obfuscated: bool,
     /// Flags configuration.
     ///
     /// For flags that failed to parse or are disabled, we store the evaluation failure directly.
@@ -35,15 +48,68 @@ pub(crate) struct CompiledFlagsConfig {
     /// `UniversalFlagConfig::bandits`.
     pub flag_to_bandit_associations:
         HashMap</* flag_key: */ Str, HashMap</* variation_value: */ Str, BanditVariationWire>>,
+    /// Model versions of referenced bandits, keyed by bandit key. Cached from
+    /// `UniversalFlagConfig::bandit_references`, which is empty for older servers.
+    pub bandit_model_versions: HashMap</* bandit_key: */ Str, /* model_version: */ Str>,
+    /// md5 hashes of flag keys looked up in obfuscated configuration, so every flag key is only
+    /// hashed once. Only keys of existing flags are cached, so the size is bounded by `flags`.
+    pub hashed_flag_keys: RwLock<HashMap</* flag_key: */ Str, /* md5 hash: */ Str>>,
+    /// Problems found while parsing the configuration.
+    pub diagnostics: ParseDiagnostics,
+    /// Approximate memory occupied by `flags` and bandit references. Computed once
+    /// during compilation as configuration is immutable.
+    pub flags_bytes: usize,
 }
 
-#[derive(Debug)]
+/// Summary of flags configuration parsing.
+///
+/// Flags that fail to parse (e.g., because the server uses a newer format) don't fail the whole
+/// configuration—they return an error when evaluated. Diagnostics allow surfacing such flags
+/// upfront (e.g., in an admin UI).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseDiagnostics {
+    /// Keys of flags that failed to parse along with the parse error, sorted by key.
+    pub failed_flags: Vec<(Str, String)>,
+    /// Keys of flags with targeting conditions that failed to parse (e.g., invalid regexes)
+    /// along with the parse error, sorted by key. Such flags are still served, but rules with
+    /// failed conditions never match.
+    pub failed_conditions: Vec<(Str, String)>,
+    /// Total number of flags in configuration, including the failed ones.
+    pub total_flags: usize,
+    /// Number of flag variations that reference a bandit.
+    pub total_bandit_refs: usize,
+    /// Paths of fields unknown to this SDK version (e.g., `flags.my-flag.allocations[0].newField`),
+    /// sorted. Such fields are ignored.
+    pub unknown_fields: Vec<String>,
+    /// Condition operators unknown to this SDK version along with keys of flags using them, sorted
+    /// by key. Rules with such conditions never match.
+    pub unknown_operators: Vec<(Str, String)>,
+}
+
+/// How to handle constructs of flags configuration that are unknown to this SDK version (e.g.,
+/// a condition operator introduced on the server after the SDK was released).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Skip unknown constructs: unknown fields are ignored and rules with unknown condition
+    /// operators never match. Skipped constructs are reported in [`ParseDiagnostics`]. Unknown
+    /// fields are only looked for when diagnostics are requested, as that requires parsing the
+    /// configuration twice.
+    #[default]
+    Lenient,
+    /// Fail parsing with [`Error::UnsupportedConfiguration`] if configuration uses unknown
+    /// condition operators or fields. Useful to validate configuration in CI.
+    Strict,
+}
+
+// `PartialEq` on compiled types is used to diff configurations.
+#[derive(Debug, PartialEq)]
 pub(crate) struct Flag {
     pub variation_type: VariationType,
     pub allocations: Box<[Allocation]>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub(crate) struct Allocation {
     pub key: Str, // key is here to support evaluation details
     pub start_at: Option<Timestamp>,
@@ -52,7 +118,7 @@ pub(crate) struct Allocation {
     pub splits: Box<[Split]>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub(crate) struct Split {
     pub shards: Vec<Shard>,
     pub variation_key: Str, // for evaluation details
@@ -65,56 +131,269 @@ pub(crate) struct Split {
 pub struct Shard {
     #[serde(skip)]
     pub(crate) sharder: PreSaltedSharder,
+    /// Salt used by custom [`Sharder`](crate::sharder::Sharder)s. The default md5 sharder has it
+    /// pre-computed in `sharder`.
+    #[serde(skip)]
+    pub(crate) salt: Str,
     pub ranges: Box<[ShardRange]>,
 }
 
+impl PartialEq for Shard {
+    fn eq(&self, other: &Shard) -> bool {
+        // `sharder` is fully determined by salt and total shards.
+        self.salt == other.salt
+            && self.sharder.total_shards() == other.sharder.total_shards()
+            && self.ranges == other.ranges
+    }
+}
+
 impl UniversalFlagConfig {
+    /// Parse and compile flags configuration. Both server and obfuscated client formats are
+    /// supported, as indicated by the `format` field.
+    ///
+    /// Constructs unknown to this SDK version are skipped (see [`ParseMode::Lenient`]).
     pub fn from_json(meta_data: SdkMetadata, json: Vec<u8>) -> Result<Self, Error> {
-        let config: UniversalFlagConfigWire = serde_json::from_slice(&json).map_err(|err| {
+        UniversalFlagConfig::from_json_with_mode(meta_data, json, ParseMode::Lenient)
+    }
+
+    /// Same as [`UniversalFlagConfig::from_json()`] but allows rejecting configuration that uses
+    /// constructs unknown to this SDK version.
+    ///
+    /// # Errors
+    ///
+    /// In [`ParseMode::Strict`], returns [`Error::UnsupportedConfiguration`] listing all unknown
+    /// condition operators and fields.
+    pub fn from_json_with_mode(
+        meta_data: SdkMetadata,
+        json: Vec<u8>,
+        mode: ParseMode,
+    ) -> Result<Self, Error> {
+        #[derive(Deserialize)]
+        struct FormatWire {
+            #[serde(default)]
+            format: Option<ConfigurationFormat>,
+        }
+
+        let parse = || -> serde_json::Result<UniversalFlagConfigWire> {
+            let FormatWire { format } = serde_json::from_slice(&json)?;
+            Ok(match format {
+                Some(ConfigurationFormat::Client) => {
+                    serde_json::from_slice::<ObfuscatedUniversalFlagConfigWire>(&json)?.into()
+                }
+                _ => serde_json::from_slice(&json)?,
+            })
+        };
+        let config = parse().map_err(|err| {
             log::warn!(target: "eppo", "failed to compile flag configuration: {err:?}");
-            Error::EvaluationError(EvaluationError::UnexpectedConfigurationParseError)
+            Error::from(err)
         })?;
+
+        let mut compiled = compile_flag_configuration(meta_data.into(), config);
+        let full_diagnostics = OnceLock::new();
+        if mode == ParseMode::Strict {
+            // Looking for unknown fields requires another pass over the whole configuration, so
+            // in lenient mode it is deferred until diagnostics are requested.
+            let unknown_fields = find_unknown_fields(&json);
+            if !unknown_fields.is_empty() || !compiled.diagnostics.unknown_operators.is_empty() {
+                return Err(Error::UnsupportedConfiguration {
+                    unknown_operators: std::mem::take(&mut compiled.diagnostics.unknown_operators),
+                    unknown_fields,
+                });
+            }
+            let _ = full_diagnostics.set(compiled.diagnostics.clone());
+        }
+
+        let diagnostics = &compiled.diagnostics;
+        if !diagnostics.failed_flags.is_empty() {
+            log::warn!(target: "eppo",
+                "{} of {} flags failed to parse and will return errors when evaluated: {:?}",
+                diagnostics.failed_flags.len(),
+                diagnostics.total_flags,
+                diagnostics.failed_flags);
+        }
+        if !diagnostics.unknown_operators.is_empty() {
+            log::warn!(target: "eppo",
+                "configuration uses condition operators unknown to this SDK version, rules with them will never match: {:?}",
+                diagnostics.unknown_operators);
+        }
+
         Ok(UniversalFlagConfig {
             wire_json: json,
-            compiled: compile_flag_configuration(meta_data.into(), config),
+            compiled,
+            diagnostics: full_diagnostics,
         })
     }
 
+    /// Same as [`UniversalFlagConfig::from_json()`] but runs on tokio's blocking thread pool.
+    ///
+    /// Parsing a large configuration takes tens of milliseconds, so this keeps the calling runtime
+    /// responsive (e.g., to stop commands) meanwhile. If the returned future is dropped, parsing
+    /// finishes in the background and the result is discarded.
+    ///
+    /// If parsing panics, [`Error::PollerThreadPanicked`] is returned, so the poller doesn't retry
+    /// parsing the same payload. If the runtime is shutting down, [`Error::PollerStopped`] is
+    /// returned.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub async fn from_json_async(meta_data: SdkMetadata, json: Vec<u8>) -> Result<Self, Error> {
+        tokio::task::spawn_blocking(move || UniversalFlagConfig::from_json(meta_data, json))
+            .await
+            .map_err(|err| {
+                if err.is_panic() {
+                    log::error!(target: "eppo", "parsing configuration panicked");
+                    Error::PollerThreadPanicked
+                } else {
+                    Error::PollerStopped
+                }
+            })?
+    }
+
     pub fn to_json(&self) -> &[u8] {
         &self.wire_json
     }
+
+    /// Problems found while parsing the configuration.
+    ///
+    /// The first call looks for unknown fields (unless parsed in [`ParseMode::Strict`]), which
+    /// requires another pass over the configuration JSON.
+    pub fn diagnostics(&self) -> &ParseDiagnostics {
+        self.diagnostics.get_or_init(|| ParseDiagnostics {
+            unknown_fields: find_unknown_fields(&self.wire_json),
+            ..self.compiled.diagnostics.clone()
+        })
+    }
 }
 
 fn compile_flag_configuration(
     meta_data: EventMetaData,
     config: UniversalFlagConfigWire,
 ) -> CompiledFlagsConfig {
-    let flags = config
+    let obfuscated = matches!(config.format, Some(ConfigurationFormat::Client));
+
+    let mut failed_flags = Vec::new();
+    let mut failed_conditions = Vec::new();
+    let mut unknown_operators = Vec::new();
+    let total_flags = config.flags.len();
+    let flags: HashMap<Str, Result<Flag, EvaluationFailure>> = config
         .flags
         .into_iter()
         .map(|(key, flag)| {
-            (
-                key,
-                Option::from(flag)
-                    .ok_or(EvaluationFailure::Error(
-                        EvaluationError::UnexpectedConfigurationParseError,
-                    ))
-                    .and_then(|flag: FlagWire| {
-                        if flag.enabled {
-                            Ok(compile_flag(meta_data, flag))
-                        } else {
-                            Err(EvaluationFailure::FlagDisabled)
+            if let TryParse::ParseFailed(value) = &flag {
+                failed_flags.push((key.clone(), parse_error(value, obfuscated)));
+            }
+            let flag = Option::from(flag)
+                .ok_or(EvaluationFailure::Error(
+                    EvaluationError::UnexpectedConfigurationParseError,
+                ))
+                .and_then(|flag: FlagWire| {
+                    for condition in failed_condition_values(&flag) {
+                        failed_conditions.push((key.clone(), condition_error(condition)));
+                        if let Some(operator) = unknown_operator(condition, obfuscated) {
+                            unknown_operators.push((key.clone(), operator.to_owned()));
                         }
-                    }),
-            )
+                    }
+                    if flag.enabled {
+                        Ok(compile_flag(meta_data, flag))
+                    } else if obfuscated {
+                        // Don't reveal which (hashed) flag keys exist.
+                        Err(EvaluationFailure::FlagUnrecognizedOrDisabled)
+                    } else {
+                        Err(EvaluationFailure::FlagDisabled)
+                    }
+                });
+            (key, flag)
         })
         .collect();
 
+    failed_flags.sort();
+    failed_conditions.sort();
+    unknown_operators.sort();
+    unknown_operators.dedup();
+
+    let flag_to_bandit_associations = get_flag_to_bandit_associations(config.bandits);
+    let total_bandit_refs = flag_to_bandit_associations.values().map(HashMap::len).sum();
+    let bandit_model_versions = config
+        .bandit_references
+        .into_iter()
+        .map(|(bandit_key, reference)| (bandit_key, reference.model_version))
+        .collect::<HashMap<_, _>>();
+    let flags_bytes = flags.heap_bytes()
+        + flag_to_bandit_associations.heap_bytes()
+        + bandit_model_versions.heap_bytes();
+
     CompiledFlagsConfig {
         created_at: config.created_at,
         environment: config.environment,
+        obfuscated,
         flags,
-        flag_to_bandit_associations: get_flag_to_bandit_associations(config.bandits),
+        flag_to_bandit_associations,
+        bandit_model_versions,
+        hashed_flag_keys: RwLock::default(),
+        diagnostics: ParseDiagnostics {
+            failed_flags,
+            failed_conditions,
+            total_flags,
+            total_bandit_refs,
+            unknown_fields: Vec::new(),
+            unknown_operators,
+        },
+        flags_bytes,
+    }
+}
+
+/// Describe why a flag failed to parse.
+///
+/// `TryParse` does not keep the error, so the flag is parsed again. This only happens for failed
+/// flags, so it does not slow down parsing of valid configurations.
+fn parse_error(value: &serde_json::Value, obfuscated: bool) -> String {
+    const DECODE_ERROR: &str = "failed to decode obfuscated flag";
+
+    let error = if obfuscated {
+        // Flags that parsed but failed to decode are replaced with `null`.
+        if value.is_null() {
+            return DECODE_ERROR.to_owned();
+        }
+        serde_json::from_value::<ObfuscatedFlagWire>(value.clone()).err()
+    } else {
+        serde_json::from_value::<FlagWire>(value.clone()).err()
+    };
+    error.map_or_else(|| DECODE_ERROR.to_owned(), |err| err.to_string())
+}
+
+/// Raw values of conditions of `flag` that failed to parse.
+fn failed_condition_values(flag: &FlagWire) -> impl Iterator<Item = &serde_json::Value> + '_ {
+    flag.allocations
+        .iter()
+        .flat_map(|allocation| allocation.rules.iter())
+        .flat_map(|rule| rule.conditions.iter())
+        .filter_map(|condition| match condition {
+            TryParse::Parsed(_) => None,
+            TryParse::ParseFailed(value) => Some(value),
+        })
+}
+
+/// Describe why a condition failed to parse. Like for flags, the error is not kept, so the
+/// condition is parsed again.
+fn condition_error(value: &serde_json::Value) -> String {
+    let condition = match serde_json::from_value::<ConditionWire>(value.clone()) {
+        Ok(condition) => condition,
+        Err(err) => return format!("failed to parse condition: {err}"),
+    };
+    match (condition.operator, &condition.value) {
+        (
+            ConditionOperator::Matches | ConditionOperator::NotMatches,
+            ConditionValue::Single(ValueWire::String(pattern)),
+        ) => match regex::Regex::new(pattern) {
+            Err(err) => format!(
+                "invalid regex {:?} on {:?}: {err}",
+                &**pattern, condition.attribute
+            ),
+            Ok(_) => format!("failed to parse condition on {:?}", condition.attribute),
+        },
+        (operator, _) => format!(
+            "unsupported value for {operator:?} condition on {:?}",
+            condition.attribute
+        ),
     }
 }
 
@@ -122,8 +401,8 @@ fn get_flag_to_bandit_associations(
     bandits: HashMap<Str, Vec<BanditVariationWire>>,
 ) -> HashMap<Str, HashMap<Str, BanditVariationWire>> {
     bandits
-        .into_iter()
-        .flat_map(|(_, bandits)| bandits.into_iter())
+        .into_values()
+        .flat_map(|bandits| bandits.into_iter())
         .fold(HashMap::new(), |mut acc, variation| {
             acc.entry(variation.flag_key.clone())
                 .or_default()
@@ -232,6 +511,8 @@ fn compile_split(
                     feature_flag: flag_key.clone(),
                     allocation: allocation_key.clone(),
                     variation: split.variation_key.clone(),
+                    variation_type: value.variation_type(),
+                    variation_value: value.variation_value(),
                     meta_data,
                     extra_logging: split.extra_logging,
                 })
@@ -257,7 +538,277 @@ fn compile_shard(shard: ShardWire, total_shards: u32) -> Option<Shard> {
     } else {
         Some(Shard {
             sharder: PreSaltedSharder::new(&[shard.salt.as_bytes(), b"-"], total_shards),
+            salt: shard.salt.into(),
             ranges: shard.ranges,
         })
     }
 }
+
+#[cfg(feature = "pyo3")]
+mod pyo3_impl {
+    use pyo3::prelude::*;
+
+    use crate::pyo3::TryToPyObject;
+
+    use super::ParseDiagnostics;
+
+    impl TryToPyObject for ParseDiagnostics {
+        fn try_to_pyobject(&self, py: Python) -> PyResult<PyObject> {
+            serde_pyobject::to_pyobject(py, self)
+                .map(|it| it.unbind())
+                .map_err(|err| err.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::{
+        eval::{get_assignment, EvaluationOptions},
+        test_server::SDK_METADATA,
+        ufc::{AssignmentValue, VariationType},
+        Attributes, Configuration, Error, Str,
+    };
+
+    use super::{ParseMode, UniversalFlagConfig};
+
+    #[test]
+    fn reports_flags_that_failed_to_parse() {
+        let configuration = Configuration::from_ufc_json_for_tests(include_bytes!(
+            "../../tests/fixtures/flags-with-malformed-flag.json"
+        ));
+
+        let diagnostics = configuration.parse_diagnostics();
+        assert_eq!(diagnostics.total_flags, 3);
+        assert_eq!(diagnostics.total_bandit_refs, 1);
+        assert_eq!(diagnostics.failed_flags.len(), 1);
+        let (key, error) = &diagnostics.failed_flags[0];
+        assert_eq!(&**key, "malformed-flag");
+        assert!(error.contains("invalid type"), "unexpected error: {error}");
+        assert_eq!(
+            configuration.unparsed_flag_keys(),
+            ["malformed-flag".into()].into()
+        );
+        assert!(diagnostics.failed_conditions.is_empty());
+    }
+
+    #[test]
+    fn invalid_regex_only_disables_its_rule() {
+        let configuration = Configuration::from_ufc_json_for_tests(
+            br#"{
+              "createdAt": "2024-07-18T00:00:00Z",
+              "format": "SERVER",
+              "environment": {"name": "test"},
+              "flags": {
+                "regex-flag": {
+                  "key": "regex-flag",
+                  "enabled": true,
+                  "variationType": "STRING",
+                  "totalShards": 10000,
+                  "variations": {
+                    "invalid": {"key": "invalid", "value": "invalid"},
+                    "valid": {"key": "valid", "value": "valid"},
+                    "default": {"key": "default", "value": "default"}
+                  },
+                  "allocations": [
+                    {
+                      "key": "invalid-regex",
+                      "rules": [{"conditions": [
+                        {"attribute": "email", "operator": "MATCHES", "value": "[unclosed"}
+                      ]}],
+                      "splits": [{"variationKey": "invalid", "shards": []}],
+                      "doLog": false
+                    },
+                    {
+                      "key": "valid-regex",
+                      "rules": [{"conditions": [
+                        {"attribute": "email", "operator": "MATCHES", "value": "@example\\.com$"}
+                      ]}],
+                      "splits": [{"variationKey": "valid", "shards": []}],
+                      "doLog": false
+                    },
+                    {
+                      "key": "default",
+                      "splits": [{"variationKey": "default", "shards": []}],
+                      "doLog": false
+                    }
+                  ]
+                }
+              }
+            }"#,
+        );
+
+        let diagnostics = configuration.parse_diagnostics();
+        assert!(diagnostics.failed_flags.is_empty());
+        assert_eq!(diagnostics.failed_conditions.len(), 1);
+        let (key, error) = &diagnostics.failed_conditions[0];
+        assert_eq!(&**key, "regex-flag");
+        assert!(
+            error.contains("invalid regex \"[unclosed\""),
+            "unexpected error: {error}"
+        );
+
+        let assign = |email: &str| {
+            let attributes = Arc::new(Attributes::from([("email".into(), email.into())]));
+            get_assignment(
+                Some(&configuration),
+                "regex-flag",
+                &"subject".into(),
+                &attributes,
+                Some(VariationType::String),
+                Utc::now(),
+                &EvaluationOptions::default(),
+            )
+            .unwrap()
+            .unwrap()
+            .value
+        };
+        assert_eq!(
+            assign("user@example.com"),
+            AssignmentValue::String("valid".into())
+        );
+        assert_eq!(
+            assign("user@other.com"),
+            AssignmentValue::String("default".into())
+        );
+    }
+
+    #[test]
+    fn unknown_operator_is_skipped_in_lenient_mode() {
+        let json = include_bytes!("../../tests/fixtures/flags-with-unknown-operator.json");
+        let configuration = Configuration::from_ufc_json_for_tests(json);
+
+        let diagnostics = configuration.parse_diagnostics();
+        assert_eq!(
+            diagnostics.unknown_operators,
+            [(Str::from("beta-users"), "ENDS_WITH".to_owned())]
+        );
+
+        let assignment = get_assignment(
+            Some(&configuration),
+            "beta-users",
+            &"subject".into(),
+            &Arc::new(Attributes::from([(
+                "email".into(),
+                "user@example.com".into(),
+            )])),
+            Some(VariationType::String),
+            Utc::now(),
+            &EvaluationOptions::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(assignment.value, AssignmentValue::String("stable".into()));
+    }
+
+    #[test]
+    fn unknown_operator_is_rejected_in_strict_mode() {
+        let json = include_bytes!("../../tests/fixtures/flags-with-unknown-operator.json");
+
+        let result = UniversalFlagConfig::from_json_with_mode(
+            SDK_METADATA,
+            json.to_vec(),
+            ParseMode::Strict,
+        );
+
+        match result {
+            Err(Error::UnsupportedConfiguration {
+                unknown_operators,
+                unknown_fields,
+            }) => {
+                assert_eq!(
+                    unknown_operators,
+                    [(Str::from("beta-users"), "ENDS_WITH".to_owned())]
+                );
+                assert!(unknown_fields.is_empty());
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unknown_field_is_reported() {
+        let json = include_bytes!("../../tests/fixtures/flags-with-unknown-field.json");
+
+        // Unknown fields are ignored in lenient mode, but reported in diagnostics.
+        let flags = UniversalFlagConfig::from_json(SDK_METADATA, json.to_vec()).unwrap();
+        assert!(flags.diagnostics().unknown_operators.is_empty());
+        assert_eq!(flags.diagnostics().unknown_fields, ["targetingKeys"]);
+
+        let result = UniversalFlagConfig::from_json_with_mode(
+            SDK_METADATA,
+            json.to_vec(),
+            ParseMode::Strict,
+        );
+        match result {
+            Err(Error::UnsupportedConfiguration {
+                unknown_operators,
+                unknown_fields,
+            }) => {
+                assert!(unknown_operators.is_empty());
+                assert_eq!(unknown_fields, ["targetingKeys"]);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn known_configuration_is_accepted_in_strict_mode() {
+        let json = include_bytes!("../../tests/fixtures/flags-with-malformed-flag.json");
+
+        let flags = UniversalFlagConfig::from_json_with_mode(
+            SDK_METADATA,
+            json.to_vec(),
+            ParseMode::Strict,
+        )
+        .unwrap();
+
+        assert!(flags.diagnostics().unknown_operators.is_empty());
+    }
+
+    #[test]
+    fn nested_unknown_fields_are_reported_with_paths() {
+        let json = br#"{
+          "createdAt": "2024-07-18T00:00:00Z",
+          "format": "SERVER",
+          "environment": {"name": "test"},
+          "flags": {
+            "kill-switch": {
+              "key": "kill-switch",
+              "enabled": true,
+              "variationType": "BOOLEAN",
+              "variations": {"on": {"key": "on", "value": true, "label": "On"}},
+              "allocations": [{
+                "key": "on-for-all",
+                "rules": [{"conditions": [], "priority": 1}],
+                "splits": [{"variationKey": "on", "shards": [], "weight": 100}],
+                "doLog": true
+              }],
+              "totalShards": 10000
+            }
+          }
+        }"#;
+
+        let result = UniversalFlagConfig::from_json_with_mode(
+            SDK_METADATA,
+            json.to_vec(),
+            ParseMode::Strict,
+        );
+
+        match result {
+            Err(Error::UnsupportedConfiguration { unknown_fields, .. }) => assert_eq!(
+                unknown_fields,
+                [
+                    "flags.kill-switch.allocations[0].rules[0].priority",
+                    "flags.kill-switch.allocations[0].splits[0].weight",
+                    "flags.kill-switch.variations.on.label",
+                ]
+            ),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+}
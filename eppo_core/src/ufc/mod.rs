@@ -2,6 +2,8 @@
 mod assignment;
 mod compiled_flag_config;
 mod models;
+mod obfuscated;
+mod schema;
 
 pub use assignment::{Assignment, AssignmentValue};
 pub use compiled_flag_config::*;
@@ -31,6 +31,10 @@ pub(crate) struct UniversalFlagConfigWire {
     /// served separately.
     #[serde(default)]
     pub bandits: HashMap<Str, Vec<BanditVariationWire>>,
+    /// Bandits referenced by flags along with their current model versions, keyed by bandit key.
+    /// Older servers don't send it.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub bandit_references: HashMap<Str, BanditReferenceWire>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -53,7 +57,7 @@ pub struct Environment {
 ///
 /// This can be helpful to isolate errors in a subtree. e.g., if configuration for one flag parses,
 /// the rest of the flags are still usable.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum TryParse<T> {
     /// Successfully parsed.
@@ -113,7 +117,7 @@ pub enum VariationType {
 /// combine it with [`VariationType`] from the flag level.
 #[derive(Debug, Serialize, Deserialize, PartialEq, From, Clone)]
 #[serde(untagged)]
-pub(crate) enum ValueWire {
+pub enum ValueWire {
     /// Boolean maps to [`AssignmentValue::Boolean`].
     Boolean(bool),
     /// Number maps to either [`AssignmentValue::Integer`] or [`AssignmentValue::Numeric`].
@@ -198,7 +202,7 @@ fn default_do_log() -> bool {
     true
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(missing_docs)]
 pub(crate) struct RuleWire {
@@ -207,10 +211,12 @@ pub(crate) struct RuleWire {
 
 /// `Condition` is a check that given user `attribute` matches the condition `value` under the given
 /// `operator`.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(try_from = "ConditionWire", into = "ConditionWire")]
 pub(crate) struct Condition {
     pub attribute: Box<str>,
+    /// If `true`, `attribute` is an md5 hash of the attribute name (obfuscated configuration).
+    pub obfuscated: bool,
     pub check: ConditionCheck,
 }
 
@@ -230,11 +236,71 @@ pub(crate) enum ConditionCheck {
         expected_membership: bool,
         values: Box<[Box<str>]>,
     },
+    /// Same as `Membership` but values are hex-encoded md5 hashes (obfuscated configuration).
+    ObfuscatedMembership {
+        expected_membership: bool,
+        hashes: Box<[Box<str>]>,
+    },
     Null {
         expected_null: bool,
     },
 }
 
+impl PartialEq for ConditionCheck {
+    fn eq(&self, other: &ConditionCheck) -> bool {
+        match (self, other) {
+            (
+                ConditionCheck::Comparison {
+                    operator,
+                    comparand,
+                },
+                ConditionCheck::Comparison {
+                    operator: other_operator,
+                    comparand: other_comparand,
+                },
+            ) => operator == other_operator && comparand == other_comparand,
+            // `Regex` doesn't implement `PartialEq`, so compare patterns.
+            (
+                ConditionCheck::Regex {
+                    expected_match,
+                    regex,
+                },
+                ConditionCheck::Regex {
+                    expected_match: other_expected_match,
+                    regex: other_regex,
+                },
+            ) => expected_match == other_expected_match && regex.as_str() == other_regex.as_str(),
+            (
+                ConditionCheck::Membership {
+                    expected_membership,
+                    values,
+                },
+                ConditionCheck::Membership {
+                    expected_membership: other_expected_membership,
+                    values: other_values,
+                },
+            )
+            | (
+                ConditionCheck::ObfuscatedMembership {
+                    expected_membership,
+                    hashes: values,
+                },
+                ConditionCheck::ObfuscatedMembership {
+                    expected_membership: other_expected_membership,
+                    hashes: other_values,
+                },
+            ) => expected_membership == other_expected_membership && values == other_values,
+            (
+                ConditionCheck::Null { expected_null },
+                ConditionCheck::Null {
+                    expected_null: other_expected_null,
+                },
+            ) => expected_null == other_expected_null,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) enum ComparisonOperator {
     Gte,
@@ -254,9 +320,36 @@ impl From<ComparisonOperator> for ConditionOperator {
     }
 }
 
+/// Parse a semantic version for comparison conditions.
+///
+/// Build metadata (`+...`) is stripped before parsing: SemVer 2.0 excludes it from precedence, and
+/// metadata that is not valid SemVer should not prevent comparison. Pre-release versions are
+/// ordered per SemVer 2.0 (e.g., `1.0.0-beta < 1.0.0-rc.2 < 1.0.0-rc.10 < 1.0.0`).
+///
+/// Only full `MAJOR.MINOR.PATCH` versions are parsed. Two-segment values like `2.3` are compared as
+/// numbers instead (so `2.10` is less than `2.3`), consistent with other Eppo SDKs. Version
+/// targeting should use three segments.
+pub(crate) fn parse_version(s: &str) -> Option<Version> {
+    let version = s.split_once('+').map_or(s, |(version, _build)| version);
+    Version::parse(version).ok()
+}
+
+/// Parse a timestamp for comparison conditions.
+///
+/// Accepts RFC 3339 timestamps (e.g., `2024-01-01T12:00:00+02:00`) and dates (`2024-01-01`,
+/// interpreted as midnight UTC).
+pub(crate) fn parse_timestamp(s: &str) -> Option<Timestamp> {
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(timestamp.to_utc());
+    }
+    let date = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+    Some(date.and_time(chrono::NaiveTime::MIN).and_utc())
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, From)]
 pub(crate) enum Comparand {
     Version(Version),
+    Timestamp(Timestamp),
     Number(f64),
 }
 
@@ -264,6 +357,7 @@ impl From<Comparand> for ConditionValue {
     fn from(value: Comparand) -> ConditionValue {
         let s = match value {
             Comparand::Version(v) => v.to_string(),
+            Comparand::Timestamp(t) => t.to_rfc3339(),
             Comparand::Number(n) => n.to_string(),
         };
         ConditionValue::Single(ValueWire::String(s.into()))
@@ -301,6 +395,10 @@ impl From<Condition> for ConditionWire {
             ConditionCheck::Membership {
                 expected_membership,
                 values,
+            }
+            | ConditionCheck::ObfuscatedMembership {
+                expected_membership,
+                hashes: values,
             } => (
                 if expected_membership {
                     ConditionOperator::OneOf
@@ -376,7 +474,11 @@ impl TryFrom<ConditionWire> for Condition {
                 };
 
                 let condition_version = match &condition.value {
-                    ConditionValue::Single(ValueWire::String(s)) => Version::parse(s).ok(),
+                    ConditionValue::Single(ValueWire::String(s)) => parse_version(s),
+                    _ => None,
+                };
+                let condition_timestamp = match &condition.value {
+                    ConditionValue::Single(ValueWire::String(s)) => parse_timestamp(s),
                     _ => None,
                 };
 
@@ -385,6 +487,11 @@ impl TryFrom<ConditionWire> for Condition {
                         operator,
                         comparand: Comparand::Version(condition_version),
                     }
+                } else if let Some(condition_timestamp) = condition_timestamp {
+                    ConditionCheck::Comparison {
+                        operator,
+                        comparand: Comparand::Timestamp(condition_timestamp),
+                    }
                 } else {
                     // numeric comparison
                     let condition_value = match &condition.value {
@@ -431,7 +538,11 @@ impl TryFrom<ConditionWire> for Condition {
                 ConditionCheck::Null { expected_null }
             }
         };
-        Ok(Condition { attribute, check })
+        Ok(Condition {
+            attribute,
+            obfuscated: false,
+            check,
+        })
     }
 }
 
@@ -530,6 +641,17 @@ pub(crate) struct BanditVariationWire {
     pub variation_value: Str,
 }
 
+/// `BanditReference` tells which model version of a bandit flags configuration was published
+/// with, so stale bandit models can be detected without fetching them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BanditReferenceWire {
+    pub model_version: Str,
+    /// Same associations as in [`UniversalFlagConfigWire::bandits`].
+    #[serde(default)]
+    pub flag_variations: Vec<BanditVariationWire>,
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs::File, io::BufReader};
@@ -546,7 +668,7 @@ mod tests {
     #[test]
     fn parse_partially_if_unexpected() {
         let ufc: UniversalFlagConfigWire = serde_json::from_str(
-            &r#"
+            r#"
               {
                 "createdAt": "2024-07-18T00:00:00Z",
                 "format": "SERVER",
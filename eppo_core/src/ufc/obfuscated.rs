@@ -0,0 +1,320 @@
+//! Obfuscated ("client") flag configuration format.
+//!
+//! Client configuration is served to untrusted environments, so it does not expose flag keys,
+//! attribute names, and values in plain text:
+//! - flag keys, condition attributes, and condition operators are md5-hashed,
+//! - `ONE_OF`/`NOT_ONE_OF` values and `IS_NULL` expectations are md5-hashed,
+//! - everything else (variations, allocation keys, dates, salts, other condition values) is
+//!   base64-encoded.
+//!
+//! Base64-encoded values are decoded into regular wire structures here, so the rest of the
+//! pipeline works the same as for server configuration. Hashed values cannot be decoded and are
+//! matched by hashing subject attributes during evaluation.
+use std::collections::HashMap;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    obfuscation::{md5_hex, Base64Str},
+    Str,
+};
+
+use super::{
+    AllocationWire, BanditReferenceWire, BanditVariationWire, Condition, ConditionCheck,
+    ConditionOperator, ConditionValue, ConditionWire, Environment, FlagWire, RuleWire, ShardRange,
+    ShardWire, SplitWire, Timestamp, TryParse, UniversalFlagConfigWire, ValueWire, VariationType,
+    VariationWire,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedUniversalFlagConfigWire {
+    pub created_at: Timestamp,
+    pub environment: Environment,
+    /// Flags keyed by md5 hash of the flag key.
+    pub flags: HashMap<Str, TryParse<ObfuscatedFlagWire>>,
+    #[serde(default)]
+    pub bandits: HashMap<Str, Vec<BanditVariationWire>>,
+    #[serde(default)]
+    pub bandit_references: HashMap<Str, BanditReferenceWire>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedFlagWire {
+    pub key: Str,
+    pub enabled: bool,
+    pub variation_type: VariationType,
+    pub variations: HashMap<String, ObfuscatedVariationWire>,
+    pub allocations: Vec<ObfuscatedAllocationWire>,
+    pub total_shards: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedVariationWire {
+    pub key: Base64Str,
+    pub value: Base64Str,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedAllocationWire {
+    pub key: Base64Str,
+    #[serde(default)]
+    pub rules: Vec<ObfuscatedRuleWire>,
+    #[serde(default)]
+    pub start_at: Option<Base64Str>,
+    #[serde(default)]
+    pub end_at: Option<Base64Str>,
+    pub splits: Vec<ObfuscatedSplitWire>,
+    #[serde(default = "default_do_log")]
+    pub do_log: bool,
+}
+
+fn default_do_log() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedRuleWire {
+    pub conditions: Vec<ObfuscatedConditionWire>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedConditionWire {
+    /// md5 hash of the attribute name.
+    pub attribute: Box<str>,
+    /// md5 hash of the operator name.
+    pub operator: Box<str>,
+    pub value: ConditionValue,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedSplitWire {
+    pub shards: Vec<ObfuscatedShardWire>,
+    pub variation_key: Base64Str,
+    #[serde(default)]
+    pub extra_logging: HashMap<Base64Str, Base64Str>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ObfuscatedShardWire {
+    pub salt: Base64Str,
+    pub ranges: Box<[ShardRange]>,
+}
+
+impl From<ObfuscatedUniversalFlagConfigWire> for UniversalFlagConfigWire {
+    fn from(config: ObfuscatedUniversalFlagConfigWire) -> UniversalFlagConfigWire {
+        let flags = config
+            .flags
+            .into_iter()
+            .map(|(key, flag)| {
+                let flag = match flag {
+                    TryParse::Parsed(flag) => match flag.deobfuscate() {
+                        Some(flag) => TryParse::Parsed(flag),
+                        None => TryParse::ParseFailed(serde_json::Value::Null),
+                    },
+                    TryParse::ParseFailed(value) => TryParse::ParseFailed(value),
+                };
+                (key, flag)
+            })
+            .collect();
+
+        UniversalFlagConfigWire {
+            created_at: config.created_at,
+            format: Some(super::ConfigurationFormat::Client),
+            environment: config.environment,
+            flags,
+            bandits: config.bandits,
+            bandit_references: config.bandit_references,
+        }
+    }
+}
+
+impl ObfuscatedFlagWire {
+    /// Decode base64-encoded fields. Returns `None` if some of the fields cannot be decoded.
+    fn deobfuscate(self) -> Option<FlagWire> {
+        let variation_type = self.variation_type;
+        let variations = self
+            .variations
+            .into_iter()
+            .map(|(key, variation)| {
+                let variation = VariationWire {
+                    key: variation.key.0,
+                    value: decode_value(variation.value.0, variation_type)?,
+                };
+                Some((key, variation))
+            })
+            .collect::<Option<_>>()?;
+
+        let allocations = self
+            .allocations
+            .into_iter()
+            .map(ObfuscatedAllocationWire::deobfuscate)
+            .collect::<Option<_>>()?;
+
+        Some(FlagWire {
+            key: self.key,
+            enabled: self.enabled,
+            variation_type,
+            variations,
+            allocations,
+            total_shards: self.total_shards,
+        })
+    }
+}
+
+impl ObfuscatedAllocationWire {
+    fn deobfuscate(self) -> Option<AllocationWire> {
+        let decode_timestamp = |s: Option<Base64Str>| -> Option<Option<Timestamp>> {
+            match s {
+                Some(s) => s.0.parse().ok().map(Some),
+                None => Some(None),
+            }
+        };
+
+        Some(AllocationWire {
+            key: self.key.0,
+            rules: self
+                .rules
+                .into_iter()
+                .map(|rule| RuleWire {
+                    conditions: rule
+                        .conditions
+                        .into_iter()
+                        .map(ObfuscatedConditionWire::deobfuscate)
+                        .collect(),
+                })
+                .collect(),
+            start_at: decode_timestamp(self.start_at)?,
+            end_at: decode_timestamp(self.end_at)?,
+            splits: self
+                .splits
+                .into_iter()
+                .map(|split| SplitWire {
+                    shards: split
+                        .shards
+                        .into_iter()
+                        .map(|shard| ShardWire {
+                            salt: shard.salt.0.to_string(),
+                            ranges: shard.ranges,
+                        })
+                        .collect(),
+                    variation_key: split.variation_key.0,
+                    extra_logging: split
+                        .extra_logging
+                        .into_iter()
+                        .map(|(k, v)| (k.0.to_string(), v.0.to_string()))
+                        .collect(),
+                })
+                .collect(),
+            do_log: self.do_log,
+        })
+    }
+}
+
+impl ObfuscatedConditionWire {
+    /// Convert into a [`Condition`] that matches hashed attribute name. Conditions that fail to
+    /// decode are kept as `ParseFailed`, so they never match (same as for server configuration).
+    fn deobfuscate(self) -> TryParse<Condition> {
+        match self.try_deobfuscate() {
+            Some(condition) => TryParse::Parsed(condition),
+            None => {
+                log::warn!(target: "eppo", "failed to parse obfuscated condition: {self:?}");
+                TryParse::ParseFailed(serde_json::to_value(&self).unwrap_or_default())
+            }
+        }
+    }
+
+    fn try_deobfuscate(&self) -> Option<Condition> {
+        let operator = decode_operator(&self.operator)?;
+
+        let check = match operator {
+            ConditionOperator::OneOf | ConditionOperator::NotOneOf => {
+                let ConditionValue::Multiple(hashes) = &self.value else {
+                    return None;
+                };
+                ConditionCheck::ObfuscatedMembership {
+                    expected_membership: operator == ConditionOperator::OneOf,
+                    hashes: hashes.clone(),
+                }
+            }
+            ConditionOperator::IsNull => {
+                let ConditionValue::Single(ValueWire::String(hash)) = &self.value else {
+                    return None;
+                };
+                let expected_null = if **hash == md5_hex("true") {
+                    true
+                } else if **hash == md5_hex("false") {
+                    false
+                } else {
+                    return None;
+                };
+                ConditionCheck::Null { expected_null }
+            }
+            ConditionOperator::Matches
+            | ConditionOperator::NotMatches
+            | ConditionOperator::Gte
+            | ConditionOperator::Gt
+            | ConditionOperator::Lte
+            | ConditionOperator::Lt => {
+                let ConditionValue::Single(ValueWire::String(value)) = &self.value else {
+                    return None;
+                };
+                let value = decode_base64(value)?;
+                Condition::try_from(ConditionWire {
+                    attribute: self.attribute.clone(),
+                    operator,
+                    value: ConditionValue::Single(ValueWire::String(value)),
+                })
+                .ok()?
+                .check
+            }
+        };
+
+        Some(Condition {
+            attribute: self.attribute.clone(),
+            obfuscated: true,
+            check,
+        })
+    }
+}
+
+pub(super) fn decode_operator(hash: &str) -> Option<ConditionOperator> {
+    const OPERATORS: [(&str, ConditionOperator); 9] = [
+        ("MATCHES", ConditionOperator::Matches),
+        ("NOT_MATCHES", ConditionOperator::NotMatches),
+        ("GTE", ConditionOperator::Gte),
+        ("GT", ConditionOperator::Gt),
+        ("LTE", ConditionOperator::Lte),
+        ("LT", ConditionOperator::Lt),
+        ("ONE_OF", ConditionOperator::OneOf),
+        ("NOT_ONE_OF", ConditionOperator::NotOneOf),
+        ("IS_NULL", ConditionOperator::IsNull),
+    ];
+    OPERATORS
+        .into_iter()
+        .find(|(name, _)| md5_hex(name) == hash)
+        .map(|(_, operator)| operator)
+}
+
+fn decode_base64(s: &str) -> Option<Str> {
+    let bytes = BASE64_STANDARD.decode(s).ok()?;
+    String::from_utf8(bytes).ok().map(Str::from)
+}
+
+/// Variation values are always base64-encoded strings, so they need to be converted back to the
+/// proper type.
+fn decode_value(value: Str, variation_type: VariationType) -> Option<ValueWire> {
+    Some(match variation_type {
+        VariationType::String | VariationType::Json => ValueWire::String(value),
+        VariationType::Integer | VariationType::Numeric => ValueWire::Number(value.parse().ok()?),
+        VariationType::Boolean => ValueWire::Boolean(value.parse().ok()?),
+    })
+}
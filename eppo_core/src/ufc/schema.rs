@@ -0,0 +1,280 @@
+//! Detection of configuration constructs unknown to this SDK version.
+//!
+//! Unknown fields are ignored by the parser, and conditions with unknown operators are kept as
+//! `ParseFailed` (they never match). Both silently change flag behavior, so they are reported
+//! in [`ParseDiagnostics`](super::ParseDiagnostics) and rejected in
+//! [`ParseMode::Strict`](super::ParseMode::Strict).
+//!
+//! Unknown operators are found among conditions that failed to parse, so they are detected for
+//! free during compilation. Unknown fields require walking the whole configuration, so they are
+//! only looked for in strict mode.
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    forward_to_deserialize_any, Deserializer,
+};
+use serde_json::Value;
+
+use super::{
+    obfuscated::decode_operator, AllocationWire, BanditReferenceWire, BanditVariationWire,
+    ConditionOperator, ConditionWire, Environment, FlagWire, RuleWire, ShardRange, ShardWire,
+    SplitWire, UniversalFlagConfigWire, VariationWire,
+};
+
+/// Fields known to the parser. Obfuscated configuration uses the same field names.
+enum Schema {
+    /// Object with fields of a wire struct. Names of the fields are taken from the struct's
+    /// `Deserialize` implementation, so they can't drift from the parser. Fields listed in
+    /// `nested` are inspected further, other fields are not.
+    Object {
+        fields: fn() -> &'static [&'static str],
+        nested: &'static [(&'static str, Schema)],
+    },
+    /// Object with arbitrary keys and values of the given schema.
+    Map(&'static Schema),
+    /// Array with elements of the given schema.
+    Array(&'static Schema),
+}
+
+const SHARD: Schema = Schema::Object {
+    fields: fields_of::<ShardWire>,
+    nested: &[(
+        "ranges",
+        Schema::Array(&Schema::Object {
+            fields: fields_of::<ShardRange>,
+            nested: &[],
+        }),
+    )],
+};
+
+const SPLIT: Schema = Schema::Object {
+    fields: fields_of::<SplitWire>,
+    nested: &[("shards", Schema::Array(&SHARD))],
+};
+
+const CONDITION: Schema = Schema::Object {
+    fields: fields_of::<ConditionWire>,
+    nested: &[],
+};
+
+const ALLOCATION: Schema = Schema::Object {
+    fields: fields_of::<AllocationWire>,
+    nested: &[
+        (
+            "rules",
+            Schema::Array(&Schema::Object {
+                fields: fields_of::<RuleWire>,
+                nested: &[("conditions", Schema::Array(&CONDITION))],
+            }),
+        ),
+        ("splits", Schema::Array(&SPLIT)),
+    ],
+};
+
+const FLAG: Schema = Schema::Object {
+    fields: fields_of::<FlagWire>,
+    nested: &[
+        (
+            "variations",
+            Schema::Map(&Schema::Object {
+                fields: fields_of::<VariationWire>,
+                nested: &[],
+            }),
+        ),
+        ("allocations", Schema::Array(&ALLOCATION)),
+    ],
+};
+
+const BANDIT_VARIATION: Schema = Schema::Object {
+    fields: fields_of::<BanditVariationWire>,
+    nested: &[],
+};
+
+const CONFIGURATION: Schema = Schema::Object {
+    fields: fields_of::<UniversalFlagConfigWire>,
+    nested: &[
+        (
+            "environment",
+            Schema::Object {
+                fields: fields_of::<Environment>,
+                nested: &[],
+            },
+        ),
+        ("flags", Schema::Map(&FLAG)),
+        ("bandits", Schema::Map(&Schema::Array(&BANDIT_VARIATION))),
+        (
+            "banditReferences",
+            Schema::Map(&Schema::Object {
+                fields: fields_of::<BanditReferenceWire>,
+                nested: &[("flagVariations", Schema::Array(&BANDIT_VARIATION))],
+            }),
+        ),
+    ],
+};
+
+/// Find paths of fields unknown to this SDK version (e.g., `flags.my-flag.allocations[0].newField`)
+/// in flags configuration `json`, sorted.
+///
+/// Configuration that is not valid JSON has no unknown fields (it fails to parse anyway).
+pub(super) fn find_unknown_fields(json: &[u8]) -> Vec<String> {
+    let Ok(value) = serde_json::from_slice::<Value>(json) else {
+        return Vec::new();
+    };
+
+    let mut result = Vec::new();
+    unknown_fields(&value, &CONFIGURATION, &mut String::new(), &mut result);
+    result.sort();
+    result
+}
+
+fn unknown_fields(value: &Value, schema: &Schema, path: &mut String, result: &mut Vec<String>) {
+    match (schema, value) {
+        (Schema::Object { fields, nested }, Value::Object(object)) => {
+            let fields = fields();
+            for (key, value) in object {
+                if let Some((_, schema)) = nested.iter().find(|(name, _)| name == key) {
+                    with_path(path, key, |path| {
+                        unknown_fields(value, schema, path, result)
+                    });
+                } else if !fields.contains(&key.as_str()) {
+                    with_path(path, key, |path| result.push(path.clone()));
+                }
+            }
+        }
+        (Schema::Map(schema), Value::Object(object)) => {
+            for (key, value) in object {
+                with_path(path, key, |path| {
+                    unknown_fields(value, schema, path, result)
+                });
+            }
+        }
+        (Schema::Array(schema), Value::Array(values)) => {
+            for (i, value) in values.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{i}]"));
+                unknown_fields(value, schema, path, result);
+                path.truncate(len);
+            }
+        }
+        // Values of unexpected types are reported as parse errors, not as unknown fields.
+        _ => {}
+    }
+}
+
+/// Run `f` with `key` appended to `path`.
+fn with_path(path: &mut String, key: &str, f: impl FnOnce(&mut String)) {
+    let len = path.len();
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(key);
+    f(path);
+    path.truncate(len);
+}
+
+/// Returns operator of a condition that failed to parse if the operator is unknown to this SDK
+/// version.
+pub(super) fn unknown_operator(condition: &Value, obfuscated: bool) -> Option<&str> {
+    let operator = condition.get("operator")?.as_str()?;
+    (!is_known_operator(operator, obfuscated)).then_some(operator)
+}
+
+fn is_known_operator(operator: &str, obfuscated: bool) -> bool {
+    if obfuscated {
+        decode_operator(operator).is_some()
+    } else {
+        serde_json::from_value::<ConditionOperator>(Value::String(operator.to_owned())).is_ok()
+    }
+}
+
+/// Names of fields of struct `T`, as expected by its derived `Deserialize` implementation.
+///
+/// Derived implementations pass field names to [`Deserializer::deserialize_struct()`], which
+/// `FieldNames` captures and returns as an error without deserializing anything.
+fn fields_of<T: DeserializeOwned>() -> &'static [&'static str] {
+    match T::deserialize(FieldNames) {
+        Err(FieldNamesError(fields)) => fields,
+        Ok(_) => &[],
+    }
+}
+
+struct FieldNames;
+
+#[derive(Debug)]
+struct FieldNamesError(&'static [&'static str]);
+
+impl std::fmt::Display for FieldNamesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fields: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for FieldNamesError {}
+
+impl de::Error for FieldNamesError {
+    fn custom<T: std::fmt::Display>(_msg: T) -> Self {
+        FieldNamesError(&[])
+    }
+}
+
+impl<'de> Deserializer<'de> for FieldNames {
+    type Error = FieldNamesError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(FieldNamesError(&[]))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(FieldNamesError(fields))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fields_of, FlagWire, Schema, CONFIGURATION};
+
+    #[test]
+    fn fields_are_taken_from_wire_structs() {
+        assert_eq!(
+            fields_of::<FlagWire>(),
+            [
+                "key",
+                "enabled",
+                "variationType",
+                "variations",
+                "allocations",
+                "totalShards"
+            ]
+        );
+    }
+
+    /// Nested schemas must refer to existing fields of their wire structs, so that renaming a
+    /// field in a wire struct fails here instead of silently skipping the nested check.
+    #[test]
+    fn nested_fields_exist_in_wire_structs() {
+        fn check(schema: &Schema, path: &str) {
+            match schema {
+                Schema::Object { fields, nested } => {
+                    let fields = fields();
+                    assert!(!fields.is_empty(), "{path}: not a struct");
+                    for (name, schema) in *nested {
+                        assert!(fields.contains(name), "{path}: unknown field {name}");
+                        check(schema, &format!("{path}.{name}"));
+                    }
+                }
+                Schema::Map(schema) | Schema::Array(schema) => check(schema, path),
+            }
+        }
+
+        check(&CONFIGURATION, "configuration");
+    }
+}
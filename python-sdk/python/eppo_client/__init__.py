@@ -1,4 +1,4 @@
-from typing import Any, Dict, Set, Union, Type
+from typing import Any, Dict, List, Set, Tuple, Union, Type
 
 # Rust currently does not define submodules as packages, so Rust
 # submodules are not importable from Python.[1] There is a hacky way
@@ -20,7 +20,8 @@ from eppo_client._eppo_client import __version__
 from eppo_client.assignment_logger import AssignmentCacheLogger
 from eppo_client.bandit import BanditResult
 
-Attribute = Union[str, int, float, bool, None]
+Scalar = Union[str, int, float, bool]
+Attribute = Union[Scalar, List[Scalar], Tuple[Scalar, ...], None]
 Attributes = Dict[str, Attribute]
 
 __doc__ = _eppo_client.__doc__
@@ -1,4 +1,4 @@
-from typing import Dict, Any, Set, Union
+from typing import Callable, Dict, Any, List, Optional, Sequence, Set, Union
 
 __version__: str
 
@@ -13,6 +13,8 @@ class Configuration:
     def get_bandits_configuration(self) -> bytes | None: ...
     def get_flag_keys(self) -> Set[str]: ...
     def get_bandit_keys(self) -> Set[str]: ...
+    def get_parse_diagnostics(self) -> Dict[str, Any]: ...
+    def get_memory_usage(self) -> Dict[str, int]: ...
 
 class ClientConfig:
     api_key: str
@@ -21,7 +23,16 @@ class ClientConfig:
     is_graceful_mode: bool
     poll_interval_seconds: int | None
     poll_jitter_seconds: int
+    request_timeout_seconds: float
+    # If set, `init()` waits up to this many seconds for the first configuration fetch. Raises if
+    # the fetch fails with an unrecoverable error (e.g., invalid API key).
+    initial_fetch_timeout_seconds: float | None
     initial_configuration: Configuration | None
+    on_configuration_change: Callable[[Configuration], None] | None
+    # Called with a RuntimeError for every failed fetch. Its `is_retriable` attribute tells
+    # whether the poller keeps retrying after the error.
+    on_error: Callable[[Exception], None] | None
+    categorical_attributes: Set[str]
 
     def __init__(
         self,
@@ -32,7 +43,12 @@ class ClientConfig:
         is_graceful_mode: bool = True,
         poll_interval_seconds: int | None = ...,
         poll_jitter_seconds: int = ...,
-        initial_configuration: Configuration | None = None
+        request_timeout_seconds: float = ...,
+        initial_fetch_timeout_seconds: float | None = None,
+        initial_configuration: Configuration | None = None,
+        on_configuration_change: Callable[[Configuration], None] | None = None,
+        on_error: Callable[[Exception], None] | None = None,
+        categorical_attributes: Set[str] | None = None
     ): ...
 
 class AssignmentLogger:
@@ -46,6 +62,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: str,
+        *,
+        log_assignment: bool = True,
     ) -> str: ...
     def get_integer_assignment(
         self,
@@ -53,6 +71,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: int,
+        *,
+        log_assignment: bool = True,
     ) -> int: ...
     def get_numeric_assignment(
         self,
@@ -60,6 +80,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: float,
+        *,
+        log_assignment: bool = True,
     ) -> float: ...
     def get_boolean_assignment(
         self,
@@ -67,6 +89,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: bool,
+        *,
+        log_assignment: bool = True,
     ) -> bool: ...
     def get_json_assignment(
         self,
@@ -74,13 +98,25 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: Any,
+        *,
+        log_assignment: bool = True,
     ) -> Any: ...
+    def get_all_assignments(
+        self,
+        subject_key: str,
+        subject_attributes: Dict[str, Union[str, int, float, bool, None]],
+        flag_keys: Optional[Sequence[str]] = None,
+        *,
+        log_assignment: bool = True,
+    ) -> Dict[str, Any]: ...
     def get_string_assignment_details(
         self,
         flag_key: str,
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: str,
+        *,
+        log_assignment: bool = True,
     ) -> EvaluationResult: ...
     def get_integer_assignment_details(
         self,
@@ -88,6 +124,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: int,
+        *,
+        log_assignment: bool = True,
     ) -> EvaluationResult: ...
     def get_numeric_assignment_details(
         self,
@@ -95,6 +133,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: float,
+        *,
+        log_assignment: bool = True,
     ) -> EvaluationResult: ...
     def get_boolean_assignment_details(
         self,
@@ -102,6 +142,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: bool,
+        *,
+        log_assignment: bool = True,
     ) -> EvaluationResult: ...
     def get_json_assignment_details(
         self,
@@ -109,6 +151,8 @@ class EppoClient:
         subject_key: str,
         subject_attributes: Dict[str, Union[str, int, float, bool, None]],
         default: Any,
+        *,
+        log_assignment: bool = True,
     ) -> EvaluationResult: ...
     def get_bandit_action(
         self,
@@ -120,6 +164,7 @@ class EppoClient:
         actions: (
             Dict[str, ContextAttributes]
             | Dict[str, Dict[str, Union[str, int, float, bool, None]]]
+            | Sequence[str]
         ),
         default: str,
     ) -> EvaluationResult: ...
@@ -133,16 +178,35 @@ class EppoClient:
         actions: (
             Dict[str, ContextAttributes]
             | Dict[str, Dict[str, Union[str, int, float, bool, None]]]
+            | Sequence[str]
         ),
         default: str,
     ) -> EvaluationResult: ...
+    def get_bandit_actions_ranked(
+        self,
+        flag_key: str,
+        subject_key: str,
+        subject_context: (
+            ContextAttributes | Dict[str, Union[str, int, float, bool, None]]
+        ),
+        actions: (
+            Dict[str, ContextAttributes]
+            | Dict[str, Dict[str, Union[str, int, float, bool, None]]]
+            | Sequence[str]
+        ),
+        default: str,
+        k: int,
+        allowed_actions: Optional[Set[str]] = None,
+    ) -> List[EvaluationResult]: ...
     def get_configuration(self) -> Configuration | None: ...
     def set_configuration(self, configuration: Configuration): ...
     def get_flag_keys(self) -> Set[str]: ...
     def get_bandit_keys(self) -> Set[str]: ...
     def set_is_graceful_mode(self, is_graceful_mode: bool): ...
     def is_initialized(self) -> bool: ...
-    def wait_for_initialization(self) -> None: ...
+    def wait_for_initialization(self, timeout_seconds: Optional[float] = None) -> None: ...
+    def refresh_configuration(self) -> None: ...
+    def get_poller_status(self) -> Optional[Dict[str, Any]]: ...
 
 class ContextAttributes:
     def __new__(
@@ -154,7 +218,8 @@ class ContextAttributes:
     def empty() -> ContextAttributes: ...
     @staticmethod
     def from_dict(
-        attributes: Dict[str, Union[str, int, float, bool, None]]
+        attributes: Dict[str, Union[str, int, float, bool, None]],
+        categorical_attributes: Set[str] | None = None,
     ) -> ContextAttributes: ...
     @property
     def numeric_attributes(self) -> Dict[str, float]: ...
@@ -165,10 +230,14 @@ class EvaluationResult:
     variation: Any
     action: str | None
     evaluation_details: Any | None
+    allocation_key: str | None
+    experiment: str | None
     def __new__(
         cls,
         variation: Any,
         action: str | None = None,
         evaluation_details: Any | None = None,
+        allocation_key: str | None = None,
+        experiment: str | None = None,
     ): ...
     def to_string(self) -> str: ...
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ops::Deref,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -9,23 +9,26 @@ use std::{
 };
 
 use pyo3::{
-    exceptions::{PyRuntimeError, PyTypeError},
+    exceptions::{PyRuntimeError, PyTypeError, PyValueError},
     intern,
     prelude::*,
-    types::{PyBool, PyFloat, PyInt, PySet, PyString},
+    types::{PyBool, PyDict, PyFloat, PyInt, PySet, PyString},
     PyTraverseError, PyVisit,
 };
 
 use eppo_core::{
-    configuration_fetcher::ConfigurationFetcher,
+    configuration_fetcher::{AuthMode, ConfigurationFetcher, DEFAULT_MAX_RESPONSE_BYTES},
     configuration_store::ConfigurationStore,
     eval::{
         eval_details::{EvaluationDetails, EvaluationResultWithDetails},
-        BanditResult, Evaluator, EvaluatorConfig,
+        Actions, BanditRequest, BanditResult, Evaluator, EvaluatorConfig,
     },
     events::{AssignmentEvent, BanditEvent},
-    poller_thread::{PollerThread, PollerThreadConfig},
+    poller_thread::{
+        ConfigurationChangeCallback, ErrorCallback, PollerStatus, PollerThread, PollerThreadConfig,
+    },
     pyo3::TryToPyObject,
+    timestamp::SystemClock,
     ufc::VariationType,
     Attributes, ContextAttributes, Str,
 };
@@ -41,20 +44,30 @@ pub struct EvaluationResult {
     action: Option<Py<PyString>>,
     /// Optional evaluation details.
     evaluation_details: Option<Py<PyAny>>,
+    /// Key of the flag allocation that assigned the bandit variation (even if the allocation is
+    /// not logged). Only set for bandit evaluations.
+    allocation_key: Option<Py<PyString>>,
+    /// Experiment of the flag allocation that assigned the bandit variation. Only set for bandit
+    /// evaluations.
+    experiment: Option<Py<PyString>>,
 }
 #[pymethods]
 impl EvaluationResult {
     #[new]
-    #[pyo3(signature = (variation, action=None, evaluation_details=None))]
+    #[pyo3(signature = (variation, action=None, evaluation_details=None, allocation_key=None, experiment=None))]
     fn new(
         variation: Py<PyAny>,
         action: Option<Py<PyString>>,
         evaluation_details: Option<Py<PyAny>>,
+        allocation_key: Option<Py<PyString>>,
+        experiment: Option<Py<PyString>>,
     ) -> EvaluationResult {
         EvaluationResult {
             variation,
             action,
             evaluation_details,
+            allocation_key,
+            experiment,
         }
     }
 
@@ -111,6 +124,8 @@ impl EvaluationResult {
             variation,
             action: action.map(|it| PyString::new_bound(py, &it).unbind()),
             evaluation_details: Some(evaluation_details.try_to_pyobject(py)?),
+            allocation_key: None,
+            experiment: None,
         })
     }
 
@@ -123,6 +138,12 @@ impl EvaluationResult {
         let action = result
             .action
             .map(|it| PyString::new_bound(py, &it).unbind());
+        let allocation_key = result
+            .allocation_key
+            .map(|it| PyString::new_bound(py, &it).unbind());
+        let experiment = result
+            .experiment
+            .map(|it| PyString::new_bound(py, &it).unbind());
 
         let evaluation_details = if let Some(details) = details {
             Some(details.try_to_pyobject(py)?)
@@ -134,6 +155,8 @@ impl EvaluationResult {
             variation,
             action,
             evaluation_details,
+            allocation_key,
+            experiment,
         })
     }
 }
@@ -145,16 +168,21 @@ pub struct EppoClient {
     poller_thread: Option<PollerThread>,
     assignment_logger: Py<AssignmentLogger>,
     is_graceful_mode: AtomicBool,
+    /// Keys of number attributes that are converted to categorical when converting plain
+    /// attribute dicts for bandits.
+    categorical_attributes: HashSet<Str>,
 }
 
 #[pymethods]
 impl EppoClient {
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_string_assignment(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyString>,
+        log_assignment: bool,
     ) -> PyResult<PyObject> {
         slf.get().get_assignment(
             slf.py(),
@@ -163,14 +191,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::String),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_integer_assignment(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyInt>,
+        log_assignment: bool,
     ) -> PyResult<PyObject> {
         slf.get().get_assignment(
             slf.py(),
@@ -179,14 +210,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Integer),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_numeric_assignment(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyFloat>,
+        log_assignment: bool,
     ) -> PyResult<PyObject> {
         slf.get().get_assignment(
             slf.py(),
@@ -195,14 +229,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Numeric),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_boolean_assignment(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyBool>,
+        log_assignment: bool,
     ) -> PyResult<PyObject> {
         slf.get().get_assignment(
             slf.py(),
@@ -211,14 +248,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Boolean),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_json_assignment(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: PyObject,
+        log_assignment: bool,
     ) -> PyResult<PyObject> {
         slf.get().get_assignment(
             slf.py(),
@@ -227,15 +267,18 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Json),
             default.into_any(),
+            log_assignment,
         )
     }
 
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_string_assignment_details(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyString>,
+        log_assignment: bool,
     ) -> PyResult<EvaluationResult> {
         slf.get().get_assignment_details(
             slf.py(),
@@ -244,14 +287,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::String),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_integer_assignment_details(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyInt>,
+        log_assignment: bool,
     ) -> PyResult<EvaluationResult> {
         slf.get().get_assignment_details(
             slf.py(),
@@ -260,14 +306,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Integer),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_numeric_assignment_details(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyFloat>,
+        log_assignment: bool,
     ) -> PyResult<EvaluationResult> {
         slf.get().get_assignment_details(
             slf.py(),
@@ -276,14 +325,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Numeric),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_boolean_assignment_details(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyBool>,
+        log_assignment: bool,
     ) -> PyResult<EvaluationResult> {
         slf.get().get_assignment_details(
             slf.py(),
@@ -292,14 +344,17 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Boolean),
             default.into_any(),
+            log_assignment,
         )
     }
+    #[pyo3(signature = (flag_key, subject_key, subject_attributes, default, *, log_assignment=true))]
     fn get_json_assignment_details(
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
         subject_attributes: Attributes,
         default: Py<PyAny>,
+        log_assignment: bool,
     ) -> PyResult<EvaluationResult> {
         slf.get().get_assignment_details(
             slf.py(),
@@ -308,9 +363,53 @@ impl EppoClient {
             subject_attributes,
             Some(VariationType::Json),
             default.into_any(),
+            log_assignment,
         )
     }
 
+    /// Evaluates all flags (or only `flag_keys` if provided) for the same subject at once.
+    ///
+    /// This is cheaper than calling typed assignment methods for every flag because the subject
+    /// is prepared only once. Values are not checked against the flag type.
+    ///
+    /// Returns:
+    ///     Dict[str, Any]: Assigned value keyed by flag key. Flags that fail to evaluate or don't
+    ///         assign a variation to the subject are omitted.
+    ///
+    /// Example:
+    /// assignments = client.get_all_assignments("subject_key", {}, ["flag-a", "flag-b"])
+    /// flag_a = assignments.get("flag-a", "default")
+    #[pyo3(signature = (subject_key, subject_attributes, flag_keys=None, *, log_assignment=true))]
+    fn get_all_assignments<'py>(
+        slf: &Bound<'py, EppoClient>,
+        subject_key: Str,
+        subject_attributes: Attributes,
+        flag_keys: Option<Vec<String>>,
+        log_assignment: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let py = slf.py();
+        let this = slf.get();
+        let flag_keys = flag_keys
+            .as_ref()
+            .map(|flag_keys| flag_keys.iter().map(String::as_str).collect::<Vec<_>>());
+        let assignments = this.evaluator.get_all_assignments(
+            &subject_key,
+            &subject_attributes.into(),
+            flag_keys.as_deref(),
+        );
+
+        let dict = PyDict::new_bound(py);
+        for (flag_key, assignment) in assignments {
+            if let Some(event) = assignment.event.filter(|_| log_assignment) {
+                if let Err(err) = this.log_assignment_event(py, event) {
+                    log::warn!(target: "eppo", "error logging assignment event: {err}")
+                }
+            }
+            dict.set_item(flag_key, assignment.value.try_to_pyobject(py)?)?;
+        }
+        Ok(dict)
+    }
+
     /// Determines the bandit action for a given subject based on the provided bandit key and subject attributes.
     ///
     /// This method performs the following steps:
@@ -326,9 +425,10 @@ impl EppoClient {
     ///     subject_key (str): The key identifying the subject.
     ///     subject_context (Union[ContextAttributes, Attributes]): The subject context.
     ///         If supplying an ActionAttributes, it gets converted to an ActionContexts instance
-    ///     actions (Union[ActionContexts, ActionAttributes]): The dictionary that maps action keys
+    ///     actions (Union[ActionContexts, ActionAttributes, Sequence[str]]): The dictionary that maps action keys
     ///         to their context of actions with their contexts.
     ///         If supplying an ActionAttributes, it gets converted to an ActionContexts instance.
+    ///         If actions have no attributes, a list of action keys may be supplied instead.
     ///     default (str): The default variation to use if an error is encountered retrieving the
     ///         assigned variation.
     ///
@@ -364,21 +464,22 @@ impl EppoClient {
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
-        #[pyo3(from_py_with = "context_attributes_from_py")] subject_context: RefOrOwned<
-            ContextAttributes,
-            PyRef<ContextAttributes>,
-        >,
-        #[pyo3(from_py_with = "actions_from_py")] actions: HashMap<Str, ContextAttributes>,
+        subject_context: &Bound<PyAny>,
+        actions: &Bound<PyAny>,
         default: Str,
     ) -> PyResult<EvaluationResult> {
         let py = slf.py();
         let this = slf.get();
+        let subject_context =
+            context_attributes_from_py(subject_context, &this.categorical_attributes)?;
+        let actions = actions_from_py(actions, &this.categorical_attributes)?;
 
         let mut result = this.evaluator.get_bandit_action(
             flag_key,
             &subject_key,
             &subject_context,
-            &actions,
+            actions.as_actions(),
+            None,
             &default,
         );
 
@@ -397,21 +498,22 @@ impl EppoClient {
         slf: &Bound<EppoClient>,
         flag_key: &str,
         subject_key: Str,
-        #[pyo3(from_py_with = "context_attributes_from_py")] subject_context: RefOrOwned<
-            ContextAttributes,
-            PyRef<ContextAttributes>,
-        >,
-        #[pyo3(from_py_with = "actions_from_py")] actions: HashMap<Str, ContextAttributes>,
+        subject_context: &Bound<PyAny>,
+        actions: &Bound<PyAny>,
         default: Str,
     ) -> PyResult<EvaluationResult> {
         let py = slf.py();
         let this = slf.get();
+        let subject_context =
+            context_attributes_from_py(subject_context, &this.categorical_attributes)?;
+        let actions = actions_from_py(actions, &this.categorical_attributes)?;
 
         let (mut result, details) = this.evaluator.get_bandit_action_details(
             flag_key,
             &subject_key,
             &subject_context,
-            &actions,
+            actions.as_actions(),
+            None,
             &default,
         );
 
@@ -425,6 +527,57 @@ impl EppoClient {
         EvaluationResult::from_bandit_result(py, result, Some(details))
     }
 
+    /// Evaluate a contextual bandit and return up to `k` best actions for the subject, ranked by
+    /// the bandit. The first result is the same action as returned by get_bandit_action().
+    ///
+    /// If `allowed_actions` is set, only actions with keys in the set are considered.
+    ///
+    /// A bandit event is logged for every returned action. If the flag variation is not a bandit,
+    /// a single result without action is returned.
+    #[pyo3(signature = (flag_key, subject_key, subject_context, actions, default, k, allowed_actions=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn get_bandit_actions_ranked(
+        slf: &Bound<EppoClient>,
+        flag_key: &str,
+        subject_key: Str,
+        subject_context: &Bound<PyAny>,
+        actions: &Bound<PyAny>,
+        default: Str,
+        k: usize,
+        allowed_actions: Option<HashSet<Str>>,
+    ) -> PyResult<Vec<EvaluationResult>> {
+        let py = slf.py();
+        let this = slf.get();
+        let subject_context =
+            context_attributes_from_py(subject_context, &this.categorical_attributes)?;
+        let actions = actions_from_py(actions, &this.categorical_attributes)?;
+
+        let results = this.evaluator.get_bandit_actions_ranked(
+            &BanditRequest {
+                flag_key,
+                subject_key: &subject_key,
+                subject_attributes: &subject_context,
+                actions: actions.as_actions(),
+                default_variation: &default,
+            },
+            k,
+            allowed_actions.as_ref(),
+        );
+
+        results
+            .into_iter()
+            .map(|mut result| {
+                if let Some(event) = result.assignment_event.take() {
+                    let _ = this.log_assignment_event(py, event);
+                }
+                if let Some(event) = result.bandit_event.take() {
+                    let _ = this.log_bandit_event(py, event);
+                }
+                EvaluationResult::from_bandit_result(py, result, None)
+            })
+            .collect()
+    }
+
     fn get_configuration(&self) -> Option<Configuration> {
         self.configuration_store
             .get_configuration()
@@ -450,16 +603,81 @@ impl EppoClient {
 
     /// Wait for configuration to get fetches.
     ///
+    /// If `timeout_seconds` is provided, raises an error if configuration is not fetched within
+    /// the timeout.
+    ///
+    /// This method releases GIL, so other Python thread can make progress.
+    #[pyo3(signature = (timeout_seconds=None))]
+    fn wait_for_initialization(&self, py: Python, timeout_seconds: Option<f64>) -> PyResult<()> {
+        let timeout = timeout_seconds
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+        if let Some(poller) = &self.poller_thread {
+            py.allow_threads(|| match timeout {
+                Some(timeout) => poller.wait_for_configuration_timeout(timeout),
+                None => poller.wait_for_configuration(),
+            })
+            .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+        } else {
+            Err(PyRuntimeError::new_err("poller is disabled"))
+        }
+    }
+
+    /// Fetch configuration immediately instead of waiting for the next poll, and wait for the fetch
+    /// to complete. Concurrent calls share the same fetch.
+    ///
+    /// Raises an error if the fetch failed or poller is disabled.
+    ///
     /// This method releases GIL, so other Python thread can make progress.
-    fn wait_for_initialization(&self, py: Python) -> PyResult<()> {
+    fn refresh_configuration(&self, py: Python) -> PyResult<()> {
         if let Some(poller) = &self.poller_thread {
-            py.allow_threads(|| poller.wait_for_configuration())
+            py.allow_threads(|| poller.poll_now())
                 .map_err(|err| PyRuntimeError::new_err(err.to_string()))
         } else {
             Err(PyRuntimeError::new_err("poller is disabled"))
         }
     }
 
+    /// Returns the current status of the poller without blocking. This can be used for readiness
+    /// probes.
+    ///
+    /// Returns a dict with the following keys:
+    /// - `status`: one of `"pending"`, `"ready"`, or `"failed"`.
+    /// - `last_success`: ISO 8601 timestamp of the last successful fetch or `None`.
+    /// - `error`: error message of the last failed fetch or `None`.
+    /// - `consecutive_failures`: number of failed fetches since the last successful one.
+    ///
+    /// Returns `None` if poller is disabled.
+    fn get_poller_status<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let Some(poller) = &self.poller_thread else {
+            return Ok(None);
+        };
+
+        let (status, last_success, error, consecutive_failures) = match poller.status() {
+            PollerStatus::Pending => ("pending", None, None, 0),
+            PollerStatus::Ready { last_success } => ("ready", Some(last_success), None, 0),
+            PollerStatus::Failed {
+                error,
+                consecutive_failures,
+                last_success,
+            } => (
+                "failed",
+                last_success,
+                Some(error.to_string()),
+                consecutive_failures,
+            ),
+        };
+
+        let dict = PyDict::new_bound(py);
+        dict.set_item("status", status)?;
+        dict.set_item("last_success", last_success.map(|it| it.to_rfc3339()))?;
+        dict.set_item("error", error)?;
+        dict.set_item("consecutive_failures", consecutive_failures)?;
+        Ok(Some(dict))
+    }
+
     /// Returns a set of all flag keys that have been initialized.
     /// This can be useful to debug the initialization process.
     ///
@@ -520,35 +738,65 @@ where
     }
 }
 
+/// Plain attribute dicts are converted with number attributes listed in `categorical` treated as
+/// categorical.
 fn context_attributes_from_py<'py>(
     obj: &'py Bound<'py, PyAny>,
+    categorical: &HashSet<Str>,
 ) -> PyResult<RefOrOwned<ContextAttributes, PyRef<'py, ContextAttributes>>> {
     if let Ok(attrs) = obj.downcast::<ContextAttributes>() {
         return Ok(RefOrOwned::Ref(attrs.borrow()));
     }
     if let Ok(attrs) = Attributes::extract_bound(obj) {
-        return Ok(RefOrOwned::Owned(attrs.into()));
+        return Ok(RefOrOwned::Owned(
+            ContextAttributes::from_attributes_with_categorical(attrs, categorical),
+        ));
     }
     Err(PyTypeError::new_err(format!(
         "attributes must be either ContextAttributes or Attributes"
     )))
 }
 
-fn actions_from_py(obj: &Bound<PyAny>) -> PyResult<HashMap<Str, ContextAttributes>> {
+/// Actions supplied from Python.
+enum ActionsArg {
+    /// List of action keys without attributes.
+    Keys(Vec<Str>),
+    WithAttributes(HashMap<Str, ContextAttributes>),
+}
+
+impl ActionsArg {
+    fn as_actions(&self) -> Actions<'_> {
+        match self {
+            ActionsArg::Keys(keys) => Actions::Keys(keys),
+            ActionsArg::WithAttributes(actions) => Actions::WithAttributes(actions),
+        }
+    }
+}
+
+fn actions_from_py(obj: &Bound<PyAny>, categorical: &HashSet<Str>) -> PyResult<ActionsArg> {
     if let Ok(result) = FromPyObject::extract_bound(&obj) {
-        return Ok(result);
+        return Ok(ActionsArg::WithAttributes(result));
     }
 
     if let Ok(result) = HashMap::<Str, Attributes>::extract_bound(&obj) {
         let result = result
             .into_iter()
-            .map(|(name, attrs)| (name, ContextAttributes::from(attrs)))
+            .map(|(name, attrs)| {
+                (
+                    name,
+                    ContextAttributes::from_attributes_with_categorical(attrs, categorical),
+                )
+            })
             .collect();
-        return Ok(result);
+        return Ok(ActionsArg::WithAttributes(result));
+    }
+
+    if let Ok(result) = Vec::<Str>::extract_bound(&obj) {
+        return Ok(ActionsArg::Keys(result));
     }
 
     Err(PyTypeError::new_err(format!(
-        "action attributes must be either ContextAttributes or Attributes"
+        "actions must be either a dict of ContextAttributes or Attributes, or a list of action keys"
     )))
 }
 
@@ -564,29 +812,100 @@ impl EppoClient {
         let evaluator = Evaluator::new(EvaluatorConfig {
             configuration_store: configuration_store.clone(),
             sdk_metadata: SDK_METADATA,
+            clock: Arc::new(SystemClock),
+            observer: None,
+            sharder: None,
+            default_attributes: Arc::default(),
+            coerce_numeric_types: false,
+            bandit_evaluation_options: Default::default(),
+        });
+
+        let request_timeout =
+            Duration::try_from_secs_f64(config.request_timeout_seconds).map_err(|err| {
+                PyValueError::new_err(format!("Invalid value for request_timeout_seconds: {err}"))
+            })?;
+
+        let block_on_initial_fetch = config
+            .initial_fetch_timeout_seconds
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|err| {
+                PyValueError::new_err(format!(
+                    "Invalid value for initial_fetch_timeout_seconds: {err}"
+                ))
+            })?;
+
+        let on_configuration_change = config.on_configuration_change.as_ref().map(|callback| {
+            let callback = callback.clone_ref(py);
+            Arc::new(move |configuration: &Arc<eppo_core::Configuration>| {
+                Python::with_gil(|py| {
+                    let configuration = Configuration {
+                        configuration: Arc::clone(configuration),
+                    };
+                    if let Err(err) = callback.call1(py, (configuration,)) {
+                        log::warn!(target: "eppo", "error in on_configuration_change callback: {err}");
+                    }
+                })
+            }) as ConfigurationChangeCallback
+        });
+
+        let on_error = config.on_error.as_ref().map(|callback| {
+            let callback = callback.clone_ref(py);
+            Arc::new(move |err: &eppo_core::Error| {
+                Python::with_gil(|py| {
+                    let exception = PyRuntimeError::new_err(err.to_string()).into_value(py);
+                    // Let the callback tell transient failures from ones that stop the poller.
+                    if let Err(err) =
+                        exception.setattr(py, intern!(py, "is_retriable"), err.is_retriable())
+                    {
+                        log::warn!(target: "eppo", "failed to classify error for on_error callback: {err}");
+                    }
+                    if let Err(err) = callback.call1(py, (exception,)) {
+                        log::warn!(target: "eppo", "error in on_error callback: {err}");
+                    }
+                })
+            }) as ErrorCallback
         });
 
         let poller_thread = config
             .poll_interval_seconds
             .map(|poll_interval_seconds| {
-                PollerThread::start_with_config(
-                    ConfigurationFetcher::new(
-                        eppo_core::configuration_fetcher::ConfigurationFetcherConfig {
-                            base_url: config.base_url.clone(),
-                            api_key: config.api_key.clone(),
-                            sdk_metadata: SDK_METADATA,
-                        },
-                    ),
-                    configuration_store.clone(),
-                    PollerThreadConfig {
-                        interval: Duration::from_secs(poll_interval_seconds.into()),
-                        jitter: Duration::from_secs(config.poll_jitter_seconds),
+                let fetcher = ConfigurationFetcher::new(
+                    eppo_core::configuration_fetcher::ConfigurationFetcherConfig {
+                        base_url: config.base_url.clone(),
+                        base_urls: Vec::new(),
+                        api_key: config.api_key.clone(),
+                        sdk_metadata: SDK_METADATA,
+                        headers: Vec::new(),
+                        request_timeout,
+                        obfuscated: false,
+                        always_fetch_bandits: false,
+                        query_params: Vec::new(),
+                        max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+                        auth_mode: AuthMode::QueryParam,
                     },
-                )
+                );
+                let poller_config = PollerThreadConfig {
+                    interval: Duration::from_secs(poll_interval_seconds.into()),
+                    jitter: Duration::from_secs(config.poll_jitter_seconds),
+                    on_configuration_change,
+                    on_error,
+                    block_on_initial_fetch,
+                    ..PollerThreadConfig::default()
+                };
+                // Release GIL while waiting for the initial fetch, so callbacks can run.
+                py.allow_threads(|| {
+                    PollerThread::start_with_config(
+                        fetcher,
+                        configuration_store.clone(),
+                        poller_config,
+                    )
+                })
             })
             .transpose()
             .map_err(|err| {
-                // This should normally never happen.
+                // This should normally never happen unless initial fetch is requested and fails
+                // with a non-retriable error (e.g., invalid API key).
                 PyRuntimeError::new_err(format!("unable to start poller thread: {err}"))
             })?;
 
@@ -603,6 +922,12 @@ impl EppoClient {
                 })?
                 .clone_ref(py),
             is_graceful_mode: AtomicBool::new(config.is_graceful_mode),
+            categorical_attributes: config
+                .categorical_attributes
+                .iter()
+                .cloned()
+                .map(Str::from)
+                .collect(),
         })
     }
 
@@ -614,6 +939,7 @@ impl EppoClient {
         subject_attributes: Attributes,
         expected_type: Option<VariationType>,
         default: Py<PyAny>,
+        log_assignment: bool,
     ) -> PyResult<PyObject> {
         let result = self.evaluator.get_assignment(
             &flag_key,
@@ -634,7 +960,7 @@ impl EppoClient {
         };
 
         if let Some(assignment) = assignment {
-            if let Some(event) = assignment.event {
+            if let Some(event) = assignment.event.filter(|_| log_assignment) {
                 if let Err(err) = self.log_assignment_event(py, event) {
                     log::warn!(target: "eppo", "error logging assignment event: {err}")
                 }
@@ -654,18 +980,24 @@ impl EppoClient {
         subject_attributes: Attributes,
         expected_type: Option<VariationType>,
         default: Py<PyAny>,
+        log_assignment: bool,
     ) -> PyResult<EvaluationResult> {
-        let (result, event) = self.evaluator.get_assignment_details(
+        let (mut result, event) = self.evaluator.get_assignment_details(
             &flag_key,
             &subject_key.into(),
             &subject_attributes.into(),
             expected_type,
         );
 
-        if let Some(event) = event {
-            if let Err(err) = self.log_assignment_event(py, event) {
-                log::warn!(target: "eppo", "error logging assignment event: {err}")
+        if log_assignment {
+            if let Some(event) = event {
+                if let Err(err) = self.log_assignment_event(py, event) {
+                    log::warn!(target: "eppo", "error logging assignment event: {err}")
+                }
             }
+        } else {
+            drop(event);
+            Arc::make_mut(&mut result.evaluation_details).logging_suppressed = true;
         }
 
         EvaluationResult::from_details(py, result, default)
@@ -1,8 +1,13 @@
-use std::num::NonZeroU64;
+use std::{collections::HashSet, num::NonZeroU64};
 
 use pyo3::{exceptions::PyValueError, prelude::*, PyTraverseError, PyVisit};
 
-use eppo_core::{configuration_fetcher::DEFAULT_BASE_URL, poller_thread::PollerThreadConfig};
+use eppo_core::{
+    configuration_fetcher::{
+        validate_api_key, validate_base_url, DEFAULT_BASE_URL, DEFAULT_REQUEST_TIMEOUT,
+    },
+    poller_thread::PollerThreadConfig,
+};
 
 use crate::{assignment_logger::AssignmentLogger, configuration::Configuration};
 
@@ -14,7 +19,12 @@ pub struct ClientConfig {
     pub(crate) is_graceful_mode: bool,
     pub(crate) poll_interval_seconds: Option<NonZeroU64>,
     pub(crate) poll_jitter_seconds: u64,
+    pub(crate) request_timeout_seconds: f64,
+    pub(crate) initial_fetch_timeout_seconds: Option<f64>,
     pub(crate) initial_configuration: Option<Py<Configuration>>,
+    pub(crate) on_configuration_change: Option<PyObject>,
+    pub(crate) on_error: Option<PyObject>,
+    pub(crate) categorical_attributes: HashSet<String>,
 }
 
 #[pymethods]
@@ -28,7 +38,12 @@ impl ClientConfig {
             is_graceful_mode=true,
             poll_interval_seconds=Some(NonZeroU64::new(PollerThreadConfig::DEFAULT_POLL_INTERVAL.as_secs()).unwrap()),
             poll_jitter_seconds=PollerThreadConfig::DEFAULT_POLL_JITTER.as_secs(),
-            initial_configuration=None
+            request_timeout_seconds=DEFAULT_REQUEST_TIMEOUT.as_secs_f64(),
+            initial_fetch_timeout_seconds=None,
+            initial_configuration=None,
+            on_configuration_change=None,
+            on_error=None,
+            categorical_attributes=None
         ))]
     fn new(
         api_key: String,
@@ -37,11 +52,24 @@ impl ClientConfig {
         is_graceful_mode: bool,
         poll_interval_seconds: Option<NonZeroU64>,
         poll_jitter_seconds: u64,
+        request_timeout_seconds: f64,
+        initial_fetch_timeout_seconds: Option<f64>,
         initial_configuration: Option<Py<Configuration>>,
+        on_configuration_change: Option<PyObject>,
+        on_error: Option<PyObject>,
+        categorical_attributes: Option<HashSet<String>>,
     ) -> PyResult<ClientConfig> {
-        if api_key.is_empty() {
+        validate_api_key(&api_key).map_err(|err| PyValueError::new_err(err.to_string()))?;
+        validate_base_url(&base_url).map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+        if !(request_timeout_seconds > 0.0) {
+            return Err(PyValueError::new_err(
+                "Invalid value for request_timeout_seconds: must be positive",
+            ));
+        }
+        if initial_fetch_timeout_seconds.is_some_and(|timeout| !(timeout > 0.0)) {
             return Err(PyValueError::new_err(
-                "Invalid value for api_key: cannot be blank",
+                "Invalid value for initial_fetch_timeout_seconds: must be positive",
             ));
         }
 
@@ -52,10 +80,31 @@ impl ClientConfig {
             is_graceful_mode,
             poll_interval_seconds,
             poll_jitter_seconds,
+            request_timeout_seconds,
+            initial_fetch_timeout_seconds,
             initial_configuration,
+            on_configuration_change,
+            on_error,
+            categorical_attributes: categorical_attributes.unwrap_or_default(),
         })
     }
 
+    // Overriding the default setter to validate `api_key`.
+    #[setter]
+    fn set_api_key(&mut self, api_key: String) -> PyResult<()> {
+        validate_api_key(&api_key).map_err(|err| PyValueError::new_err(err.to_string()))?;
+        self.api_key = api_key;
+        Ok(())
+    }
+
+    // Overriding the default setter to validate `base_url`.
+    #[setter]
+    fn set_base_url(&mut self, base_url: String) -> PyResult<()> {
+        validate_base_url(&base_url).map_err(|err| PyValueError::new_err(err.to_string()))?;
+        self.base_url = base_url;
+        Ok(())
+    }
+
     // Overriding the default setter to make `assignment_logger` non-optional.
     #[setter]
     fn set_assignment_logger(&mut self, assignment_logger: Py<AssignmentLogger>) {
@@ -70,9 +119,17 @@ impl ClientConfig {
         if let Some(assignment_logger) = &self.assignment_logger {
             visit.call(assignment_logger)?;
         }
+        if let Some(on_configuration_change) = &self.on_configuration_change {
+            visit.call(on_configuration_change)?;
+        }
+        if let Some(on_error) = &self.on_error {
+            visit.call(on_error)?;
+        }
         Ok(())
     }
     fn __clear__(&mut self) {
         self.assignment_logger = None;
+        self.on_configuration_change = None;
+        self.on_error = None;
     }
 }
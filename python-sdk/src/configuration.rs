@@ -8,7 +8,9 @@ use pyo3::{
     types::PySet,
 };
 
-use eppo_core::{ufc::UniversalFlagConfig, Configuration as CoreConfiguration};
+use eppo_core::{
+    pyo3::TryToPyObject, ufc::UniversalFlagConfig, Configuration as CoreConfiguration,
+};
 
 use crate::SDK_METADATA;
 
@@ -65,6 +67,24 @@ impl Configuration {
         )
     }
 
+    /// Return problems found while parsing flags configuration.
+    ///
+    /// The result is a dict with `failedFlags`, `failedConditions`, and `unknownOperators` (lists
+    /// of `[flag_key, error]` pairs), `unknownFields` (list of field paths), `totalFlags`, and
+    /// `totalBanditRefs`. This can be useful to debug flags that are missing from the
+    /// configuration or targeting rules that never match.
+    fn get_parse_diagnostics(&self, py: Python) -> PyResult<PyObject> {
+        self.configuration.parse_diagnostics().try_to_pyobject(py)
+    }
+
+    /// Return approximate memory occupied by the configuration.
+    ///
+    /// The result is a dict with `flagsBytes`, `banditsBytes`, `rawJsonBytes`, `flagCount`, and
+    /// `banditCount`. Numbers are estimates that are useful to size containers, not exact values.
+    fn get_memory_usage(&self, py: Python) -> PyResult<PyObject> {
+        self.configuration.memory_usage().try_to_pyobject(py)
+    }
+
     /// Return bytes representing flags configuration.
     ///
     /// It should be treated as opaque and passed on to another Eppo client (e.g., javascript client
@@ -48,4 +48,6 @@ def test_event_format():
     assert event["allocation"] == "partial-example"
     assert event["featureFlag"] == "regex-flag"
     assert event["experiment"] == "regex-flag-partial-example"
+    assert event["variationType"] == "STRING"
+    assert event["variationValue"] == "partial-example"
     assert event["metaData"]["sdkName"] == "python"
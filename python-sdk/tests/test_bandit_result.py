@@ -20,3 +20,19 @@ def test_bandit_result_to_string_action():
 def test_bandit_result_to_string_number_variation():
     result = BanditResult(variation=13, action=None)
     assert result.to_string() == "13"
+
+
+@pytest.mark.rust_only
+def test_bandit_result_allocation():
+    result = BanditResult(
+        variation="variation",
+        action="action",
+        allocation_key="allocation",
+        experiment="flag-allocation",
+    )
+    assert result.allocation_key == "allocation"
+    assert result.experiment == "flag-allocation"
+
+    result = BanditResult(variation="variation", action=None)
+    assert result.allocation_key is None
+    assert result.experiment is None
@@ -86,3 +86,52 @@ def test_get_bandit_action_details(test_case):
             f"Flag {flag} failed for subject {subject['subjectKey']}:"
             f"expected action {subject['assignment']['action']}, got {result.action}"
         )
+
+
+@pytest.mark.rust_only
+@pytest.mark.parametrize("test_case", test_data, ids=lambda x: x["file_name"])
+def test_get_bandit_action_with_action_keys(test_case):
+    client = eppo_client.get_instance()
+
+    flag = test_case["flag"]
+    default_value = test_case["defaultValue"]
+
+    for subject in test_case["subjects"]:
+        action_keys = [action["actionKey"] for action in subject["actions"]]
+
+        result = client.get_bandit_action(
+            flag, subject["subjectKey"], {}, action_keys, default_value
+        )
+        expected = client.get_bandit_action(
+            flag,
+            subject["subjectKey"],
+            {},
+            {key: ContextAttributes.empty() for key in action_keys},
+            default_value,
+        )
+
+        assert result.variation == expected.variation
+        assert result.action == expected.action
+
+
+@pytest.mark.rust_only
+@pytest.mark.parametrize("test_case", test_data, ids=lambda x: x["file_name"])
+def test_get_bandit_action_allocation(test_case):
+    client = eppo_client.get_instance()
+
+    flag = test_case["flag"]
+    default_value = test_case["defaultValue"]
+
+    for subject in test_case["subjects"]:
+        result = client.get_bandit_action(
+            flag,
+            subject["subjectKey"],
+            {},
+            [action["actionKey"] for action in subject["actions"]],
+            default_value,
+        )
+
+        # Bandit is only evaluated if the flag has assigned a bandit variation.
+        if result.action is not None:
+            assert result.allocation_key is not None
+            assert result.experiment == f"{flag}-{result.allocation_key}"
@@ -87,6 +87,8 @@ def make_bandit_event(
     action="action",
     action_probability=1.0,
     optimality_gap=None,
+    action_score=None,
+    gamma=None,
     evaluation=None,
     bandit_data=None,
     subject_context_attributes=None,
@@ -101,6 +103,8 @@ def make_bandit_event(
         "action": action,
         "actionProbability": action_probability,
         "optimalityGap": optimality_gap,
+        "actionScore": action_score,
+        "gamma": gamma,
         "modelVersion": model_version,
         "timestamp": timestamp,
         "subjectNumericAttributes": {},
@@ -24,6 +24,39 @@ def test_wait_for_initialization():
     assert client.is_initialized() == True
 
 
+@pytest.mark.rust_only
+def test_get_poller_status_ready():
+    client = init("ufc", wait_for_init=True)
+    status = client.get_poller_status()
+    assert status["status"] == "ready"
+    assert status["last_success"] is not None
+    assert status["error"] is None
+    assert status["consecutive_failures"] == 0
+
+
+@pytest.mark.rust_only
+def test_log_assignment_false_does_not_log():
+    class RecordingLogger(AssignmentLogger):
+        def __init__(self):
+            self.events = []
+
+        def log_assignment(self, event):
+            self.events.append(event)
+
+    logger = RecordingLogger()
+    client = init("ufc", wait_for_init=True, assignment_logger=logger)
+
+    client.get_numeric_assignment(
+        "numeric_flag", "alice", {}, 0.0, log_assignment=False
+    )
+    result = client.get_numeric_assignment_details(
+        "numeric_flag", "alice", {}, 0.0, log_assignment=False
+    )
+
+    assert logger.events == []
+    assert result.evaluation_details["loggingSuppressed"] == True
+
+
 def test_get_flag_keys_none():
     client = init("ufc", wait_for_init=False)
     assert client.get_flag_keys() == set()
@@ -50,3 +83,45 @@ def test_get_bandit_keys_some():
     assert isinstance(keys, set)
     assert len(keys) != 0
     assert "banner_bandit" in keys
+
+
+@pytest.mark.rust_only
+def test_on_error_receives_retriable_flag():
+    errors = []
+    eppo_client.init(
+        Config(
+            api_key="test",
+            # Nothing listens on this port, so every fetch fails with a connection error.
+            base_url="http://127.0.0.1:1/api",
+            assignment_logger=AssignmentLogger(),
+            on_error=errors.append,
+        )
+    )
+
+    for _ in range(100):
+        if errors:
+            break
+        sleep(0.1)
+
+    assert errors
+    assert isinstance(errors[0], RuntimeError)
+    assert errors[0].is_retriable
+
+
+@pytest.mark.rust_only
+def test_initial_fetch_timeout_continues_after_retriable_error():
+    errors = []
+    client = eppo_client.init(
+        Config(
+            api_key="test",
+            # Nothing listens on this port, so every fetch fails with a connection error.
+            base_url="http://127.0.0.1:1/api",
+            assignment_logger=AssignmentLogger(),
+            initial_fetch_timeout_seconds=0.5,
+            on_error=errors.append,
+        )
+    )
+
+    # Retriable errors don't fail init(), and on_error is called while init() waits.
+    assert client.is_initialized() == False
+    assert errors
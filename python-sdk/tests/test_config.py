@@ -13,6 +13,27 @@ class TestConfig:
         with pytest.raises(ValueError):
             Config(api_key="", assignment_logger=AssignmentLogger())
 
+    @pytest.mark.rust_only
+    def test_requires_non_blank_key(self):
+        with pytest.raises(ValueError):
+            Config(api_key="  ", assignment_logger=AssignmentLogger())
+
+    @pytest.mark.rust_only
+    def test_cant_set_blank_key(self):
+        config = Config(api_key="test-key", assignment_logger=AssignmentLogger())
+        with pytest.raises(ValueError):
+            config.api_key = ""
+        assert config.api_key == "test-key"
+
+    @pytest.mark.rust_only
+    def test_requires_valid_base_url(self):
+        with pytest.raises(ValueError):
+            Config(
+                api_key="test-key",
+                assignment_logger=AssignmentLogger(),
+                base_url="not a url",
+            )
+
     def test_requires_api_key(self):
         # Python SDK raises Pydantic's ValidationError.
         # Python-on-rust raises TypeError.
@@ -61,3 +82,46 @@ class TestConfig:
                 assignment_logger=AssignmentLogger(),
                 poll_interval_seconds=0,
             )
+
+    @pytest.mark.rust_only
+    def test_on_configuration_change_defaults_to_none(self):
+        config = Config(api_key="test-key", assignment_logger=AssignmentLogger())
+        assert config.on_configuration_change is None
+
+    @pytest.mark.rust_only
+    def test_accepts_on_configuration_change(self):
+        def callback(configuration):
+            pass
+
+        config = Config(
+            api_key="test-key",
+            assignment_logger=AssignmentLogger(),
+            on_configuration_change=callback,
+        )
+        assert config.on_configuration_change is callback
+
+    @pytest.mark.rust_only
+    def test_accepts_on_error(self):
+        def callback(error):
+            pass
+
+        config = Config(
+            api_key="test-key",
+            assignment_logger=AssignmentLogger(),
+            on_error=callback,
+        )
+        assert config.on_error is callback
+
+    @pytest.mark.rust_only
+    def test_categorical_attributes_default_to_empty(self):
+        config = Config(api_key="test-key", assignment_logger=AssignmentLogger())
+        assert config.categorical_attributes == set()
+
+    @pytest.mark.rust_only
+    def test_accepts_categorical_attributes(self):
+        config = Config(
+            api_key="test-key",
+            assignment_logger=AssignmentLogger(),
+            categorical_attributes={"zip_code"},
+        )
+        assert config.categorical_attributes == {"zip_code"}
@@ -56,6 +56,19 @@ def test_from_dict():
     }
 
 
+@pytest.mark.rust_only
+def test_from_dict_categorical_attributes():
+    attrs = ContextAttributes.from_dict(
+        {
+            "age": 30,
+            "zip_code": 94107,
+        },
+        categorical_attributes={"zip_code"},
+    )
+    assert attrs.numeric_attributes == {"age": 30.0}
+    assert attrs.categorical_attributes == {"zip_code": 94107.0}
+
+
 # `bool` is a subclass of `int` in Python, so it was incorrectly
 # captured as numeric attribute:
 # https://linear.app/eppo/issue/FF-3106/
@@ -45,3 +45,87 @@ def test_update_configuration():
     )
 
     assert client.is_initialized()
+
+
+def test_array_attributes_match_one_of():
+    client = eppo_client.init(
+        Config(
+            api_key="test",
+            poll_interval_seconds=None,
+            assignment_logger=AssignmentLogger(),
+            initial_configuration=Configuration(
+                flags_configuration=b"""{
+                  "createdAt": "2024-09-09T10:18:15.988Z",
+                  "format": "SERVER",
+                  "environment": {"name": "test"},
+                  "flags": {
+                    "plan": {
+                      "key": "plan",
+                      "enabled": true,
+                      "variationType": "STRING",
+                      "totalShards": 10000,
+                      "variations": {"pro": {"key": "pro", "value": "pro"}},
+                      "allocations": [{
+                        "key": "pro-users",
+                        "rules": [{"conditions": [
+                          {"attribute": "entitlements", "operator": "ONE_OF", "value": ["pro"]}
+                        ]}],
+                        "splits": [{"variationKey": "pro", "shards": []}],
+                        "doLog": false
+                      }]
+                    }
+                  }
+                }"""
+            ),
+        )
+    )
+
+    assert (
+        client.get_string_assignment(
+            "plan", "alice", {"entitlements": ["beta", "pro"]}, "default"
+        )
+        == "pro"
+    )
+    assert (
+        client.get_string_assignment(
+            "plan", "bob", {"entitlements": ("beta",)}, "default"
+        )
+        == "default"
+    )
+
+
+def test_get_all_assignments():
+    client = eppo_client.init(
+        Config(
+            api_key="test",
+            poll_interval_seconds=None,
+            assignment_logger=AssignmentLogger(),
+            initial_configuration=Configuration(
+                flags_configuration=b"""{
+                  "createdAt": "2024-09-09T10:18:15.988Z",
+                  "format": "SERVER",
+                  "environment": {"name": "test"},
+                  "flags": {
+                    "color": {
+                      "key": "color",
+                      "enabled": true,
+                      "variationType": "STRING",
+                      "totalShards": 10000,
+                      "variations": {"blue": {"key": "blue", "value": "blue"}},
+                      "allocations": [{
+                        "key": "everyone",
+                        "rules": [],
+                        "splits": [{"variationKey": "blue", "shards": []}],
+                        "doLog": false
+                      }]
+                    }
+                  }
+                }"""
+            ),
+        )
+    )
+
+    assert client.get_all_assignments("alice", {}, ["color", "unknown-flag"]) == {
+        "color": "blue",
+    }
+    assert client.get_all_assignments("alice", {}) == {"color": "blue"}
@@ -1,14 +1,26 @@
-use std::{cell::RefCell, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use eppo_core::{
-    configuration_fetcher::{ConfigurationFetcher, ConfigurationFetcherConfig},
+    configuration_fetcher::{
+        AuthMode, ConfigurationFetcher, ConfigurationFetcherConfig, DEFAULT_MAX_RESPONSE_BYTES,
+    },
     configuration_store::ConfigurationStore,
-    eval::{Evaluator, EvaluatorConfig},
+    eval::{Actions, BanditRequest, Evaluator, EvaluatorConfig},
     poller_thread::{PollerThread, PollerThreadConfig},
+    timestamp::SystemClock,
     ufc::VariationType,
-    Attributes, ContextAttributes,
+    Attributes, ContextAttributes, Str,
+};
+use magnus::{
+    error::Result, exception, prelude::*, Error, IntoValue, RArray, RHash, Ruby, TryConvert, Value,
 };
-use magnus::{error::Result, exception, prelude::*, Error, IntoValue, Ruby, TryConvert, Value};
+use serde::Deserialize;
 
 use crate::{configuration::Configuration, SDK_METADATA};
 
@@ -19,7 +31,10 @@ pub struct Config {
     base_url: String,
     poll_interval: Option<Duration>,
     poll_jitter: Duration,
+    request_timeout: Duration,
+    initial_fetch_timeout: Option<Duration>,
     log_level: Option<log::LevelFilter>,
+    categorical_attributes: HashSet<Str>,
 }
 
 impl TryConvert for Config {
@@ -30,6 +45,33 @@ impl TryConvert for Config {
         let poll_interval_seconds =
             Option::<u64>::try_convert(val.funcall("poll_interval_seconds", ())?)?;
         let poll_jitter_seconds = u64::try_convert(val.funcall("poll_jitter_seconds", ())?)?;
+        let request_timeout = {
+            let seconds = f64::try_convert(val.funcall("request_timeout_seconds", ())?)?;
+            let timeout = Duration::try_from_secs_f64(seconds)
+                .map_err(|err| Error::new(exception::arg_error(), err.to_string()))?;
+            if timeout.is_zero() {
+                return Err(Error::new(
+                    exception::arg_error(),
+                    "request_timeout_seconds must be positive",
+                ));
+            }
+            timeout
+        };
+        let initial_fetch_timeout = {
+            let seconds =
+                Option::<f64>::try_convert(val.funcall("initial_fetch_timeout_seconds", ())?)?;
+            let timeout = seconds
+                .map(Duration::try_from_secs_f64)
+                .transpose()
+                .map_err(|err| Error::new(exception::arg_error(), err.to_string()))?;
+            if timeout.is_some_and(|timeout| timeout.is_zero()) {
+                return Err(Error::new(
+                    exception::arg_error(),
+                    "initial_fetch_timeout_seconds must be positive",
+                ));
+            }
+            timeout
+        };
 
         let log_level = {
             let s = Option::<String>::try_convert(val.funcall("log_level", ())?)?;
@@ -40,12 +82,21 @@ impl TryConvert for Config {
             .transpose()?
         };
 
+        let categorical_attributes =
+            Vec::<String>::try_convert(val.funcall("categorical_attributes", ())?)?
+                .into_iter()
+                .map(Str::from)
+                .collect();
+
         Ok(Config {
             api_key,
             base_url,
             poll_interval: poll_interval_seconds.map(Duration::from_secs),
             poll_jitter: Duration::from_secs(poll_jitter_seconds),
+            request_timeout,
+            initial_fetch_timeout,
             log_level,
+            categorical_attributes,
         })
     }
 }
@@ -60,10 +111,13 @@ pub struct Client {
     // This should be safe as Ruby only uses a single OS thread, and `Client` lives in the Ruby
     // world.
     poller_thread: RefCell<Option<PollerThread>>,
+    /// Keys of number attributes that are converted to categorical when converting plain
+    /// attribute hashes for bandits.
+    categorical_attributes: HashSet<Str>,
 }
 
 impl Client {
-    pub fn new(config: Config) -> Client {
+    pub fn new(config: Config) -> Result<Client> {
         // Initialize logger
         {
             let mut builder = env_logger::Builder::from_env(
@@ -88,16 +142,34 @@ impl Client {
                 PollerThread::start_with_config(
                     ConfigurationFetcher::new(ConfigurationFetcherConfig {
                         base_url: config.base_url,
+                        base_urls: Vec::new(),
                         api_key: config.api_key,
                         sdk_metadata: SDK_METADATA,
+                        headers: Vec::new(),
+                        request_timeout: config.request_timeout,
+                        obfuscated: false,
+                        always_fetch_bandits: false,
+                        query_params: Vec::new(),
+                        max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+                        auth_mode: AuthMode::QueryParam,
                     }),
                     configuration_store.clone(),
                     PollerThreadConfig {
                         interval: poll_interval,
                         jitter: config.poll_jitter,
+                        on_configuration_change: None,
+                        block_on_initial_fetch: config.initial_fetch_timeout,
+                        ..PollerThreadConfig::default()
                     },
                 )
-                .expect("should be able to start poller thread"),
+                // This should normally never happen unless initial fetch is requested and fails
+                // with a non-retriable error (e.g., invalid API key).
+                .map_err(|err| {
+                    Error::new(
+                        exception::runtime_error(),
+                        format!("unable to start poller thread: {err}"),
+                    )
+                })?,
             )
         } else {
             None
@@ -106,13 +178,20 @@ impl Client {
         let evaluator = Evaluator::new(EvaluatorConfig {
             configuration_store: configuration_store.clone(),
             sdk_metadata: SDK_METADATA,
+            clock: Arc::new(SystemClock),
+            observer: None,
+            sharder: None,
+            default_attributes: Arc::default(),
+            coerce_numeric_types: false,
+            bandit_evaluation_options: Default::default(),
         });
 
-        Client {
+        Ok(Client {
             configuration_store,
             evaluator,
             poller_thread: RefCell::new(poller_thread),
-        }
+            categorical_attributes: config.categorical_attributes,
+        })
     }
 
     pub fn get_assignment(
@@ -140,6 +219,31 @@ impl Client {
         Ok(result.into_value_with(&ruby))
     }
 
+    pub fn get_all_assignments(
+        ruby: &Ruby,
+        rb_self: &Self,
+        subject_key: String,
+        subject_attributes: Value,
+        flag_keys: Option<Vec<String>>,
+    ) -> Result<RHash> {
+        let subject_attributes: Attributes = serde_magnus::deserialize(subject_attributes)?;
+        let flag_keys = flag_keys
+            .as_ref()
+            .map(|flag_keys| flag_keys.iter().map(String::as_str).collect::<Vec<_>>());
+
+        let assignments = rb_self.evaluator.get_all_assignments(
+            &subject_key.into(),
+            &Arc::new(subject_attributes),
+            flag_keys.as_deref(),
+        );
+
+        let hash = ruby.hash_new();
+        for (flag_key, assignment) in assignments {
+            hash.aset(&*flag_key, assignment.into_value_with(ruby))?;
+        }
+        Ok(hash)
+    }
+
     pub fn get_assignment_details(
         &self,
         flag_key: String,
@@ -170,22 +274,23 @@ impl Client {
         actions: Value,
         default_variation: String,
     ) -> Result<Value> {
-        let subject_attributes = serde_magnus::deserialize::<_, ContextAttributes>(
-            subject_attributes,
-        )
-        .map_err(|err| {
-            Error::new(
-                exception::runtime_error(),
-                format!("enexpected value for subject_attributes: {err}"),
-            )
-        })?;
-        let actions = serde_magnus::deserialize(actions)?;
+        let subject_attributes =
+            serde_magnus::deserialize::<_, ContextAttributesArg>(subject_attributes)
+                .map_err(|err| {
+                    Error::new(
+                        exception::runtime_error(),
+                        format!("enexpected value for subject_attributes: {err}"),
+                    )
+                })?
+                .into_context_attributes(&self.categorical_attributes);
+        let actions = ActionsArg::from_value(actions, &self.categorical_attributes)?;
 
         let result = self.evaluator.get_bandit_action(
             &flag_key,
             &subject_key.into(),
             &subject_attributes,
-            &actions,
+            actions.as_actions(),
+            None,
             &default_variation.into(),
         );
 
@@ -200,28 +305,68 @@ impl Client {
         actions: Value,
         default_variation: String,
     ) -> Result<Value> {
-        let subject_attributes = serde_magnus::deserialize::<_, ContextAttributes>(
-            subject_attributes,
-        )
-        .map_err(|err| {
-            Error::new(
-                exception::runtime_error(),
-                format!("enexpected value for subject_attributes: {err}"),
-            )
-        })?;
-        let actions = serde_magnus::deserialize(actions)?;
+        let subject_attributes =
+            serde_magnus::deserialize::<_, ContextAttributesArg>(subject_attributes)
+                .map_err(|err| {
+                    Error::new(
+                        exception::runtime_error(),
+                        format!("enexpected value for subject_attributes: {err}"),
+                    )
+                })?
+                .into_context_attributes(&self.categorical_attributes);
+        let actions = ActionsArg::from_value(actions, &self.categorical_attributes)?;
 
         let result = self.evaluator.get_bandit_action_details(
             &flag_key,
             &subject_key.into(),
             &subject_attributes,
-            &actions,
+            actions.as_actions(),
+            None,
             &default_variation.into(),
         );
 
         serde_magnus::serialize(&result)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_bandit_actions_ranked(
+        &self,
+        flag_key: String,
+        subject_key: String,
+        subject_attributes: Value,
+        actions: Value,
+        default_variation: String,
+        k: usize,
+        allowed_actions: Option<Vec<String>>,
+    ) -> Result<Value> {
+        let subject_attributes =
+            serde_magnus::deserialize::<_, ContextAttributesArg>(subject_attributes)
+                .map_err(|err| {
+                    Error::new(
+                        exception::runtime_error(),
+                        format!("enexpected value for subject_attributes: {err}"),
+                    )
+                })?
+                .into_context_attributes(&self.categorical_attributes);
+        let actions = ActionsArg::from_value(actions, &self.categorical_attributes)?;
+        let allowed_actions =
+            allowed_actions.map(|keys| keys.into_iter().map(Str::from).collect::<HashSet<_>>());
+
+        let results = self.evaluator.get_bandit_actions_ranked(
+            &BanditRequest {
+                flag_key: &flag_key,
+                subject_key: &subject_key.into(),
+                subject_attributes: &subject_attributes,
+                actions: actions.as_actions(),
+                default_variation: &default_variation.into(),
+            },
+            k,
+            allowed_actions.as_ref(),
+        );
+
+        serde_magnus::serialize(&results)
+    }
+
     pub fn get_configuration(&self) -> Option<Configuration> {
         self.configuration_store
             .get_configuration()
@@ -239,3 +384,53 @@ impl Client {
         }
     }
 }
+
+/// Actions supplied from Ruby: either a hash of action attributes or an array of action keys.
+enum ActionsArg {
+    Keys(Vec<Str>),
+    WithAttributes(HashMap<Str, ContextAttributes>),
+}
+
+impl ActionsArg {
+    fn from_value(actions: Value, categorical: &HashSet<Str>) -> Result<ActionsArg> {
+        if RArray::from_value(actions).is_some() {
+            Ok(ActionsArg::Keys(serde_magnus::deserialize(actions)?))
+        } else {
+            let actions: HashMap<Str, ContextAttributesArg> = serde_magnus::deserialize(actions)?;
+            Ok(ActionsArg::WithAttributes(
+                actions
+                    .into_iter()
+                    .map(|(key, attributes)| (key, attributes.into_context_attributes(categorical)))
+                    .collect(),
+            ))
+        }
+    }
+
+    fn as_actions(&self) -> Actions<'_> {
+        match self {
+            ActionsArg::Keys(keys) => Actions::Keys(keys),
+            ActionsArg::WithAttributes(actions) => Actions::WithAttributes(actions),
+        }
+    }
+}
+
+/// Subject or action attributes supplied from Ruby: either split into numeric and categorical
+/// attributes (by `coerce_context_attributes` in client.rb) or a plain hash of attributes.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ContextAttributesArg {
+    Split(ContextAttributes),
+    Plain(Attributes),
+}
+
+impl ContextAttributesArg {
+    /// Number attributes of a plain hash listed in `categorical` are treated as categorical.
+    fn into_context_attributes(self, categorical: &HashSet<Str>) -> ContextAttributes {
+        match self {
+            ContextAttributesArg::Split(attributes) => attributes,
+            ContextAttributesArg::Plain(attributes) => {
+                ContextAttributes::from_attributes_with_categorical(attributes, categorical)
+            }
+        }
+    }
+}
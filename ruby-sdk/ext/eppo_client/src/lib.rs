@@ -20,6 +20,7 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     let core_client = core.define_class("Client", magnus::class::object())?;
     core_client.define_singleton_method("new", function!(Client::new, 1))?;
     core_client.define_method("get_assignment", method!(Client::get_assignment, 4))?;
+    core_client.define_method("get_all_assignments", method!(Client::get_all_assignments, 3))?;
     core_client.define_method(
         "get_assignment_details",
         method!(Client::get_assignment_details, 4),
@@ -29,6 +30,10 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
         "get_bandit_action_details",
         method!(Client::get_bandit_action_details, 5),
     )?;
+    core_client.define_method(
+        "get_bandit_actions_ranked",
+        method!(Client::get_bandit_actions_ranked, 7),
+    )?;
     core_client.define_method("configuration", method!(Client::get_configuration, 0))?;
     core_client.define_method("configuration=", method!(Client::set_configuration, 1))?;
     core_client.define_method("shutdown", method!(Client::shutdown, 0))?;
@@ -45,6 +50,10 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
         "DEFAULT_POLL_JITTER_SECONDS",
         eppo_core::poller_thread::PollerThreadConfig::DEFAULT_POLL_JITTER.as_secs(),
     )?;
+    core.const_set(
+        "DEFAULT_REQUEST_TIMEOUT_SECONDS",
+        eppo_core::configuration_fetcher::DEFAULT_REQUEST_TIMEOUT.as_secs_f64(),
+    )?;
 
     configuration::init(ruby)?;
 
@@ -63,6 +63,17 @@ module EppoClient
       get_assignment_inner(flag_key, subject_key, subject_attributes, "JSON", default_value)
     end
 
+    # Evaluates all flags (or only flag_keys if given) for the same subject at once. Returns a
+    # hash from flag key to assigned value. Flags that fail to evaluate or don't assign a
+    # variation to the subject are omitted.
+    def get_all_assignments(subject_key, subject_attributes, flag_keys = nil)
+      assignments = @core.get_all_assignments(subject_key, subject_attributes, flag_keys)
+      assignments.transform_values do |assignment|
+        log_assignment(assignment[:event])
+        assignment[:value]
+      end
+    end
+
     def get_string_assignment_details(flag_key, subject_key, subject_attributes, default_value)
       get_assignment_details_inner(flag_key, subject_key, subject_attributes, "STRING", default_value)
     end
@@ -85,18 +96,23 @@ module EppoClient
 
     def get_bandit_action(flag_key, subject_key, subject_attributes, actions, default_variation)
       attributes = coerce_context_attributes(subject_attributes)
-      actions = actions.to_h { |action, attributes| [action, coerce_context_attributes(attributes)] }
+      actions = coerce_actions(actions)
       result = @core.get_bandit_action(flag_key, subject_key, attributes, actions, default_variation)
 
       log_assignment(result[:assignment_event])
       log_bandit_action(result[:bandit_event])
 
-      return {:variation => result[:variation], :action => result[:action]}
+      return {
+        :variation => result[:variation],
+        :action => result[:action],
+        :allocationKey => result[:allocation_key],
+        :experiment => result[:experiment]
+      }
     end
 
     def get_bandit_action_details(flag_key, subject_key, subject_attributes, actions, default_variation)
       attributes = coerce_context_attributes(subject_attributes)
-      actions = actions.to_h { |action, attributes| [action, coerce_context_attributes(attributes)] }
+      actions = coerce_actions(actions)
       result, details = @core.get_bandit_action_details(flag_key, subject_key, attributes, actions, default_variation)
 
       log_assignment(result[:assignment_event])
@@ -105,10 +121,33 @@ module EppoClient
       return {
         :variation => result[:variation],
         :action => result[:action],
+        :allocationKey => result[:allocation_key],
+        :experiment => result[:experiment],
         :evaluationDetails => details
       }
     end
 
+    # Returns up to k best actions for the subject, ranked by the bandit. The first result is the
+    # same action as returned by get_bandit_action. If allowed_actions is given, only actions with
+    # keys in it are considered. A bandit event is logged for every returned action.
+    def get_bandit_actions_ranked(flag_key, subject_key, subject_attributes, actions, default_variation, k, allowed_actions = nil)
+      attributes = coerce_context_attributes(subject_attributes)
+      actions = coerce_actions(actions)
+      results = @core.get_bandit_actions_ranked(flag_key, subject_key, attributes, actions, default_variation, k, allowed_actions&.to_a)
+
+      results.map do |result|
+        log_assignment(result[:assignment_event])
+        log_bandit_action(result[:bandit_event])
+
+        {
+          :variation => result[:variation],
+          :action => result[:action],
+          :allocationKey => result[:allocation_key],
+          :experiment => result[:experiment]
+        }
+      end
+    end
+
     private
 
     # rubocop:disable Metrics/MethodLength
@@ -179,6 +218,18 @@ module EppoClient
       end
     end
 
+    # Actions are either a hash of action keys to their attributes or an array of action keys
+    # without attributes.
+    def coerce_actions(actions)
+      if actions.is_a?(Array) then
+        actions.map(&:to_s)
+      else
+        actions.to_h { |action, attributes| [action, coerce_context_attributes(attributes)] }
+      end
+    end
+
+    # Attributes are either split into numeric and categorical attributes or a plain hash. Numbers
+    # in a plain hash are numeric unless listed in Config#categorical_attributes.
     def coerce_context_attributes(attributes)
       numeric_attributes = attributes[:numeric_attributes] || attributes["numericAttributes"]
       categorical_attributes = attributes[:categorical_attributes] || attributes["categoricalAttributes"]
@@ -191,6 +242,8 @@ module EppoClient
             value.nil? ? [nil, nil] : [key, value.to_s]
           end.compact,
         }
+      else
+        attributes
       end
     end
   end
@@ -6,19 +6,27 @@ require_relative "assignment_logger"
 module EppoClient
   # The class for configuring the Eppo client singleton
   class Config
-    attr_reader :api_key, :assignment_logger, :base_url, :poll_interval_seconds, :poll_jitter_seconds, :log_level
+    attr_reader :api_key, :assignment_logger, :base_url, :poll_interval_seconds, :poll_jitter_seconds, :request_timeout_seconds, :initial_fetch_timeout_seconds, :log_level, :categorical_attributes
 
-    def initialize(api_key, assignment_logger: AssignmentLogger.new, base_url: EppoClient::Core::DEFAULT_BASE_URL, poll_interval_seconds: EppoClient::Core::DEFAULT_POLL_INTERVAL_SECONDS, poll_jitter_seconds: EppoClient::Core::DEFAULT_POLL_JITTER_SECONDS, initial_configuration: nil, log_level: nil)
+    def initialize(api_key, assignment_logger: AssignmentLogger.new, base_url: EppoClient::Core::DEFAULT_BASE_URL, poll_interval_seconds: EppoClient::Core::DEFAULT_POLL_INTERVAL_SECONDS, poll_jitter_seconds: EppoClient::Core::DEFAULT_POLL_JITTER_SECONDS, request_timeout_seconds: EppoClient::Core::DEFAULT_REQUEST_TIMEOUT_SECONDS, initial_fetch_timeout_seconds: nil, initial_configuration: nil, log_level: nil, categorical_attributes: [])
       @api_key = api_key
       @assignment_logger = assignment_logger
       @base_url = base_url
       @poll_interval_seconds = poll_interval_seconds
       @poll_jitter_seconds = poll_jitter_seconds
+      @request_timeout_seconds = request_timeout_seconds
+      # If set, `EppoClient.init` waits up to this many seconds for the first configuration fetch.
+      @initial_fetch_timeout_seconds = initial_fetch_timeout_seconds
       @log_level = log_level
+      # Keys of number attributes that bandits should treat as categorical when attributes are
+      # passed as a plain hash (e.g., zip codes).
+      @categorical_attributes = categorical_attributes.map(&:to_s)
     end
 
     def validate
       EppoClient.validate_not_blank("api_key", @api_key)
+      EppoClient.validate_positive("request_timeout_seconds", @request_timeout_seconds)
+      EppoClient.validate_positive("initial_fetch_timeout_seconds", @initial_fetch_timeout_seconds) unless @initial_fetch_timeout_seconds.nil?
     end
 
     # Hide instance variables (specifically api_key) from logs
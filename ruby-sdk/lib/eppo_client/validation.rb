@@ -11,4 +11,10 @@ module EppoClient
       EppoClient::InvalidValueError, "#{field_name} cannot be blank"
     )
   end
+
+  def validate_positive(field_name, field_value)
+    (field_value.nil? || field_value <= 0) && raise(
+      EppoClient::InvalidValueError, "#{field_name} must be positive"
+    )
+  end
 end
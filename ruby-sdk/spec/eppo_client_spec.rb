@@ -37,6 +37,74 @@ RSpec.describe EppoClient do
     end
   end
 
+  describe "array attributes" do
+    it "match ONE_OF conditions if any element matches" do
+      init_client_for "offline"
+
+      EppoClient::Client.instance.configuration = EppoClient::Configuration.new(flags_configuration: <<~JSON)
+        {
+          "createdAt": "2024-09-09T10:18:15.988Z",
+          "format": "SERVER",
+          "environment": {"name": "test"},
+          "flags": {
+            "plan": {
+              "key": "plan",
+              "enabled": true,
+              "variationType": "STRING",
+              "totalShards": 10000,
+              "variations": {"pro": {"key": "pro", "value": "pro"}},
+              "allocations": [{
+                "key": "pro-users",
+                "rules": [{"conditions": [
+                  {"attribute": "entitlements", "operator": "ONE_OF", "value": ["pro"]}
+                ]}],
+                "splits": [{"variationKey": "pro", "shards": []}],
+                "doLog": false
+              }]
+            }
+          }
+        }
+      JSON
+
+      client = EppoClient::Client.instance
+      expect(client.get_string_assignment("plan", "alice", { "entitlements" => ["beta", "pro"] }, "default")).to eq("pro")
+      expect(client.get_string_assignment("plan", "bob", { "entitlements" => ["beta"] }, "default")).to eq("default")
+    end
+  end
+
+  describe "get_all_assignments" do
+    it "evaluates several flags at once" do
+      init_client_for "offline"
+
+      EppoClient::Client.instance.configuration = EppoClient::Configuration.new(flags_configuration: <<~JSON)
+        {
+          "createdAt": "2024-09-09T10:18:15.988Z",
+          "format": "SERVER",
+          "environment": {"name": "test"},
+          "flags": {
+            "color": {
+              "key": "color",
+              "enabled": true,
+              "variationType": "STRING",
+              "totalShards": 10000,
+              "variations": {"blue": {"key": "blue", "value": "blue"}},
+              "allocations": [{
+                "key": "everyone",
+                "rules": [],
+                "splits": [{"variationKey": "blue", "shards": []}],
+                "doLog": false
+              }]
+            }
+          }
+        }
+      JSON
+
+      client = EppoClient::Client.instance
+      expect(client.get_all_assignments("alice", {}, ["color", "unknown-flag"])).to eq({ "color" => "blue" })
+      expect(client.get_all_assignments("alice", {})).to eq({ "color" => "blue" })
+    end
+  end
+
   describe "UFC flag evaluation", :flags do
     before :all do
       init_client_for "ufc"
@@ -147,10 +215,64 @@ RSpec.describe EppoClient do
             result =
                 EppoClient::Client.instance.get_bandit_action(flag_key, subject_key, subject_attributes, actions, default_value)
 
+            expect(result.slice(:variation, :action)).to eq(expected)
+            if result[:action]
+              expect(result[:allocationKey]).not_to be_nil
+              expect(result[:experiment]).to eq("#{flag_key}-#{result[:allocationKey]}")
+            end
+          end
+
+          it "#{subject_key} with action keys", :subject => subject_key do
+            action_keys = actions.keys
+            empty_actions = action_keys.to_h { |key| [key, { "numericAttributes" => {}, "categoricalAttributes" => {} }] }
+
+            result =
+                EppoClient::Client.instance.get_bandit_action(flag_key, subject_key, subject_attributes, action_keys, default_value)
+            expected =
+                EppoClient::Client.instance.get_bandit_action(flag_key, subject_key, subject_attributes, empty_actions, default_value)
+
+            expect(result).to eq(expected)
+          end
+
+          it "#{subject_key} with plain attributes", :subject => subject_key do
+            plain_subject_attributes = subject_attributes["numericAttributes"].merge(subject_attributes["categoricalAttributes"])
+            plain_actions = actions.transform_values { |attributes| attributes["numericAttributes"].merge(attributes["categoricalAttributes"]) }
+
+            result =
+                EppoClient::Client.instance.get_bandit_action(flag_key, subject_key, plain_subject_attributes, plain_actions, default_value)
+            expected =
+                EppoClient::Client.instance.get_bandit_action(flag_key, subject_key, subject_attributes, actions, default_value)
+
             expect(result).to eq(expected)
           end
         end
       end
     end
   end
+
+  describe "Config" do
+    it "treats numbers in plain attributes as numeric by default" do
+      config = EppoClient::Config.new("test-api-key")
+
+      expect(config.categorical_attributes).to eq([])
+    end
+
+    it "accepts categorical attributes" do
+      config = EppoClient::Config.new("test-api-key", categorical_attributes: [:zip_code, "plan_tier"])
+
+      expect(config.categorical_attributes).to eq(["zip_code", "plan_tier"])
+    end
+
+    it "rejects zero request timeout" do
+      config = EppoClient::Config.new("test-api-key", request_timeout_seconds: 0)
+
+      expect { config.validate }.to raise_error(EppoClient::InvalidValueError)
+    end
+
+    it "rejects zero initial fetch timeout" do
+      config = EppoClient::Config.new("test-api-key", initial_fetch_timeout_seconds: 0)
+
+      expect { config.validate }.to raise_error(EppoClient::InvalidValueError)
+    end
+  end
 end
@@ -8,7 +8,7 @@ pub fn main() -> eppo::Result<()> {
 
     let api_key =
         std::env::var("EPPO_API_KEY").expect("EPPO_API_KEY env variable should contain API key");
-    let mut client = eppo::ClientConfig::from_api_key(api_key)
+    let client = eppo::ClientConfig::from_api_key(api_key)
         .assignment_logger(|event| {
             println!("Logging assignment event: {:?}", event);
         })
@@ -26,8 +26,8 @@ pub fn main() -> eppo::Result<()> {
     // Get assignment for test-subject.
     let assignment_with_details = client.get_boolean_assignment_details(
         "a-boolean-flag",
-        &"test-subject".into(),
-        &Arc::new([("name".into(), AttributeValue::from("<your name>"))].into()),
+        "test-subject",
+        Arc::new([("name".into(), AttributeValue::from("<your name>"))].into()),
     );
 
     println!(
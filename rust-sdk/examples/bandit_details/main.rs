@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use eppo::ContextAttributes;
+
+pub fn main() -> eppo::Result<()> {
+    // Configure env_logger to see Eppo SDK logs.
+    env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("eppo")).init();
+
+    let api_key =
+        std::env::var("EPPO_API_KEY").expect("EPPO_API_KEY env variable should contain API key");
+    let client = eppo::ClientConfig::from_api_key(api_key)
+        .assignment_logger(|event| {
+            println!("Logging assignment event: {:?}", event);
+        })
+        .to_client();
+
+    // Start a poller thread to fetch configuration from the server.
+    let poller = client.start_poller_thread()?;
+
+    // Block waiting for configuration. Until this call returns, the client will return default
+    // variation for all bandits.
+    if let Err(err) = poller.wait_for_configuration() {
+        println!("error requesting configuration: {:?}", err);
+    }
+
+    let subject_attributes = [("age", 30.0)].into_iter().collect::<ContextAttributes>();
+    let actions = HashMap::from([
+        (
+            "nike".into(),
+            [("brand_affinity", 2.3)]
+                .into_iter()
+                .collect::<ContextAttributes>(),
+        ),
+        (
+            "adidas".into(),
+            [("brand_affinity", 1.4)]
+                .into_iter()
+                .collect::<ContextAttributes>(),
+        ),
+    ]);
+
+    // Get bandit action for test-subject.
+    let (result, details) = client.get_bandit_action_details(
+        "a-bandit-flag",
+        &"test-subject".into(),
+        &subject_attributes,
+        &actions,
+        &"default".into(),
+    );
+
+    println!(
+        "Variation: {:?}, action: {:?}",
+        result.variation, result.action
+    );
+    println!("{}", serde_json::to_string_pretty(&details).unwrap());
+
+    Ok(())
+}
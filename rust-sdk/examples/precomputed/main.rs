@@ -0,0 +1,101 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use eppo::{Client, PrecomputedRequest};
+
+/// A tiny HTTP server that serves precomputed assignments to client SDKs, using only the standard
+/// library. See `precomputed_axum` for the same service built with axum.
+///
+/// Try it with:
+/// ```sh
+/// curl -X POST 'http://localhost:8080/assignments' \
+///   -H 'Content-Type: application/json' \
+///   -d '{"subject_key": "test-subject", "subject_attributes": {"numericAttributes": {"age": 42}, "categoricalAttributes": {}}}'
+/// ```
+pub fn main() -> eppo::Result<()> {
+    // Configure env_logger to see Eppo SDK logs.
+    env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("eppo")).init();
+
+    let api_key =
+        std::env::var("EPPO_API_KEY").expect("EPPO_API_KEY env variable should contain API key");
+    let client = eppo::ClientConfig::from_api_key(api_key).to_client();
+
+    // Start a poller thread to fetch configuration from the server.
+    let poller = client.start_poller_thread()?;
+    if let Err(err) = poller.wait_for_configuration() {
+        println!("error requesting configuration: {:?}", err);
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:8080")?;
+    println!("Listening on http://127.0.0.1:8080");
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                // Client is cheap to clone and can be shared between threads.
+                let client = client.clone();
+                std::thread::spawn(move || {
+                    if let Err(err) = handle_connection(&client, stream) {
+                        println!("error handling request: {:?}", err);
+                    }
+                });
+            }
+            Err(err) => println!("error accepting connection: {:?}", err),
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_connection(client: &Client, mut stream: TcpStream) -> std::io::Result<()> {
+    let body = read_body(&stream)?;
+
+    let request: PrecomputedRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(err) => {
+            return write_response(
+                &mut stream,
+                "400 Bad Request",
+                &format!("invalid request: {err}"),
+            )
+        }
+    };
+
+    let response = client.handle_precomputed_request(request);
+    match serde_json::to_string(&response) {
+        Ok(body) => write_response(&mut stream, "200 OK", &body),
+        Err(err) => write_response(
+            &mut stream,
+            "500 Internal Server Error",
+            &format!("error serializing configuration: {err}"),
+        ),
+    }
+}
+
+/// Skip the request line and headers, and read as many body bytes as `Content-Length` says.
+fn read_body(stream: &TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+}
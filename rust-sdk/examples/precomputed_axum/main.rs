@@ -0,0 +1,44 @@
+use axum::{extract::State, routing::post, Json, Router};
+
+use eppo::{Client, PrecomputedRequest, PrecomputedResponse};
+
+/// A service that serves precomputed assignments to client SDKs, built with axum.
+///
+/// Try it with:
+/// ```sh
+/// curl -X POST 'http://localhost:8080/assignments' \
+///   -H 'Content-Type: application/json' \
+///   -d '{"subject_key": "test-subject", "subject_attributes": {"numericAttributes": {"age": 42}, "categoricalAttributes": {}}}'
+/// ```
+#[tokio::main]
+async fn main() -> eppo::Result<()> {
+    // Configure env_logger to see Eppo SDK logs.
+    env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("eppo")).init();
+
+    let api_key =
+        std::env::var("EPPO_API_KEY").expect("EPPO_API_KEY env variable should contain API key");
+    let client = eppo::ClientConfig::from_api_key(api_key).to_client();
+
+    // Poll configuration in a task on the axum runtime.
+    let poller = client.start_poller_task()?;
+    if let Err(err) = poller.wait_for_configuration().await {
+        println!("error requesting configuration: {:?}", err);
+    }
+
+    let app = Router::new()
+        .route("/assignments", post(assignments))
+        .with_state(client);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
+    println!("Listening on http://127.0.0.1:8080");
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+async fn assignments(
+    State(client): State<Client<'static>>,
+    Json(request): Json<PrecomputedRequest>,
+) -> Json<PrecomputedResponse> {
+    Json(client.handle_precomputed_request(request))
+}
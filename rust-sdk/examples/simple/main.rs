@@ -4,9 +4,9 @@ pub fn main() -> eppo::Result<()> {
     // Configure env_logger to see Eppo SDK logs.
     env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("eppo")).init();
 
-    let api_key =
-        std::env::var("EPPO_API_KEY").expect("EPPO_API_KEY env variable should contain API key");
-    let mut client = eppo::ClientConfig::from_api_key(api_key)
+    // Reads EPPO_API_KEY (and optionally EPPO_BASE_URL, EPPO_POLL_INTERVAL_SECONDS, and
+    // EPPO_POLL_JITTER_SECONDS) from the environment.
+    let client = eppo::ClientConfig::from_env()?
         .assignment_logger(|event| {
             println!("Logging assignment event: {:?}", event);
         })
@@ -23,11 +23,7 @@ pub fn main() -> eppo::Result<()> {
 
     // Get assignment for test-subject.
     let assignment = client
-        .get_boolean_assignment(
-            "a-boolean-flag",
-            &"test-subject".into(),
-            &HashMap::new().into(),
-        )
+        .get_boolean_assignment("a-boolean-flag", "test-subject", HashMap::new())
         .unwrap_or_default()
         // default assignment
         .unwrap_or(false);
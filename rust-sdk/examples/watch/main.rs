@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+#[tokio::main]
+async fn main() -> eppo::Result<()> {
+    // Configure env_logger to see Eppo SDK logs.
+    env_logger::Builder::from_env(env_logger::Env::new().default_filter_or("eppo")).init();
+
+    // Reads EPPO_API_KEY (and optionally EPPO_BASE_URL, EPPO_POLL_INTERVAL_SECONDS, and
+    // EPPO_POLL_JITTER_SECONDS) from the environment.
+    let client = eppo::ClientConfig::from_env()?
+        .assignment_logger(|event| {
+            println!("Logging assignment event: {:?}", event);
+        })
+        .to_client();
+
+    // Start a poller thread to fetch configuration from the server.
+    let poller = client.start_poller_thread()?;
+
+    // Wait for the first configuration without blocking the async runtime. Unlike
+    // `wait_for_configuration()`, the receiver can be used in `tokio::select!` alongside other
+    // events.
+    let mut configurations = poller.watch();
+    match configurations
+        .wait_for(|configuration| configuration.is_some())
+        .await
+    {
+        Ok(configuration) => println!(
+            "Received configuration fetched at {}",
+            configuration.as_ref().unwrap().fetched_at
+        ),
+        Err(_) => println!("configuration store has been dropped"),
+    }
+
+    // Get assignment for test-subject.
+    let assignment = client
+        .get_boolean_assignment("a-boolean-flag", "test-subject", HashMap::new())
+        .unwrap_or_default()
+        // default assignment
+        .unwrap_or(false);
+
+    println!("Assignment: {:?}", assignment);
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::AssignmentEvent;
+use crate::{AssignmentEvent, BanditEvent};
 
 /// A trait for logging assignment events to your storage system. Implementations should handle
 /// persisting assignment events for analytics and tracking purposes.
@@ -33,6 +33,38 @@ pub trait AssignmentLogger {
     /// `log_assignment` does not block the calling thread to prevent performance implications and
     /// delays in returning assignments.
     fn log_assignment(&self, event: AssignmentEvent);
+
+    /// Logs the bandit action event to the storage system.
+    ///
+    /// The default implementation does nothing, so existing loggers keep working. Override it if
+    /// you use bandits, so that bandit exposures are recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - A [`BanditEvent`] to be logged.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eppo::{AssignmentLogger, AssignmentEvent, BanditEvent};
+    /// struct MyAssignmentLogger;
+    ///
+    /// impl AssignmentLogger for MyAssignmentLogger {
+    ///     fn log_assignment(&self, event: AssignmentEvent) {
+    ///         // Implement assignment logging logic here
+    ///     }
+    ///
+    ///     fn log_bandit_action(&self, event: BanditEvent) {
+    ///         // Implement bandit logging logic here
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`AssignmentLogger::log_assignment`], this method should not return errors and
+    /// should not panic.
+    fn log_bandit_action(&self, _event: BanditEvent) {}
 }
 
 pub(crate) struct NoopAssignmentLogger;
@@ -0,0 +1,432 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use crate::{AssignmentEvent, AssignmentLogger, BanditEvent, Result};
+
+/// What to do when a new event is logged while the queue of [`BatchingAssignmentLogger`] is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+    /// Drop the oldest event in the queue to make room for the new one.
+    DropOldest,
+    /// Block the calling thread until the background thread makes room in the queue.
+    Block,
+}
+
+/// Configuration for [`BatchingAssignmentLogger`].
+#[derive(Debug, Clone)]
+pub struct BatchingAssignmentLoggerConfig {
+    /// Number of buffered events that triggers a flush.
+    ///
+    /// Defaults to [`BatchingAssignmentLoggerConfig::DEFAULT_BATCH_SIZE`].
+    pub batch_size: usize,
+    /// Maximum time events are buffered before getting flushed.
+    ///
+    /// Defaults to [`BatchingAssignmentLoggerConfig::DEFAULT_FLUSH_INTERVAL`].
+    pub flush_interval: Duration,
+    /// Maximum number of events held in the queue.
+    ///
+    /// Defaults to [`BatchingAssignmentLoggerConfig::DEFAULT_CAPACITY`].
+    pub capacity: usize,
+    /// What to do when the queue is full.
+    ///
+    /// Defaults to [`OverflowPolicy::DropOldest`].
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl BatchingAssignmentLoggerConfig {
+    /// Default value for [`BatchingAssignmentLoggerConfig::batch_size`].
+    pub const DEFAULT_BATCH_SIZE: usize = 100;
+    /// Default value for [`BatchingAssignmentLoggerConfig::flush_interval`].
+    pub const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+    /// Default value for [`BatchingAssignmentLoggerConfig::capacity`].
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    /// Create a new `BatchingAssignmentLoggerConfig` using default configuration.
+    pub fn new() -> BatchingAssignmentLoggerConfig {
+        BatchingAssignmentLoggerConfig::default()
+    }
+
+    /// Update batch size with `batch_size`.
+    pub fn with_batch_size(mut self, batch_size: usize) -> BatchingAssignmentLoggerConfig {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Update flush interval with `flush_interval`.
+    pub fn with_flush_interval(
+        mut self,
+        flush_interval: Duration,
+    ) -> BatchingAssignmentLoggerConfig {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Update queue capacity with `capacity`.
+    pub fn with_capacity(mut self, capacity: usize) -> BatchingAssignmentLoggerConfig {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Update overflow policy with `overflow_policy`.
+    pub fn with_overflow_policy(
+        mut self,
+        overflow_policy: OverflowPolicy,
+    ) -> BatchingAssignmentLoggerConfig {
+        self.overflow_policy = overflow_policy;
+        self
+    }
+}
+
+impl Default for BatchingAssignmentLoggerConfig {
+    fn default() -> BatchingAssignmentLoggerConfig {
+        BatchingAssignmentLoggerConfig {
+            batch_size: BatchingAssignmentLoggerConfig::DEFAULT_BATCH_SIZE,
+            flush_interval: BatchingAssignmentLoggerConfig::DEFAULT_FLUSH_INTERVAL,
+            capacity: BatchingAssignmentLoggerConfig::DEFAULT_CAPACITY,
+            overflow_policy: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+/// An [`AssignmentLogger`] adapter that buffers events and passes them to the inner logger on a
+/// background thread.
+///
+/// Buffered events are flushed every [`batch_size`](BatchingAssignmentLoggerConfig::batch_size)
+/// events or [`flush_interval`](BatchingAssignmentLoggerConfig::flush_interval), whichever comes
+/// first. Remaining events are flushed when the logger is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use eppo::{BatchingAssignmentLogger, ClientConfig};
+/// # fn test() -> eppo::Result<()> {
+/// let logger = BatchingAssignmentLogger::new(|event| {
+///     println!("{:?}", event);
+/// })?;
+/// let client = ClientConfig::from_api_key("api-key")
+///     .assignment_logger(logger)
+///     .to_client();
+/// # Ok(())
+/// # }
+/// ```
+pub struct BatchingAssignmentLogger {
+    shared: Arc<Shared>,
+    join_handle: Option<std::thread::JoinHandle<()>>,
+}
+
+enum Event {
+    Assignment(AssignmentEvent),
+    // Boxed because bandit events are several times larger than assignment events.
+    Bandit(Box<BanditEvent>),
+}
+
+struct Shared {
+    config: BatchingAssignmentLoggerConfig,
+    state: Mutex<State>,
+    /// Notified whenever `state` changes.
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    queue: VecDeque<Event>,
+    /// Set when the logger is dropped.
+    shutdown: bool,
+    /// Set when the background thread exits.
+    stopped: bool,
+    /// Incremented on every `flush()` call.
+    flush_requested: u64,
+    /// Latest value of `flush_requested` that background thread has completed.
+    flush_completed: u64,
+}
+
+impl BatchingAssignmentLogger {
+    /// Create a new `BatchingAssignmentLogger` wrapping `inner` logger using default
+    /// configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`](crate::Error::Io) if the background thread failed to start.
+    pub fn new(inner: impl AssignmentLogger + Send + 'static) -> Result<BatchingAssignmentLogger> {
+        BatchingAssignmentLogger::with_config(inner, BatchingAssignmentLoggerConfig::default())
+    }
+
+    /// Create a new `BatchingAssignmentLogger` wrapping `inner` logger using the provided
+    /// configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`](crate::Error::Io) if the background thread failed to start.
+    pub fn with_config(
+        inner: impl AssignmentLogger + Send + 'static,
+        mut config: BatchingAssignmentLoggerConfig,
+    ) -> Result<BatchingAssignmentLogger> {
+        // Zero values would make the logger never flush or block forever.
+        config.batch_size = config.batch_size.max(1);
+        config.capacity = config.capacity.max(1);
+
+        let shared = Arc::new(Shared {
+            config,
+            state: Mutex::new(State::default()),
+            changed: Condvar::new(),
+        });
+
+        let join_handle = {
+            let shared = Arc::clone(&shared);
+            std::thread::Builder::new()
+                .name("eppo-logger".to_owned())
+                .spawn(move || shared.run(inner))?
+        };
+
+        Ok(BatchingAssignmentLogger {
+            shared,
+            join_handle: Some(join_handle),
+        })
+    }
+
+    /// Pass all buffered events to the inner logger and block until it's done.
+    pub fn flush(&self) {
+        let mut state = self.shared.lock();
+        state.flush_requested += 1;
+        let target = state.flush_requested;
+        self.shared.changed.notify_all();
+
+        while state.flush_completed < target && !state.stopped {
+            state = self.shared.wait(state);
+        }
+    }
+
+    fn push(&self, event: Event) {
+        let shared = &*self.shared;
+        let mut state = shared.lock();
+
+        if state.queue.len() >= shared.config.capacity {
+            match shared.config.overflow_policy {
+                OverflowPolicy::DropOldest => {
+                    log::warn!(target: "eppo", "assignment logger queue is full, dropping oldest event");
+                    state.queue.pop_front();
+                }
+                OverflowPolicy::Block => {
+                    while state.queue.len() >= shared.config.capacity && !state.stopped {
+                        state = shared.wait(state);
+                    }
+                }
+            }
+        }
+
+        if state.stopped {
+            // Background thread is gone (inner logger panicked), so nobody is going to consume
+            // the event.
+            return;
+        }
+
+        state.queue.push_back(event);
+        if state.queue.len() >= shared.config.batch_size {
+            shared.changed.notify_all();
+        }
+    }
+}
+
+impl AssignmentLogger for BatchingAssignmentLogger {
+    fn log_assignment(&self, event: AssignmentEvent) {
+        self.push(Event::Assignment(event));
+    }
+
+    fn log_bandit_action(&self, event: BanditEvent) {
+        self.push(Event::Bandit(Box::new(event)));
+    }
+}
+
+impl Drop for BatchingAssignmentLogger {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.changed.notify_all();
+
+        if let Some(join_handle) = self.join_handle.take() {
+            // Error means that the thread has panicked and there's nothing useful we can do in
+            // that case.
+            let _ = join_handle.join();
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The lock is never held while calling into user code, so it cannot get poisoned.
+        self.state.lock().unwrap()
+    }
+
+    fn wait<'a>(&self, guard: MutexGuard<'a, State>) -> MutexGuard<'a, State> {
+        self.changed.wait(guard).unwrap()
+    }
+
+    /// Background thread main loop.
+    fn run(&self, inner: impl AssignmentLogger) {
+        // Marks the logger as stopped even if `inner` panics, so that callers blocked in `flush()`
+        // or `log_assignment()` are released.
+        struct StopGuard<'a>(&'a Shared);
+        impl Drop for StopGuard<'_> {
+            fn drop(&mut self) {
+                self.0.lock().stopped = true;
+                self.0.changed.notify_all();
+            }
+        }
+        let _guard = StopGuard(self);
+
+        let mut state = self.lock();
+        loop {
+            let deadline = Instant::now() + self.config.flush_interval;
+            while state.queue.len() < self.config.batch_size.min(self.config.capacity)
+                && !state.shutdown
+                && state.flush_completed == state.flush_requested
+            {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                state = self.changed.wait_timeout(state, deadline - now).unwrap().0;
+            }
+
+            let batch = std::mem::take(&mut state.queue);
+            let flush_requested = state.flush_requested;
+            let shutdown = state.shutdown;
+            // Wake up callers blocked on full queue.
+            self.changed.notify_all();
+            drop(state);
+
+            for event in batch {
+                match event {
+                    Event::Assignment(event) => inner.log_assignment(event),
+                    Event::Bandit(event) => inner.log_bandit_action(*event),
+                }
+            }
+
+            state = self.lock();
+            state.flush_completed = flush_requested;
+            self.changed.notify_all();
+
+            if shutdown && state.queue.is_empty() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use eppo_core::{
+        events::{AssignmentEventBase, EventMetaData},
+        ufc::{ValueWire, VariationType},
+    };
+
+    use crate::{AssignmentEvent, AssignmentLogger, SDK_METADATA};
+
+    use super::{BatchingAssignmentLogger, BatchingAssignmentLoggerConfig, OverflowPolicy};
+
+    fn event(subject: &str) -> AssignmentEvent {
+        AssignmentEvent {
+            base: Arc::new(AssignmentEventBase {
+                feature_flag: "flag".into(),
+                allocation: "allocation".into(),
+                experiment: "flag-allocation".to_owned(),
+                variation: "variation".into(),
+                variation_type: VariationType::Boolean,
+                variation_value: ValueWire::Boolean(true),
+                meta_data: EventMetaData::from(SDK_METADATA),
+                extra_logging: HashMap::new(),
+            }),
+            subject: subject.into(),
+            subject_attributes: Arc::new(HashMap::new()),
+            timestamp: chrono::Utc::now(),
+            evaluation_details: None,
+        }
+    }
+
+    fn collecting_logger() -> (
+        Arc<Mutex<Vec<String>>>,
+        impl AssignmentLogger + Send + 'static,
+    ) {
+        let subjects = Arc::new(Mutex::new(Vec::new()));
+        let logger = {
+            let subjects = subjects.clone();
+            move |event: AssignmentEvent| subjects.lock().unwrap().push(event.subject.to_string())
+        };
+        (subjects, logger)
+    }
+
+    /// Config that never flushes by itself.
+    fn manual_config() -> BatchingAssignmentLoggerConfig {
+        BatchingAssignmentLoggerConfig::new()
+            .with_batch_size(1000)
+            .with_flush_interval(Duration::from_secs(3600))
+    }
+
+    #[test]
+    fn flush_passes_events_to_inner_logger() {
+        let (subjects, inner) = collecting_logger();
+        let logger = BatchingAssignmentLogger::with_config(inner, manual_config()).unwrap();
+
+        logger.log_assignment(event("a"));
+        logger.log_assignment(event("b"));
+        logger.flush();
+
+        assert_eq!(*subjects.lock().unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn drop_flushes_remaining_events() {
+        let (subjects, inner) = collecting_logger();
+        let logger = BatchingAssignmentLogger::with_config(inner, manual_config()).unwrap();
+
+        logger.log_assignment(event("a"));
+        drop(logger);
+
+        assert_eq!(*subjects.lock().unwrap(), vec!["a"]);
+    }
+
+    #[test]
+    fn flushes_when_batch_is_full() {
+        let (subjects, inner) = collecting_logger();
+        let logger =
+            BatchingAssignmentLogger::with_config(inner, manual_config().with_batch_size(2))
+                .unwrap();
+
+        logger.log_assignment(event("a"));
+        logger.log_assignment(event("b"));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while subjects.lock().unwrap().len() < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(*subjects.lock().unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn drop_oldest_when_queue_is_full() {
+        let (subjects, inner) = collecting_logger();
+        let logger = BatchingAssignmentLogger::with_config(
+            inner,
+            manual_config()
+                .with_capacity(2)
+                .with_overflow_policy(OverflowPolicy::DropOldest),
+        )
+        .unwrap();
+
+        logger.log_assignment(event("a"));
+        logger.log_assignment(event("b"));
+        logger.log_assignment(event("c"));
+        logger.flush();
+
+        assert_eq!(*subjects.lock().unwrap(), vec!["b", "c"]);
+    }
+}
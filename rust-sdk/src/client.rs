@@ -1,18 +1,38 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::Duration,
+};
 
 use crate::{
-    poller::{PollerThread, PollerThreadConfig},
-    AssignmentValue, Attributes, ClientConfig, Error, EvaluationError, EvaluationResultWithDetails,
-    SDK_METADATA,
+    poller::{PollerTask, PollerThread, PollerThreadConfig},
+    subject::{AsSubject, IntoSubjectAttributes},
+    AssignmentCacheKey, AssignmentEvent, AssignmentValue, BanditEvent, BanditRequest, BanditResult,
+    ClientConfig, Configuration, ConfigurationMemoryStats, ConfigurationTimestamps,
+    ContextAttributes, Error, EvaluationDetails, EvaluationError, EvaluationResultWithDetails,
+    FetchStats, FlagEvaluationCode, PrecomputedConfiguration, StartupMode, SDK_METADATA,
 };
 
 use eppo_core::{
+    configuration_persistence::load_configuration,
     configuration_store::ConfigurationStore,
-    eval::{Evaluator, EvaluatorConfig},
+    eval::{Actions, BanditEvaluationOptions, Evaluator, EvaluatorConfig},
     ufc::{Assignment, VariationType},
     Str,
 };
 
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "server")]
+use crate::{PrecomputedRequest, PrecomputedResponse};
+
+#[cfg(doc)]
+use crate::Subject;
+
 /// A client for Eppo API.
 ///
 /// In order to create a client instance, first create [`ClientConfig`].
@@ -26,44 +46,189 @@ use eppo_core::{
 /// The reason the poller thread is not started automatically is to allow SDK extension to support
 /// `async` configuration fetching in the future (using async Rust runtimes).
 ///
+/// # Sharing
+///
+/// `Client` is cheap to clone: all clones share the same configuration, loggers, and poller
+/// thread. When the client is created from a `ClientConfig<'static>`, it is `Send + Sync +
+/// 'static` and can be stored directly in application state of web frameworks.
+///
 /// # Examples
 /// ```no_run
 /// # use eppo::{Client, ClientConfig};
-/// let mut client = Client::new(ClientConfig::from_api_key("api-key"));
+/// let client = Client::new(ClientConfig::from_api_key("api-key"));
 /// client.start_poller_thread();
 /// ```
+#[derive(Clone)]
 pub struct Client<'a> {
+    inner: Arc<ClientInner<'a>>,
+}
+
+/// Client state shared between all clones of [`Client`].
+struct ClientInner<'a> {
     config: ClientConfig<'a>,
     configuration_store: Arc<ConfigurationStore>,
     evaluator: Evaluator,
+    is_graceful_mode: AtomicBool,
+    poller_thread: Mutex<Option<PollerThread>>,
 }
 
 impl<'a> Client<'a> {
     /// Create a new `Client` using the specified configuration.
     ///
+    /// This function does not validate the configuration. Invalid API key or base URL are only
+    /// reported when configuration is fetched. Errors during startup (see
+    /// [`ClientConfig::startup_mode()`]) are logged. Prefer [`Client::try_new()`], which reports
+    /// such errors immediately.
+    ///
     /// ```
     /// # use eppo::{ClientConfig, Client};
     /// let client = Client::new(ClientConfig::from_api_key("api-key"));
     /// ```
     pub fn new(config: ClientConfig<'a>) -> Self {
-        Client::new_with_configuration_store(config, Arc::new(ConfigurationStore::new()))
+        Client::with_configuration_store(config, Arc::new(ConfigurationStore::new()))
     }
 
-    fn new_with_configuration_store(
+    /// Create a new `Client` that reads configuration from (and stores fetched configuration to)
+    /// the provided `configuration_store`.
+    ///
+    /// This allows several clients to share the same configuration store, or serving assignments
+    /// from a store that is kept up to date by other means (e.g., an out-of-band process calling
+    /// [`ConfigurationStore::set_configuration()`]).
+    ///
+    /// If [`ClientConfig::initial_configuration_from_json()`] was used, the initial configuration
+    /// replaces configuration in the store. Errors during startup are logged (see
+    /// [`Client::new()`]).
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use eppo::{Client, ClientConfig, ConfigurationStore};
+    /// let store = Arc::new(ConfigurationStore::new());
+    /// let client = Client::with_configuration_store(
+    ///     ClientConfig::from_api_key("api-key"),
+    ///     store.clone(),
+    /// );
+    /// # assert!(!client.is_initialized());
+    /// ```
+    pub fn with_configuration_store(
         config: ClientConfig<'a>,
         configuration_store: Arc<ConfigurationStore>,
     ) -> Self {
+        let client = Client::new_with_configuration_store(config, configuration_store);
+        if let Err(err) = client.startup() {
+            log::warn!(target: "eppo", "error starting client: {err}");
+        }
+        client
+    }
+
+    /// Same as [`Client::with_configuration_store()`], but validates the configuration first and
+    /// reports errors during startup.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Client::try_new()`].
+    pub fn try_with_configuration_store(
+        config: ClientConfig<'a>,
+        configuration_store: Arc<ConfigurationStore>,
+    ) -> Result<Self, Error> {
+        config.validate()?;
+        let client = Client::new_with_configuration_store(config, configuration_store);
+        client.startup()?;
+        Ok(client)
+    }
+
+    /// Create a new `Client` using the specified configuration, validating it first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`] if API key is blank, base URL is invalid, or an
+    /// extra query parameter overrides one set by the SDK.
+    ///
+    /// Also returns errors that occur during startup (see [`ClientConfig::startup_mode()`]).
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, Client};
+    /// let client = Client::try_new(ClientConfig::from_api_key("api-key")).unwrap();
+    /// assert!(Client::try_new(ClientConfig::from_api_key("")).is_err());
+    /// ```
+    pub fn try_new(config: ClientConfig<'a>) -> Result<Self, Error> {
+        Client::try_with_configuration_store(config, Arc::new(ConfigurationStore::new()))
+    }
+
+    /// Start polling according to [`StartupMode`].
+    fn startup(&self) -> Result<(), Error> {
+        match self.inner.config.startup_mode {
+            StartupMode::Manual => {
+                // Poller is not started, so load configuration right away. Persisted configuration
+                // is newer than the configuration file.
+                let persisted = self
+                    .inner
+                    .config
+                    .configuration_persistence
+                    .as_ref()
+                    .and_then(|persistence| load_configuration(&**persistence, SDK_METADATA));
+                if let Some(configuration) = persisted {
+                    self.inner
+                        .configuration_store
+                        .set_configuration(Arc::new(configuration));
+                } else if let Some(file) = self.inner.config.configuration_file_paths() {
+                    let configuration = file.read(SDK_METADATA)?;
+                    self.inner
+                        .configuration_store
+                        .set_configuration(Arc::new(configuration));
+                }
+                Ok(())
+            }
+            StartupMode::Background => {
+                self.start_poller_thread()?;
+                Ok(())
+            }
+            StartupMode::BlockUntilReady { timeout } => self
+                .start_poller_thread()?
+                .wait_for_configuration_timeout(timeout),
+        }
+    }
+
+    fn new_with_configuration_store(
+        mut config: ClientConfig<'a>,
+        configuration_store: Arc<ConfigurationStore>,
+    ) -> Self {
+        if let Some(configuration) = config.initial_configuration.take() {
+            configuration_store.set_configuration(configuration);
+        }
+
         let evaluator = Evaluator::new(EvaluatorConfig {
             configuration_store: configuration_store.clone(),
-            sdk_metadata: SDK_METADATA.clone(),
+            sdk_metadata: SDK_METADATA,
+            clock: config.clock.clone(),
+            observer: config.evaluation_observer.clone(),
+            sharder: config.sharder.clone(),
+            default_attributes: config.default_attributes.clone(),
+            coerce_numeric_types: config.coerce_numeric_types,
+            bandit_evaluation_options: BanditEvaluationOptions {
+                unknown_action_policy: config.unknown_bandit_action_policy,
+            },
         });
+        let is_graceful_mode = AtomicBool::new(config.is_graceful_mode);
         Self {
-            configuration_store,
-            config,
-            evaluator,
+            inner: Arc::new(ClientInner {
+                configuration_store,
+                config,
+                evaluator,
+                is_graceful_mode,
+                poller_thread: Mutex::new(None),
+            }),
         }
     }
 
+    /// Enable or disable graceful mode at runtime.
+    ///
+    /// See [`ClientConfig::is_graceful_mode()`] for more details.
+    pub fn set_graceful_mode(&self, is_graceful_mode: bool) {
+        self.inner
+            .is_graceful_mode
+            .store(is_graceful_mode, Ordering::Release);
+    }
+
     /// Get the assignment value for a given feature flag and subject.
     ///
     /// If the subject is not eligible for any allocation, returns `Ok(None)`.
@@ -96,7 +261,7 @@ impl<'a> Client<'a> {
     /// let assignment = client
     ///     .get_assignment(
     ///         "a-boolean-flag",
-    ///         &"user-id".into(),
+    ///         "user-id",
     ///         &Arc::new(
     ///             [("age".into(), 42.0.into())]
     ///                 .into_iter()
@@ -112,8 +277,8 @@ impl<'a> Client<'a> {
     pub fn get_assignment(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> Result<Option<AssignmentValue>, EvaluationError> {
         self.get_assignment_inner(flag_key, subject_key, subject_attributes, None, |x| x)
     }
@@ -135,7 +300,7 @@ impl<'a> Client<'a> {
     /// # use std::sync::Arc;
     /// # fn test(client: &eppo::Client) {
     /// let assignment = client
-    ///     .get_string_assignment("a-string-flag", &"user-id".into(), &Arc::new([
+    ///     .get_string_assignment("a-string-flag", "user-id", &Arc::new([
     ///         ("language".into(), "en".into())
     ///     ].into_iter().collect()))
     ///     .unwrap_or_default()
@@ -145,8 +310,8 @@ impl<'a> Client<'a> {
     pub fn get_string_assignment(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> Result<Option<Str>, EvaluationError> {
         self.get_assignment_inner(
             flag_key,
@@ -178,7 +343,7 @@ impl<'a> Client<'a> {
     /// # use std::sync::Arc;
     /// # fn test(client: &eppo::Client) {
     /// let assignment = client
-    ///     .get_integer_assignment("an-int-flag", &"user-id".into(), &Arc::new([
+    ///     .get_integer_assignment("an-int-flag", "user-id", &Arc::new([
     ///         ("age".into(), 42.0.into())
     ///     ].into_iter().collect()))
     ///     .unwrap_or_default()
@@ -188,8 +353,8 @@ impl<'a> Client<'a> {
     pub fn get_integer_assignment(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> Result<Option<i64>, EvaluationError> {
         self.get_assignment_inner(
             flag_key,
@@ -221,7 +386,7 @@ impl<'a> Client<'a> {
     /// # use std::sync::Arc;
     /// # fn test(client: &eppo::Client) {
     /// let assignment = client
-    ///     .get_numeric_assignment("a-num-flag", &"user-id".into(), &Arc::new([
+    ///     .get_numeric_assignment("a-num-flag", "user-id", &Arc::new([
     ///         ("age".into(), 42.0.into())
     ///     ].iter().cloned().collect()))
     ///     .unwrap_or_default()
@@ -231,8 +396,8 @@ impl<'a> Client<'a> {
     pub fn get_numeric_assignment(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> Result<Option<f64>, EvaluationError> {
         self.get_assignment_inner(
             flag_key,
@@ -264,7 +429,7 @@ impl<'a> Client<'a> {
     /// # use std::sync::Arc;
     /// # fn test(client: &eppo::Client) {
     /// let assignment = client
-    ///     .get_boolean_assignment("a-bool-flag", &"user-id".into(), &Arc::new([
+    ///     .get_boolean_assignment("a-bool-flag", "user-id", &Arc::new([
     ///         ("age".into(), 42.0.into())
     ///     ].into_iter().collect()))
     ///     .unwrap_or_default()
@@ -274,8 +439,8 @@ impl<'a> Client<'a> {
     pub fn get_boolean_assignment(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> Result<Option<bool>, EvaluationError> {
         self.get_assignment_inner(
             flag_key,
@@ -308,7 +473,7 @@ impl<'a> Client<'a> {
     /// # use serde_json::json;
     /// # fn test(client: &eppo::Client) {
     /// let assignment = client
-    ///     .get_json_assignment("a-json-flag", &"user-id".into(), &Arc::new([
+    ///     .get_json_assignment("a-json-flag", "user-id", &Arc::new([
     ///         ("language".into(), "en".into())
     ///     ].into_iter().collect()))
     ///     .unwrap_or_default()
@@ -318,8 +483,8 @@ impl<'a> Client<'a> {
     pub fn get_json_assignment(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> Result<Option<Arc<serde_json::Value>>, EvaluationError> {
         self.get_assignment_inner(
             flag_key,
@@ -334,35 +499,387 @@ impl<'a> Client<'a> {
         )
     }
 
+    /// Same as [`Client::get_string_assignment()`] but accepts a [`Subject`] (or any other
+    /// [`AsSubject`]) instead of separate subject key and attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eppo::Subject;
+    /// # fn test(client: &eppo::Client) {
+    /// let subject = Subject::new("user-id").with_string("language", "en");
+    /// let assignment = client
+    ///     .get_string_assignment_for_subject("a-string-flag", &subject)
+    ///     .unwrap_or_default()
+    ///     .unwrap_or("default_value".into());
+    /// # }
+    /// ```
+    pub fn get_string_assignment_for_subject(
+        &self,
+        flag_key: &str,
+        subject: &impl AsSubject,
+    ) -> Result<Option<Str>, EvaluationError> {
+        self.get_string_assignment(
+            flag_key,
+            subject.subject_key(),
+            subject.subject_attributes(),
+        )
+    }
+
+    /// Same as [`Client::get_integer_assignment()`] but accepts a [`Subject`] (or any other
+    /// [`AsSubject`]) instead of separate subject key and attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eppo::Subject;
+    /// # fn test(client: &eppo::Client) {
+    /// let subject = Subject::new("user-id").with_string("language", "en");
+    /// let assignment = client
+    ///     .get_integer_assignment_for_subject("an-int-flag", &subject)
+    ///     .unwrap_or_default()
+    ///     .unwrap_or(0);
+    /// # }
+    /// ```
+    pub fn get_integer_assignment_for_subject(
+        &self,
+        flag_key: &str,
+        subject: &impl AsSubject,
+    ) -> Result<Option<i64>, EvaluationError> {
+        self.get_integer_assignment(
+            flag_key,
+            subject.subject_key(),
+            subject.subject_attributes(),
+        )
+    }
+
+    /// Same as [`Client::get_numeric_assignment()`] but accepts a [`Subject`] (or any other
+    /// [`AsSubject`]) instead of separate subject key and attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eppo::Subject;
+    /// # fn test(client: &eppo::Client) {
+    /// let subject = Subject::new("user-id").with_string("language", "en");
+    /// let assignment = client
+    ///     .get_numeric_assignment_for_subject("a-num-flag", &subject)
+    ///     .unwrap_or_default()
+    ///     .unwrap_or(0.0);
+    /// # }
+    /// ```
+    pub fn get_numeric_assignment_for_subject(
+        &self,
+        flag_key: &str,
+        subject: &impl AsSubject,
+    ) -> Result<Option<f64>, EvaluationError> {
+        self.get_numeric_assignment(
+            flag_key,
+            subject.subject_key(),
+            subject.subject_attributes(),
+        )
+    }
+
+    /// Same as [`Client::get_boolean_assignment()`] but accepts a [`Subject`] (or any other
+    /// [`AsSubject`]) instead of separate subject key and attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eppo::Subject;
+    /// # fn test(client: &eppo::Client) {
+    /// let subject = Subject::new("user-id").with_string("language", "en");
+    /// let assignment = client
+    ///     .get_boolean_assignment_for_subject("a-bool-flag", &subject)
+    ///     .unwrap_or_default()
+    ///     .unwrap_or(false);
+    /// # }
+    /// ```
+    pub fn get_boolean_assignment_for_subject(
+        &self,
+        flag_key: &str,
+        subject: &impl AsSubject,
+    ) -> Result<Option<bool>, EvaluationError> {
+        self.get_boolean_assignment(
+            flag_key,
+            subject.subject_key(),
+            subject.subject_attributes(),
+        )
+    }
+
+    /// Same as [`Client::get_json_assignment()`] but accepts a [`Subject`] (or any other
+    /// [`AsSubject`]) instead of separate subject key and attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eppo::Subject;
+    /// # use serde_json::json;
+    /// # fn test(client: &eppo::Client) {
+    /// let subject = Subject::new("user-id").with_string("language", "en");
+    /// let assignment = client
+    ///     .get_json_assignment_for_subject("a-json-flag", &subject)
+    ///     .unwrap_or_default()
+    ///     .unwrap_or(json!({}).into());
+    /// # }
+    /// ```
+    pub fn get_json_assignment_for_subject(
+        &self,
+        flag_key: &str,
+        subject: &impl AsSubject,
+    ) -> Result<Option<Arc<serde_json::Value>>, EvaluationError> {
+        self.get_json_assignment(
+            flag_key,
+            subject.subject_key(),
+            subject.subject_attributes(),
+        )
+    }
+
+    /// Evaluate all flags (or only `flag_keys`, if specified) for the given subject in a single
+    /// pass.
+    ///
+    /// Returns a map from flag key to assigned value. Flags that fail to evaluate or don't produce
+    /// an assignment for the subject (e.g., disabled flags or subject not matching any allocation)
+    /// are omitted from the result, so the caller should fall back to its own default values for
+    /// missing flags.
+    ///
+    /// Assignment events are logged for every returned assignment that needs to be logged.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{collections::HashMap, sync::Arc};
+    /// # fn test(client: &eppo::Client) {
+    /// let assignments = client.get_all_assignments("user-id", HashMap::new(), None);
+    /// for (flag_key, value) in &assignments {
+    ///     println!("{flag_key}: {value:?}");
+    /// }
+    ///
+    /// // Only evaluate a subset of flags.
+    /// let assignments =
+    ///     client.get_all_assignments("user-id", HashMap::new(), Some(&["flag-a", "flag-b"]));
+    /// # }
+    /// ```
+    pub fn get_all_assignments(
+        &self,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
+        flag_keys: Option<&[&str]>,
+    ) -> HashMap<Str, AssignmentValue> {
+        let subject_key = &subject_key.into();
+        let subject_attributes = &subject_attributes.into_subject_attributes();
+
+        self.inner
+            .evaluator
+            .get_all_assignments(subject_key, subject_attributes, flag_keys)
+            .into_iter()
+            .map(|(flag_key, Assignment { value, event })| {
+                if let Some(event) = event {
+                    self.log_assignment_event(event);
+                }
+                (flag_key, value)
+            })
+            .collect()
+    }
+
+    /// Retrieves the assignment value for a given JSON feature flag and subject, deserialized into
+    /// `T`.
+    ///
+    /// The value is deserialized directly from the raw JSON string stored in configuration, which
+    /// avoids going through an intermediate [`serde_json::Value`].
+    ///
+    /// Returns `Ok(None)` in the same cases as [`Client::get_json_assignment()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::JsonDeserialization`] if the value cannot be deserialized into `T`.
+    /// Evaluation errors are returned as [`Error::EvaluationError`] (only if graceful mode is
+    /// disabled).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::collections::HashMap;
+    /// #[derive(serde::Deserialize)]
+    /// struct BannerConfig {
+    ///     title: String,
+    ///     color: String,
+    /// }
+    ///
+    /// # fn test(client: &eppo::Client) -> eppo::Result<()> {
+    /// let banner: Option<BannerConfig> =
+    ///     client.get_json_assignment_as("banner-config", "user-id", HashMap::new())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_json_assignment_as<T: DeserializeOwned>(
+        &self,
+        flag_key: &str,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
+    ) -> Result<Option<T>, Error> {
+        let raw = self
+            .get_assignment_inner(
+                flag_key,
+                subject_key,
+                subject_attributes,
+                Some(VariationType::Json),
+                |x| match x {
+                    AssignmentValue::Json { raw, .. } => Some(raw),
+                    // Cannot happen because the type is checked during evaluation.
+                    _ => None,
+                },
+            )
+            .map_err(Error::EvaluationError)?
+            .flatten();
+
+        let Some(raw) = raw else {
+            return Ok(None);
+        };
+
+        serde_json::from_str(&raw)
+            .map(Some)
+            .map_err(|err| Error::JsonDeserialization {
+                flag_key: flag_key.to_owned(),
+                source: Arc::new(err),
+            })
+    }
+
     fn get_assignment_inner<T>(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
         expected_type: Option<VariationType>,
         convert: impl FnOnce(AssignmentValue) -> T,
     ) -> Result<Option<T>, EvaluationError> {
-        let assignment = self.evaluator.get_assignment(
-            flag_key,
-            subject_key,
-            subject_attributes,
-            expected_type,
-        )?;
+        let assignment =
+            self.evaluate_assignment(flag_key, subject_key, subject_attributes, expected_type)?;
 
         let Some(Assignment { value, event }) = assignment else {
             return Ok(None);
         };
 
         if let Some(event) = event {
-            log::trace!(target: "eppo",
-                        event:serde;
-                        "logging assignment");
-            self.config.assignment_logger.log_assignment(event);
+            self.log_assignment_event(event);
         }
 
         Ok(Some(convert(value)))
     }
 
+    /// Evaluate assignment, handling evaluation errors according to graceful mode. Does not log
+    /// the assignment event.
+    fn evaluate_assignment(
+        &self,
+        flag_key: &str,
+        subject_key: impl Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
+        expected_type: Option<VariationType>,
+    ) -> Result<Option<Assignment>, EvaluationError> {
+        let subject_key = &subject_key.into();
+        let subject_attributes = &subject_attributes.into_subject_attributes();
+
+        let result = self.inner.evaluator.get_assignment(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            expected_type,
+        );
+
+        match result {
+            Ok(assignment) => Ok(assignment),
+            Err(err) => {
+                if self.inner.is_graceful_mode.load(Ordering::Acquire) {
+                    log::warn!(target: "eppo",
+                               flag_key,
+                               subject_key;
+                               "returning default assignment because of evaluation error: {err}");
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    /// Same as [`Client::get_assignment()`] but never logs the assignment event.
+    ///
+    /// This is intended for internal tooling (e.g., an admin screen previewing assignments for
+    /// arbitrary subjects) where evaluations must not be recorded as real exposures.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::collections::HashMap;
+    /// # fn test(client: &eppo::Client) {
+    /// let preview = client
+    ///     .get_assignment_unlogged("a-boolean-flag", "user-id", HashMap::new())
+    ///     .unwrap_or_default();
+    /// # }
+    /// ```
+    pub fn get_assignment_unlogged(
+        &self,
+        flag_key: &str,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
+    ) -> Result<Option<AssignmentValue>, EvaluationError> {
+        let assignment =
+            self.evaluate_assignment(flag_key, subject_key, subject_attributes, None)?;
+        Ok(assignment.map(|assignment| assignment.value))
+    }
+
+    /// Get the assignment value for a given feature flag and subject, along with a
+    /// [`FlagEvaluationCode`] explaining why this value was selected (or why there is no value).
+    ///
+    /// This is a cheaper alternative to [`Client::get_assignment_details()`] that is suitable for
+    /// production use, e.g., for per-request logging of why a subject got the default value.
+    ///
+    /// Evaluation errors (e.g., type mismatch) are not returned but are reflected in the code
+    /// instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::collections::HashMap;
+    /// # use eppo::FlagEvaluationCode;
+    /// # fn test(client: &eppo::Client) {
+    /// let (assignment, code) =
+    ///     client.get_assignment_with_reason("a-boolean-flag", "user-id", HashMap::new());
+    /// if code != FlagEvaluationCode::Match {
+    ///     println!("serving default value because of {code:?}");
+    /// }
+    /// let assignment = assignment.and_then(|x| x.as_boolean()).unwrap_or(false);
+    /// # }
+    /// ```
+    pub fn get_assignment_with_reason(
+        &self,
+        flag_key: &str,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
+    ) -> (Option<AssignmentValue>, FlagEvaluationCode) {
+        let subject_key = &subject_key.into();
+        let subject_attributes = &subject_attributes.into_subject_attributes();
+
+        let (assignment, mut code) = self.inner.evaluator.get_assignment_with_reason(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            None,
+        );
+        if code == FlagEvaluationCode::Match && self.is_configuration_stale() {
+            code = FlagEvaluationCode::ConfigurationStale;
+        }
+
+        let value = assignment.map(|Assignment { value, event }| {
+            if let Some(event) = event {
+                self.log_assignment_event(event);
+            }
+            value
+        });
+
+        (value, code)
+    }
+
     /// Get the assignment value for a given feature flag and subject, along with details of why
     /// this value was selected.
     ///
@@ -385,8 +902,8 @@ impl<'a> Client<'a> {
     pub fn get_assignment_details(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> EvaluationResultWithDetails<AssignmentValue> {
         self.get_assignment_details_inner(flag_key, subject_key, subject_attributes, None)
     }
@@ -399,8 +916,8 @@ impl<'a> Client<'a> {
     pub fn get_string_assignment_details(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> EvaluationResultWithDetails<Str> {
         self.get_assignment_details_inner(
             flag_key,
@@ -422,8 +939,8 @@ impl<'a> Client<'a> {
     pub fn get_integer_assignment_details(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> EvaluationResultWithDetails<i64> {
         self.get_assignment_details_inner(
             flag_key,
@@ -445,8 +962,8 @@ impl<'a> Client<'a> {
     pub fn get_numeric_assignment_details(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> EvaluationResultWithDetails<f64> {
         self.get_assignment_details_inner(
             flag_key,
@@ -468,8 +985,8 @@ impl<'a> Client<'a> {
     pub fn get_boolean_assignment_details(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> EvaluationResultWithDetails<bool> {
         self.get_assignment_details_inner(
             flag_key,
@@ -491,8 +1008,8 @@ impl<'a> Client<'a> {
     pub fn get_json_assignment_details(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
     ) -> EvaluationResultWithDetails<Arc<serde_json::Value>> {
         self.get_assignment_details_inner(
             flag_key,
@@ -509,11 +1026,14 @@ impl<'a> Client<'a> {
     fn get_assignment_details_inner(
         &self,
         flag_key: &str,
-        subject_key: &Str,
-        subject_attributes: &Arc<Attributes>,
+        subject_key: impl Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
         expected_type: Option<VariationType>,
     ) -> EvaluationResultWithDetails<AssignmentValue> {
-        let (result, event) = self.evaluator.get_assignment_details(
+        let subject_key = &subject_key.into();
+        let subject_attributes = &subject_attributes.into_subject_attributes();
+
+        let (mut result, event) = self.inner.evaluator.get_assignment_details(
             flag_key,
             subject_key,
             subject_attributes,
@@ -521,45 +1041,2679 @@ impl<'a> Client<'a> {
         );
 
         if let Some(event) = event {
-            log::trace!(target: "eppo",
-                        event:serde;
-                        "logging assignment");
-            self.config.assignment_logger.log_assignment(event);
+            self.log_assignment_event(event);
         }
 
+        self.report_stale_configuration(&mut result.evaluation_details);
         result
     }
 
-    /// Start a poller thread to fetch configuration from the server.
-    pub fn start_poller_thread(&mut self) -> Result<PollerThread, Error> {
-        PollerThread::start(PollerThreadConfig {
-            store: self.configuration_store.clone(),
-            base_url: self.config.base_url.clone(),
-            api_key: self.config.api_key.clone(),
-        })
-    }
-}
+    /// Same as [`Client::get_assignment_details()`] but never logs the assignment event.
+    ///
+    /// Returned evaluation details have
+    /// [`logging_suppressed`](EvaluationDetails::logging_suppressed) set.
+    pub fn get_assignment_details_unlogged(
+        &self,
+        flag_key: &str,
+        subject_key: impl AsRef<str> + Into<Str>,
+        subject_attributes: impl IntoSubjectAttributes,
+    ) -> EvaluationResultWithDetails<AssignmentValue> {
+        let subject_key = &subject_key.into();
+        let subject_attributes = &subject_attributes.into_subject_attributes();
 
-#[cfg(test)]
-mod tests {
-    use std::{collections::HashMap, sync::Arc};
+        let (mut result, event) = self.inner.evaluator.get_assignment_details(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            None,
+        );
 
-    use crate::{Client, ClientConfig};
-    use eppo_core::configuration_store::ConfigurationStore;
+        // Drop the event first, so evaluation details are not shared and don't need to be cloned.
+        drop(event);
+        Arc::make_mut(&mut result.evaluation_details).logging_suppressed = true;
 
-    #[test]
-    fn returns_none_while_no_configuration() {
-        let configuration_store = Arc::new(ConfigurationStore::new());
-        let client = Client::new_with_configuration_store(
-            ClientConfig::from_api_key("api-key"),
-            configuration_store.clone(),
-        );
+        self.report_stale_configuration(&mut result.evaluation_details);
+        result
+    }
 
-        assert_eq!(
-            client
-                .get_assignment("flag", &"subject".into(), &Arc::new(HashMap::new()))
+    /// Returns `true` if the currently-active configuration is older than
+    /// [`ClientConfig::max_configuration_age()`].
+    fn is_configuration_stale(&self) -> bool {
+        let Some(max_age) = self.inner.config.max_configuration_age else {
+            return false;
+        };
+        self.inner
+            .configuration_store
+            .get_configuration()
+            .is_some_and(|configuration| {
+                configuration.is_stale_at(max_age, self.inner.config.clock.now())
+            })
+    }
+
+    /// Replace `Match` code with `ConfigurationStale` if configuration used for evaluation is
+    /// older than [`ClientConfig::max_configuration_age()`].
+    fn report_stale_configuration(&self, details: &mut Arc<EvaluationDetails>) {
+        let Some(max_age) = self.inner.config.max_configuration_age else {
+            return;
+        };
+        let Some(fetched_at) = details.config_fetched_at else {
+            return;
+        };
+        if details.flag_evaluation_code != Some(FlagEvaluationCode::Match)
+            || !details.is_configuration_stale(max_age)
+        {
+            return;
+        }
+
+        let details = Arc::make_mut(details);
+        details.flag_evaluation_code = Some(FlagEvaluationCode::ConfigurationStale);
+        details.flag_evaluation_description = format!(
+            "{} Configuration is stale: it was fetched at {fetched_at}, which is more than {max_age:?} ago.",
+            details.flag_evaluation_description
+        );
+    }
+
+    /// Evaluate a contextual bandit for the given subject and actions.
+    ///
+    /// The flag is evaluated as a string flag first. If the resulting variation is associated with
+    /// a bandit, the bandit is evaluated to select one of the supplied `actions`. Otherwise, the
+    /// flag variation is returned and [`BanditResult::action`] is `None`.
+    ///
+    /// `actions` is either a map from action keys to their attributes, or a slice of action keys
+    /// if actions have no attributes (see [`Actions`]).
+    ///
+    /// If the configuration has not been fetched yet or evaluation fails, `default_variation` is
+    /// returned.
+    ///
+    /// Assignment and bandit events are passed to the [`AssignmentLogger`](crate::AssignmentLogger)
+    /// and are not included in the returned result.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::collections::HashMap;
+    /// # use eppo::ContextAttributes;
+    /// # fn test(client: &eppo::Client) {
+    /// let subject_attributes = [("age", 42.0)].into_iter().collect::<ContextAttributes>();
+    /// let actions = HashMap::from([
+    ///     ("nike".into(), [("brand_affinity", 2.3)].into_iter().collect()),
+    ///     ("adidas".into(), [("brand_affinity", 1.4)].into_iter().collect()),
+    /// ]);
+    /// let result = client.get_bandit_action(
+    ///     "a-bandit-flag",
+    ///     &"user-id".into(),
+    ///     &subject_attributes,
+    ///     &actions,
+    ///     &"default".into(),
+    /// );
+    /// if let Some(action) = result.action {
+    ///     println!("selected action: {action}");
+    /// }
+    ///
+    /// // Actions without attributes can be supplied as keys.
+    /// let result = client.get_bandit_action(
+    ///     "a-bandit-flag",
+    ///     &"user-id".into(),
+    ///     &subject_attributes,
+    ///     &["nike".into(), "adidas".into()],
+    ///     &"default".into(),
+    /// );
+    /// # }
+    /// ```
+    pub fn get_bandit_action<'b>(
+        &self,
+        flag_key: &str,
+        subject_key: &Str,
+        subject_attributes: &ContextAttributes,
+        actions: impl Into<Actions<'b>>,
+        default_variation: &Str,
+    ) -> BanditResult {
+        let mut result = self.inner.evaluator.get_bandit_action(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            actions,
+            None,
+            default_variation,
+        );
+
+        self.log_bandit_result_events(&mut result);
+
+        result
+    }
+
+    /// Same as [`Client::get_bandit_action()`] but only considers actions with keys in
+    /// `allowed_actions`.
+    ///
+    /// This allows excluding temporarily unavailable actions (e.g., out-of-stock items) without
+    /// rebuilding `actions`, so action attributes can be cached between calls. Excluded actions
+    /// are never selected, even if they have the best score. If none of the actions is allowed,
+    /// only the flag variation is returned, same as if no actions were supplied.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::collections::{HashMap, HashSet};
+    /// # use eppo::ContextAttributes;
+    /// # fn test(client: &eppo::Client) {
+    /// let actions = HashMap::from([
+    ///     ("nike".into(), [("brand_affinity", 2.3)].into_iter().collect()),
+    ///     ("adidas".into(), [("brand_affinity", 1.4)].into_iter().collect()),
+    /// ]);
+    /// // Only "nike" is in stock for this request.
+    /// let in_stock = HashSet::from(["nike".into()]);
+    /// let result = client.get_bandit_action_with_allowed_actions(
+    ///     "a-bandit-flag",
+    ///     &"user-id".into(),
+    ///     &ContextAttributes::default(),
+    ///     &actions,
+    ///     &in_stock,
+    ///     &"default".into(),
+    /// );
+    /// # }
+    /// ```
+    pub fn get_bandit_action_with_allowed_actions<'b>(
+        &self,
+        flag_key: &str,
+        subject_key: &Str,
+        subject_attributes: &ContextAttributes,
+        actions: impl Into<Actions<'b>>,
+        allowed_actions: &HashSet<Str>,
+        default_variation: &Str,
+    ) -> BanditResult {
+        let mut result = self.inner.evaluator.get_bandit_action(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            actions,
+            Some(allowed_actions),
+            default_variation,
+        );
+
+        self.log_bandit_result_events(&mut result);
+
+        result
+    }
+
+    /// Evaluate a contextual bandit and return up to `k` best actions for the subject, ranked by
+    /// the bandit.
+    ///
+    /// The first result is the same as returned by [`Client::get_bandit_action()`]. Every next
+    /// action is selected from the remaining ones, so the ranking is consistent with single-action
+    /// selection and deterministic for the same inputs. If the flag variation is not a bandit or
+    /// bandit evaluation fails, a single result without action is returned.
+    ///
+    /// A bandit event is logged for every returned action, so all shown actions can be logged.
+    /// The flag assignment event is logged once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eppo::{BanditRequest, ContextAttributes};
+    /// # fn test(client: &eppo::Client) {
+    /// let actions = vec!["nike".into(), "adidas".into(), "reebok".into()];
+    /// let results = client.get_bandit_actions_ranked(
+    ///     &BanditRequest {
+    ///         flag_key: "a-bandit-flag",
+    ///         subject_key: &"user-id".into(),
+    ///         subject_attributes: &ContextAttributes::default(),
+    ///         actions: (&actions).into(),
+    ///         default_variation: &"default".into(),
+    ///     },
+    ///     2,
+    /// );
+    /// for result in results {
+    ///     println!("{:?}", result.action);
+    /// }
+    /// # }
+    /// ```
+    pub fn get_bandit_actions_ranked(
+        &self,
+        request: &BanditRequest,
+        k: usize,
+    ) -> Vec<BanditResult> {
+        let mut results = self
+            .inner
+            .evaluator
+            .get_bandit_actions_ranked(request, k, None);
+
+        for result in &mut results {
+            self.log_bandit_result_events(result);
+        }
+
+        results
+    }
+
+    /// Same as [`Client::get_bandit_actions_ranked()`] but only ranks actions with keys in
+    /// `allowed_actions`. See [`Client::get_bandit_action_with_allowed_actions()`].
+    pub fn get_bandit_actions_ranked_with_allowed_actions(
+        &self,
+        request: &BanditRequest,
+        k: usize,
+        allowed_actions: &HashSet<Str>,
+    ) -> Vec<BanditResult> {
+        let mut results =
+            self.inner
+                .evaluator
+                .get_bandit_actions_ranked(request, k, Some(allowed_actions));
+
+        for result in &mut results {
+            self.log_bandit_result_events(result);
+        }
+
+        results
+    }
+
+    /// Precompute assignments for all flags and bandit actions for the given subject.
+    ///
+    /// This is useful to serve precomputed assignments to client SDKs (e.g., from an edge
+    /// service). `flag_actions` maps bandit flag keys to actions available for the subject.
+    ///
+    /// Assignment events are not logged: they are expected to be logged by the client SDK when
+    /// it uses assignments. Use [`PrecomputedConfiguration::to_obfuscated_json()`] to serialize
+    /// result to the format expected by client SDKs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use eppo::ContextAttributes;
+    /// # fn test(client: &eppo::Client) {
+    /// let subject_attributes = [("age", 42.0)].into_iter().collect::<ContextAttributes>();
+    /// let precomputed = client.get_precomputed_configuration(
+    ///     &"user-id".into(),
+    ///     &subject_attributes,
+    ///     &HashMap::new(),
+    /// );
+    /// let json = precomputed.to_obfuscated_json().unwrap();
+    /// # }
+    /// ```
+    pub fn get_precomputed_configuration(
+        &self,
+        subject_key: &Str,
+        subject_attributes: &ContextAttributes,
+        flag_actions: &HashMap<
+            /* flag_key: */ Str,
+            HashMap</* action_key: */ Str, ContextAttributes>,
+        >,
+    ) -> PrecomputedConfiguration {
+        self.inner.evaluator.get_precomputed_configuration(
+            subject_key,
+            &Arc::new(subject_attributes.clone()),
+            flag_actions,
+            None,
+            false,
+        )
+    }
+
+    /// Same as [`Client::get_precomputed_configuration()`] but every assignment carries compact
+    /// evaluation details: evaluation code, matched allocation key, and variation key.
+    ///
+    /// This is useful to debug why a precomputed assignment differs from server-side evaluation.
+    /// Details are included when the result is serialized as is (e.g., with `serde_json`) and are
+    /// omitted from obfuscated configuration. Collecting details is expensive, so this should not
+    /// be used to serve client SDKs.
+    pub fn get_precomputed_configuration_with_details(
+        &self,
+        subject_key: &Str,
+        subject_attributes: &ContextAttributes,
+        flag_actions: &HashMap<
+            /* flag_key: */ Str,
+            HashMap</* action_key: */ Str, ContextAttributes>,
+        >,
+    ) -> PrecomputedConfiguration {
+        self.inner.evaluator.get_precomputed_configuration(
+            subject_key,
+            &Arc::new(subject_attributes.clone()),
+            flag_actions,
+            None,
+            true,
+        )
+    }
+
+    /// Same as [`Client::get_precomputed_configuration()`] but only evaluates flags with keys in
+    /// `flag_keys` (along with their bandits). Other flags are absent from the result.
+    ///
+    /// This is useful when clients only need a small subset of flags, as evaluating all flags for
+    /// every request is wasteful.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::{HashMap, HashSet};
+    /// # use eppo::ContextAttributes;
+    /// # fn test(client: &eppo::Client) {
+    /// let web_flags = ["web-banner".into(), "web-theme".into()].into_iter().collect::<HashSet<_>>();
+    /// let precomputed = client.get_precomputed_configuration_for_flags(
+    ///     &"user-id".into(),
+    ///     &ContextAttributes::default(),
+    ///     &HashMap::new(),
+    ///     &web_flags,
+    /// );
+    /// # }
+    /// ```
+    pub fn get_precomputed_configuration_for_flags(
+        &self,
+        subject_key: &Str,
+        subject_attributes: &ContextAttributes,
+        flag_actions: &HashMap<
+            /* flag_key: */ Str,
+            HashMap</* action_key: */ Str, ContextAttributes>,
+        >,
+        flag_keys: &HashSet<Str>,
+    ) -> PrecomputedConfiguration {
+        self.inner.evaluator.get_precomputed_configuration(
+            subject_key,
+            &Arc::new(subject_attributes.clone()),
+            flag_actions,
+            Some(flag_keys),
+            false,
+        )
+    }
+
+    /// Respond to a request for precomputed assignments from a client SDK.
+    ///
+    /// This is a framework-agnostic building block for precomputed assignment services: parse
+    /// [`PrecomputedRequest`] from the JSON request body and respond with the returned
+    /// [`PrecomputedResponse`] serialized as JSON. See `examples/precomputed` (standard library only)
+    /// and `examples/precomputed_axum` for complete services.
+    ///
+    /// Requires the `server` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use eppo::PrecomputedRequest;
+    /// # fn test(client: &eppo::Client) {
+    /// let request: PrecomputedRequest = serde_json::from_str(r#"{"subject_key": "user-id"}"#).unwrap();
+    /// let response = client.handle_precomputed_request(request);
+    /// let json = serde_json::to_string(&response).unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "server")]
+    pub fn handle_precomputed_request(&self, request: PrecomputedRequest) -> PrecomputedResponse {
+        self.get_precomputed_configuration(
+            &request.subject_key,
+            &request.subject_attributes,
+            &request.bandit_actions,
+        )
+        .obfuscate()
+    }
+
+    /// Evaluate a contextual bandit for the given subject and actions, along with details of why
+    /// this variation and action were selected.
+    ///
+    /// Evaluation details are also attached to the logged assignment event.
+    ///
+    /// *NOTE:* It is a debug function and is slower due to the need to collect all the
+    /// details. Prefer using [`Client::get_bandit_action()`] in production.
+    pub fn get_bandit_action_details<'b>(
+        &self,
+        flag_key: &str,
+        subject_key: &Str,
+        subject_attributes: &ContextAttributes,
+        actions: impl Into<Actions<'b>>,
+        default_variation: &Str,
+    ) -> (BanditResult, EvaluationDetails) {
+        let (mut result, details) = self.inner.evaluator.get_bandit_action_details(
+            flag_key,
+            subject_key,
+            subject_attributes,
+            actions,
+            None,
+            default_variation,
+        );
+
+        self.log_bandit_result_events(&mut result);
+
+        (result, details)
+    }
+
+    fn log_bandit_result_events(&self, result: &mut BanditResult) {
+        if let Some(event) = result.assignment_event.take() {
+            self.log_assignment_event(event);
+        }
+
+        if let Some(event) = result.bandit_event.take() {
+            self.log_bandit_event(event);
+        }
+    }
+
+    fn log_assignment_event(&self, event: AssignmentEvent) {
+        if let Some(cache) = &self.inner.config.assignment_cache {
+            let (key, value) = AssignmentCacheKey::from_assignment_event(&event);
+            if !cache.insert(key, value) {
+                log::trace!(target: "eppo", "skipping duplicate assignment event");
+                return;
+            }
+        }
+
+        log::trace!(target: "eppo",
+                    event:serde;
+                    "logging assignment");
+        self.inner.config.assignment_logger.log_assignment(event);
+    }
+
+    fn log_bandit_event(&self, event: BanditEvent) {
+        if let Some(cache) = &self.inner.config.bandit_cache {
+            let (key, value) = AssignmentCacheKey::from_bandit_event(&event);
+            if !cache.insert(key, value) {
+                log::trace!(target: "eppo", "skipping duplicate bandit event");
+                return;
+            }
+        }
+
+        log::trace!(target: "eppo",
+                    event:serde;
+                    "logging bandit action");
+        match &self.inner.config.bandit_logger {
+            Some(bandit_logger) => bandit_logger(event),
+            None => self.inner.config.assignment_logger.log_bandit_action(event),
+        }
+    }
+
+    /// Get currently-active configuration. Returns `None` if configuration hasn't been fetched or
+    /// set yet.
+    ///
+    /// The returned configuration is an immutable snapshot and is not affected by further
+    /// configuration updates.
+    pub fn get_configuration(&self) -> Option<Arc<Configuration>> {
+        self.inner.configuration_store.get_configuration()
+    }
+
+    /// Write flags configuration that the client is currently serving to `path` in UFC JSON
+    /// format, exactly as it was received from the server. This is useful to capture
+    /// configuration for incident forensics.
+    ///
+    /// The file can be loaded back with [`ClientConfig::configuration_file()`]. Bandit models can
+    /// be exported with [`Configuration::to_bandits_json()`] from [`Client::get_configuration()`].
+    ///
+    /// Returns `false` (and doesn't create the file) if configuration hasn't been fetched or set
+    /// yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be written.
+    pub fn dump_configuration(&self, path: impl AsRef<Path>) -> Result<bool, Error> {
+        let Some(configuration) = self.inner.configuration_store.get_configuration() else {
+            return Ok(false);
+        };
+        std::fs::write(path, configuration.to_ufc_json())?;
+        Ok(true)
+    }
+
+    /// Returns `true` if the client has configuration (either fetched from the server or set
+    /// explicitly) and can serve assignments.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let client = ClientConfig::from_api_key("api-key").to_client();
+    /// assert!(!client.is_initialized());
+    /// ```
+    pub fn is_initialized(&self) -> bool {
+        self.inner.configuration_store.get_configuration().is_some()
+    }
+
+    /// Returns time elapsed since the currently-active configuration was fetched, or `None` if
+    /// configuration hasn't been fetched or set yet.
+    ///
+    /// This is useful for health checks to distinguish a client that never received configuration
+    /// from a client with stale configuration.
+    pub fn configuration_age(&self) -> Option<Duration> {
+        let configuration = self.inner.configuration_store.get_configuration()?;
+        // Clock may go backwards, so we clamp negative durations to zero.
+        Some(
+            chrono::Utc::now()
+                .signed_duration_since(configuration.fetched_at)
+                .to_std()
+                .unwrap_or(Duration::ZERO),
+        )
+    }
+
+    /// Returns timestamps and environment of the currently-active configuration, or `None` if
+    /// configuration hasn't been fetched or set yet.
+    ///
+    /// `published_at` can be used to alert when flag configuration becomes stale.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let client = ClientConfig::from_api_key("api-key").to_client();
+    /// if let Some(timestamps) = client.configuration_timestamps() {
+    ///     println!("configuration published at {}", timestamps.published_at);
+    /// }
+    /// ```
+    pub fn configuration_timestamps(&self) -> Option<ConfigurationTimestamps> {
+        Some(
+            self.inner
+                .configuration_store
+                .get_configuration()?
+                .timestamps(),
+        )
+    }
+
+    /// Returns approximate memory occupied by the currently-active configuration, or `None` if
+    /// configuration hasn't been fetched or set yet.
+    ///
+    /// This is useful to size containers for large configurations. See
+    /// [`Configuration::memory_usage()`] for how the estimate is computed.
+    pub fn configuration_memory_usage(&self) -> Option<ConfigurationMemoryStats> {
+        Some(
+            self.inner
+                .configuration_store
+                .get_configuration()?
+                .memory_usage(),
+        )
+    }
+
+    /// Returns keys of all flags in the currently-active configuration, sorted alphabetically.
+    /// Returns an empty vector if configuration hasn't been fetched or set yet.
+    ///
+    /// The list includes disabled flags and flags that failed to parse. Use
+    /// [`Client::unparsed_flag_keys()`] to find the latter.
+    pub fn flag_keys(&self) -> Vec<Str> {
+        self.configuration_keys(Configuration::flag_keys)
+    }
+
+    /// Returns keys of flags in the currently-active configuration that failed to parse, sorted
+    /// alphabetically. The SDK cannot serve assignments for these flags, which usually means that
+    /// the SDK needs an upgrade.
+    pub fn unparsed_flag_keys(&self) -> Vec<Str> {
+        self.configuration_keys(Configuration::unparsed_flag_keys)
+    }
+
+    /// Returns keys of all bandits in the currently-active configuration, sorted alphabetically.
+    /// Returns an empty vector if configuration hasn't been fetched or set yet.
+    pub fn bandit_keys(&self) -> Vec<Str> {
+        self.configuration_keys(Configuration::bandit_keys)
+    }
+
+    fn configuration_keys(
+        &self,
+        get_keys: impl FnOnce(&Configuration) -> HashSet<Str>,
+    ) -> Vec<Str> {
+        let Some(configuration) = self.inner.configuration_store.get_configuration() else {
+            return Vec::new();
+        };
+        let mut keys = get_keys(&configuration).into_iter().collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    /// Replace currently-active configuration.
+    ///
+    /// This can be used to warm-start the client with configuration obtained elsewhere (e.g., from
+    /// another process). Note that if the poller thread is running, the configuration will be
+    /// replaced again on the next successful fetch that returns a newer configuration.
+    pub fn set_configuration(&self, configuration: Arc<Configuration>) {
+        self.inner
+            .configuration_store
+            .set_configuration(configuration);
+    }
+
+    /// Start a poller thread to fetch configuration from the server.
+    ///
+    /// The poller thread is owned by the client and is stopped when the client (including all its
+    /// clones) is dropped (use
+    /// [`Client::shutdown()`] to wait for the thread to exit). The returned handle can be used to
+    /// wait for configuration. If a poller thread is already running, it is stopped and replaced
+    /// with a new one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPollInterval`] if poll interval is zero and
+    /// [`Error::InvalidConfiguration`] if request timeout is zero.
+    pub fn start_poller_thread(&self) -> Result<PollerThread, Error> {
+        let poller_thread = PollerThread::start(self.poller_thread_config()?)?;
+        if let Some(previous) = self.poller_thread().replace(poller_thread.clone()) {
+            previous.stop();
+        }
+        Ok(poller_thread)
+    }
+
+    /// Stop the poller thread (if running) and block waiting for it to exit.
+    ///
+    /// The poller thread is shared by all clones of the client, so this stops polling for all of
+    /// them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PollerThreadPanicked`] if the thread has panicked.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use eppo::{Client, ClientConfig};
+    /// let client = Client::new(ClientConfig::from_api_key("api-key"));
+    /// client.start_poller_thread().unwrap();
+    /// // ...
+    /// client.shutdown().unwrap();
+    /// ```
+    pub fn shutdown(self) -> Result<(), Error> {
+        // Release the lock before blocking on the thread.
+        let poller_thread = self.poller_thread().take();
+        match poller_thread {
+            Some(poller_thread) => poller_thread.shutdown(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns metadata about configuration fetches of the poller thread started with
+    /// [`Client::start_poller_thread()`]. Useful for debugging stale configuration.
+    ///
+    /// Returns `None` if the poller thread is not running.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use eppo::{Client, ClientConfig};
+    /// let client = Client::new(ClientConfig::from_api_key("api-key"));
+    /// client.start_poller_thread().unwrap();
+    /// // ...
+    /// if let Some(stats) = client.fetch_stats() {
+    ///     println!("last successful fetch: {:?}", stats.last_success_at);
+    /// }
+    /// ```
+    pub fn fetch_stats(&self) -> Option<FetchStats> {
+        self.poller_thread().as_ref().map(PollerThread::stats)
+    }
+
+    /// Fetch configuration from the server immediately instead of waiting for the next poll, and
+    /// block until the fetch completes. Useful to pick up urgent changes (e.g., a kill switch)
+    /// right away.
+    ///
+    /// Concurrent calls share the same fetch.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::PollerStopped`] if the poller thread started with
+    ///   [`Client::start_poller_thread()`] is not running.
+    /// - The error of the fetch if it failed. Previous configuration is kept in this case.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use eppo::{Client, ClientConfig};
+    /// let client = Client::new(ClientConfig::from_api_key("api-key"));
+    /// client.start_poller_thread().unwrap();
+    /// // ...
+    /// client.refresh_configuration().unwrap();
+    /// ```
+    pub fn refresh_configuration(&self) -> Result<(), Error> {
+        // Release the lock before blocking on the fetch.
+        let poller_thread = self.poller_thread().clone();
+        match poller_thread {
+            Some(poller_thread) => poller_thread.poll_now(),
+            None => Err(Error::PollerStopped),
+        }
+    }
+
+    /// Start a poller task to fetch configuration from the server.
+    ///
+    /// This is an alternative to [`Client::start_poller_thread()`] for applications that already
+    /// run a tokio runtime. The task is spawned on the current runtime, so no dedicated OS thread
+    /// is created. The runtime must have the time driver enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPollInterval`] if poll interval is zero and
+    /// [`Error::InvalidConfiguration`] if request timeout is zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async fn test() {
+    /// # use eppo::{Client, ClientConfig};
+    /// let client = Client::new(ClientConfig::from_api_key("api-key"));
+    /// let poller = client.start_poller_task().unwrap();
+    /// poller.wait_for_configuration().await.unwrap();
+    /// # }
+    /// ```
+    pub fn start_poller_task(&self) -> Result<PollerTask, Error> {
+        Ok(PollerTask::start(self.poller_thread_config()?))
+    }
+
+    fn poller_thread(&self) -> MutexGuard<'_, Option<PollerThread>> {
+        self.inner
+            .poller_thread
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn poller_thread_config(&self) -> Result<PollerThreadConfig, Error> {
+        if self.inner.config.poll_interval.is_zero() {
+            return Err(Error::InvalidPollInterval);
+        }
+        if self.inner.config.request_timeout.is_zero() {
+            return Err(Error::InvalidConfiguration(
+                "request timeout must be greater than zero".to_owned(),
+            ));
+        }
+
+        Ok(PollerThreadConfig {
+            store: self.inner.configuration_store.clone(),
+            base_url: self.inner.config.base_url.clone(),
+            base_urls: self.inner.config.base_urls.clone(),
+            api_key: self.inner.config.api_key.clone(),
+            interval: self.inner.config.poll_interval,
+            jitter: self.inner.config.poll_jitter,
+            headers: self.inner.config.extra_headers.clone(),
+            request_timeout: self.inner.config.request_timeout,
+            obfuscated: self.inner.config.obfuscated,
+            always_fetch_bandits: self.inner.config.always_fetch_bandits,
+            query_params: self.inner.config.query_params.clone(),
+            max_response_bytes: self.inner.config.max_response_bytes,
+            auth_mode: self.inner.config.auth_mode,
+            configuration_file: self.inner.config.configuration_file_paths(),
+            persistence: self.inner.config.configuration_persistence.clone(),
+            on_configuration_change: self.inner.config.on_configuration_change.clone(),
+            on_error: self.inner.config.on_error.clone(),
+        })
+    }
+}
+
+impl Drop for ClientInner<'_> {
+    fn drop(&mut self) {
+        let poller_thread = self
+            .poller_thread
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner());
+        if let Some(poller_thread) = poller_thread {
+            // Using `.stop()` instead of `.shutdown()` here because we don't need to wait for the
+            // poller thread to exit.
+            poller_thread.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{
+        Actions, AssignmentValue, Attributes, AuthMode, BanditRequest, Client, ClientConfig,
+        Configuration, ConfigurationStore, ContextAttributes, Error, EvaluationError,
+        EvaluationObserver, FileConfigurationPersistence, FlagEvaluationCode, LruAssignmentCache,
+        StartupMode, Subject, Timestamp, SDK_METADATA,
+    };
+    use eppo_core::{
+        poller_thread::PollerThreadConfig,
+        ufc::{UniversalFlagConfig, VariationType},
+        Str,
+    };
+
+    const TEST_FLAGS_JSON: &[u8] = br#"{
+      "createdAt": "2024-07-18T00:00:00Z",
+      "format": "SERVER",
+      "environment": {"name": "test"},
+      "flags": {
+        "kill-switch": {
+          "key": "kill-switch",
+          "enabled": true,
+          "variationType": "BOOLEAN",
+          "variations": {
+            "on": {"key": "on", "value": true},
+            "off": {"key": "off", "value": false}
+          },
+          "allocations": [
+            {
+              "key": "on-for-all",
+              "splits": [{"variationKey": "on", "shards": []}],
+              "doLog": true
+            }
+          ],
+          "totalShards": 10000
+        }
+      }
+    }"#;
+
+    const TEST_TYPED_FLAGS_JSON: &[u8] = br#"{
+      "createdAt": "2024-07-18T00:00:00Z",
+      "format": "SERVER",
+      "environment": {"name": "test"},
+      "flags": {
+        "string-flag": {
+          "key": "string-flag",
+          "enabled": true,
+          "variationType": "STRING",
+          "variations": {
+            "de": {"key": "de", "value": "hallo"},
+            "other": {"key": "other", "value": "hello"}
+          },
+          "allocations": [
+            {
+              "key": "germany",
+              "rules": [
+                {
+                  "conditions": [
+                    {"attribute": "country", "operator": "ONE_OF", "value": ["DE"]}
+                  ]
+                }
+              ],
+              "splits": [{"variationKey": "de", "shards": []}],
+              "doLog": true
+            },
+            {
+              "key": "everyone-else",
+              "splits": [{"variationKey": "other", "shards": []}],
+              "doLog": true
+            }
+          ],
+          "totalShards": 10000
+        },
+        "integer-flag": {
+          "key": "integer-flag",
+          "enabled": true,
+          "variationType": "INTEGER",
+          "variations": {"one": {"key": "one", "value": 1}},
+          "allocations": [
+            {
+              "key": "all",
+              "rules": [
+                {
+                  "conditions": [{"attribute": "age", "operator": "GTE", "value": 18}]
+                }
+              ],
+              "splits": [{"variationKey": "one", "shards": []}],
+              "doLog": true
+            }
+          ],
+          "totalShards": 10000
+        },
+        "numeric-flag": {
+          "key": "numeric-flag",
+          "enabled": true,
+          "variationType": "NUMERIC",
+          "variations": {"half": {"key": "half", "value": 2.5}},
+          "allocations": [
+            {
+              "key": "all",
+              "splits": [{"variationKey": "half", "shards": []}],
+              "doLog": true
+            }
+          ],
+          "totalShards": 10000
+        },
+        "boolean-flag": {
+          "key": "boolean-flag",
+          "enabled": true,
+          "variationType": "BOOLEAN",
+          "variations": {"on": {"key": "on", "value": true}},
+          "allocations": [
+            {
+              "key": "premium",
+              "rules": [
+                {
+                  "conditions": [
+                    {"attribute": "is_premium", "operator": "ONE_OF", "value": ["true"]}
+                  ]
+                }
+              ],
+              "splits": [{"variationKey": "on", "shards": []}],
+              "doLog": true
+            }
+          ],
+          "totalShards": 10000
+        },
+        "json-flag": {
+          "key": "json-flag",
+          "enabled": true,
+          "variationType": "JSON",
+          "variations": {"obj": {"key": "obj", "value": "{\"a\": 1}"}},
+          "allocations": [
+            {
+              "key": "all",
+              "splits": [{"variationKey": "obj", "shards": []}],
+              "doLog": true
+            }
+          ],
+          "totalShards": 10000
+        }
+      }
+    }"#;
+
+    const TEST_BANDIT_FLAGS_JSON: &[u8] = br#"{
+      "createdAt": "2024-07-18T00:00:00Z",
+      "format": "SERVER",
+      "environment": {"name": "test"},
+      "flags": {
+        "banner-bandit-flag": {
+          "key": "banner-bandit-flag",
+          "enabled": true,
+          "variationType": "STRING",
+          "variations": {
+            "banner-bandit": {"key": "banner-bandit", "value": "banner-bandit"}
+          },
+          "allocations": [
+            {
+              "key": "bandit-for-all",
+              "splits": [{"variationKey": "banner-bandit", "shards": []}],
+              "doLog": true
+            }
+          ],
+          "totalShards": 10000
+        }
+      },
+      "bandits": {
+        "banner-bandit": [
+          {
+            "key": "banner-bandit",
+            "flagKey": "banner-bandit-flag",
+            "variationKey": "banner-bandit",
+            "variationValue": "banner-bandit"
+          }
+        ]
+      }
+    }"#;
+
+    const TEST_BANDITS_JSON: &[u8] = br#"{
+      "updatedAt": "2024-07-18T00:00:00Z",
+      "bandits": {
+        "banner-bandit": {
+          "banditKey": "banner-bandit",
+          "modelName": "falcon",
+          "modelVersion": "v1",
+          "modelData": {
+            "gamma": 1.0,
+            "defaultActionScore": 0.0,
+            "actionProbabilityFloor": 0.0,
+            "coefficients": {}
+          },
+          "updatedAt": "2024-07-18T00:00:00Z"
+        }
+      }
+    }"#;
+
+    #[test]
+    fn returns_none_while_no_configuration() {
+        let configuration_store = Arc::new(ConfigurationStore::new());
+        let client = Client::with_configuration_store(
+            ClientConfig::from_api_key("api-key"),
+            configuration_store.clone(),
+        );
+
+        assert_eq!(
+            client
+                .get_assignment("flag", "subject", Arc::new(HashMap::new()))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn reports_configuration_memory_usage() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert!(client.configuration_memory_usage().is_none());
+
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        let usage = client.configuration_memory_usage().unwrap();
+        assert_eq!(usage.flag_count, 1);
+        assert_eq!(usage.raw_json_bytes, TEST_FLAGS_JSON.len());
+        assert!(usage.flags_bytes > 0);
+    }
+
+    #[test]
+    fn serves_initial_configuration() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        assert_eq!(
+            client
+                .get_boolean_assignment("kill-switch", "subject", Arc::new(HashMap::new()))
+                .unwrap(),
+            Some(true)
+        );
+    }
+
+    /// Write `contents` to a file in the temp directory, unique for the test `name`.
+    fn write_temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("eppo-{}-{name}.json", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn dumped_configuration_can_be_loaded_back() {
+        let path = std::env::temp_dir().join(format!(
+            "eppo-{}-dumped-configuration.json",
+            std::process::id()
+        ));
+        let empty = ClientConfig::from_api_key("api-key").to_client();
+        assert!(!empty.dump_configuration(&path).unwrap());
+        assert!(!path.exists());
+
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        assert!(client.dump_configuration(&path).unwrap());
+        assert_eq!(std::fs::read(&path).unwrap(), TEST_FLAGS_JSON);
+
+        let restored = ClientConfig::from_api_key("api-key")
+            .configuration_file(&path)
+            .try_to_client()
+            .unwrap();
+        assert_eq!(
+            restored
+                .get_boolean_assignment("kill-switch", "subject", Arc::new(HashMap::new()))
+                .unwrap(),
+            Some(true)
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loads_configuration_file() {
+        let path = write_temp_file("loads-configuration-file", TEST_FLAGS_JSON);
+
+        let client = ClientConfig::from_api_key("api-key")
+            .configuration_file(&path)
+            .try_to_client()
+            .unwrap();
+
+        assert_eq!(
+            client
+                .get_boolean_assignment("kill-switch", "subject", Arc::new(HashMap::new()))
+                .unwrap(),
+            Some(true)
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_configuration_file_is_reported() {
+        let path = std::env::temp_dir().join("eppo-missing-configuration-file.json");
+
+        assert!(matches!(
+            ClientConfig::from_api_key("api-key")
+                .configuration_file(&path)
+                .try_to_client(),
+            Err(Error::Io(_))
+        ));
+
+        let client = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .configuration_file(&path)
+            .to_client();
+        let poller = client.start_poller_thread().unwrap();
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::from_secs(10)),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_configuration_file_is_reported_by_poller() {
+        let path = write_temp_file("invalid-configuration-file", b"not json");
+
+        let client = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .configuration_file(&path)
+            .to_client();
+        let poller = client.start_poller_thread().unwrap();
+
+        assert!(matches!(
+            poller.wait_for_configuration_timeout(Duration::from_secs(10)),
+            Err(Error::ConfigurationParseError(_))
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn fetch_errors_are_reported_to_on_error_callback() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let sender = std::sync::Mutex::new(sender);
+        let client = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .on_error(move |err| {
+                let _ = sender.lock().unwrap().send(err.is_retriable());
+            })
+            .to_client();
+
+        client.start_poller_thread().unwrap();
+
+        // Connection failures are retriable.
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).unwrap());
+    }
+
+    #[test]
+    fn configuration_persistence_reaches_poller_config() {
+        let client = ClientConfig::from_api_key("api-key")
+            .configuration_persistence(FileConfigurationPersistence::new(
+                std::env::temp_dir().join("eppo-persistence-poller-config.json"),
+            ))
+            .to_client();
+
+        let config = client.poller_thread_config().unwrap();
+
+        assert!(config.persistence.is_some());
+    }
+
+    #[test]
+    fn corrupt_persisted_configuration_falls_back_to_configuration_file() {
+        let persisted = write_temp_file("corrupt-persisted-configuration", b"not json");
+        let path = write_temp_file("persistence-fallback-configuration-file", TEST_FLAGS_JSON);
+
+        let client = ClientConfig::from_api_key("api-key")
+            .configuration_persistence(FileConfigurationPersistence::new(&persisted))
+            .configuration_file(&path)
+            .try_to_client()
+            .unwrap();
+
+        assert_eq!(
+            client
+                .get_boolean_assignment("kill-switch", "subject", Arc::new(HashMap::new()))
+                .unwrap(),
+            Some(true)
+        );
+        std::fs::remove_file(persisted).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn fetch_stats_are_reported_by_poller() {
+        let client = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .to_client();
+        assert_eq!(client.fetch_stats(), None);
+
+        client.start_poller_thread().unwrap();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        let stats = loop {
+            let stats = client.fetch_stats().unwrap();
+            if stats.consecutive_failures > 0 || std::time::Instant::now() > deadline {
+                break stats;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(stats.consecutive_failures, 1);
+        assert!(stats.last_attempt_at.is_some());
+        assert!(stats.last_duration.is_some());
+        assert_eq!(stats.last_success_at, None);
+        assert_eq!(stats.last_payload_bytes, None);
+    }
+
+    #[test]
+    fn refresh_configuration_requires_poller_thread() {
+        let client = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .to_client();
+        assert!(matches!(
+            client.refresh_configuration(),
+            Err(Error::PollerStopped)
+        ));
+
+        client.start_poller_thread().unwrap();
+
+        assert!(matches!(
+            client.refresh_configuration(),
+            Err(Error::NetworkError { status: None, .. })
+        ));
+    }
+
+    #[test]
+    fn accepts_plain_subject_key_and_attributes() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        let attributes = Attributes::from([("age".into(), 42.0.into())]);
+        let shared_attributes = Arc::new(attributes.clone());
+        let subject_key = Str::from("subject");
+
+        assert_eq!(
+            client.get_boolean_assignment("kill-switch", "subject", &attributes),
+            Ok(Some(true))
+        );
+        assert_eq!(
+            client.get_boolean_assignment("kill-switch", String::from("subject"), attributes),
+            Ok(Some(true))
+        );
+        assert_eq!(
+            client.get_boolean_assignment("kill-switch", &subject_key, &shared_attributes),
+            Ok(Some(true))
+        );
+        assert_eq!(
+            client
+                .get_boolean_assignment_details("kill-switch", subject_key, shared_attributes)
+                .variation,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn graceful_mode_returns_none_on_type_mismatch() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        assert_eq!(
+            client.get_string_assignment("kill-switch", "subject", HashMap::new()),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn non_graceful_mode_returns_type_mismatch() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .is_graceful_mode(false)
+            .to_client();
+
+        assert_eq!(
+            client.get_string_assignment("kill-switch", "subject", HashMap::new()),
+            Err(EvaluationError::TypeMismatch {
+                expected: VariationType::String,
+                found: VariationType::Boolean
+            })
+        );
+
+        client.set_graceful_mode(true);
+        assert_eq!(
+            client.get_string_assignment("kill-switch", "subject", HashMap::new()),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn poll_interval_and_jitter_reach_poller_config() {
+        let client = ClientConfig::from_api_key("api-key")
+            .poll_interval(Duration::from_secs(60))
+            .poll_jitter(Duration::from_secs(5))
+            .to_client();
+
+        let config = client.poller_thread_config().unwrap();
+
+        assert_eq!(config.interval, Duration::from_secs(60));
+        assert_eq!(config.jitter, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn request_timeout_reaches_poller_config() {
+        let client = ClientConfig::from_api_key("api-key")
+            .request_timeout(Duration::from_secs(2))
+            .to_client();
+
+        let config = client.poller_thread_config().unwrap();
+
+        assert_eq!(config.request_timeout, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn obfuscated_reaches_poller_config() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert!(!client.poller_thread_config().unwrap().obfuscated);
+
+        let client = ClientConfig::from_api_key("api-key")
+            .obfuscated(true)
+            .to_client();
+        assert!(client.poller_thread_config().unwrap().obfuscated);
+    }
+
+    #[test]
+    fn always_fetch_bandits_reaches_poller_config() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert!(!client.poller_thread_config().unwrap().always_fetch_bandits);
+
+        let client = ClientConfig::from_api_key("api-key")
+            .always_fetch_bandits(true)
+            .to_client();
+        assert!(client.poller_thread_config().unwrap().always_fetch_bandits);
+    }
+
+    #[test]
+    fn extra_headers_reach_poller_config() {
+        let client = ClientConfig::from_api_key("api-key")
+            .extra_header("X-Org-Token", "token")
+            .to_client();
+
+        let config = client.poller_thread_config().unwrap();
+
+        assert_eq!(
+            config.headers,
+            vec![("X-Org-Token".to_owned(), "token".to_owned())]
+        );
+    }
+
+    #[test]
+    fn extra_query_params_reach_poller_config() {
+        let client = ClientConfig::from_api_key("api-key")
+            .extra_query_param("wrapperName", "platform-sdk")
+            .to_client();
+
+        let config = client.poller_thread_config().unwrap();
+
+        assert_eq!(
+            config.query_params,
+            vec![("wrapperName".to_owned(), "platform-sdk".to_owned())]
+        );
+    }
+
+    #[test]
+    fn reserved_query_param_is_rejected() {
+        assert!(matches!(
+            ClientConfig::from_api_key("api-key")
+                .extra_query_param("sdkVersion", "1.2.3")
+                .try_to_client(),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn max_response_bytes_reach_poller_config() {
+        let client = ClientConfig::from_api_key("api-key")
+            .max_response_bytes(1024)
+            .to_client();
+
+        let config = client.poller_thread_config().unwrap();
+
+        assert_eq!(config.max_response_bytes, 1024);
+    }
+
+    #[test]
+    fn auth_mode_reach_poller_config() {
+        let client = ClientConfig::from_api_key("api-key")
+            .auth_mode(AuthMode::BearerHeader)
+            .to_client();
+
+        let config = client.poller_thread_config().unwrap();
+
+        assert_eq!(config.auth_mode, AuthMode::BearerHeader);
+    }
+
+    #[test]
+    fn config_from_vars() {
+        let vars = HashMap::from([
+            ("EPPO_API_KEY", "api-key"),
+            ("EPPO_BASE_URL", "https://example.com/api"),
+            ("EPPO_POLL_INTERVAL_SECONDS", "60"),
+            ("EPPO_POLL_JITTER_SECONDS", " 5 "),
+        ]);
+        let config =
+            ClientConfig::from_vars(|name| vars.get(name).map(|it| (*it).to_owned())).unwrap();
+
+        assert_eq!(config.api_key, "api-key");
+        assert_eq!(config.base_url, "https://example.com/api");
+        assert_eq!(config.poll_interval, Duration::from_secs(60));
+        assert_eq!(config.poll_jitter, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn config_from_vars_errors() {
+        let Err(Error::InvalidConfiguration(message)) = ClientConfig::from_vars(|_| None) else {
+            panic!("expected missing API key error");
+        };
+        assert!(message.contains("EPPO_API_KEY"));
+
+        let vars = HashMap::from([
+            ("EPPO_API_KEY", "api-key"),
+            ("EPPO_POLL_INTERVAL_SECONDS", "soon"),
+        ]);
+        let Err(Error::InvalidConfiguration(message)) =
+            ClientConfig::from_vars(|name| vars.get(name).map(|it| (*it).to_owned()))
+        else {
+            panic!("expected invalid poll interval error");
+        };
+        assert!(message.contains("EPPO_POLL_INTERVAL_SECONDS"));
+    }
+
+    #[test]
+    fn config_from_vars_defaults() {
+        let config =
+            ClientConfig::from_vars(|name| (name == "EPPO_API_KEY").then(|| "api-key".to_owned()))
+                .unwrap();
+
+        assert_eq!(config.api_key, "api-key");
+        assert_eq!(config.base_url, ClientConfig::DEFAULT_BASE_URL);
+        assert_eq!(
+            config.poll_interval,
+            PollerThreadConfig::DEFAULT_POLL_INTERVAL
+        );
+        assert_eq!(config.poll_jitter, PollerThreadConfig::DEFAULT_POLL_JITTER);
+    }
+
+    #[test]
+    fn zero_poll_interval_is_rejected() {
+        let client = ClientConfig::from_api_key("api-key")
+            .poll_interval(Duration::ZERO)
+            .to_client();
+
+        assert!(matches!(
+            client.start_poller_thread(),
+            Err(Error::InvalidPollInterval)
+        ));
+    }
+
+    #[test]
+    fn zero_request_timeout_is_rejected() {
+        let client = ClientConfig::from_api_key("api-key")
+            .request_timeout(Duration::ZERO)
+            .to_client();
+
+        assert!(matches!(
+            client.start_poller_thread(),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn assignment_event_json_includes_details_when_requested() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .assignment_logger(|event| events.lock().unwrap().push(event))
+            .to_client();
+
+        client.get_boolean_assignment_details("kill-switch", "alice", Arc::new(HashMap::new()));
+        drop(client);
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 1);
+        let json = events[0].to_json();
+        assert_eq!(json["featureFlag"], "kill-switch");
+        assert_eq!(json["subject"], "alice");
+        assert!(json.get("evaluationDetails").is_some());
+        assert!(events[0]
+            .to_json_without_details()
+            .get("evaluationDetails")
+            .is_none());
+    }
+
+    #[test]
+    fn assignment_cache_dedupes_assignment_events() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .assignment_logger(|event| events.lock().unwrap().push(event))
+            .assignment_cache(LruAssignmentCache::default())
+            .to_client();
+
+        for _ in 0..3 {
+            client
+                .get_boolean_assignment("kill-switch", "alice", Arc::new(HashMap::new()))
+                .unwrap();
+        }
+        client
+            .get_boolean_assignment("kill-switch", "bob", Arc::new(HashMap::new()))
+            .unwrap();
+        drop(client);
+
+        let subjects = events
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|event| event.subject)
+            .collect::<Vec<Str>>();
+        assert_eq!(subjects, vec![Str::from("alice"), Str::from("bob")]);
+    }
+
+    #[test]
+    fn bandit_cache_dedupes_bandit_events() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(TEST_BANDITS_JSON))
+            .unwrap()
+            .bandit_logger(|event| events.lock().unwrap().push(event))
+            .bandit_cache(LruAssignmentCache::default())
+            .to_client();
+
+        for _ in 0..3 {
+            client.get_bandit_action(
+                "banner-bandit-flag",
+                &"subject".into(),
+                &ContextAttributes::default(),
+                &[("nike".into(), ContextAttributes::default())]
+                    .into_iter()
+                    .collect::<HashMap<_, _>>(),
+                &"default".into(),
+            );
+        }
+        drop(client);
+
+        assert_eq!(events.into_inner().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn is_initialized_and_configuration_age() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert!(!client.is_initialized());
+        assert_eq!(client.configuration_age(), None);
+
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        assert!(client.is_initialized());
+        assert!(client.configuration_age().unwrap() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn typed_assignments_for_subject() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        let subject = Subject::new("user-123")
+            .with_number("age", 30)
+            .with_string("country", "DE")
+            .with_bool("is_premium", true);
+
+        assert_eq!(
+            client
+                .get_string_assignment_for_subject("string-flag", &subject)
+                .unwrap(),
+            Some("hallo".into())
+        );
+        assert_eq!(
+            client
+                .get_string_assignment_for_subject("string-flag", &Subject::new("user-123"))
+                .unwrap(),
+            Some("hello".into())
+        );
+        assert_eq!(
+            client
+                .get_integer_assignment_for_subject("integer-flag", &subject)
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            client
+                .get_numeric_assignment_for_subject("numeric-flag", &subject)
+                .unwrap(),
+            Some(2.5)
+        );
+        assert_eq!(
+            client
+                .get_boolean_assignment_for_subject("boolean-flag", &subject)
+                .unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            client
+                .get_json_assignment_for_subject("json-flag", &subject)
+                .unwrap(),
+            Some(Arc::new(serde_json::json!({"a": 1})))
+        );
+    }
+
+    #[test]
+    fn tuple_can_be_used_as_subject() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        let subject: (Str, Arc<Attributes>) =
+            Subject::new("user-123").with_number("age", 30).into();
+
+        assert_eq!(
+            client
+                .get_integer_assignment_for_subject("integer-flag", &subject)
+                .unwrap(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn get_all_assignments() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .assignment_logger(|event| events.lock().unwrap().push(event))
+            .to_client();
+        let subject = Subject::new("user-123").with_string("country", "DE");
+
+        let assignments = client.get_all_assignments(subject.key(), subject.attributes(), None);
+
+        // integer-flag and boolean-flag don't match the subject.
+        assert_eq!(
+            assignments,
+            HashMap::from([
+                (
+                    "string-flag".into(),
+                    AssignmentValue::String("hallo".into())
+                ),
+                ("numeric-flag".into(), AssignmentValue::Numeric(2.5)),
+                (
+                    "json-flag".into(),
+                    AssignmentValue::Json {
+                        raw: "{\"a\": 1}".into(),
+                        parsed: Arc::new(serde_json::json!({"a": 1}))
+                    }
+                ),
+            ])
+        );
+
+        drop(client);
+        let mut logged_flags = events
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|event| event.base.feature_flag.clone())
+            .collect::<Vec<Str>>();
+        logged_flags.sort();
+        assert_eq!(
+            logged_flags,
+            vec![
+                Str::from("json-flag"),
+                Str::from("numeric-flag"),
+                Str::from("string-flag")
+            ]
+        );
+    }
+
+    #[test]
+    fn get_all_assignments_with_flag_keys() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        let assignments = client.get_all_assignments(
+            "user-123",
+            HashMap::new(),
+            Some(&["string-flag", "boolean-flag", "missing-flag"]),
+        );
+
+        assert_eq!(
+            assignments,
+            HashMap::from([(
+                "string-flag".into(),
+                AssignmentValue::String("hello".into())
+            )])
+        );
+    }
+
+    #[test]
+    fn get_all_assignments_logs_events() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .assignment_logger(|event| events.lock().unwrap().push(event))
+            .to_client();
+        let subject = Subject::new("user-123").with_string("country", "DE");
+
+        let assignments = client.get_all_assignments(
+            subject.key(),
+            subject.attributes(),
+            Some(&["string-flag", "boolean-flag", "missing-flag"]),
+        );
+
+        assert_eq!(
+            assignments,
+            HashMap::from([(
+                "string-flag".into(),
+                AssignmentValue::String("hallo".into())
+            )])
+        );
+
+        drop(client);
+        let logged_flags = events
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|event| event.base.feature_flag.clone())
+            .collect::<Vec<Str>>();
+        assert_eq!(logged_flags, vec![Str::from("string-flag")]);
+    }
+
+    #[test]
+    fn default_attributes_are_logged() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .default_attribute("country", "DE")
+            .default_attribute("platform", "ios")
+            .assignment_logger(|event| events.lock().unwrap().push(event))
+            .to_client();
+
+        assert_eq!(
+            client
+                .get_string_assignment("string-flag", "user-123", HashMap::new())
+                .unwrap(),
+            Some("hallo".into())
+        );
+        assert_eq!(
+            client
+                .get_string_assignment(
+                    "string-flag",
+                    "user-123",
+                    HashMap::from([("country".into(), "US".into())])
+                )
+                .unwrap(),
+            Some("hello".into())
+        );
+
+        drop(client);
+        let events = events.into_inner().unwrap();
+        assert_eq!(
+            *events[0].subject_attributes,
+            Attributes::from([
+                ("country".into(), "DE".into()),
+                ("platform".into(), "ios".into()),
+            ])
+        );
+        assert_eq!(
+            *events[1].subject_attributes,
+            Attributes::from([
+                ("country".into(), "US".into()),
+                ("platform".into(), "ios".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn numeric_types_are_coerced_when_enabled() {
+        let config = || {
+            ClientConfig::from_api_key("api-key")
+                .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+                .unwrap()
+                .is_graceful_mode(false)
+        };
+        let adult = || HashMap::from([("age".into(), 30.0.into())]);
+        let mismatch = |expected, found| EvaluationError::TypeMismatch { expected, found };
+
+        let strict = config().to_client();
+        assert_eq!(
+            strict.get_numeric_assignment("integer-flag", "user-123", adult()),
+            Err(mismatch(VariationType::Numeric, VariationType::Integer))
+        );
+
+        let client = config().coerce_numeric_types(true).to_client();
+        assert_eq!(
+            client.get_numeric_assignment("integer-flag", "user-123", adult()),
+            Ok(Some(1.0))
+        );
+        // 2.5 cannot be converted to an integer without loss.
+        assert_eq!(
+            client.get_integer_assignment("numeric-flag", "user-123", HashMap::new()),
+            Err(mismatch(VariationType::Integer, VariationType::Numeric))
+        );
+
+        let details = client.get_numeric_assignment_details("integer-flag", "user-123", adult());
+        assert_eq!(details.variation, Some(1.0));
+        assert_eq!(
+            details.evaluation_details.coerced_from,
+            Some(VariationType::Integer)
+        );
+        let details =
+            client.get_integer_assignment_details("numeric-flag", "user-123", HashMap::new());
+        assert_eq!(details.variation, None);
+        assert_eq!(
+            details.error(),
+            Some(mismatch(VariationType::Integer, VariationType::Numeric))
+        );
+    }
+
+    #[test]
+    fn bandit_details_include_action_scores() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(TEST_BANDITS_JSON))
+            .unwrap()
+            .to_client();
+        let actions = ["nike", "adidas", "reebok"]
+            .into_iter()
+            .map(|key| (key.into(), ContextAttributes::default()))
+            .collect::<HashMap<Str, _>>();
+
+        let (result, details) = client.get_bandit_action_details(
+            "banner-bandit-flag",
+            &"alice".into(),
+            &ContextAttributes::default(),
+            &actions,
+            &"default".into(),
+        );
+
+        // Model has no coefficients, so all actions get the default score and ties are ordered by
+        // key.
+        assert_eq!(
+            details
+                .actions
+                .iter()
+                .map(|action| (action.key.as_str(), action.score))
+                .collect::<Vec<_>>(),
+            [("adidas", 0.0), ("nike", 0.0), ("reebok", 0.0)]
+        );
+        // Equally-scored actions are equally likely to be selected.
+        assert!(details
+            .actions
+            .iter()
+            .all(|action| (action.weight - 1.0 / 3.0).abs() < 1e-9));
+        assert_eq!(details.bandit_action, result.action);
+
+        let json = serde_json::to_value(&details).unwrap();
+        assert_eq!(json["actions"][0]["key"], "adidas");
+        assert_eq!(json["actions"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn bandit_actions_can_be_supplied_as_keys() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(TEST_BANDITS_JSON))
+            .unwrap()
+            .to_client();
+        let keys: [Str; 3] = ["nike".into(), "adidas".into(), "reebok".into()];
+        let actions = keys
+            .iter()
+            .map(|key| (key.clone(), ContextAttributes::default()))
+            .collect::<HashMap<_, _>>();
+
+        for i in 0..100 {
+            let subject_key = format!("subject-{i}").into();
+            let evaluate = |actions: Actions| {
+                client.get_bandit_action_details(
+                    "banner-bandit-flag",
+                    &subject_key,
+                    &ContextAttributes::default(),
+                    actions,
+                    &"default".into(),
+                )
+            };
+
+            let (result, details) = evaluate(Actions::Keys(&keys));
+            let (expected_result, expected_details) = evaluate(Actions::WithAttributes(&actions));
+
+            assert!(result.action.is_some());
+            assert_eq!(result.action, expected_result.action);
+            assert_eq!(details.actions, expected_details.actions);
+        }
+    }
+
+    #[test]
+    fn clock_controls_allocation_windows() {
+        const SCHEDULED_FLAGS_JSON: &[u8] = br#"{
+          "createdAt": "2024-07-18T00:00:00Z",
+          "format": "SERVER",
+          "environment": {"name": "test"},
+          "flags": {
+            "launch": {
+              "key": "launch",
+              "enabled": true,
+              "variationType": "STRING",
+              "variations": {
+                "new": {"key": "new", "value": "new"},
+                "old": {"key": "old", "value": "old"}
+              },
+              "allocations": [
+                {
+                  "key": "launch-day",
+                  "startAt": "2100-01-01T00:00:00Z",
+                  "splits": [{"variationKey": "new", "shards": []}],
+                  "doLog": true
+                },
+                {
+                  "key": "before-launch",
+                  "splits": [{"variationKey": "old", "shards": []}],
+                  "doLog": true
+                }
+              ],
+              "totalShards": 10000
+            }
+          }
+        }"#;
+
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(SCHEDULED_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        assert_eq!(
+            client
+                .get_string_assignment("launch", "alice", HashMap::new())
                 .unwrap(),
-            None
+            Some("old".into())
+        );
+
+        let at: Timestamp = "2100-01-02T00:00:00Z".parse().unwrap();
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(SCHEDULED_FLAGS_JSON, None)
+            .unwrap()
+            .clock(move || at)
+            .to_client();
+        assert_eq!(
+            client
+                .get_string_assignment("launch", "alice", HashMap::new())
+                .unwrap(),
+            Some("new".into())
+        );
+    }
+
+    /// Client serving `TEST_FLAGS_JSON` fetched `age` ago.
+    fn client_with_configuration_age(
+        config: ClientConfig<'static>,
+        age: Duration,
+    ) -> Client<'static> {
+        let client = config.to_client();
+        let mut configuration = Configuration::from_server_response(
+            UniversalFlagConfig::from_json(SDK_METADATA, TEST_FLAGS_JSON.to_vec()).unwrap(),
+            None,
+        );
+        configuration.fetched_at = chrono::Utc::now() - age;
+        client.set_configuration(Arc::new(configuration));
+        client
+    }
+
+    #[test]
+    fn stale_configuration_is_reported_but_still_served() {
+        let hour = Duration::from_secs(3600);
+        let client = client_with_configuration_age(
+            ClientConfig::from_api_key("api-key").max_configuration_age(Duration::from_secs(60)),
+            hour,
+        );
+
+        assert_eq!(
+            client
+                .get_boolean_assignment("kill-switch", "alice", HashMap::new())
+                .unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            client.get_assignment_with_reason("kill-switch", "alice", HashMap::new()),
+            (
+                Some(AssignmentValue::Boolean(true)),
+                FlagEvaluationCode::ConfigurationStale
+            )
+        );
+        let details = client.get_assignment_details("kill-switch", "alice", HashMap::new());
+        assert_eq!(details.variation, Some(AssignmentValue::Boolean(true)));
+        assert_eq!(
+            details.flag_evaluation_code(),
+            Some(FlagEvaluationCode::ConfigurationStale)
+        );
+        assert!(details
+            .evaluation_details
+            .flag_evaluation_description
+            .contains("Configuration is stale"));
+        assert_eq!(
+            client
+                .get_assignment_details_unlogged("kill-switch", "alice", HashMap::new())
+                .flag_evaluation_code(),
+            Some(FlagEvaluationCode::ConfigurationStale)
+        );
+        // Other codes explain why the default value is served, so they are kept.
+        assert_eq!(
+            client.get_assignment_with_reason("unknown-flag", "alice", HashMap::new()),
+            (None, FlagEvaluationCode::FlagUnrecognizedOrDisabled)
+        );
+    }
+
+    #[test]
+    fn fresh_configuration_is_not_reported_as_stale() {
+        let client = client_with_configuration_age(
+            ClientConfig::from_api_key("api-key").max_configuration_age(Duration::from_secs(60)),
+            Duration::from_secs(10),
+        );
+        assert_eq!(
+            client
+                .get_assignment_with_reason("kill-switch", "alice", HashMap::new())
+                .1,
+            FlagEvaluationCode::Match
+        );
+        assert_eq!(
+            client
+                .get_assignment_details("kill-switch", "alice", HashMap::new())
+                .flag_evaluation_code(),
+            Some(FlagEvaluationCode::Match)
+        );
+
+        // Staleness is not reported unless max age is configured.
+        let client = client_with_configuration_age(
+            ClientConfig::from_api_key("api-key"),
+            Duration::from_secs(3600),
+        );
+        assert_eq!(
+            client
+                .get_assignment_with_reason("kill-switch", "alice", HashMap::new())
+                .1,
+            FlagEvaluationCode::Match
+        );
+    }
+
+    #[test]
+    fn get_assignment_with_reason() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert_eq!(
+            client.get_assignment_with_reason("kill-switch", "alice", HashMap::new()),
+            (None, FlagEvaluationCode::ConfigurationMissing)
+        );
+
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .assignment_logger(|event| events.lock().unwrap().push(event))
+            .to_client();
+        assert_eq!(
+            client.get_assignment_with_reason("numeric-flag", "alice", HashMap::new()),
+            (
+                Some(AssignmentValue::Numeric(2.5)),
+                FlagEvaluationCode::Match
+            )
+        );
+        assert_eq!(
+            client.get_assignment_with_reason("integer-flag", "alice", HashMap::new()),
+            (None, FlagEvaluationCode::DefaultAllocationNull)
+        );
+        assert_eq!(
+            client.get_assignment_with_reason("unknown-flag", "alice", HashMap::new()),
+            (None, FlagEvaluationCode::FlagUnrecognizedOrDisabled)
+        );
+        drop(client);
+
+        assert_eq!(events.into_inner().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn unlogged_assignments_do_not_log() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .assignment_logger(|event| events.lock().unwrap().push(event))
+            .to_client();
+
+        assert_eq!(
+            client
+                .get_assignment_unlogged("kill-switch", "alice", HashMap::new())
+                .unwrap(),
+            Some(AssignmentValue::Boolean(true))
+        );
+        let details =
+            client.get_assignment_details_unlogged("kill-switch", "alice", HashMap::new());
+        assert_eq!(details.variation, Some(AssignmentValue::Boolean(true)));
+        assert!(details.evaluation_details.logging_suppressed);
+
+        let details = client.get_assignment_details("kill-switch", "alice", HashMap::new());
+        assert!(!details.evaluation_details.logging_suppressed);
+        drop(client);
+
+        assert_eq!(events.into_inner().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn details_expose_evaluation_error_and_code() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        let result = client.get_integer_assignment_details("string-flag", "alice", HashMap::new());
+        assert_eq!(
+            result.error(),
+            Some(EvaluationError::TypeMismatch {
+                expected: VariationType::Integer,
+                found: VariationType::String,
+            })
+        );
+        assert_eq!(
+            result.flag_evaluation_code(),
+            Some(FlagEvaluationCode::TypeMismatch)
+        );
+
+        let result = client.get_string_assignment_details("unknown-flag", "alice", HashMap::new());
+        assert_eq!(result.error(), None);
+        assert_eq!(
+            result.flag_evaluation_code(),
+            Some(FlagEvaluationCode::FlagUnrecognizedOrDisabled)
+        );
+
+        let result = client.get_string_assignment_details("string-flag", "alice", HashMap::new());
+        assert_eq!(result.error(), None);
+        assert_eq!(
+            result.flag_evaluation_code(),
+            Some(FlagEvaluationCode::Match)
+        );
+    }
+
+    #[test]
+    fn client_is_send_sync_and_clone() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        fn assert_clone<T: Clone>() {}
+        assert_send_sync::<Client<'static>>();
+        assert_clone::<Client<'static>>();
+    }
+
+    #[test]
+    fn clones_share_state() {
+        let source = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        let clone = client.clone();
+
+        client.set_configuration(source.get_configuration().unwrap());
+
+        assert!(clone.is_initialized());
+    }
+
+    #[test]
+    fn clients_share_configuration_store() {
+        let configuration_store = Arc::new(ConfigurationStore::new());
+        let writer = Client::with_configuration_store(
+            ClientConfig::from_api_key("api-key")
+                .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+                .unwrap(),
+            configuration_store.clone(),
+        );
+        let reader = Client::with_configuration_store(
+            ClientConfig::from_api_key("api-key"),
+            configuration_store.clone(),
+        );
+
+        assert!(writer.is_initialized());
+        assert_eq!(
+            reader
+                .get_boolean_assignment("kill-switch", "alice", HashMap::new())
+                .unwrap(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn json_assignment_as_struct() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Value {
+            a: i32,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Other {
+            b: String,
+        }
+
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_TYPED_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        assert_eq!(
+            client
+                .get_json_assignment_as::<Value>("json-flag", "alice", HashMap::new())
+                .unwrap(),
+            Some(Value { a: 1 })
+        );
+        assert!(client
+            .get_json_assignment_as::<Value>("unknown-flag", "alice", HashMap::new())
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            client.get_json_assignment_as::<Other>("json-flag", "alice", HashMap::new()),
+            Err(Error::JsonDeserialization { flag_key, .. }) if flag_key == "json-flag"
+        ));
+    }
+
+    #[test]
+    fn evaluation_observer_is_notified() {
+        type Observations = Arc<Mutex<Vec<(String, FlagEvaluationCode)>>>;
+
+        struct RecordingObserver(Observations);
+
+        impl EvaluationObserver for RecordingObserver {
+            fn on_evaluation(
+                &self,
+                flag_key: &str,
+                _duration: Duration,
+                outcome: FlagEvaluationCode,
+            ) {
+                self.0.lock().unwrap().push((flag_key.to_owned(), outcome));
+            }
+        }
+
+        let observations = Observations::default();
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(TEST_BANDITS_JSON))
+            .unwrap()
+            .evaluation_observer(RecordingObserver(observations.clone()))
+            .to_client();
+
+        client
+            .get_integer_assignment("banner-bandit-flag", "alice", HashMap::new())
+            .unwrap_or_default();
+        client
+            .get_string_assignment("unknown-flag", "alice", HashMap::new())
+            .unwrap();
+        client.get_bandit_action(
+            "banner-bandit-flag",
+            &"alice".into(),
+            &ContextAttributes::default(),
+            &[("nike".into(), ContextAttributes::default())]
+                .into_iter()
+                .collect::<HashMap<_, _>>(),
+            &"default".into(),
+        );
+
+        assert_eq!(
+            *observations.lock().unwrap(),
+            vec![
+                (
+                    "banner-bandit-flag".to_owned(),
+                    FlagEvaluationCode::TypeMismatch
+                ),
+                (
+                    "unknown-flag".to_owned(),
+                    FlagEvaluationCode::FlagUnrecognizedOrDisabled
+                ),
+                ("banner-bandit-flag".to_owned(), FlagEvaluationCode::Match),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_precomputed_configuration() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        let json = client
+            .get_precomputed_configuration(
+                &"subject".into(),
+                &ContextAttributes::default(),
+                &HashMap::new(),
+            )
+            .to_obfuscated_json()
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["obfuscated"], true);
+        assert_eq!(value["format"], "PRECOMPUTED");
+        assert_eq!(value["flags"].as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn get_precomputed_configuration_with_details() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+
+        let precomputed = client.get_precomputed_configuration_with_details(
+            &"subject".into(),
+            &ContextAttributes::default(),
+            &HashMap::new(),
+        );
+        let value = serde_json::to_value(&precomputed).unwrap();
+
+        assert_eq!(
+            value["flags"]["kill-switch"]["details"],
+            serde_json::json!({
+                "flagEvaluationCode": "MATCH",
+                "allocationKey": "on-for-all",
+                "variationKey": "on",
+            })
+        );
+        let obfuscated: serde_json::Value =
+            serde_json::from_str(&precomputed.to_obfuscated_json().unwrap()).unwrap();
+        assert!(obfuscated["flags"]
+            .as_object()
+            .unwrap()
+            .values()
+            .all(|flag| flag.get("details").is_none()));
+    }
+
+    #[test]
+    fn get_precomputed_configuration_for_flags() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        let subject_key = "subject".into();
+        let subject_attributes = ContextAttributes::default();
+
+        let flag_count = |flag_keys: &[&str]| {
+            let precomputed = client.get_precomputed_configuration_for_flags(
+                &subject_key,
+                &subject_attributes,
+                &HashMap::new(),
+                &flag_keys.iter().map(|&key| Str::from(key)).collect(),
+            );
+            let value: serde_json::Value =
+                serde_json::from_str(&precomputed.to_obfuscated_json().unwrap()).unwrap();
+            value["flags"].as_object().unwrap().len()
+        };
+
+        assert_eq!(flag_count(&["kill-switch"]), 1);
+        assert_eq!(flag_count(&["other-flag"]), 0);
+        assert_eq!(flag_count(&[]), 0);
+    }
+
+    #[test]
+    fn configuration_timestamps() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert_eq!(client.configuration_timestamps(), None);
+
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        let timestamps = client.configuration_timestamps().unwrap();
+        assert_eq!(
+            timestamps.published_at.to_rfc3339(),
+            "2024-07-18T00:00:00+00:00"
+        );
+        assert_eq!(timestamps.environment, "test".into());
+    }
+
+    #[test]
+    fn flag_and_bandit_keys() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert!(client.flag_keys().is_empty());
+        assert!(client.bandit_keys().is_empty());
+
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(TEST_BANDITS_JSON))
+            .unwrap()
+            .to_client();
+        assert_eq!(client.flag_keys(), vec![Str::from("banner-bandit-flag")]);
+        assert!(client.unparsed_flag_keys().is_empty());
+        assert_eq!(client.bandit_keys(), vec![Str::from("banner-bandit")]);
+    }
+
+    #[test]
+    fn unparsed_flag_keys() {
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(
+                r#"{
+                  "createdAt": "2024-07-18T00:00:00Z",
+                  "environment": {"name": "test"},
+                  "flags": {
+                    "future-flag": {"key": "future-flag", "variationType": "FROM_THE_FUTURE"}
+                  }
+                }"#,
+                None,
+            )
+            .unwrap()
+            .to_client();
+
+        assert_eq!(client.flag_keys(), vec![Str::from("future-flag")]);
+        assert_eq!(client.unparsed_flag_keys(), vec![Str::from("future-flag")]);
+    }
+
+    #[test]
+    fn try_new_validates_configuration() {
+        assert!(ClientConfig::from_api_key("api-key")
+            .try_to_client()
+            .is_ok());
+        assert!(matches!(
+            ClientConfig::from_api_key(" ").try_to_client(),
+            Err(Error::InvalidConfiguration(_))
+        ));
+        assert!(matches!(
+            ClientConfig::from_api_key("api-key")
+                .base_url("not a url")
+                .try_to_client(),
+            Err(Error::InvalidConfiguration(_))
+        ));
+        assert!(matches!(
+            ClientConfig::from_api_key("api-key")
+                .base_urls(["https://example.com/api", "not a url"])
+                .try_to_client(),
+            Err(Error::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn base_url_replaces_base_urls() {
+        let config = ClientConfig::from_api_key("api-key")
+            .base_urls(["https://one.example.com/api", "https://two.example.com/api"]);
+        assert_eq!(config.base_url, "https://one.example.com/api");
+        assert_eq!(config.base_urls.len(), 2);
+
+        let config = config.base_url("https://three.example.com/api");
+        assert_eq!(config.base_url, "https://three.example.com/api");
+        assert!(config.base_urls.is_empty());
+    }
+
+    #[test]
+    fn shutdown_stops_poller_thread() {
+        let client = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .to_client();
+        let poller_thread = client.start_poller_thread().unwrap();
+
+        client.shutdown().unwrap();
+
+        // The thread has exited without fetching configuration.
+        assert!(matches!(
+            poller_thread.wait_for_configuration_timeout(Duration::ZERO),
+            Err(Error::PollerStopped)
+        ));
+    }
+
+    #[test]
+    fn manual_startup_mode_does_not_start_poller() {
+        let client = ClientConfig::from_api_key("api-key").to_client();
+
+        assert!(client.poller_thread().is_none());
+    }
+
+    #[test]
+    fn background_startup_mode_starts_poller() {
+        let client = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .startup_mode(StartupMode::Background)
+            .try_to_client()
+            .unwrap();
+
+        assert!(client.poller_thread().is_some());
+    }
+
+    #[test]
+    fn block_until_ready_startup_mode_returns_timeout() {
+        let result = ClientConfig::from_api_key("api-key")
+            .base_url("http://localhost:1/api")
+            .startup_mode(StartupMode::BlockUntilReady {
+                timeout: Duration::from_millis(100),
+            })
+            .try_to_client();
+
+        assert!(matches!(result, Err(Error::ConfigurationWaitTimeout)));
+    }
+
+    #[test]
+    fn get_and_set_configuration() {
+        let source = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_FLAGS_JSON, None)
+            .unwrap()
+            .to_client();
+        let client = ClientConfig::from_api_key("api-key").to_client();
+        assert!(client.get_configuration().is_none());
+
+        let configuration = source.get_configuration().unwrap();
+        assert_eq!(configuration.environment_name(), "test");
+        client.set_configuration(configuration);
+
+        assert_eq!(
+            client
+                .get_configuration()
+                .map(|it| it.flag_keys().into_iter().collect::<Vec<_>>()),
+            Some(vec!["kill-switch".into()])
+        );
+    }
+
+    #[test]
+    fn initial_configuration_parse_error() {
+        let result = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(b"{\"flags\": 42}".as_slice(), None);
+
+        assert!(matches!(result, Err(Error::ConfigurationParseError(_))));
+    }
+
+    #[test]
+    fn bandit_returns_default_while_no_configuration() {
+        let configuration_store = Arc::new(ConfigurationStore::new());
+        let client = Client::with_configuration_store(
+            ClientConfig::from_api_key("api-key"),
+            configuration_store.clone(),
+        );
+
+        let result = client.get_bandit_action(
+            "flag",
+            &"subject".into(),
+            &ContextAttributes::default(),
+            &HashMap::new(),
+            &"default".into(),
+        );
+
+        assert_eq!(result.variation, "default".into());
+        assert_eq!(result.action, None);
+        assert!(result.assignment_event.is_none());
+        assert!(result.bandit_event.is_none());
+    }
+
+    #[test]
+    fn bandit_never_selects_disallowed_actions() {
+        // "nike" has by far the best score, so it is selected for virtually every subject.
+        const BANDITS_JSON: &[u8] = br#"{
+          "updatedAt": "2024-07-18T00:00:00Z",
+          "bandits": {
+            "banner-bandit": {
+              "banditKey": "banner-bandit",
+              "modelName": "falcon",
+              "modelVersion": "v1",
+              "modelData": {
+                "gamma": 1000.0,
+                "defaultActionScore": 0.0,
+                "actionProbabilityFloor": 0.0,
+                "coefficients": {
+                  "nike": {
+                    "actionKey": "nike",
+                    "intercept": 10.0,
+                    "subjectNumericCoefficients": [],
+                    "subjectCategoricalCoefficients": [],
+                    "actionNumericCoefficients": [],
+                    "actionCategoricalCoefficients": []
+                  }
+                }
+              },
+              "updatedAt": "2024-07-18T00:00:00Z"
+            }
+          }
+        }"#;
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(BANDITS_JSON))
+            .unwrap()
+            .to_client();
+        let actions = ["nike", "adidas", "reebok"]
+            .into_iter()
+            .map(|key| (key.into(), ContextAttributes::default()))
+            .collect::<HashMap<Str, _>>();
+        let bandit_action = |subject_key: &str, allowed_actions: Option<&HashSet<Str>>| {
+            let subject_key = subject_key.into();
+            let result = match allowed_actions {
+                Some(allowed_actions) => client.get_bandit_action_with_allowed_actions(
+                    "banner-bandit-flag",
+                    &subject_key,
+                    &ContextAttributes::default(),
+                    &actions,
+                    allowed_actions,
+                    &"default".into(),
+                ),
+                None => client.get_bandit_action(
+                    "banner-bandit-flag",
+                    &subject_key,
+                    &ContextAttributes::default(),
+                    &actions,
+                    &"default".into(),
+                ),
+            };
+            assert_eq!(result.variation, "banner-bandit".into());
+            result.action
+        };
+
+        let allowed = HashSet::from(["adidas".into(), "reebok".into()]);
+        for i in 0..100 {
+            let subject_key = format!("subject-{i}");
+            assert_eq!(bandit_action(&subject_key, None).as_deref(), Some("nike"));
+            let action = bandit_action(&subject_key, Some(&allowed)).unwrap();
+            assert!(allowed.contains(&action), "{action}");
+        }
+
+        // No allowed actions behaves the same as no actions: only the variation is returned.
+        assert_eq!(bandit_action("alice", Some(&HashSet::new())), None);
+    }
+
+    #[test]
+    fn ranked_bandit_actions_log_every_action() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(TEST_BANDITS_JSON))
+            .unwrap()
+            .bandit_logger(|event| events.lock().unwrap().push(event.action))
+            .to_client();
+        let actions: [Str; 3] = ["nike".into(), "adidas".into(), "reebok".into()];
+        let request = BanditRequest {
+            flag_key: "banner-bandit-flag",
+            subject_key: &"subject".into(),
+            subject_attributes: &ContextAttributes::default(),
+            actions: (&actions).into(),
+            default_variation: &"default".into(),
+        };
+
+        let ranked = client.get_bandit_actions_ranked(&request, 3);
+        let allowed = HashSet::from(["adidas".into(), "reebok".into()]);
+        let ranked_allowed =
+            client.get_bandit_actions_ranked_with_allowed_actions(&request, 3, &allowed);
+        drop(client);
+
+        let ranked = ranked
+            .into_iter()
+            .map(|result| result.action.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ranked.len(), 3);
+        assert!(actions.iter().all(|action| ranked.contains(action)));
+        let ranked_allowed = ranked_allowed
+            .into_iter()
+            .map(|result| result.action.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ranked_allowed.len(), 2);
+        assert!(ranked_allowed.iter().all(|action| allowed.contains(action)));
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events, [ranked, ranked_allowed].concat());
+    }
+
+    #[test]
+    fn bandit_logger_receives_bandit_events() {
+        let events = Mutex::new(Vec::new());
+        let client = ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(TEST_BANDIT_FLAGS_JSON, Some(TEST_BANDITS_JSON))
+            .unwrap()
+            .bandit_logger(|event| events.lock().unwrap().push(event))
+            .to_client();
+
+        let result = client.get_bandit_action(
+            "banner-bandit-flag",
+            &"subject".into(),
+            &ContextAttributes::default(),
+            &[("nike".into(), ContextAttributes::default())]
+                .into_iter()
+                .collect::<HashMap<_, _>>(),
+            &"default".into(),
         );
+        drop(client);
+
+        assert_eq!(result.action, Some("nike".into()));
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].bandit_key, "banner-bandit".into());
+        assert_eq!(events[0].action, "nike".into());
     }
 }
@@ -1,4 +1,46 @@
-use crate::{assignment_logger::NoopAssignmentLogger, AssignmentLogger, Client};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use eppo_core::{
+    configuration_fetcher::{
+        validate_api_key, validate_base_url, validate_query_params, AuthMode, ConfigurationFile,
+        DEFAULT_MAX_RESPONSE_BYTES, DEFAULT_REQUEST_TIMEOUT,
+    },
+    configuration_persistence::ConfigurationPersistence,
+    eval::{EvaluationObserver, UnknownActionPolicy},
+    poller_thread::{ErrorCallback, PollerThreadConfig},
+    sharder::Sharder,
+    timestamp::{Clock, SystemClock},
+    ufc::UniversalFlagConfig,
+    Str,
+};
+
+use crate::{
+    assignment_logger::NoopAssignmentLogger, AssignmentCache, AssignmentLogger, AttributeValue,
+    Attributes, BanditEvent, Client, Configuration, Error, Result, SDK_METADATA,
+};
+
+/// Determines whether the client starts polling for configuration automatically when it is
+/// created.
+///
+/// See [`ClientConfig::startup_mode()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StartupMode {
+    /// Don't start polling automatically. Call [`Client::start_poller_thread()`] or
+    /// [`Client::start_poller_task()`] manually.
+    #[default]
+    Manual,
+    /// Start poller thread when the client is created, but don't wait for configuration.
+    Background,
+    /// Start poller thread when the client is created and block until the first configuration is
+    /// fetched or `timeout` elapses.
+    BlockUntilReady {
+        /// Maximum time to wait for configuration.
+        timeout: Duration,
+    },
+}
+
+pub(crate) type ConfigurationChangeCallback = Arc<dyn Fn(&Configuration) + Send + Sync>;
 
 /// Configuration for [`Client`].
 ///
@@ -14,7 +56,35 @@ use crate::{assignment_logger::NoopAssignmentLogger, AssignmentLogger, Client};
 pub struct ClientConfig<'a> {
     pub(crate) api_key: String,
     pub(crate) base_url: String,
+    pub(crate) base_urls: Vec<String>,
     pub(crate) assignment_logger: Box<dyn AssignmentLogger + Send + Sync + 'a>,
+    pub(crate) bandit_logger: Option<Box<dyn Fn(BanditEvent) + Send + Sync + 'a>>,
+    pub(crate) assignment_cache: Option<Box<dyn AssignmentCache + Send + Sync + 'a>>,
+    pub(crate) bandit_cache: Option<Box<dyn AssignmentCache + Send + Sync + 'a>>,
+    pub(crate) initial_configuration: Option<Arc<Configuration>>,
+    pub(crate) configuration_file: Option<PathBuf>,
+    pub(crate) bandit_models_file: Option<PathBuf>,
+    pub(crate) configuration_persistence: Option<Arc<dyn ConfigurationPersistence>>,
+    pub(crate) is_graceful_mode: bool,
+    pub(crate) poll_interval: Duration,
+    pub(crate) poll_jitter: Duration,
+    pub(crate) extra_headers: Vec<(String, String)>,
+    pub(crate) request_timeout: Duration,
+    pub(crate) obfuscated: bool,
+    pub(crate) always_fetch_bandits: bool,
+    pub(crate) query_params: Vec<(String, String)>,
+    pub(crate) max_response_bytes: usize,
+    pub(crate) auth_mode: AuthMode,
+    pub(crate) startup_mode: StartupMode,
+    pub(crate) on_configuration_change: Option<ConfigurationChangeCallback>,
+    pub(crate) on_error: Option<ErrorCallback>,
+    pub(crate) clock: Arc<dyn Clock>,
+    pub(crate) evaluation_observer: Option<Arc<dyn EvaluationObserver>>,
+    pub(crate) sharder: Option<Arc<dyn Sharder>>,
+    pub(crate) default_attributes: Arc<Attributes>,
+    pub(crate) coerce_numeric_types: bool,
+    pub(crate) unknown_bandit_action_policy: UnknownActionPolicy,
+    pub(crate) max_configuration_age: Option<Duration>,
 }
 
 impl<'a> ClientConfig<'a> {
@@ -28,10 +98,83 @@ impl<'a> ClientConfig<'a> {
         ClientConfig {
             api_key: api_key.into(),
             base_url: ClientConfig::DEFAULT_BASE_URL.to_owned(),
+            base_urls: Vec::new(),
             assignment_logger: Box::new(NoopAssignmentLogger),
+            bandit_logger: None,
+            assignment_cache: None,
+            bandit_cache: None,
+            initial_configuration: None,
+            configuration_file: None,
+            bandit_models_file: None,
+            configuration_persistence: None,
+            is_graceful_mode: true,
+            poll_interval: PollerThreadConfig::DEFAULT_POLL_INTERVAL,
+            poll_jitter: PollerThreadConfig::DEFAULT_POLL_JITTER,
+            extra_headers: Vec::new(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            obfuscated: false,
+            always_fetch_bandits: false,
+            query_params: Vec::new(),
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+            auth_mode: AuthMode::QueryParam,
+            startup_mode: StartupMode::Manual,
+            on_configuration_change: None,
+            on_error: None,
+            clock: Arc::new(SystemClock),
+            evaluation_observer: None,
+            sharder: None,
+            default_attributes: Arc::default(),
+            coerce_numeric_types: false,
+            unknown_bandit_action_policy: UnknownActionPolicy::UseDefaultScore,
+            max_configuration_age: None,
         }
     }
 
+    /// Create a default Eppo configuration from environment variables.
+    ///
+    /// The following variables are read:
+    /// - `EPPO_API_KEY` (required) — API key.
+    /// - `EPPO_BASE_URL` (optional) — see [`ClientConfig::base_url()`].
+    /// - `EPPO_POLL_INTERVAL_SECONDS` (optional) — see [`ClientConfig::poll_interval()`].
+    /// - `EPPO_POLL_JITTER_SECONDS` (optional) — see [`ClientConfig::poll_jitter()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`] if `EPPO_API_KEY` is not set, or if any variable
+    /// holds an invalid value. The error message includes the name of the variable.
+    ///
+    /// ```no_run
+    /// # use eppo::ClientConfig;
+    /// # fn test() -> eppo::Result<()> {
+    /// let client = ClientConfig::from_env()?.to_client();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_env() -> Result<Self> {
+        ClientConfig::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Create configuration, reading variables using `var` function. See
+    /// [`ClientConfig::from_env()`].
+    pub(crate) fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let api_key = var("EPPO_API_KEY").ok_or_else(|| {
+            Error::InvalidConfiguration("EPPO_API_KEY environment variable is not set".to_owned())
+        })?;
+
+        let mut config = ClientConfig::from_api_key(api_key);
+        if let Some(base_url) = var("EPPO_BASE_URL") {
+            config = config.base_url(base_url);
+        }
+        if let Some(seconds) = var("EPPO_POLL_INTERVAL_SECONDS") {
+            config = config.poll_interval(parse_seconds("EPPO_POLL_INTERVAL_SECONDS", &seconds)?);
+        }
+        if let Some(seconds) = var("EPPO_POLL_JITTER_SECONDS") {
+            config = config.poll_jitter(parse_seconds("EPPO_POLL_JITTER_SECONDS", &seconds)?);
+        }
+
+        Ok(config)
+    }
+
     /// Set assignment logger to store variation assignments to your data warehouse.
     ///
     /// ```
@@ -48,17 +191,554 @@ impl<'a> ClientConfig<'a> {
         self
     }
 
+    /// Set bandit logger to store bandit actions to your data warehouse.
+    ///
+    /// This is a shortcut for closure-based loggers. When set, bandit events are passed to
+    /// `bandit_logger` instead of [`AssignmentLogger::log_bandit_action()`].
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .assignment_logger(|event| {
+    ///         println!("{:?}", event);
+    ///     })
+    ///     .bandit_logger(|event| {
+    ///         println!("{:?}", event);
+    ///     });
+    /// ```
+    pub fn bandit_logger(mut self, bandit_logger: impl Fn(BanditEvent) + Send + Sync + 'a) -> Self {
+        self.bandit_logger = Some(Box::new(bandit_logger));
+        self
+    }
+
+    /// Set assignment cache to deduplicate assignment events. When set, an assignment event is only
+    /// passed to the assignment logger if the subject has not been served the same allocation and
+    /// variation before.
+    ///
+    /// No cache is used by default.
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, LruAssignmentCache};
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .assignment_cache(LruAssignmentCache::default());
+    /// ```
+    pub fn assignment_cache(
+        mut self,
+        assignment_cache: impl AssignmentCache + Send + Sync + 'a,
+    ) -> Self {
+        self.assignment_cache = Some(Box::new(assignment_cache));
+        self
+    }
+
+    /// Set bandit cache to deduplicate bandit events. When set, a bandit event is only logged if
+    /// the subject has not been served the same action (with the same model version) before.
+    ///
+    /// No cache is used by default.
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, LruAssignmentCache};
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .bandit_cache(LruAssignmentCache::default());
+    /// ```
+    pub fn bandit_cache(mut self, bandit_cache: impl AssignmentCache + Send + Sync + 'a) -> Self {
+        self.bandit_cache = Some(Box::new(bandit_cache));
+        self
+    }
+
+    /// Enable or disable graceful mode. Graceful mode is enabled by default.
+    ///
+    /// When graceful mode is enabled, evaluation errors are logged and the client returns
+    /// `Ok(None)` (so the caller falls back to default value) instead of returning errors. Disabling
+    /// graceful mode may be helpful during development to detect configuration errors early.
+    ///
+    /// Graceful mode can also be changed at runtime with [`Client::set_graceful_mode()`].
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").is_graceful_mode(false);
+    /// ```
+    pub fn is_graceful_mode(mut self, is_graceful_mode: bool) -> Self {
+        self.is_graceful_mode = is_graceful_mode;
+        self
+    }
+
     /// Default base URL for API calls.
     pub const DEFAULT_BASE_URL: &'static str = "https://fscdn.eppo.cloud/api";
 
     /// Override base URL for API calls. Clients should use the default setting in most cases.
+    ///
+    /// Replaces any URLs previously set with [`ClientConfig::base_urls()`].
     pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
         self.base_url = base_url.into();
+        self.base_urls.clear();
+        self
+    }
+
+    /// Set multiple base URLs for API calls (e.g., primary CDN, secondary CDN, direct API).
+    ///
+    /// URLs are tried in order. If a server cannot be reached or responds with a 5xx error, the
+    /// next URL is tried. The last working URL is tried first on subsequent fetches. Unauthorized
+    /// responses do not trigger failover.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").base_urls([
+    ///     "https://fscdn.eppo.cloud/api",
+    ///     "https://eppo.example.com/api",
+    /// ]);
+    /// ```
+    pub fn base_urls(mut self, base_urls: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.base_urls = base_urls.into_iter().map(Into::into).collect();
+        if let Some(first) = self.base_urls.first() {
+            self.base_url = first.clone();
+        }
+        self
+    }
+
+    /// Add an HTTP header to send with configuration requests. This is useful if requests go
+    /// through a gateway that requires additional headers. Can be called multiple times to add
+    /// multiple headers.
+    ///
+    /// Headers are passed through verbatim. If header name or value is invalid, the poller stops
+    /// with [`Error::InvalidHeader`](crate::Error::InvalidHeader).
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").extra_header("X-Org-Token", "token");
+    /// ```
+    pub fn extra_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add a query parameter to send with configuration requests. This is useful to identify an
+    /// SDK that wraps this one. Can be called multiple times to add multiple parameters.
+    ///
+    /// SDK name and version, as well as core library version, are always sent and cannot be
+    /// overridden: [`ClientConfig::try_to_client()`] rejects such parameters, and
+    /// [`ClientConfig::to_client()`] ignores them with a warning.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .extra_query_param("wrapperName", "platform-sdk")
+    ///     .extra_query_param("wrapperVersion", "1.2.3");
+    /// ```
+    pub fn extra_query_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query_params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set how the API key is sent to the server. Defaults to [`AuthMode::QueryParam`] (as
+    /// `apiKey` query parameter).
+    ///
+    /// Use [`AuthMode::BearerHeader`] to send the key in `Authorization` header instead, so it
+    /// does not end up in proxy logs.
+    ///
+    /// ```
+    /// # use eppo::{AuthMode, ClientConfig};
+    /// let config = ClientConfig::from_api_key("api-key").auth_mode(AuthMode::BearerHeader);
+    /// ```
+    pub fn auth_mode(mut self, auth_mode: AuthMode) -> Self {
+        self.auth_mode = auth_mode;
+        self
+    }
+
+    /// Set the maximum size of a configuration response in bytes. Larger responses are rejected
+    /// with [`Error::ResponseTooLarge`](crate::Error::ResponseTooLarge) without being read
+    /// completely. Defaults to 16MB.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").max_response_bytes(64 * 1024 * 1024);
+    /// ```
+    pub fn max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Set timeout for configuration requests. It applies both to connecting to the server and to
+    /// the whole request. Defaults to 10 seconds.
+    ///
+    /// Timed out requests are retried on the next poll interval. Zero timeout is rejected with
+    /// [`Error::InvalidConfiguration`](crate::Error::InvalidConfiguration) when the poller is
+    /// started.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").request_timeout(Duration::from_secs(5));
+    /// ```
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Fetch obfuscated (client) flags configuration instead of the server one. Defaults to
+    /// `false`.
+    ///
+    /// Obfuscated configuration hides flag keys, attributes, and values, so it can be used with
+    /// client API keys or distributed to untrusted environments. Evaluation results are the same.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").obfuscated(true);
+    /// ```
+    pub fn obfuscated(mut self, obfuscated: bool) -> Self {
+        self.obfuscated = obfuscated;
+        self
+    }
+
+    /// Fetch bandit models on every poll, even if flags configuration does not reference any
+    /// bandits. Defaults to `false`, so accounts without bandits save a request per poll.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").always_fetch_bandits(true);
+    /// ```
+    pub fn always_fetch_bandits(mut self, always_fetch_bandits: bool) -> Self {
+        self.always_fetch_bandits = always_fetch_bandits;
+        self
+    }
+
+    /// Set a callback to invoke when the poller fetches a configuration that differs from the
+    /// previous one (e.g., new flags were published). This can be used to invalidate
+    /// application-level caches.
+    ///
+    /// The callback is invoked from the poller thread (or task) after the new configuration is
+    /// activated. Panics inside the callback are caught and logged, so they don't stop the poller.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").on_configuration_change(|configuration| {
+    ///     println!("configuration changed: {:?}", configuration.fetched_at);
+    /// });
+    /// ```
+    pub fn on_configuration_change(
+        mut self,
+        callback: impl Fn(&Configuration) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_configuration_change = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set a callback to invoke for every failed configuration fetch (e.g., to forward errors to
+    /// an error reporter). Use [`Error::is_retriable()`] to tell whether the poller keeps retrying
+    /// after the error.
+    ///
+    /// The callback is invoked from the poller thread (or task). Panics inside the callback are
+    /// caught and logged, so they don't stop the poller.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").on_error(|err| {
+    ///     if !err.is_retriable() {
+    ///         eprintln!("poller stopped: {err}");
+    ///     }
+    /// });
+    /// ```
+    pub fn on_error(mut self, callback: impl Fn(&Error) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    /// Report configuration older than `max_age` as stale. Disabled by default.
+    ///
+    /// Stale configuration is still used to serve assignments, so assignments don't change during
+    /// an outage. However, [`Client::get_assignment_with_reason()`](crate::Client::get_assignment_with_reason)
+    /// and details functions (e.g.,
+    /// [`Client::get_assignment_details()`](crate::Client::get_assignment_details)) report
+    /// [`FlagEvaluationCode::ConfigurationStale`](crate::FlagEvaluationCode::ConfigurationStale)
+    /// instead of `Match`, which can be used to emit a metric. Other codes are reported as is, as
+    /// they explain why the default value is served.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .max_configuration_age(Duration::from_secs(5 * 60));
+    /// ```
+    pub fn max_configuration_age(mut self, max_age: Duration) -> Self {
+        self.max_configuration_age = Some(max_age);
+        self
+    }
+
+    /// Set interval to wait between requests for configuration. Defaults to 30 seconds.
+    ///
+    /// Zero interval is rejected with [`Error::InvalidPollInterval`](crate::Error::InvalidPollInterval)
+    /// when the poller is started.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").poll_interval(Duration::from_secs(60));
+    /// ```
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Set maximum randomized duration that is subtracted from poll interval. This helps to avoid
+    /// multiple server instances synchronizing and producing spiky network load. Defaults to 3
+    /// seconds.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").poll_jitter(Duration::from_secs(5));
+    /// ```
+    pub fn poll_jitter(mut self, poll_jitter: Duration) -> Self {
+        self.poll_jitter = poll_jitter;
+        self
+    }
+
+    /// Set whether the client should start polling for configuration automatically when it is
+    /// created. Defaults to [`StartupMode::Manual`].
+    ///
+    /// Startup errors (e.g., timing out waiting for configuration in
+    /// [`StartupMode::BlockUntilReady`]) are returned from [`ClientConfig::try_to_client()`].
+    /// [`ClientConfig::to_client()`] logs them instead.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use eppo::{ClientConfig, StartupMode};
+    /// # fn test() -> eppo::Result<()> {
+    /// let client = ClientConfig::from_api_key("api-key")
+    ///     .startup_mode(StartupMode::BlockUntilReady {
+    ///         timeout: Duration::from_secs(5),
+    ///     })
+    ///     .try_to_client()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn startup_mode(mut self, startup_mode: StartupMode) -> Self {
+        self.startup_mode = startup_mode;
+        self
+    }
+
+    /// Set clock used to get the current time for flag evaluation. Defaults to
+    /// [`SystemClock`](crate::SystemClock).
+    ///
+    /// This allows testing allocations with start/end dates or replaying historical traffic. Use
+    /// [`FixedClock`](crate::FixedClock) to evaluate at a fixed time. Any closure returning
+    /// [`Timestamp`](crate::Timestamp) can be used as a clock as well.
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, FixedClock, Timestamp};
+    /// let at: Timestamp = "2030-01-01T00:00:00Z".parse().unwrap();
+    /// let config = ClientConfig::from_api_key("api-key").clock(FixedClock(at));
+    /// ```
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Set observer that is notified about flag evaluations (flag key, evaluation duration, and
+    /// outcome). This can be used to export per-flag latency and outcome metrics without paying for
+    /// full evaluation details.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use eppo::{ClientConfig, EvaluationObserver, FlagEvaluationCode};
+    /// struct LogObserver;
+    ///
+    /// impl EvaluationObserver for LogObserver {
+    ///     fn on_evaluation(&self, flag_key: &str, duration: Duration, outcome: FlagEvaluationCode) {
+    ///         println!("evaluated {flag_key} in {duration:?}: {outcome:?}");
+    ///     }
+    /// }
+    ///
+    /// let config = ClientConfig::from_api_key("api-key").evaluation_observer(LogObserver);
+    /// ```
+    pub fn evaluation_observer(mut self, observer: impl EvaluationObserver + 'static) -> Self {
+        self.evaluation_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Set sharder used to assign subjects to splits and to shuffle bandit actions. Defaults to
+    /// [`Md5Sharder`](crate::Md5Sharder).
+    ///
+    /// Other Eppo SDKs always use md5, so a custom sharder should only be used when assignments
+    /// need to match another system (e.g., during a migration), or when no other SDK evaluates
+    /// the same flags. With the `xxhash` feature, `Xxh3Sharder` is a faster alternative for such
+    /// Rust-only deployments. Any custom sharder reassigns subjects of running experiments, see
+    /// [assignment compatibility](eppo_core::sharder#assignment-compatibility).
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, Sharder};
+    /// struct ModuloSharder;
+    ///
+    /// impl Sharder for ModuloSharder {
+    ///     fn shard(&self, input: &[&str], total_shards: u32) -> u32 {
+    ///         let sum = input.iter().flat_map(|s| s.bytes()).map(u32::from).sum::<u32>();
+    ///         sum % total_shards
+    ///     }
+    /// }
+    ///
+    /// let config = ClientConfig::from_api_key("api-key").sharder(ModuloSharder);
+    /// ```
+    pub fn sharder(mut self, sharder: impl Sharder + 'static) -> Self {
+        self.sharder = Some(Arc::new(sharder));
+        self
+    }
+
+    /// Add an attribute to every subject evaluated by the client (e.g., region or app version).
+    ///
+    /// Default attributes are included in logged assignment events. Attributes passed to
+    /// evaluation take precedence over default attributes with the same name.
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .default_attribute("region", "eu-west-1")
+    ///     .default_attribute("appVersion", "2.3.0");
+    /// ```
+    pub fn default_attribute(
+        mut self,
+        key: impl Into<Str>,
+        value: impl Into<AttributeValue>,
+    ) -> Self {
+        Arc::make_mut(&mut self.default_attributes).insert(key.into(), value.into());
         self
     }
 
+    /// Allow typed getters to convert between Integer and Numeric flags. Defaults to `false`.
+    ///
+    /// When enabled, [`Client::get_numeric_assignment()`](crate::Client::get_numeric_assignment)
+    /// accepts Integer flags, and
+    /// [`Client::get_integer_assignment()`](crate::Client::get_integer_assignment) accepts
+    /// Numeric flags as long as the assigned value is integral. Non-integral values (e.g., `3.5`)
+    /// still produce [`EvaluationError::TypeMismatch`](crate::EvaluationError::TypeMismatch).
+    /// Evaluation details note the conversion in
+    /// [`EvaluationDetails::coerced_from`](crate::EvaluationDetails::coerced_from).
+    ///
+    /// ```
+    /// # use eppo::ClientConfig;
+    /// let config = ClientConfig::from_api_key("api-key").coerce_numeric_types(true);
+    /// ```
+    pub fn coerce_numeric_types(mut self, coerce_numeric_types: bool) -> Self {
+        self.coerce_numeric_types = coerce_numeric_types;
+        self
+    }
+
+    /// How bandits handle supplied actions their model has no coefficients for (e.g., actions
+    /// added after the model was last trained). Defaults to
+    /// [`UnknownActionPolicy::UseDefaultScore`], which scores such actions with the model's
+    /// default action score so they can be explored.
+    ///
+    /// With [`UnknownActionPolicy::Exclude`], unknown actions are never selected. If none of the
+    /// supplied actions are known, the flag variation is returned without an action.
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, UnknownActionPolicy};
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .unknown_bandit_action_policy(UnknownActionPolicy::Exclude);
+    /// ```
+    pub fn unknown_bandit_action_policy(mut self, policy: UnknownActionPolicy) -> Self {
+        self.unknown_bandit_action_policy = policy;
+        self
+    }
+
+    /// Initialize client with configuration from UFC JSON payload (and optional bandit models
+    /// JSON), so it can serve assignments without fetching configuration from the server.
+    ///
+    /// This is useful for offline initialization and environments where the poller thread cannot
+    /// be started. If poller thread is started, the configuration will be replaced once a new one
+    /// is fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigurationParseError`](crate::Error::ConfigurationParseError) if
+    /// either of configurations cannot be parsed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use eppo::ClientConfig;
+    /// # fn test() -> eppo::Result<()> {
+    /// let flags = std::fs::read("flags-v1.json").unwrap();
+    /// let client = ClientConfig::from_api_key("api-key")
+    ///     .initial_configuration_from_json(flags, None)?
+    ///     .to_client();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn initial_configuration_from_json(
+        mut self,
+        flags_json: impl Into<Vec<u8>>,
+        bandits_json: Option<&[u8]>,
+    ) -> Result<Self> {
+        let flags = UniversalFlagConfig::from_json(SDK_METADATA, flags_json.into())?;
+        let bandits = bandits_json.map(serde_json::from_slice).transpose()?;
+
+        self.initial_configuration = Some(Arc::new(Configuration::from_server_response(
+            flags, bandits,
+        )));
+        Ok(self)
+    }
+
+    /// Load initial configuration from a UFC JSON file (same format as the server response)
+    /// instead of waiting for the network. Useful for integration tests and air-gapped
+    /// deployments.
+    ///
+    /// The file is loaded when the poller starts (see [`ClientConfig::startup_mode()`]), so read
+    /// and parse errors are reported by
+    /// [`PollerThread::wait_for_configuration()`](crate::PollerThread::wait_for_configuration)
+    /// like network errors. The poller later replaces it with configuration fetched from the
+    /// server. With [`StartupMode::Manual`], the file is loaded when the client is created.
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, StartupMode};
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .configuration_file("flags-v1.json")
+    ///     .bandit_models_file("bandit-models-v1.json")
+    ///     .startup_mode(StartupMode::Background);
+    /// ```
+    pub fn configuration_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.configuration_file = Some(path.into());
+        self
+    }
+
+    /// Load bandit models alongside [`ClientConfig::configuration_file()`]. Ignored if
+    /// configuration file is not set.
+    pub fn bandit_models_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.bandit_models_file = Some(path.into());
+        self
+    }
+
+    /// Save fetched configuration to `persistence` and load it when the client starts, so that
+    /// the client can serve assignments right away after a restart, while the poller refreshes
+    /// configuration in the background. The first fetch sends cache validators of the saved
+    /// configuration, so the server can respond with 304 Not Modified.
+    ///
+    /// Saved configuration takes precedence over [`ClientConfig::configuration_file()`]. Saved
+    /// configuration that cannot be parsed is ignored with a warning.
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, FileConfigurationPersistence, StartupMode};
+    /// let config = ClientConfig::from_api_key("api-key")
+    ///     .configuration_persistence(FileConfigurationPersistence::new("/tmp/eppo-config.json"))
+    ///     .startup_mode(StartupMode::Background);
+    /// ```
+    pub fn configuration_persistence(
+        mut self,
+        persistence: impl ConfigurationPersistence + 'static,
+    ) -> Self {
+        self.configuration_persistence = Some(Arc::new(persistence));
+        self
+    }
+
+    pub(crate) fn configuration_file_paths(&self) -> Option<ConfigurationFile> {
+        Some(ConfigurationFile {
+            flags: self.configuration_file.clone()?,
+            bandits: self.bandit_models_file.clone(),
+        })
+    }
+
     /// Create a new [`Client`] using the specified configuration.
     ///
+    /// This function does not validate the configuration. Prefer [`ClientConfig::try_to_client()`],
+    /// which reports invalid configuration immediately.
+    ///
     /// ```
     /// # use eppo::{ClientConfig, Client};
     /// let client: Client = ClientConfig::from_api_key("api-key").to_client();
@@ -66,4 +746,46 @@ impl<'a> ClientConfig<'a> {
     pub fn to_client(self) -> Client<'a> {
         Client::new(self)
     }
+
+    /// Validate configuration and create a new [`Client`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfiguration`](crate::Error::InvalidConfiguration) if API key is
+    /// blank, base URL is invalid, or an extra query parameter overrides one set by the SDK.
+    ///
+    /// ```
+    /// # use eppo::{ClientConfig, Client};
+    /// # fn test() -> eppo::Result<()> {
+    /// let client: Client = ClientConfig::from_api_key("api-key").try_to_client()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_to_client(self) -> Result<Client<'a>> {
+        Client::try_new(self)
+    }
+
+    /// Check that API key, base URL, and extra query parameters are valid.
+    pub(crate) fn validate(&self) -> Result<()> {
+        validate_api_key(&self.api_key)?;
+        validate_base_url(&self.base_url)?;
+        for base_url in &self.base_urls {
+            validate_base_url(base_url)?;
+        }
+        validate_query_params(&self.query_params)?;
+        Ok(())
+    }
+}
+
+/// Parse duration in whole seconds from the value of environment variable `name`.
+fn parse_seconds(name: &str, value: &str) -> Result<Duration> {
+    value
+        .trim()
+        .parse()
+        .map(Duration::from_secs)
+        .map_err(|err| {
+            Error::InvalidConfiguration(format!(
+                "invalid value for {name} environment variable ({value:?}): {err}"
+            ))
+        })
 }
@@ -21,10 +21,24 @@
 //! detect type mismatch even before evaluating the feature, so the error is returned even if
 //! subject is otherwise uneligible (`get_assignment()` return `Ok(None)` in that case).
 //!
+//! # Configuration polling
+//!
+//! The client needs to fetch configuration from the server before it can serve assignments. Use
+//! [`Client::start_poller_thread()`] to poll in a background thread, or
+//! [`Client::start_poller_task()`] to poll in a task on an existing tokio runtime. Alternatively,
+//! set [`ClientConfig::startup_mode()`] to start the poller thread when the client is created.
+//!
+//! # Bandits
+//!
+//! Contextual bandits are evaluated with [`Client::get_bandit_action()`]. Subject and actions
+//! attributes are passed as [`ContextAttributes`], and the result is returned as [`BanditResult`].
+//!
 //! # Assignment logger
 //!
 //! An [`AssignmentLogger`] should be provided to save assignment events to your storage,
-//! facilitating tracking of which user received which feature flag values.
+//! facilitating tracking of which user received which feature flag values. If you use bandits,
+//! implement [`AssignmentLogger::log_bandit_action()`] as well (or set
+//! [`ClientConfig::bandit_logger()`]) to save bandit events.
 //!
 //! ```
 //! # use eppo::ClientConfig;
@@ -33,6 +47,12 @@
 //! });
 //! ```
 //!
+//! To avoid logging the same assignment over and over again, set an [`AssignmentCache`] with
+//! [`ClientConfig::assignment_cache()`].
+//!
+//! For high-throughput services, wrap your logger in [`BatchingAssignmentLogger`] to buffer events
+//! and pass them to your logger on a background thread.
+//!
 //! # Error Handling
 //!
 //! Errors are represented by the [`Error`] enum.
@@ -41,6 +61,37 @@
 //! critical enough to cause system crashes. However, the returned errors are valuable for debugging
 //! and usually indicate that developer's attention is needed.
 //!
+//! By default, the client runs in graceful mode and evaluation errors are logged and converted to
+//! `Ok(None)`. Use [`ClientConfig::is_graceful_mode()`] to get evaluation errors returned.
+//!
+//! # Cargo features
+//!
+//! The TLS implementation used to fetch configuration is selected with cargo features:
+//!
+//! | Feature                | TLS implementation                                  |
+//! |------------------------|-----------------------------------------------------|
+//! | `tls-rustls` (default) | [rustls](https://docs.rs/rustls); no OpenSSL needed |
+//! | `tls-native`           | Platform-native (OpenSSL on Linux)                  |
+//!
+//! To use the native implementation, disable default features:
+//!
+//! ```toml
+//! eppo = { version = "4", default-features = false, features = ["tls-native", "compression"] }
+//! ```
+//!
+//! If both features are enabled, the native implementation is used.
+//!
+//! The `compression` feature (enabled by default) requests gzip/brotli-compressed configuration
+//! responses. Disable it to drop the flate/brotli dependencies, e.g., on WASM or embedded targets.
+//!
+//! The `xxhash` feature adds `Xxh3Sharder`, a faster alternative to the default md5 sharder for
+//! deployments where no other Eppo SDK evaluates the same flags. It assigns subjects differently
+//! from other SDKs, so it must be enabled explicitly with [`ClientConfig::sharder()`].
+//!
+//! The `server` feature adds `PrecomputedRequest` and `PrecomputedResponse` along with
+//! `Client::handle_precomputed_request()` to serve precomputed assignments to client SDKs from any
+//! HTTP framework.
+//!
 //! # Logging
 //!
 //! The package uses the [`log`](https://docs.rs/log/latest/log/) crate for logging
@@ -56,21 +107,53 @@
 #![warn(missing_docs)]
 
 mod assignment_logger;
+mod batching_assignment_logger;
 mod client;
 mod config;
+mod multi_client;
 mod poller;
+mod subject;
 
 use eppo_core::SdkMetadata;
 #[doc(inline)]
 pub use eppo_core::{
-    eval::eval_details::*, events::AssignmentEvent, ufc::AssignmentValue, AttributeValue,
-    Attributes, CategoricalAttribute, Error, EvaluationError, NumericAttribute, Result,
+    assignment_cache::{
+        AssignmentCache, AssignmentCacheKey, AssignmentCacheValue, LruAssignmentCache,
+        TtlAssignmentCache,
+    },
+    configuration_fetcher::AuthMode,
+    configuration_persistence::{ConfigurationPersistence, FileConfigurationPersistence},
+    configuration_store::ConfigurationStore,
+    eval::eval_details::*,
+    eval::{Actions, BanditRequest, BanditResult, EvaluationObserver, UnknownActionPolicy},
+    events::{AssignmentEvent, BanditEvent},
+    multi_configuration_store::MultiConfigurationStore,
+    poller_thread::{ConfigurationReceiver, FetchStats, PollerState, PollerStatus},
+    sharder::{Md5Sharder, Sharder},
+    timestamp::{Clock, FixedClock, SystemClock, Timestamp},
+    ufc::AssignmentValue,
+    AttributeKind, AttributeValue, Attributes, CategoricalAttribute, Configuration,
+    ConfigurationMemoryStats, ConfigurationTimestamps, ContextAttributes, Error, EvaluationError,
+    NumericAttribute, PrecomputedConfiguration, Result,
 };
 
 pub use assignment_logger::AssignmentLogger;
+pub use batching_assignment_logger::{
+    BatchingAssignmentLogger, BatchingAssignmentLoggerConfig, OverflowPolicy,
+};
+#[cfg(feature = "xxhash")]
+#[doc(inline)]
+pub use eppo_core::sharder::Xxh3Sharder;
+
+#[cfg(feature = "server")]
+#[doc(inline)]
+pub use eppo_core::precomputed_server::{PrecomputedRequest, PrecomputedResponse};
+
 pub use client::Client;
-pub use config::ClientConfig;
-pub use poller::PollerThread;
+pub use config::{ClientConfig, StartupMode};
+pub use multi_client::MultiClient;
+pub use poller::{PollerTask, PollerThread};
+pub use subject::{AsSubject, IntoSubjectAttributes, Subject};
 
 pub(crate) const SDK_METADATA: SdkMetadata = SdkMetadata {
     name: "rust",
@@ -0,0 +1,239 @@
+use std::{collections::HashMap, sync::Arc};
+
+use eppo_core::{multi_configuration_store::MultiConfigurationStore, Str};
+
+use crate::{Client, ClientConfig, Error};
+
+/// A client serving assignments for several Eppo environments (e.g., staging and production)
+/// from one process.
+///
+/// Each environment has its own [`ClientConfig`] (with the SDK key of that environment) and is
+/// served by its own [`Client`], which polls configuration of that environment according to
+/// [`ClientConfig::startup_mode()`]. Configuration of all environments is kept in a shared
+/// [`MultiConfigurationStore`], and environments never see each other's configuration.
+///
+/// # Examples
+/// ```
+/// # use eppo::{ClientConfig, MultiClient};
+/// let mut client = MultiClient::new();
+/// client.add_environment("staging", ClientConfig::from_api_key("staging-key"));
+/// client.add_environment("production", ClientConfig::from_api_key("production-key"));
+///
+/// let staging = client.for_environment("staging").unwrap();
+/// # assert!(!staging.is_initialized());
+/// ```
+pub struct MultiClient<'a> {
+    configuration_store: Arc<MultiConfigurationStore>,
+    clients: HashMap<Str, Client<'a>>,
+}
+
+impl<'a> MultiClient<'a> {
+    /// Create a new `MultiClient` without any environments.
+    pub fn new() -> Self {
+        MultiClient::with_configuration_store(Arc::new(MultiConfigurationStore::new()))
+    }
+
+    /// Create a new `MultiClient` that keeps configuration of environments in the provided
+    /// `configuration_store`.
+    pub fn with_configuration_store(configuration_store: Arc<MultiConfigurationStore>) -> Self {
+        MultiClient {
+            configuration_store,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Add a client for `environment`. If the environment has been added before, its client is
+    /// replaced, but configuration of the environment is kept.
+    ///
+    /// Like [`Client::new()`], this does not validate the configuration and errors during
+    /// startup are logged. Prefer [`MultiClient::try_add_environment()`], which reports such
+    /// errors immediately.
+    pub fn add_environment(
+        &mut self,
+        environment: impl Into<Str>,
+        config: ClientConfig<'a>,
+    ) -> &Client<'a> {
+        let environment = environment.into();
+        let client =
+            Client::with_configuration_store(config, self.configuration_store.store(&environment));
+        self.insert(environment, client)
+    }
+
+    /// Add a client for `environment`, validating its configuration first. If the environment has
+    /// been added before, its client is replaced, but configuration of the environment is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Client::try_new()`]. The environment is not added in that
+    /// case.
+    pub fn try_add_environment(
+        &mut self,
+        environment: impl Into<Str>,
+        config: ClientConfig<'a>,
+    ) -> Result<&Client<'a>, Error> {
+        let environment = environment.into();
+        // Validate before touching the shared store, so an invalid environment leaves no trace.
+        config.validate()?;
+        let is_new = !self
+            .configuration_store
+            .environments()
+            .contains(&environment);
+        match Client::try_with_configuration_store(
+            config,
+            self.configuration_store.store(&environment),
+        ) {
+            Ok(client) => Ok(self.insert(environment, client)),
+            Err(err) => {
+                if is_new {
+                    self.configuration_store.remove(&environment);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    fn insert(&mut self, environment: Str, client: Client<'a>) -> &Client<'a> {
+        // The previous client (and its poller thread) is dropped.
+        self.clients.insert(environment.clone(), client);
+        &self.clients[&environment]
+    }
+
+    /// Get the client of `environment`. Returns `None` if the environment hasn't been added.
+    pub fn for_environment(&self, environment: &str) -> Option<&Client<'a>> {
+        self.clients.get(environment)
+    }
+
+    /// Returns all added environments, sorted alphabetically.
+    pub fn environments(&self) -> Vec<Str> {
+        let mut environments = self.clients.keys().cloned().collect::<Vec<_>>();
+        environments.sort();
+        environments
+    }
+
+    /// Configuration store shared by all environments.
+    pub fn configuration_store(&self) -> &Arc<MultiConfigurationStore> {
+        &self.configuration_store
+    }
+
+    /// Stop poller threads of all environments and wait for them to exit.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PollerThreadPanicked`] if any of the threads has panicked. Other threads
+    /// are still stopped.
+    pub fn shutdown(self) -> Result<(), Error> {
+        let mut result = Ok(());
+        for client in self.clients.into_values() {
+            if let Err(err) = client.shutdown() {
+                result = Err(err);
+            }
+        }
+        result
+    }
+}
+
+impl Default for MultiClient<'_> {
+    fn default() -> Self {
+        MultiClient::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use eppo_core::{ufc::UniversalFlagConfig, Str};
+
+    use crate::{AssignmentValue, ClientConfig, Configuration, Error, StartupMode, SDK_METADATA};
+
+    use super::MultiClient;
+
+    // Configurations with a single string flag `color` that returns the environment name.
+    const STAGING_JSON: &[u8] = include_bytes!("../../eppo_core/tests/fixtures/color-staging.json");
+    const PRODUCTION_JSON: &[u8] =
+        include_bytes!("../../eppo_core/tests/fixtures/color-production.json");
+
+    fn config(flags_json: &[u8]) -> ClientConfig<'static> {
+        ClientConfig::from_api_key("api-key")
+            .initial_configuration_from_json(flags_json, None)
+            .unwrap()
+    }
+
+    fn color(client: &MultiClient, environment: &str) -> Option<String> {
+        client
+            .for_environment(environment)
+            .unwrap()
+            .get_string_assignment("color", "subject", Arc::new(HashMap::new()))
+            .unwrap()
+            .map(|value| value.to_string())
+    }
+
+    #[test]
+    fn environments_are_isolated() {
+        let mut client = MultiClient::new();
+        client.add_environment("staging", config(STAGING_JSON));
+        client.add_environment("production", ClientConfig::from_api_key("api-key"));
+
+        assert_eq!(color(&client, "staging").as_deref(), Some("staging"));
+        assert_eq!(color(&client, "production"), None);
+
+        client.configuration_store().set(
+            "production",
+            Arc::new(Configuration::from_server_response(
+                UniversalFlagConfig::from_json(SDK_METADATA, PRODUCTION_JSON.to_vec()).unwrap(),
+                None,
+            )),
+        );
+
+        assert_eq!(color(&client, "staging").as_deref(), Some("staging"));
+        assert_eq!(color(&client, "production").as_deref(), Some("production"));
+        assert_eq!(
+            client.environments(),
+            [Str::from("production"), Str::from("staging")]
+        );
+        assert!(client.for_environment("development").is_none());
+    }
+
+    #[test]
+    fn replacing_environment_keeps_configuration() {
+        let mut client = MultiClient::new();
+        client.add_environment("staging", config(STAGING_JSON));
+
+        client.add_environment("staging", ClientConfig::from_api_key("api-key"));
+
+        assert_eq!(color(&client, "staging").as_deref(), Some("staging"));
+    }
+
+    #[test]
+    fn invalid_environment_is_not_added() {
+        let mut client = MultiClient::new();
+
+        assert!(matches!(
+            client.try_add_environment("staging", ClientConfig::from_api_key("")),
+            Err(Error::InvalidConfiguration(_))
+        ));
+        assert!(client.for_environment("staging").is_none());
+        assert!(client.configuration_store().environments().is_empty());
+
+        assert!(client
+            .try_add_environment(
+                "staging",
+                ClientConfig::from_api_key("api-key")
+                    .startup_mode(StartupMode::Manual)
+                    .configuration_file("/nonexistent/flags.json"),
+            )
+            .is_err());
+        assert!(client.for_environment("staging").is_none());
+        assert!(client.configuration_store().environments().is_empty());
+
+        let staging = client
+            .try_add_environment("staging", config(STAGING_JSON))
+            .unwrap();
+        assert_eq!(
+            staging
+                .get_assignment("color", "subject", Arc::new(HashMap::new()))
+                .unwrap(),
+            Some(AssignmentValue::String("staging".into()))
+        );
+    }
+}
@@ -1,16 +1,79 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use crate::{Result, SDK_METADATA};
-use eppo_core::configuration_fetcher::{ConfigurationFetcher, ConfigurationFetcherConfig};
+use crate::config::ConfigurationChangeCallback;
+use crate::{Configuration, Error, Result, SDK_METADATA};
+use eppo_core::configuration_fetcher::{
+    AuthMode, ConfigurationFetcher, ConfigurationFetcherConfig, ConfigurationFile,
+};
+use eppo_core::configuration_persistence::ConfigurationPersistence;
 use eppo_core::configuration_store::ConfigurationStore;
-use eppo_core::poller_thread::PollerThread as PollerThreadImpl;
-#[cfg(doc)]
-use eppo_core::Error;
+use eppo_core::poller_task::PollerTask as PollerTaskImpl;
+use eppo_core::poller_thread::{
+    ConfigurationReceiver, ErrorCallback, FetchStats, PollerState, PollerStatus,
+    PollerThread as PollerThreadImpl, PollerThreadConfig as PollerThreadImplConfig,
+};
 
 pub(crate) struct PollerThreadConfig {
     pub(crate) store: Arc<ConfigurationStore>,
     pub(crate) base_url: String,
+    pub(crate) base_urls: Vec<String>,
     pub(crate) api_key: String,
+    pub(crate) interval: Duration,
+    pub(crate) jitter: Duration,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) request_timeout: Duration,
+    pub(crate) obfuscated: bool,
+    pub(crate) always_fetch_bandits: bool,
+    pub(crate) query_params: Vec<(String, String)>,
+    pub(crate) max_response_bytes: usize,
+    pub(crate) auth_mode: AuthMode,
+    pub(crate) configuration_file: Option<ConfigurationFile>,
+    pub(crate) persistence: Option<Arc<dyn ConfigurationPersistence>>,
+    pub(crate) on_configuration_change: Option<ConfigurationChangeCallback>,
+    pub(crate) on_error: Option<ErrorCallback>,
+}
+
+impl PollerThreadConfig {
+    fn split(
+        self,
+    ) -> (
+        ConfigurationFetcher,
+        Arc<ConfigurationStore>,
+        PollerThreadImplConfig,
+    ) {
+        let fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
+            base_url: self.base_url,
+            base_urls: self.base_urls,
+            api_key: self.api_key,
+            sdk_metadata: SDK_METADATA,
+            headers: self.headers,
+            request_timeout: self.request_timeout,
+            obfuscated: self.obfuscated,
+            always_fetch_bandits: self.always_fetch_bandits,
+            query_params: self.query_params,
+            max_response_bytes: self.max_response_bytes,
+            auth_mode: self.auth_mode,
+        });
+        let mut config = PollerThreadImplConfig::new()
+            .with_interval(self.interval)
+            .with_jitter(self.jitter);
+        if let Some(file) = self.configuration_file {
+            config = config.with_configuration_file(file);
+        }
+        if let Some(persistence) = self.persistence {
+            config = config.with_persistence(persistence);
+        }
+        if let Some(callback) = self.on_configuration_change {
+            config =
+                config.with_on_configuration_change(move |configuration: &Arc<Configuration>| {
+                    callback(configuration)
+                });
+        }
+        if let Some(callback) = self.on_error {
+            config = config.with_on_error(move |err| callback(err));
+        }
+        (fetcher, self.store, config)
+    }
 }
 
 /// A configuration poller thread.
@@ -21,7 +84,11 @@ pub(crate) struct PollerThreadConfig {
 ///
 /// The Client returns `None` for assignments before the first configuration is fetched. So it is
 /// recommended to call [`PollerThread::wait_for_configuration`] before requesting assignments.
-pub struct PollerThread(PollerThreadImpl);
+///
+/// `PollerThread` is a handle to the thread owned by the [`Client`](crate::Client). The thread is
+/// stopped when the client is dropped.
+#[derive(Clone)]
+pub struct PollerThread(Arc<PollerThreadImpl>);
 
 impl PollerThread {
     /// Starts the configuration poller thread.
@@ -39,17 +106,14 @@ impl PollerThread {
     /// This method can return the following errors:
     ///
     /// - [`Error::InvalidBaseUrl`] if the base URL configuration is invalid.
+    /// - [`Error::InvalidHeader`] if an extra header name or value is invalid.
     /// - [`Error::Unauthorized`] if the request is unauthorized, possibly due to an invalid API key.
     /// - [`Error::PollerThreadPanicked`] if an unexpected panic occurs in the poller thread.
     /// - [`Error::Io`] for any I/O related errors.
     pub(crate) fn start(config: PollerThreadConfig) -> Result<PollerThread> {
-        let fetcher = ConfigurationFetcher::new(ConfigurationFetcherConfig {
-            base_url: config.base_url,
-            api_key: config.api_key,
-            sdk_metadata: SDK_METADATA.clone(),
-        });
-        let inner = PollerThreadImpl::start(fetcher, config.store)?;
-        Ok(PollerThread(inner))
+        let (fetcher, store, config) = config.split();
+        let inner = PollerThreadImpl::start_with_config(fetcher, store, config)?;
+        Ok(PollerThread(Arc::new(inner)))
     }
 
     /// Waits for the configuration to be fetched.
@@ -66,12 +130,19 @@ impl PollerThread {
     /// This method can fail with the following errors:
     ///
     /// - [`Error::PollerThreadPanicked`]: If the poller thread panicked while waiting for
-    /// configuration.
+    ///   configuration.
+    /// - [`Error::PollerStopped`]: If the poller thread was stopped before fetching
+    ///   configuration.
+    /// - [`Error::RateLimited`]: If the server throttled requests before configuration was
+    ///   fetched. The poller keeps retrying, so a later call may succeed.
+    /// - [`Error::Io`] or [`Error::ConfigurationParseError`]: If
+    ///   [`ClientConfig::configuration_file()`](crate::ClientConfig::configuration_file) cannot be
+    ///   loaded and configuration hasn't been fetched from the server yet.
     ///
     /// # Example
     ///
     /// ```
-    /// # fn test(mut client: eppo::Client) {
+    /// # fn test(client: eppo::Client) {
     /// let poller = client.start_poller_thread().unwrap();
     /// match poller.wait_for_configuration() {
     ///     Ok(()) => println!("Configuration fetched successfully."),
@@ -83,6 +154,113 @@ impl PollerThread {
         self.0.wait_for_configuration()
     }
 
+    /// Waits for the configuration to be fetched, but no longer than `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail with the following errors:
+    ///
+    /// - [`Error::ConfigurationWaitTimeout`] if configuration wasn't fetched within `timeout`.
+    /// - [`Error::PollerThreadPanicked`]: If the poller thread panicked while waiting for
+    ///   configuration.
+    /// - [`Error::PollerStopped`]: If the poller thread was stopped before fetching
+    ///   configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # fn test(client: eppo::Client) {
+    /// let poller = client.start_poller_thread().unwrap();
+    /// match poller.wait_for_configuration_timeout(Duration::from_secs(5)) {
+    ///     Ok(()) => println!("Configuration fetched successfully."),
+    ///     Err(err) => eprintln!("Error fetching configuration: {:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub fn wait_for_configuration_timeout(&self, timeout: Duration) -> Result<()> {
+        self.0.wait_for_configuration_timeout(timeout)
+    }
+
+    /// Returns the current status of the poller without blocking. This is useful for readiness
+    /// probes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use eppo::PollerStatus;
+    /// # fn test(client: eppo::Client) {
+    /// let poller = client.start_poller_thread().unwrap();
+    /// let is_ready = matches!(poller.status(), PollerStatus::Ready { .. });
+    /// # }
+    /// ```
+    pub fn status(&self) -> PollerStatus {
+        self.0.status()
+    }
+
+    /// Returns the current health of the poller: whether it is starting, healthy, backing off after
+    /// failed fetches, or stopped because of an unrecoverable error.
+    pub fn state(&self) -> PollerState {
+        self.0.state()
+    }
+
+    /// Fetch configuration immediately and block until the fetch completes. Concurrent calls
+    /// share the same fetch.
+    ///
+    /// # Errors
+    ///
+    /// - The error of the fetch if it failed.
+    /// - [`Error::PollerStopped`] if the poller thread has stopped.
+    pub fn poll_now(&self) -> Result<()> {
+        self.0.poll_now()
+    }
+
+    /// Returns the error that stopped the poller thread, if any (e.g., [`Error::Unauthorized`]
+    /// if the API key is invalid). Configuration is not updated after that.
+    ///
+    /// The error stays available after the thread has exited, and
+    /// [`PollerThread::wait_for_configuration()`] returns it as well.
+    pub fn terminal_error(&self) -> Option<Error> {
+        self.0.terminal_error()
+    }
+
+    /// Returns metadata about configuration fetches: when the last fetch was attempted and
+    /// succeeded, how long it took, and how big the payload was.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn test(client: eppo::Client) {
+    /// let poller = client.start_poller_thread().unwrap();
+    /// let stats = poller.stats();
+    /// println!("last successful fetch: {:?}", stats.last_success_at);
+    /// # }
+    /// ```
+    pub fn stats(&self) -> FetchStats {
+        self.0.stats()
+    }
+
+    /// Subscribe to configuration updates. The receiver starts with the current configuration and
+    /// is notified every time it is replaced.
+    ///
+    /// The client still reads configuration from its store, so this is only needed to react to
+    /// configuration updates in async code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # async fn test(client: eppo::Client<'_>) {
+    /// let poller = client.start_poller_thread().unwrap();
+    /// let mut configurations = poller.watch();
+    /// if configurations.wait_for(|configuration| configuration.is_some()).await.is_ok() {
+    ///     println!("configuration is ready");
+    /// }
+    /// # }
+    /// ```
+    pub fn watch(&self) -> ConfigurationReceiver {
+        self.0.watch()
+    }
+
     /// Stop the poller thread.
     ///
     /// This function does not wait for the thread to actually stop.
@@ -108,7 +286,100 @@ impl PollerThread {
     /// poller_thread.shutdown().expect("Failed to shut down the poller thread");
     /// # }
     /// ```
-    pub fn shutdown(self) -> Result<()> {
+    pub fn shutdown(&self) -> Result<()> {
         self.0.shutdown()
     }
 }
+
+/// A configuration poller task.
+///
+/// The poller task polls the server periodically to fetch the latest configuration. Unlike
+/// [`PollerThread`], it runs as a task on the current tokio runtime and does not spawn a dedicated
+/// OS thread.
+///
+/// Use [`Client::start_poller_task`][crate::Client::start_poller_task] to get an instance.
+///
+/// The task is stopped when `PollerTask` is dropped.
+pub struct PollerTask(PollerTaskImpl);
+
+impl PollerTask {
+    /// Spawns the configuration poller task on the current tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside the context of a tokio runtime.
+    pub(crate) fn start(config: PollerThreadConfig) -> PollerTask {
+        let (fetcher, store, config) = config.split();
+        PollerTask(PollerTaskImpl::start_with_config(fetcher, store, config))
+    }
+
+    /// Waits for the configuration to be fetched.
+    ///
+    /// # Errors
+    ///
+    /// This method can fail with the following errors:
+    ///
+    /// - [`Error::InvalidBaseUrl`] if the base URL configuration is invalid.
+    /// - [`Error::InvalidHeader`] if an extra header name or value is invalid.
+    /// - [`Error::Unauthorized`] if the request is unauthorized, possibly due to an invalid API key.
+    /// - [`Error::PollerThreadPanicked`] if the poller task panicked.
+    /// - [`Error::PollerStopped`] if the poller task was stopped before fetching configuration.
+    /// - [`Error::RateLimited`] if the server throttled requests before configuration was fetched.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn test(client: eppo::Client<'_>) {
+    /// let poller = client.start_poller_task().unwrap();
+    /// match poller.wait_for_configuration().await {
+    ///     Ok(()) => println!("Configuration fetched successfully."),
+    ///     Err(err) => eprintln!("Error fetching configuration: {:?}", err),
+    /// }
+    /// # }
+    /// ```
+    pub async fn wait_for_configuration(&self) -> Result<()> {
+        self.0.wait_for_configuration().await
+    }
+
+    /// Returns the current status of the poller without blocking. See [`PollerThread::status`].
+    pub fn status(&self) -> PollerStatus {
+        self.0.status()
+    }
+
+    /// Returns the current health of the poller. See [`PollerThread::state`].
+    pub fn state(&self) -> PollerState {
+        self.0.state()
+    }
+
+    /// Returns the error that stopped the poller task, if any. See
+    /// [`PollerThread::terminal_error`].
+    pub fn terminal_error(&self) -> Option<Error> {
+        self.0.terminal_error()
+    }
+
+    /// Returns metadata about configuration fetches. See [`PollerThread::stats`].
+    pub fn stats(&self) -> FetchStats {
+        self.0.stats()
+    }
+
+    /// Subscribe to configurations stored by the poller task. See [`PollerThread::watch`].
+    pub fn watch(&self) -> ConfigurationReceiver {
+        self.0.watch()
+    }
+
+    /// Stop the poller task. An in-flight configuration request is cancelled.
+    ///
+    /// This function does not wait for the task to actually stop.
+    pub fn stop(&self) {
+        self.0.stop()
+    }
+
+    /// Stop the poller task and wait for it to exit.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::PollerThreadPanicked`] if the task has panicked.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.0.shutdown().await
+    }
+}
@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use eppo_core::Str;
+
+use crate::{AttributeValue, Attributes};
+
+/// A subject (e.g., user) to evaluate flags for: subject key along with subject attributes.
+///
+/// `Subject` is a convenient builder for subject attributes. Attributes are stored behind an
+/// `Arc`, so the subject can be built once and reused across multiple assignments without
+/// re-allocating the attributes map.
+///
+/// # Examples
+/// ```
+/// # use eppo::Subject;
+/// let subject = Subject::new("user-123")
+///     .with_number("age", 30)
+///     .with_string("country", "DE")
+///     .with_bool("is_premium", true);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subject {
+    key: Str,
+    attributes: Arc<Attributes>,
+}
+
+impl Subject {
+    /// Create a new subject with the specified key and no attributes.
+    pub fn new(key: impl Into<Str>) -> Subject {
+        Subject {
+            key: key.into(),
+            attributes: Arc::new(Attributes::new()),
+        }
+    }
+
+    /// Add an attribute to the subject. If attribute with the same name already exists, it is
+    /// replaced.
+    ///
+    /// Numbers are treated as numeric attributes; strings and booleans are treated as categorical
+    /// attributes. Use [`AttributeValue::numeric()`] or [`AttributeValue::categorical()`] to
+    /// specify attribute kind explicitly.
+    pub fn with(mut self, name: impl Into<Str>, value: impl Into<AttributeValue>) -> Subject {
+        Arc::make_mut(&mut self.attributes).insert(name.into(), value.into());
+        self
+    }
+
+    /// Add a numeric attribute to the subject.
+    pub fn with_number(self, name: impl Into<Str>, value: impl Into<f64>) -> Subject {
+        self.with(name, AttributeValue::numeric(value.into()))
+    }
+
+    /// Add a boolean (categorical) attribute to the subject.
+    pub fn with_bool(self, name: impl Into<Str>, value: bool) -> Subject {
+        self.with(name, AttributeValue::categorical(value))
+    }
+
+    /// Add a string (categorical) attribute to the subject.
+    pub fn with_string(self, name: impl Into<Str>, value: impl Into<Str>) -> Subject {
+        self.with(name, AttributeValue::categorical(value.into()))
+    }
+
+    /// Subject key.
+    pub fn key(&self) -> &Str {
+        &self.key
+    }
+
+    /// Subject attributes.
+    pub fn attributes(&self) -> &Arc<Attributes> {
+        &self.attributes
+    }
+
+    /// Split subject into subject key and attributes.
+    pub fn into_parts(self) -> (Str, Arc<Attributes>) {
+        (self.key, self.attributes)
+    }
+}
+
+impl From<(Str, Arc<Attributes>)> for Subject {
+    fn from((key, attributes): (Str, Arc<Attributes>)) -> Subject {
+        Subject { key, attributes }
+    }
+}
+
+impl From<Subject> for (Str, Arc<Attributes>) {
+    fn from(subject: Subject) -> (Str, Arc<Attributes>) {
+        subject.into_parts()
+    }
+}
+
+/// Types that can be used as a subject in `Client::get_*_assignment_for_subject()` methods.
+///
+/// Implemented for [`Subject`] and `(Str, Arc<Attributes>)` pairs.
+pub trait AsSubject {
+    /// Subject key.
+    fn subject_key(&self) -> &Str;
+    /// Subject attributes.
+    fn subject_attributes(&self) -> &Arc<Attributes>;
+}
+
+impl AsSubject for Subject {
+    fn subject_key(&self) -> &Str {
+        &self.key
+    }
+
+    fn subject_attributes(&self) -> &Arc<Attributes> {
+        &self.attributes
+    }
+}
+
+impl AsSubject for (Str, Arc<Attributes>) {
+    fn subject_key(&self) -> &Str {
+        &self.0
+    }
+
+    fn subject_attributes(&self) -> &Arc<Attributes> {
+        &self.1
+    }
+}
+
+/// Conversion into subject attributes accepted by `Client` assignment methods.
+///
+/// Implemented for `Arc<Attributes>` and `Attributes` (both owned and by reference). Passing an
+/// `Arc` (or a reference to it) does not copy attributes.
+pub trait IntoSubjectAttributes {
+    /// Convert into shared subject attributes.
+    fn into_subject_attributes(self) -> Arc<Attributes>;
+}
+
+impl IntoSubjectAttributes for Arc<Attributes> {
+    fn into_subject_attributes(self) -> Arc<Attributes> {
+        self
+    }
+}
+
+impl IntoSubjectAttributes for &Arc<Attributes> {
+    fn into_subject_attributes(self) -> Arc<Attributes> {
+        Arc::clone(self)
+    }
+}
+
+impl IntoSubjectAttributes for Attributes {
+    fn into_subject_attributes(self) -> Arc<Attributes> {
+        Arc::new(self)
+    }
+}
+
+impl IntoSubjectAttributes for &Attributes {
+    fn into_subject_attributes(self) -> Arc<Attributes> {
+        Arc::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use eppo_core::Str;
+
+    use crate::{AttributeValue, Attributes};
+
+    use super::Subject;
+
+    #[test]
+    fn builds_attributes() {
+        let subject = Subject::new("user-123")
+            .with_number("age", 30)
+            .with_string("country", "DE")
+            .with_bool("is_premium", true)
+            .with("plan", "pro");
+
+        let (key, attributes) = subject.into_parts();
+
+        assert_eq!(key, Str::from("user-123"));
+        assert_eq!(
+            attributes,
+            Arc::new(Attributes::from([
+                ("age".into(), AttributeValue::numeric(30.0)),
+                ("country".into(), AttributeValue::categorical("DE")),
+                ("is_premium".into(), AttributeValue::categorical(true)),
+                ("plan".into(), AttributeValue::categorical("pro")),
+            ]))
+        );
+    }
+
+    #[test]
+    fn later_attribute_overrides_earlier() {
+        let subject = Subject::new("user-123")
+            .with_number("age", 30)
+            .with_number("age", 31);
+
+        assert_eq!(
+            subject.attributes().get("age"),
+            Some(&AttributeValue::numeric(31.0))
+        );
+    }
+}